@@ -97,6 +97,7 @@ import type {
   SessionInfo,
   SessionDetails,
   SessionListPage,
+  SessionSortKey,
   SessionRuntimeState,
   RuntimeBootstrapState,
   RuntimeToolSnapshot,
@@ -115,7 +116,7 @@ import type {
   ExternalCliRuntimeConfig,
 } from './types.js';
 import { SessionPersistence, type PersistedSessionDataV2 } from './persistence.js';
-import { getCheckpointer, setCheckpointerDataDir } from './checkpointer.js';
+import { copyCheckpointThread, getCheckpointer, setCheckpointerDataDir } from './checkpointer.js';
 import { HumanMessage, SystemMessage } from '@langchain/core/messages';
 import { ChatOpenAI } from '@langchain/openai';
 import { ExternalCliDiscoveryService } from './external-cli/discovery-service.js';
@@ -5298,6 +5299,28 @@ export class AgentRunner {
     });
   }
 
+  /**
+   * Permission requests still waiting for a decision.
+   */
+  listPendingPermissions(sessionId: string): ExtendedPermissionRequest[] {
+    const session = this.sessions.get(sessionId);
+    if (!session) {
+      throw new Error(`Session not found: ${sessionId}`);
+    }
+    return Array.from(session.pendingPermissions.values()).map((pending) => pending.request);
+  }
+
+  /**
+   * Questions still waiting for an answer.
+   */
+  listPendingQuestions(sessionId: string): QuestionRequest[] {
+    const session = this.sessions.get(sessionId);
+    if (!session) {
+      throw new Error(`Session not found: ${sessionId}`);
+    }
+    return Array.from(session.pendingQuestions.values()).map((pending) => pending.request);
+  }
+
   /**
    * Respond to a question from the agent.
    */
@@ -5520,8 +5543,33 @@ export class AgentRunner {
     limit?: number;
     offset?: number;
     query?: string;
+    sortBy?: SessionSortKey;
+    order?: 'asc' | 'desc';
   }): SessionListPage {
     const allSessions = this.listSessions();
+    if (options?.sortBy || options?.order) {
+      // Same ordering as the desktop's sort_sessions: ties fall back to id.
+      const sortBy = options.sortBy ?? 'updated_at';
+      const direction = (options.order ?? 'desc') === 'desc' ? -1 : 1;
+      const key = (session: SessionInfo): number | string => {
+        switch (sortBy) {
+          case 'created_at':
+            return session.createdAt;
+          case 'last_accessed_at':
+            return session.lastAccessedAt;
+          case 'title':
+            return (session.title || '').toLowerCase();
+          default:
+            return session.updatedAt;
+        }
+      };
+      allSessions.sort((a, b) => {
+        const left = key(a);
+        const right = key(b);
+        if (left !== right) return (left < right ? -1 : 1) * direction;
+        return a.id < b.id ? -1 : a.id > b.id ? 1 : 0;
+      });
+    }
     const query = (options?.query || '').trim().toLowerCase();
     const filteredSessions =
       query.length > 0
@@ -5781,6 +5829,131 @@ export class AgentRunner {
     eventEmitter.sessionUpdated(sessionInfo);
   }
 
+  /**
+   * Import a session exported by the desktop app. The caller has already
+   * validated the bundle and picked a free id; the agent is recreated lazily
+   * on the first message, as for sessions restored from disk.
+   */
+  async importSession(
+    imported: {
+      id: string;
+      type?: SessionType | null;
+      provider?: ProviderId | null;
+      executionMode?: ExecutionMode | null;
+      title?: string | null;
+      workingDirectory?: string | null;
+      model?: string | null;
+      chatItems?: ChatItem[] | null;
+      tasks?: Task[] | null;
+      artifacts?: Artifact[] | null;
+      createdAt?: number;
+      updatedAt?: number;
+      lastAccessedAt?: number;
+    },
+    originalSessionId?: string,
+  ): Promise<SessionInfo> {
+    if (!imported?.id) {
+      throw new Error('Imported session has no id');
+    }
+    if (this.sessions.has(imported.id)) {
+      throw new Error(`Session already exists: ${imported.id}`);
+    }
+
+    const provider = (imported.provider || this.runtimeConfig.activeProvider || 'google') as ProviderId;
+    const model = imported.model || this.modelCatalog[0]?.id;
+    if (!model) {
+      throw new Error('Imported session has no model and no models are available');
+    }
+    const workingDirectory = resolve(imported.workingDirectory?.trim() || process.cwd());
+    const importedAt = Date.now();
+
+    const session = await this.recreateSession({
+      metadata: {
+        version: 2,
+        id: imported.id,
+        type: imported.type || 'main',
+        provider,
+        executionMode: imported.executionMode || 'execute',
+        title: imported.title ?? null,
+        workingDirectory,
+        model,
+        approvalMode: 'auto',
+        createdAt: imported.createdAt || importedAt,
+        updatedAt: importedAt,
+        lastAccessedAt: importedAt,
+      },
+      chatItems: Array.isArray(imported.chatItems) ? imported.chatItems : [],
+      tasks: Array.isArray(imported.tasks) ? imported.tasks : [],
+      artifacts: Array.isArray(imported.artifacts) ? imported.artifacts : [],
+    });
+
+    this.sessions.set(session.id, session);
+    this.subscribeToAgentEvents(session);
+    await this.persistSessionSnapshot(session);
+
+    if (originalSessionId && originalSessionId !== session.id) {
+      process.stderr.write(`[agent-runner] imported session ${originalSessionId} as ${session.id}\n`);
+    }
+
+    const sessionInfo: SessionInfo = {
+      id: session.id,
+      type: session.type,
+      provider: session.provider,
+      executionMode: session.executionMode,
+      title: session.title,
+      firstMessage: this.getFirstMessagePreview(session),
+      workingDirectory: session.workingDirectory,
+      model: session.model,
+      createdAt: session.createdAt,
+      updatedAt: session.updatedAt,
+      lastAccessedAt: session.lastAccessedAt,
+      messageCount: session.chatItems.filter(ci => ci.kind === 'user_message' || ci.kind === 'assistant_message').length,
+    };
+    eventEmitter.sessionUpdated(sessionInfo);
+    return sessionInfo;
+  }
+
+  /**
+   * Append a batch of chat items replayed from another session. Items are
+   * renumbered onto this session's sequence and persisted once per batch.
+   */
+  async appendChatItems(sessionId: string, chatItems: ChatItem[]): Promise<{ appended: number }> {
+    const session = this.sessions.get(sessionId);
+    if (!session) {
+      throw new Error(`Session not found: ${sessionId}`);
+    }
+
+    for (const item of chatItems) {
+      session.chatItems.push({
+        ...item,
+        sequence: this.getNextSequence(session),
+      } as ChatItem);
+    }
+    session.updatedAt = Date.now();
+
+    await this.persistSessionSnapshot(session);
+    return { appended: chatItems.length };
+  }
+
+  /**
+   * Give a duplicated session the source session's agent state (its
+   * checkpointer thread), so the next turn has the full context and not
+   * only the replayed transcript.
+   */
+  async copyAgentState(sourceSessionId: string, targetSessionId: string): Promise<{ copied: boolean }> {
+    const source = this.sessions.get(sourceSessionId);
+    if (!source) {
+      throw new Error(`Session not found: ${sourceSessionId}`);
+    }
+    const target = this.sessions.get(targetSessionId);
+    if (!target) {
+      throw new Error(`Session not found: ${targetSessionId}`);
+    }
+
+    const copied = await copyCheckpointThread(source.threadId, target.threadId);
+    return { copied };
+  }
+
   /**
    * Update session last accessed time.
    */
@@ -10286,20 +10459,85 @@ ${stitchGuidance}
       timestamp: now(),
     } as ChatItem;
 
-    // Keep only recent chatItems (those from the last keepLast messages) + summary
+    session.chatItems = [summaryItem, ...this.recentChatItems(session, messages, keepLast)];
+    if (!this.shouldUseLongTermMemory(session)) {
+      await this.persistSummary(session.workingDirectory, summary);
+    }
+    this.emitContextUsage(session);
+  }
+
+  /**
+   * Chat items belonging to the last `keepLast` messages, including their
+   * tool activity.
+   */
+  private recentChatItems(session: ActiveSession, messages: Message[], keepLast: number): ChatItem[] {
     const keepMessageIds = new Set(messages.slice(-keepLast).map(m => m.id));
-    const recentChatItems = session.chatItems.filter(ci => {
+    return session.chatItems.filter(ci => {
       if (ci.kind === 'user_message') return keepMessageIds.has(ci.turnId || ci.id);
       if (ci.kind === 'assistant_message') return keepMessageIds.has(ci.id);
       // Keep all non-message items that are recent (tool_start, tool_result, etc.)
       const turnId = ci.turnId;
       return turnId ? keepMessageIds.has(turnId) : false;
     });
-    session.chatItems = [summaryItem, ...recentChatItems];
-    if (!this.shouldUseLongTermMemory(session)) {
-      await this.persistSummary(session.workingDirectory, summary);
+  }
+
+  /**
+   * Compact a session on request, regardless of how full its context is.
+   * `summarize` replaces older turns with a model-written summary, `truncate`
+   * drops them, and `drop-tools` keeps every message but removes tool
+   * activity outside the most recent turns.
+   */
+  async compactSession(
+    sessionId: string,
+    strategy: 'summarize' | 'truncate' | 'drop-tools',
+  ): Promise<{ usedTokens: number; maxTokens: number; percentUsed: number; lastUpdated: number }> {
+    const session = this.sessions.get(sessionId);
+    if (!session) {
+      throw new Error(`Session not found: ${sessionId}`);
     }
+
+    const keepLast = 6;
+    const messages = this.deriveMessagesFromChatItems(session.chatItems);
+    if (strategy === 'drop-tools') {
+      const recent = new Set(this.recentChatItems(session, messages, keepLast));
+      session.chatItems = session.chatItems.filter(ci =>
+        recent.has(ci)
+        || ci.kind === 'user_message'
+        || ci.kind === 'assistant_message'
+        || ci.kind === 'system_message');
+    } else if (messages.length > keepLast) {
+      let note = `${messages.length - keepLast} earlier messages were removed to free up context.`;
+      if (strategy === 'summarize') {
+        if (!this.getSessionProviderKey(session)) {
+          throw new Error('Summarizing requires an API key for the session provider');
+        }
+        const summary = await this.summarizeMessages(session, messages.slice(0, -keepLast), session.model);
+        if (!summary) {
+          throw new Error('The model returned an empty summary; the session was left unchanged');
+        }
+        note = `Summary of earlier conversation:\n${summary}`;
+      }
+      const noteItem: ChatItem = {
+        id: generateChatItemId(),
+        kind: 'system_message',
+        content: note,
+        timestamp: now(),
+      } as ChatItem;
+      session.chatItems = [noteItem, ...this.recentChatItems(session, messages, keepLast)];
+    }
+
+    // The last provider-reported count describes the old history.
+    session.lastKnownPromptTokens = 0;
+    await this.persistSessionSnapshot(session);
     this.emitContextUsage(session);
+
+    const usage = this.getContextUsage(sessionId);
+    return {
+      usedTokens: usage.used,
+      maxTokens: usage.total,
+      percentUsed: usage.total > 0 ? (usage.used / usage.total) * 100 : 0,
+      lastUpdated: now(),
+    };
   }
 
   private async summarizeMessages(
@@ -0,0 +1,44 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+import { describe, expect, it, vi } from 'vitest';
+import { copyCheckpointThread } from './checkpointer.js';
+
+describe('copyCheckpointThread', () => {
+  it('writes the latest source checkpoint under the target thread', async () => {
+    const checkpoint = {
+      id: 'cp-3',
+      channel_values: { messages: ['hello', 'hi there'] },
+      channel_versions: { messages: 3 },
+    };
+    const metadata = { source: 'loop', step: 2, parents: {} };
+    const saver = {
+      getTuple: vi.fn(async () => ({
+        config: { configurable: { thread_id: 'source', checkpoint_ns: '', checkpoint_id: 'cp-3' } },
+        checkpoint,
+        metadata,
+      })),
+      put: vi.fn(async (config: unknown) => config),
+    };
+
+    await expect(copyCheckpointThread('source', 'copy', saver as never)).resolves.toBe(true);
+
+    expect(saver.getTuple).toHaveBeenCalledWith({ configurable: { thread_id: 'source' } });
+    expect(saver.put).toHaveBeenCalledWith(
+      { configurable: { thread_id: 'copy', checkpoint_ns: '' } },
+      checkpoint,
+      metadata,
+      checkpoint.channel_versions,
+    );
+  });
+
+  it('does nothing when the source thread has no state', async () => {
+    const saver = {
+      getTuple: vi.fn(async () => undefined),
+      put: vi.fn(),
+    };
+
+    await expect(copyCheckpointThread('empty', 'copy', saver as never)).resolves.toBe(false);
+    expect(saver.put).not.toHaveBeenCalled();
+  });
+});
@@ -47,3 +47,31 @@ export function getCheckpointer(): SqliteSaver {
 export async function closeCheckpointer(): Promise<void> {
   checkpointerInstance = null;
 }
+
+/**
+ * Copy the latest checkpoint of one thread into another, so a duplicated
+ * session carries the model's conversation state and not just the
+ * transcript. Returns false when the source thread has no state yet.
+ */
+export async function copyCheckpointThread(
+  sourceThreadId: string,
+  targetThreadId: string,
+  saver: Pick<SqliteSaver, 'getTuple' | 'put'> = getCheckpointer(),
+): Promise<boolean> {
+  const latest = await saver.getTuple({ configurable: { thread_id: sourceThreadId } });
+  if (!latest) {
+    return false;
+  }
+  await saver.put(
+    {
+      configurable: {
+        thread_id: targetThreadId,
+        checkpoint_ns: latest.config.configurable?.checkpoint_ns ?? '',
+      },
+    },
+    latest.checkpoint,
+    latest.metadata ?? ({} as Parameters<typeof saver.put>[2]),
+    latest.checkpoint.channel_versions,
+  );
+  return true;
+}
@@ -10,6 +10,7 @@
 
 import { ConnectorManager, getSecretService } from './connectors/index.js';
 import type { ConnectorManifest, MCPTool as ConnectorMCPTool } from '@cowork/shared';
+import { IPCError } from './ipc-errors.js';
 
 export interface ConnectorTool {
   name: string;
@@ -52,7 +53,7 @@ class ConnectorBridge {
     const manager = await this.ensureInitialized();
     const result = await manager.connect(manifest);
     if (!result.success) {
-      throw new Error(result.error || 'Connection failed');
+      throw new IPCError(result.errorCode ?? 'internal', result.error || 'Connection failed');
     }
     return {
       tools: result.capabilities?.tools || [],
@@ -80,7 +81,7 @@ class ConnectorBridge {
     const manager = await this.ensureInitialized();
     const result = await manager.reconnect(manifest);
     if (!result.success) {
-      throw new Error(result.error || 'Reconnection failed');
+      throw new IPCError(result.errorCode ?? 'internal', result.error || 'Reconnection failed');
     }
     return {
       tools: result.capabilities?.tools || [],
@@ -19,6 +19,7 @@ import type {
   MCPApp,
 } from '@cowork/shared';
 import type { SecretService } from './secret-service.js';
+import { IPCError, connectionErrorCode, type IPCErrorCode } from '../ipc-errors.js';
 
 // ============================================================================
 // Types
@@ -58,6 +59,7 @@ export interface ConnectorConnectionResult {
   success: boolean;
   capabilities?: ConnectorCapabilities;
   error?: string;
+  errorCode?: IPCErrorCode;
 }
 
 // ============================================================================
@@ -161,6 +163,7 @@ export class ConnectorManager {
         return {
           success: false,
           error: `Transport type "${(manifest.transport as { type: string }).type}" is not supported`,
+          errorCode: 'validation',
         };
       }
 
@@ -246,6 +249,7 @@ export class ConnectorManager {
       return {
         success: false,
         error: errorMessage,
+        errorCode: connectionErrorCode(error),
       };
     }
   }
@@ -489,7 +493,7 @@ export class ConnectorManager {
           const envKey = secret.envVar || secret.key;
           env[envKey] = value;
         } else if (secret.required) {
-          throw new Error(`Missing required secret: ${secret.key}`);
+          throw new IPCError('auth', `Missing required secret: ${secret.key}`);
         }
       }
     }
@@ -8,11 +8,14 @@
  * - Authorization Code flow with PKCE (Google, GitHub, Slack, Linear)
  * - Device Code flow (Microsoft)
  *
+ * The redirect of an authorization-code flow is captured by the desktop app's
+ * loopback server, which then hands the code back via
+ * `completeAuthorizationCode`. The desktop app also owns the PKCE verifier:
+ * only its S256 challenge is sent here, and the verifier arrives with the code.
+ *
  * Tokens are stored securely in the file-based secure storage via SecretService.
  */
 
-import { createServer, type Server, type IncomingMessage, type ServerResponse } from 'http';
-import crypto from 'crypto';
 import {
   getProviderConfig,
   isProviderConfigured,
@@ -44,14 +47,35 @@ export interface OAuthFlowResult {
   interval?: number;
 }
 
+/**
+ * Redirect target, CSRF state and PKCE challenge chosen by the desktop
+ * loopback server
+ */
+export interface LoopbackRedirect {
+  redirectUri: string;
+  state: string;
+  codeChallenge: string;
+}
+
+/**
+ * Authorization code captured by the desktop loopback server
+ */
+export interface AuthorizationCodeCallback {
+  code: string;
+  state: string;
+  redirectUri: string;
+  codeVerifier: string;
+}
+
 /**
  * Internal state for pending authorization code flows
  */
 interface PendingFlow {
-  codeVerifier: string;
   provider: string;
   scopes: string[];
   connectorId: string;
+  redirectUri: string;
+  state: string;
 }
 
 /**
@@ -71,13 +95,7 @@ interface TokenResponse {
 
 export class ConnectorOAuthService {
   private secretService: SecretService;
-  private callbackServer: Server | null = null;
-  private callbackPort: number = 0;
   private pendingFlows: Map<string, PendingFlow> = new Map();
-  private flowCompletionCallbacks: Map<string, {
-    resolve: (success: boolean) => void;
-    reject: (error: Error) => void;
-  }> = new Map();
 
   constructor(secretService: SecretService) {
     this.secretService = secretService;
@@ -94,7 +112,8 @@ export class ConnectorOAuthService {
     connectorId: string,
     provider: string,
     flow: OAuthFlowType,
-    scopes: string[]
+    scopes: string[],
+    redirect?: LoopbackRedirect
   ): Promise<OAuthFlowResult> {
     const config = getProviderConfig(provider);
     if (!config) {
@@ -113,7 +132,12 @@ export class ConnectorOAuthService {
     if (flow === 'device_code') {
       return this.startDeviceCodeFlow(connectorId, provider, config, validatedScopes);
     }
-    return this.startAuthorizationCodeFlow(connectorId, provider, config, validatedScopes);
+    if (!redirect?.redirectUri || !redirect.state || !redirect.codeChallenge) {
+      throw new Error(
+        'redirectUri, state and codeChallenge are required for authorization code flows'
+      );
+    }
+    return this.startAuthorizationCodeFlow(connectorId, provider, config, validatedScopes, redirect);
   }
 
   /**
@@ -273,38 +297,32 @@ export class ConnectorOAuthService {
     connectorId: string,
     provider: string,
     config: OAuthProviderConfig,
-    scopes: string[]
+    scopes: string[],
+    redirect: LoopbackRedirect
   ): Promise<OAuthFlowResult> {
-    // Generate PKCE code verifier and challenge
-    const codeVerifier = this.generateCodeVerifier();
-    const codeChallenge = this.generateCodeChallenge(codeVerifier);
-
     // Store pending flow state
     this.pendingFlows.set(connectorId, {
-      codeVerifier,
       provider,
       scopes,
       connectorId,
+      redirectUri: redirect.redirectUri,
+      state: redirect.state,
     });
 
-    // Start callback server to receive authorization code
-    this.callbackPort = await this.startCallbackServer(connectorId);
-    const redirectUri = `http://localhost:${this.callbackPort}/oauth/callback`;
-
     // Build authorization URL
     const params = new URLSearchParams({
       client_id: config.clientId,
-      redirect_uri: redirectUri,
+      redirect_uri: redirect.redirectUri,
       response_type: 'code',
       scope: scopes.join(' '),
-      state: connectorId,
+      state: redirect.state,
       access_type: 'offline', // Request refresh token
       prompt: 'consent', // Force consent screen to get refresh token
     });
 
     // Add PKCE parameters if provider supports it
     if (config.usesPKCE) {
-      params.set('code_challenge', codeChallenge);
+      params.set('code_challenge', redirect.codeChallenge);
       params.set('code_challenge_method', 'S256');
     }
 
@@ -317,30 +335,32 @@ export class ConnectorOAuthService {
   }
 
   /**
-   * Complete authorization code flow (called from callback)
+   * Complete authorization code flow with the code captured by the desktop
+   * loopback server
    */
-  private async completeAuthorizationCode(
+  async completeAuthorizationCode(
     connectorId: string,
-    code: string
+    callback: AuthorizationCodeCallback
   ): Promise<void> {
     const flow = this.pendingFlows.get(connectorId);
     if (!flow) {
       throw new Error('No pending OAuth flow');
     }
+    if (callback.state !== flow.state || callback.redirectUri !== flow.redirectUri) {
+      throw new Error('OAuth callback does not match the pending flow');
+    }
 
     const config = getProviderConfig(flow.provider);
     if (!config) {
       throw new Error(`Unknown provider: ${flow.provider}`);
     }
 
-    const redirectUri = `http://localhost:${this.callbackPort}/oauth/callback`;
-
     // Build token request body
     const body = new URLSearchParams({
       client_id: config.clientId,
-      code,
+      code: callback.code,
       grant_type: 'authorization_code',
-      redirect_uri: redirectUri,
+      redirect_uri: flow.redirectUri,
     });
 
     // Add client secret if required
@@ -350,7 +370,7 @@ export class ConnectorOAuthService {
 
     // Add PKCE verifier if used
     if (config.usesPKCE) {
-      body.set('code_verifier', flow.codeVerifier);
+      body.set('code_verifier', callback.codeVerifier);
     }
 
     // Exchange code for tokens
@@ -375,14 +395,6 @@ export class ConnectorOAuthService {
 
     // Cleanup
     this.pendingFlows.delete(connectorId);
-    this.stopCallbackServer();
-
-    // Notify completion
-    const callback = this.flowCompletionCallbacks.get(connectorId);
-    if (callback) {
-      callback.resolve(true);
-      this.flowCompletionCallbacks.delete(connectorId);
-    }
   }
 
   // ==========================================================================
@@ -469,139 +481,4 @@ export class ConnectorOAuthService {
     await this.secretService.deleteSecret(connectorId, '_PROVIDER');
     this.pendingFlows.delete(connectorId);
   }
-
-  // ==========================================================================
-  // Private: Callback Server
-  // ==========================================================================
-
-  private startCallbackServer(connectorId: string): Promise<number> {
-    return new Promise((resolve, reject) => {
-      this.callbackServer = createServer(
-        (req: IncomingMessage, res: ServerResponse) => {
-          const url = new URL(req.url || '', 'http://localhost');
-
-          if (url.pathname === '/oauth/callback') {
-            const code = url.searchParams.get('code');
-            const state = url.searchParams.get('state');
-            const error = url.searchParams.get('error');
-            const errorDescription = url.searchParams.get('error_description');
-
-            if (error) {
-              res.writeHead(400, { 'Content-Type': 'text/html' });
-              res.end(`
-                <!DOCTYPE html>
-                <html>
-                <head>
-                  <title>Authorization Failed</title>
-                  <style>
-                    body { font-family: system-ui; padding: 40px; text-align: center; background: #1a1a1a; color: #fff; }
-                    h1 { color: #ef4444; }
-                    p { color: #9ca3af; }
-                  </style>
-                </head>
-                <body>
-                  <h1>Authorization Failed</h1>
-                  <p>${errorDescription || error}</p>
-                  <p>You can close this window and try again.</p>
-                  <script>setTimeout(() => window.close(), 5000);</script>
-                </body>
-                </html>
-              `);
-
-              // Notify failure
-              const callback = this.flowCompletionCallbacks.get(state || connectorId);
-              if (callback) {
-                callback.reject(new Error(errorDescription || error));
-                this.flowCompletionCallbacks.delete(state || connectorId);
-              }
-              return;
-            }
-
-            if (code && state === connectorId) {
-              res.writeHead(200, { 'Content-Type': 'text/html' });
-              res.end(`
-                <!DOCTYPE html>
-                <html>
-                <head>
-                  <title>Authorization Successful</title>
-                  <style>
-                    body { font-family: system-ui; padding: 40px; text-align: center; background: #1a1a1a; color: #fff; }
-                    h1 { color: #22c55e; }
-                    p { color: #9ca3af; }
-                  </style>
-                </head>
-                <body>
-                  <h1>Authorization Successful!</h1>
-                  <p>You can close this window and return to Cowork.</p>
-                  <script>setTimeout(() => window.close(), 3000);</script>
-                </body>
-                </html>
-              `);
-
-              // Complete the flow asynchronously
-              this.completeAuthorizationCode(connectorId, code).catch((err) => {
-                console.error('Failed to complete OAuth flow:', err);
-                const callback = this.flowCompletionCallbacks.get(connectorId);
-                if (callback) {
-                  callback.reject(err);
-                  this.flowCompletionCallbacks.delete(connectorId);
-                }
-              });
-            } else {
-              res.writeHead(400, { 'Content-Type': 'text/plain' });
-              res.end('Invalid callback parameters');
-            }
-          } else {
-            res.writeHead(404, { 'Content-Type': 'text/plain' });
-            res.end('Not found');
-          }
-        }
-      );
-
-      // Listen on random available port
-      this.callbackServer.listen(0, '127.0.0.1', () => {
-        const addr = this.callbackServer!.address();
-        if (addr && typeof addr === 'object') {
-          resolve(addr.port);
-        } else {
-          reject(new Error('Failed to get callback server port'));
-        }
-      });
-
-      this.callbackServer.on('error', reject);
-
-      // Auto-close after 10 minutes
-      setTimeout(() => {
-        this.stopCallbackServer();
-      }, 10 * 60 * 1000);
-    });
-  }
-
-  private stopCallbackServer(): void {
-    if (this.callbackServer) {
-      this.callbackServer.close();
-      this.callbackServer = null;
-      this.callbackPort = 0;
-    }
-  }
-
-  // ==========================================================================
-  // Private: PKCE
-  // ==========================================================================
-
-  /**
-   * Generate a cryptographically random code verifier for PKCE
-   * Length: 43-128 characters, URL-safe base64
-   */
-  private generateCodeVerifier(): string {
-    return crypto.randomBytes(32).toString('base64url');
-  }
-
-  /**
-   * Generate code challenge from verifier using S256 method
-   * SHA256 hash of verifier, URL-safe base64 encoded
-   */
-  private generateCodeChallenge(verifier: string): string {
-    return crypto.createHash('sha256').update(verifier).digest('base64url');
-  }
 }
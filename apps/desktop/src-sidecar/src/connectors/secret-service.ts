@@ -79,6 +79,11 @@ export interface FileStorageOptions {
 // ============================================================================
 
 function getConfigDir(): string {
+  // Mirrors the desktop app: COWORK_DATA_DIR relocates the config dir too.
+  const dataDirOverride = process.env.COWORK_DATA_DIR?.trim();
+  if (dataDirOverride) {
+    return path.join(dataDirOverride, 'config', 'cowork');
+  }
   if (process.platform === 'darwin') {
     return path.join(os.homedir(), 'Library', 'Application Support', 'cowork');
   }
@@ -302,6 +307,18 @@ export class FileStorage implements SecretStorage, RotatableSecretStorage {
     }
   }
 
+  /**
+   * Re-run the legacy plaintext migration on demand. Returns whether any
+   * plaintext store was found (and folded into the vault).
+   */
+  migrateLegacyPlaintextStores(): boolean {
+    const found = fs.existsSync(this.legacyStorePath) || fs.existsSync(this.legacyStorePathV0);
+    if (found) {
+      this.migrateLegacyStoresIfNeeded();
+    }
+    return found;
+  }
+
   private migrateLegacyStoreFile(vault: EncryptedSecretVault, filePath: string): boolean {
     if (!fs.existsSync(filePath)) {
       return false;
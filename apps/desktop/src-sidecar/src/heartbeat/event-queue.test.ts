@@ -0,0 +1,56 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+import { afterEach, describe, expect, it, vi } from 'vitest';
+import { EventQueue } from './event-queue.js';
+
+describe('EventQueue retention', () => {
+  afterEach(() => {
+    vi.useRealTimers();
+  });
+
+  it('evicts the oldest event when the queue is full', () => {
+    vi.useFakeTimers();
+    vi.setSystemTime(1_000);
+    const queue = new EventQueue(2);
+    const first = queue.enqueue({ type: 'custom', priority: 'high' });
+    vi.setSystemTime(2_000);
+    const second = queue.enqueue({ type: 'custom', priority: 'low' });
+    vi.setSystemTime(3_000);
+    const third = queue.enqueue({ type: 'custom', priority: 'normal' });
+
+    expect(queue.getAll().map(e => e.id)).toEqual([third, second]);
+    expect(queue.getAll().some(e => e.id === first)).toBe(false);
+    expect(queue.evictedCount).toBe(1);
+  });
+
+  it('drops events older than the retention age', () => {
+    vi.useFakeTimers();
+    vi.setSystemTime(10_000);
+    const queue = new EventQueue(10, 5_000);
+    queue.enqueue({ type: 'custom', priority: 'normal' });
+    vi.setSystemTime(12_000);
+    const fresh = queue.enqueue({ type: 'custom', priority: 'normal' });
+
+    vi.setSystemTime(16_000);
+    expect(queue.query().map(e => e.id)).toEqual([fresh]);
+    expect(queue.evictedCount).toBe(1);
+  });
+
+  it('shrinks to a lower capacity and filters by since and limit', () => {
+    vi.useFakeTimers();
+    const queue = new EventQueue(10);
+    const ids: string[] = [];
+    for (let i = 1; i <= 4; i++) {
+      vi.setSystemTime(i * 1_000);
+      ids.push(queue.enqueue({ type: 'custom', priority: 'normal' }));
+    }
+
+    expect(queue.query({ since: 2_000, limit: 2 }).map(e => e.id)).toEqual([ids[1], ids[2]]);
+
+    queue.setRetention({ maxSize: 2 });
+    expect(queue.capacity).toBe(2);
+    expect(queue.getAll().map(e => e.id)).toEqual([ids[2], ids[3]]);
+    expect(queue.clear()).toBe(2);
+  });
+});
@@ -6,6 +6,7 @@
  *
  * Events are sorted by priority (high → normal → low)
  * Within same priority, FIFO order is maintained
+ * Retention is bounded by count and age; the oldest events are evicted first
  */
 
 import type { SystemEvent, SystemEventType, EventPriority } from '@cowork/shared';
@@ -28,15 +29,52 @@ function generateId(prefix: string): string {
   return `${prefix}_${timestamp}${random}`;
 }
 
+/**
+ * Retention limits for queued events
+ */
+export interface EventQueueRetention {
+  maxSize: number;
+  /** Maximum event age in ms; 0 keeps events until they are processed */
+  maxAgeMs: number;
+}
+
+/**
+ * Options for reading queued events
+ */
+export interface EventQueueQuery {
+  /** Only events scheduled at or after this timestamp */
+  since?: number;
+  limit?: number;
+}
+
 /**
  * Priority event queue for system events
  */
 export class EventQueue {
   private events: SystemEvent[] = [];
   private maxSize: number;
+  private maxAgeMs: number;
+  private evicted = 0;
 
-  constructor(maxSize = 1000) {
-    this.maxSize = maxSize;
+  constructor(maxSize = 1000, maxAgeMs = 0) {
+    this.maxSize = Math.max(1, maxSize);
+    this.maxAgeMs = Math.max(0, maxAgeMs);
+  }
+
+  /**
+   * Update retention limits, evicting events that no longer fit
+   */
+  setRetention(retention: Partial<EventQueueRetention>): void {
+    if (retention.maxSize !== undefined) {
+      this.maxSize = Math.max(1, Math.floor(retention.maxSize));
+    }
+    if (retention.maxAgeMs !== undefined) {
+      this.maxAgeMs = Math.max(0, retention.maxAgeMs);
+    }
+    this.prune();
+    while (this.events.length > this.maxSize) {
+      this.evictOldest();
+    }
   }
 
   /**
@@ -50,16 +88,10 @@ export class EventQueue {
       scheduledAt: Date.now(),
     };
 
-    // Check capacity
-    if (this.events.length >= this.maxSize) {
-      // Remove oldest low-priority event first
-      const lowPriorityIndex = this.events.findIndex(e => e.priority === 'low');
-      if (lowPriorityIndex >= 0) {
-        this.events.splice(lowPriorityIndex, 1);
-      } else {
-        // Remove oldest event if no low priority events
-        this.events.shift();
-      }
+    // Drop expired events, then make room by evicting the oldest
+    this.prune(fullEvent.scheduledAt);
+    while (this.events.length >= this.maxSize) {
+      this.evictOldest();
     }
 
     // Insert in priority order (high first, then by scheduledAt)
@@ -90,6 +122,30 @@ export class EventQueue {
     return [...this.events];
   }
 
+  /**
+   * Get events in queue order, optionally filtered by age and capped
+   */
+  query({ since, limit }: EventQueueQuery = {}): SystemEvent[] {
+    this.prune();
+    const matching =
+      since === undefined ? [...this.events] : this.events.filter(e => e.scheduledAt >= since);
+    return limit === undefined ? matching : matching.slice(0, Math.max(0, limit));
+  }
+
+  /**
+   * Drop events older than the retention age
+   * Returns the number of events dropped
+   */
+  prune(now = Date.now()): number {
+    if (this.maxAgeMs <= 0) return 0;
+    const cutoff = now - this.maxAgeMs;
+    const before = this.events.length;
+    this.events = this.events.filter(e => e.scheduledAt >= cutoff);
+    const dropped = before - this.events.length;
+    this.evicted += dropped;
+    return dropped;
+  }
+
   /**
    * Get events by type
    */
@@ -111,9 +167,12 @@ export class EventQueue {
 
   /**
    * Clear all events
+   * Returns the number of events removed
    */
-  clear(): void {
+  clear(): number {
+    const count = this.events.length;
     this.events = [];
+    return count;
   }
 
   /**
@@ -123,6 +182,20 @@ export class EventQueue {
     return this.events.length;
   }
 
+  /**
+   * Maximum number of events retained
+   */
+  get capacity(): number {
+    return this.maxSize;
+  }
+
+  /**
+   * Events dropped by count or age limits since startup
+   */
+  get evictedCount(): number {
+    return this.evicted;
+  }
+
   /**
    * Check if empty
    */
@@ -130,6 +203,21 @@ export class EventQueue {
     return this.events.length === 0;
   }
 
+  /**
+   * Remove the event that was scheduled first
+   */
+  private evictOldest(): void {
+    if (this.events.length === 0) return;
+    let oldestIndex = 0;
+    for (let i = 1; i < this.events.length; i++) {
+      if (this.events[i].scheduledAt < this.events[oldestIndex].scheduledAt) {
+        oldestIndex = i;
+      }
+    }
+    this.events.splice(oldestIndex, 1);
+    this.evicted++;
+  }
+
   /**
    * Find insert position maintaining priority order
    * Higher priority events come first
@@ -7,7 +7,7 @@
  * Features:
  * - Configurable heartbeat interval
  * - Priority event queue for system events
- * - Immediate wake via wake('now')
+ * - Immediate wake via wake('now'), with bursts coalesced into one cycle
  * - Events emitted for each heartbeat and processed event
  */
 
@@ -18,7 +18,7 @@ import type {
   SystemEvent,
   WakeMode,
 } from '@cowork/shared';
-import { EventQueue } from './event-queue.js';
+import { EventQueue, type EventQueueQuery } from './event-queue.js';
 import {
   readJsonFile,
   writeJsonFileAtomic,
@@ -34,6 +34,9 @@ const DEFAULT_CONFIG: HeartbeatConfig = {
   intervalMs: 60000, // 1 minute
   systemEventsEnabled: true,
   cronEnabled: true,
+  maxQueuedEvents: 1000,
+  eventRetentionMs: 24 * 60 * 60 * 1000, // 1 day
+  wakeDebounceMs: 250,
 };
 
 /**
@@ -47,11 +50,17 @@ export class HeartbeatService extends EventEmitter {
   private lastHeartbeat = 0;
   private nextHeartbeat = 0;
   private initialized = false;
+  private wakeTimer: NodeJS.Timeout | null = null;
+  private wakeAfterProcessing = false;
+  private coalescedWakes = 0;
 
   constructor() {
     super();
     this.config = { ...DEFAULT_CONFIG };
-    this.eventQueue = new EventQueue();
+    this.eventQueue = new EventQueue(
+      DEFAULT_CONFIG.maxQueuedEvents,
+      DEFAULT_CONFIG.eventRetentionMs
+    );
   }
 
   /**
@@ -68,6 +77,7 @@ export class HeartbeatService extends EventEmitter {
       DEFAULT_CONFIG
     );
     this.config = { ...DEFAULT_CONFIG, ...savedConfig };
+    this.applyRetention();
     this.initialized = true;
   }
 
@@ -88,6 +98,11 @@ export class HeartbeatService extends EventEmitter {
       clearTimeout(this.timer);
       this.timer = null;
     }
+    if (this.wakeTimer) {
+      clearTimeout(this.wakeTimer);
+      this.wakeTimer = null;
+    }
+    this.wakeAfterProcessing = false;
     this.emit('heartbeat:stopped');
   }
 
@@ -104,6 +119,7 @@ export class HeartbeatService extends EventEmitter {
   async configure(config: Partial<HeartbeatConfig>): Promise<void> {
     const wasEnabled = this.config.enabled;
     this.config = { ...this.config, ...config };
+    this.applyRetention();
 
     // Save to disk
     await writeJsonFileAtomic(getHeartbeatConfigPath(), this.config);
@@ -152,43 +168,90 @@ export class HeartbeatService extends EventEmitter {
 
   /**
    * Trigger immediate heartbeat
+   * Wakes arriving within `wakeDebounceMs` of each other, or while a cycle is
+   * running, are merged into a single cycle.
    * @param mode 'now' processes immediately, 'next-heartbeat' waits for scheduled time
    */
   wake(mode: WakeMode = 'next-heartbeat'): void {
-    if (mode === 'now' && !this.isProcessing) {
-      // Cancel scheduled and run immediately
-      if (this.timer) {
-        clearTimeout(this.timer);
-        this.timer = null;
-      }
-      this.processHeartbeat().then(() => {
-        // Reschedule after immediate processing
-        if (this.config.enabled && !this.timer) {
-          this.scheduleNext();
-        }
-      });
-    }
     // 'next-heartbeat' does nothing special - just waits for scheduled time
+    if (mode !== 'now') return;
+
+    if (this.wakeTimer) {
+      this.coalescedWakes++;
+      return;
+    }
+    if (this.isProcessing) {
+      this.coalescedWakes++;
+      this.wakeAfterProcessing = true;
+      return;
+    }
+
+    this.wakeTimer = setTimeout(() => {
+      this.wakeTimer = null;
+      this.runWake();
+    }, Math.max(0, this.config.wakeDebounceMs));
+    if (this.wakeTimer.unref) {
+      this.wakeTimer.unref();
+    }
+  }
+
+  /**
+   * Run one wake cycle in place of the scheduled one
+   */
+  private runWake(): void {
+    // Cancel scheduled and run immediately
+    if (this.timer) {
+      clearTimeout(this.timer);
+      this.timer = null;
+    }
+    this.processHeartbeat().then(() => {
+      // Reschedule after immediate processing
+      if (this.config.enabled && !this.timer) {
+        this.scheduleNext();
+      }
+    });
   }
 
   /**
    * Get current status
    */
   getStatus(): HeartbeatStatus {
+    this.eventQueue.prune();
     return {
       isRunning: this.timer !== null,
       lastHeartbeat: this.lastHeartbeat,
       nextHeartbeat: this.nextHeartbeat,
       eventQueueSize: this.eventQueue.size,
+      eventQueueCapacity: this.eventQueue.capacity,
+      evictedEvents: this.eventQueue.evictedCount,
+      coalescedWakes: this.coalescedWakes,
       isProcessing: this.isProcessing,
     };
   }
 
   /**
-   * Get queued events
+   * Get queued events, optionally only those since a timestamp and capped
    */
-  getQueuedEvents(): SystemEvent[] {
-    return this.eventQueue.getAll();
+  getQueuedEvents(query: EventQueueQuery = {}): SystemEvent[] {
+    return this.eventQueue.query(query);
+  }
+
+  /**
+   * Drop all queued events
+   * Returns the number of events removed
+   */
+  clearEvents(): number {
+    return this.eventQueue.clear();
+  }
+
+  /**
+   * Push retention limits from config into the queue
+   */
+  private applyRetention(): void {
+    this.eventQueue.setRetention({
+      maxSize: this.config.maxQueuedEvents,
+      maxAgeMs: this.config.eventRetentionMs,
+    });
   }
 
   /**
@@ -237,6 +300,11 @@ export class HeartbeatService extends EventEmitter {
       }
     } finally {
       this.isProcessing = false;
+      // Wakes that arrived mid-cycle get one merged follow-up cycle
+      if (this.wakeAfterProcessing) {
+        this.wakeAfterProcessing = false;
+        this.wake('now');
+      }
     }
   }
 }
@@ -0,0 +1,38 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+import { describe, expect, it } from 'vitest';
+import { IPCError, connectionErrorCode, getErrorCode } from './ipc-errors.js';
+
+function httpError(status: number): Error {
+  return Object.assign(new Error(`HTTP ${status}`), { code: status });
+}
+
+describe('ipc errors', () => {
+  it('only reports codes carried by an IPCError', () => {
+    expect(getErrorCode(new IPCError('not_found', 'missing'))).toBe('not_found');
+    expect(getErrorCode(new Error('plain'))).toBeUndefined();
+    expect(getErrorCode('text')).toBeUndefined();
+  });
+
+  it('classifies connection failures by HTTP status', () => {
+    expect(connectionErrorCode(httpError(401))).toBe('auth');
+    expect(connectionErrorCode(httpError(403))).toBe('auth');
+    expect(connectionErrorCode(httpError(404))).toBe('not_found');
+    expect(connectionErrorCode(httpError(400))).toBe('validation');
+    expect(connectionErrorCode(httpError(429))).toBe('network');
+    expect(connectionErrorCode(httpError(503))).toBe('network');
+  });
+
+  it('keeps explicit codes and treats other failures as network errors', () => {
+    expect(connectionErrorCode(new IPCError('auth', 'Missing required secret: TOKEN'))).toBe('auth');
+    expect(connectionErrorCode(Object.assign(new Error('nope'), { name: 'UnauthorizedError' }))).toBe(
+      'auth',
+    );
+    // Node system errors use string codes such as ECONNREFUSED.
+    expect(connectionErrorCode(Object.assign(new Error('refused'), { code: 'ECONNREFUSED' }))).toBe(
+      'network',
+    );
+    expect(connectionErrorCode(new Error('MCP process exited'))).toBe('network');
+  });
+});
@@ -0,0 +1,43 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+/** Matches the desktop's `ErrorCode` so it can branch without reading messages. */
+export type IPCErrorCode =
+  | 'auth'
+  | 'network'
+  | 'transport'
+  | 'validation'
+  | 'not_found'
+  | 'internal';
+
+export class IPCError extends Error {
+  readonly code: IPCErrorCode;
+
+  constructor(code: IPCErrorCode, message: string) {
+    super(message);
+    this.name = 'IPCError';
+    this.code = code;
+  }
+}
+
+export function getErrorCode(error: unknown): IPCErrorCode | undefined {
+  return error instanceof IPCError ? error.code : undefined;
+}
+
+/**
+ * Classify a failed connection attempt. HTTP transports report the response
+ * status as a numeric `code`; anything else is treated as a network or
+ * process failure that may succeed on retry.
+ */
+export function connectionErrorCode(error: unknown): IPCErrorCode {
+  if (error instanceof IPCError) return error.code;
+  if (error instanceof Error && error.name === 'UnauthorizedError') return 'auth';
+
+  const status = (error as { code?: unknown } | null)?.code;
+  if (typeof status === 'number' && status >= 400 && status < 600) {
+    if (status === 401 || status === 403) return 'auth';
+    if (status === 404) return 'not_found';
+    if (status < 500 && status !== 408 && status !== 429) return 'validation';
+  }
+  return 'network';
+}
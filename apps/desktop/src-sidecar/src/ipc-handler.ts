@@ -2,6 +2,7 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 import { agentRunner } from './agent-runner.js';
+import { IPCError, getErrorCode } from './ipc-errors.js';
 import { loadGeminiExtensions } from './gemini-extensions.js';
 import { skillService } from './skill-service.js';
 import { checkSkillEligibility } from './eligibility-checker.js';
@@ -30,10 +31,12 @@ import type { SecretService } from './connectors/secret-service.js';
 import { ConnectorOAuthService } from './connectors/connector-oauth-service.js';
 import { securityAuditLog } from './security/audit-log.js';
 import type {
+  ChatItem,
   CronJob,
   CronRun,
   CreateWorkflowDraftInput,
   CreateWorkflowFromPromptInput,
+  HeartbeatConfig,
   SystemEvent,
   ToolPolicy,
   ToolRule,
@@ -246,6 +249,7 @@ const SECURITY_AUDIT_COMMANDS = new Set([
   'disconnect_connector',
   'reconnect_connector',
   'start_connector_oauth_flow',
+  'complete_connector_oauth_flow',
   'poll_oauth_device_code',
   'refresh_oauth_tokens',
   'revoke_oauth_tokens',
@@ -257,7 +261,13 @@ const SECURITY_AUDIT_COMMANDS = new Set([
 ]);
 const idempotencyStore: Map<
   string,
-  { createdAt: number; success: boolean; result?: unknown; error?: string }
+  {
+    createdAt: number;
+    success: boolean;
+    result?: unknown;
+    error?: string;
+    errorCode?: IPCResponse['errorCode'];
+  }
 > = new Map();
 
 function getErrorMessage(error: unknown): string {
@@ -368,6 +378,7 @@ export async function handleRequest(request: IPCRequest): Promise<IPCResponse> {
         success: cached.success,
         result: cached.result,
         error: cached.error,
+        errorCode: cached.errorCode,
       };
     }
   }
@@ -419,12 +430,14 @@ export async function handleRequest(request: IPCRequest): Promise<IPCResponse> {
       id: request.id,
       success: false,
       error: getErrorMessage(error),
+      errorCode: getErrorCode(error),
     };
     if (idempotencyStoreKey) {
       idempotencyStore.set(idempotencyStoreKey, {
         createdAt: nowMs,
         success: false,
         error: response.error,
+        errorCode: response.errorCode,
       });
     }
     return response;
@@ -575,6 +588,13 @@ registerHandler('respond_permission', async (params) => {
   return { success: true };
 });
 
+// List permission requests awaiting a decision
+registerHandler('list_pending_permissions', async (params) => {
+  const p = params as { sessionId: string };
+  if (!p.sessionId) throw new Error('sessionId is required');
+  return agentRunner.listPendingPermissions(p.sessionId);
+});
+
 // Set approval mode
 registerHandler('set_approval_mode', async (params) => {
   const p = params as unknown as SetApprovalModeParams;
@@ -658,6 +678,13 @@ registerHandler('respond_question', async (params) => {
   return { success: true };
 });
 
+// List questions awaiting an answer
+registerHandler('list_pending_questions', async (params) => {
+  const p = params as { sessionId: string };
+  if (!p.sessionId) throw new Error('sessionId is required');
+  return agentRunner.listPendingQuestions(p.sessionId);
+});
+
 // Sync skills
 registerHandler('set_skills', async (params) => {
   const p = params as { skills: Array<{ id: string; name: string; path: string; description?: string; enabled?: boolean }> };
@@ -747,6 +774,8 @@ registerHandler('list_sessions_page', async (params) => {
     limit: typeof p.limit === 'number' ? p.limit : undefined,
     offset: typeof p.offset === 'number' ? p.offset : undefined,
     query: typeof p.query === 'string' ? p.query : undefined,
+    sortBy: typeof p.sortBy === 'string' ? p.sortBy : undefined,
+    order: p.order === 'asc' || p.order === 'desc' ? p.order : undefined,
   });
 });
 
@@ -790,6 +819,34 @@ registerHandler('update_session_title', async (params) => {
   return { success: true };
 });
 
+// Append replayed chat items (used when duplicating a session)
+registerHandler('session_append_chat_items', async (params) => {
+  const p = params as { sessionId: string; chatItems: ChatItem[] };
+  if (!p.sessionId || !Array.isArray(p.chatItems)) {
+    throw new Error('sessionId and chatItems are required');
+  }
+  return agentRunner.appendChatItems(p.sessionId, p.chatItems);
+});
+
+// Copy the agent state of a session into its duplicate
+registerHandler('session_copy_agent_state', async (params) => {
+  const p = params as { sourceSessionId: string; targetSessionId: string };
+  if (!p.sourceSessionId || !p.targetSessionId) {
+    throw new Error('sourceSessionId and targetSessionId are required');
+  }
+  return agentRunner.copyAgentState(p.sourceSessionId, p.targetSessionId);
+});
+
+// Import a session exported by the desktop app
+registerHandler('import_session', async (params) => {
+  const p = params as {
+    session: Parameters<typeof agentRunner.importSession>[0];
+    originalSessionId?: string;
+  };
+  if (!p.session?.id) throw new Error('session with an id is required');
+  return agentRunner.importSession(p.session, p.originalSessionId);
+});
+
 // Update session working directory
 registerHandler('update_session_working_directory', async (params) => {
   const p = params as { sessionId: string; workingDirectory: string };
@@ -827,6 +884,18 @@ registerHandler('get_context_usage', async (params) => {
   return agentRunner.getContextUsage(p.sessionId);
 });
 
+const COMPACTION_STRATEGIES = ['summarize', 'truncate', 'drop-tools'] as const;
+
+registerHandler('compact_session', async (params) => {
+  const p = params as { sessionId?: string; strategy?: string };
+  if (!p.sessionId) throw new Error('sessionId is required');
+  const strategy = COMPACTION_STRATEGIES.find((entry) => entry === p.strategy);
+  if (!strategy) {
+    throw new Error(`strategy must be one of: ${COMPACTION_STRATEGIES.join(', ')}`);
+  }
+  return agentRunner.compactSession(p.sessionId, strategy);
+});
+
 // Load memory from GEMINI.md
 registerHandler('load_memory', async (params) => {
   const p = params as unknown as LoadMemoryParams;
@@ -876,6 +945,34 @@ registerHandler('ping', async () => {
   return { pong: true, timestamp: Date.now() };
 });
 
+const CAPABILITIES_PROTOCOL_VERSION = 1;
+
+/** UI features and the command each one needs. */
+const CAPABILITY_FEATURES: Record<string, string> = {
+  remoteAccess: 'remote_access_get_status',
+  cron: 'cron_list_jobs',
+  workflows: 'workflow_list',
+  heartbeat: 'heartbeat_get_status',
+  deepMemory: 'deep_memory_list',
+  subagents: 'subagent_list',
+  connectors: 'discover_connectors',
+  integrations: 'integration_list_statuses',
+  sessionBranches: 'session_branch_create',
+};
+
+// Capabilities handshake: the app caches this per connection to reject
+// commands this build does not handle and to hide unsupported features.
+registerHandler('get_daemon_capabilities', async () => {
+  return {
+    advertised: true,
+    protocolVersion: CAPABILITIES_PROTOCOL_VERSION,
+    commands: Array.from(handlers.keys()).sort(),
+    features: Object.entries(CAPABILITY_FEATURES)
+      .filter(([, command]) => handlers.has(command))
+      .map(([feature]) => feature),
+  };
+});
+
 registerHandler('daemon_health', async () => {
   const init = agentRunner.getInitializationStatus();
   return {
@@ -960,9 +1057,10 @@ registerHandler('remote_access_disable', async () => {
   return remoteAccessService.disable();
 });
 
-registerHandler('remote_access_generate_qr', async () => {
+registerHandler('remote_access_generate_qr', async (params) => {
   await ensureRemoteAccessInitialized();
-  return remoteAccessService.generatePairingQr();
+  const p = (params || {}) as { maxDevices?: number };
+  return remoteAccessService.generatePairingQr(p.maxDevices);
 });
 
 registerHandler('remote_access_list_devices', async () => {
@@ -1116,6 +1214,29 @@ registerHandler('check_skill_eligibility', async (params) => {
   return eligibility;
 });
 
+// Check eligibility for several skills at once
+registerHandler('check_skills_eligibility', async (params) => {
+  const p = params as { skillIds?: string[] };
+  if (!Array.isArray(p.skillIds)) throw new Error('skillIds is required');
+  const results: Record<string, Awaited<ReturnType<typeof checkSkillEligibility>>> = {};
+  const errors: Record<string, string> = {};
+  await Promise.all(
+    p.skillIds.map(async (skillId) => {
+      try {
+        const skill = await skillService.getSkill(skillId);
+        if (!skill) {
+          errors[skillId] = `Skill not found: ${skillId}`;
+          return;
+        }
+        results[skillId] = await checkSkillEligibility(skill);
+      } catch (error) {
+        errors[skillId] = error instanceof Error ? error.message : String(error);
+      }
+    })
+  );
+  return { results, errors };
+});
+
 // Get skill content
 registerHandler('get_skill_content', async (params) => {
   const p = params as { skillId: string };
@@ -1158,8 +1279,9 @@ registerHandler('create_skill', async (params) => {
     content: p.content,
     requirements: p.requirements,
   });
+  const skill = await skillService.getSkill(skillId);
 
-  return { skillId };
+  return { skillId, skillPath: skill?.skillPath };
 });
 
 registerHandler('draft_skill_from_session', async (params) => {
@@ -1631,6 +1753,12 @@ registerHandler('workflow_resume_run', async (params): Promise<WorkflowRun> => {
   return workflowService.resumeRun(runId);
 });
 
+registerHandler('workflow_retry_run', async (params): Promise<WorkflowRun> => {
+  const { runId, fromNodeId } = params as { runId: string; fromNodeId: string };
+  if (!runId || !fromNodeId) throw new Error('runId and fromNodeId are required');
+  return workflowService.retryRun(runId, fromNodeId);
+});
+
 registerHandler('workflow_backfill_schedule', async (params): Promise<{ queued: number }> => {
   const { workflowId, from, to } = params as { workflowId: string; from: number; to: number };
   if (!workflowId) throw new Error('workflowId is required');
@@ -1693,9 +1821,46 @@ registerHandler('heartbeat_wake', async (params): Promise<void> => {
   heartbeatService.wake(mode || 'now');
 });
 
-// Get queued events
-registerHandler('heartbeat_get_events', async (): Promise<SystemEvent[]> => {
-  return heartbeatService.getQueuedEvents();
+// Get heartbeat configuration
+registerHandler('heartbeat_get_config', async (): Promise<HeartbeatConfig> => {
+  await heartbeatService.initialize();
+  return heartbeatService.getConfig();
+});
+
+// Update heartbeat configuration, including event retention limits
+registerHandler('heartbeat_set_config', async (params): Promise<HeartbeatConfig> => {
+  await heartbeatService.initialize();
+  await heartbeatService.configure(params as Partial<HeartbeatConfig>);
+  return heartbeatService.getConfig();
+});
+
+// Queue a system event for the next heartbeat
+registerHandler('heartbeat_queue_event', async (params): Promise<{ eventId: string }> => {
+  const { type, payload, priority, sessionId } = params as {
+    type?: SystemEvent['type'];
+    payload?: SystemEvent['payload'];
+    priority?: SystemEvent['priority'];
+    sessionId?: string;
+  };
+  if (!type) throw new Error('type is required');
+  const eventId = heartbeatService.queueEvent({
+    type,
+    payload,
+    priority: priority || 'normal',
+    sessionId,
+  });
+  return { eventId };
+});
+
+// Get queued events, optionally since a timestamp and capped by limit
+registerHandler('heartbeat_get_events', async (params): Promise<SystemEvent[]> => {
+  const { since, limit } = (params || {}) as { since?: number; limit?: number };
+  return heartbeatService.getQueuedEvents({ since, limit });
+});
+
+// Clear all queued events
+registerHandler('heartbeat_clear_events', async (): Promise<{ count: number }> => {
+  return { count: heartbeatService.clearEvents() };
 });
 
 // ============================================================================
@@ -2479,10 +2644,10 @@ registerHandler('uninstall_connector', async (params) => {
 // Connect to a connector's MCP server
 registerHandler('connect_connector', async (params) => {
   const p = params as { connectorId: string };
-  if (!p.connectorId) throw new Error('connectorId is required');
+  if (!p.connectorId) throw new IPCError('validation', 'connectorId is required');
 
   const connector = await connectorService.getConnector(p.connectorId);
-  if (!connector) throw new Error(`Connector not found: ${p.connectorId}`);
+  if (!connector) throw new IPCError('not_found', `Connector not found: ${p.connectorId}`);
 
   const result = await connectorBridge.connect(connector);
 
@@ -2506,10 +2671,10 @@ registerHandler('disconnect_connector', async (params) => {
 // Reconnect to a connector
 registerHandler('reconnect_connector', async (params) => {
   const p = params as { connectorId: string };
-  if (!p.connectorId) throw new Error('connectorId is required');
+  if (!p.connectorId) throw new IPCError('validation', 'connectorId is required');
 
   const connector = await connectorService.getConnector(p.connectorId);
-  if (!connector) throw new Error(`Connector not found: ${p.connectorId}`);
+  if (!connector) throw new IPCError('not_found', `Connector not found: ${p.connectorId}`);
 
   const result = await connectorBridge.reconnect(connector);
 
@@ -2533,6 +2698,24 @@ registerHandler('configure_connector_secrets', async (params) => {
   return { success: true };
 });
 
+// Fold any leftover plaintext connector secret stores into the vault
+registerHandler('migrate_connector_secrets', async () => {
+  const secretService = await getConnectorSecretService();
+  const migrated = secretService.migrateLegacyPlaintextStores();
+  return { migrated };
+});
+
+// Re-encrypt connector secrets under a new seed. Rust stores the seed only
+// after this succeeds, and calls it again with the old seed to roll back.
+registerHandler('rotate_connector_secrets', async (params) => {
+  const p = params as { seed?: string };
+  const seed = p.seed?.trim();
+  if (!seed) throw new Error('seed is required');
+
+  const secretService = await getConnectorSecretService();
+  return secretService.rotateEncryptionKey(seed);
+});
+
 // Get secrets status for a connector
 registerHandler('get_connector_secrets_status', async (params) => {
   const p = params as { connectorId: string; secretDefs?: SecretDefinition[] };
@@ -2674,10 +2857,17 @@ registerHandler('disconnect_all_connectors', async () => {
 /**
  * Start OAuth flow for a connector.
  * Returns either a browser URL to open (authorization_code flow)
- * or device code info (device_code flow).
+ * or device code info (device_code flow). Authorization-code flows redirect
+ * to the desktop app's loopback server given by `redirectUri` and use its
+ * S256 `codeChallenge`.
  */
 registerHandler('start_connector_oauth_flow', async (params) => {
-  const p = params as { connectorId: string };
+  const p = params as {
+    connectorId: string;
+    redirectUri?: string;
+    state?: string;
+    codeChallenge?: string;
+  };
   if (!p.connectorId) throw new Error('connectorId is required');
 
   const connector = await connectorService.getConnector(p.connectorId);
@@ -2692,12 +2882,41 @@ registerHandler('start_connector_oauth_flow', async (params) => {
     p.connectorId,
     connector.auth.provider,
     connector.auth.flow,
-    connector.auth.scopes
+    connector.auth.scopes,
+    p.redirectUri && p.state && p.codeChallenge
+      ? { redirectUri: p.redirectUri, state: p.state, codeChallenge: p.codeChallenge }
+      : undefined
   );
 
   return result;
 });
 
+/**
+ * Exchange the authorization code captured by the desktop loopback server.
+ */
+registerHandler('complete_connector_oauth_flow', async (params) => {
+  const p = params as {
+    connectorId: string;
+    code: string;
+    state: string;
+    redirectUri: string;
+    codeVerifier: string;
+  };
+  if (!p.connectorId || !p.code || !p.state || !p.redirectUri || !p.codeVerifier) {
+    throw new Error('connectorId, code, state, redirectUri and codeVerifier are required');
+  }
+
+  const oauthService = await getConnectorOAuthService();
+  await oauthService.completeAuthorizationCode(p.connectorId, {
+    code: p.code,
+    state: p.state,
+    redirectUri: p.redirectUri,
+    codeVerifier: p.codeVerifier,
+  });
+
+  return { success: true };
+});
+
 /**
  * Poll for device code completion (Microsoft device_code flow).
  * Returns true if authorized, false if still pending.
@@ -209,3 +209,73 @@ describe('remote-access stop and delete flows', () => {
     expect(status.configHealth).toBe('valid');
   });
 });
+
+describe('remote-access device cap', () => {
+  it('rejects pairing past the cap without consuming the code', async () => {
+    const appDir = await createTempAppDir();
+    const remoteDir = join(appDir, 'remote-access');
+    await mkdir(remoteDir, { recursive: true });
+    await writeFile(
+      join(remoteDir, 'config.json'),
+      JSON.stringify({ ...configWithMode('cloudflare'), maxDevices: 1 }, null, 2),
+      'utf8',
+    );
+
+    const service = new RemoteAccessService();
+    await service.initialize(appDir);
+
+    const internals = service as unknown as {
+      config: RemoteAccessConfig;
+      pairingCodes: Map<string, { code: string; expiresAt: number }>;
+      readJsonBody: (request: unknown) => Promise<Record<string, unknown>>;
+      sendJson: (response: unknown, statusCode: number, payload: unknown) => void;
+      handlePairRequest: (request: unknown, response: unknown) => Promise<void>;
+    };
+    expect(internals.config.maxDevices).toBe(1);
+    expect(service.getStatus().maxDevices).toBe(1);
+
+    internals.config.devices.push({
+      id: 'device_1',
+      name: 'My iPhone',
+      platform: 'ios',
+      tokenHash: 'abc',
+      createdAt: Date.now(),
+      lastUsedAt: Date.now(),
+      expiresAt: Date.now() + 60_000,
+    });
+    internals.pairingCodes.set('code-1', { code: 'code-1', expiresAt: Date.now() + 60_000 });
+
+    const replies: Array<{ statusCode: number; payload: unknown }> = [];
+    vi.spyOn(internals, 'readJsonBody').mockResolvedValue({ pairingCode: 'code-1', deviceName: 'Pixel' });
+    vi.spyOn(internals, 'sendJson').mockImplementation((_response, statusCode, payload) => {
+      replies.push({ statusCode, payload });
+    });
+
+    await internals.handlePairRequest({}, {});
+    expect(replies[0].statusCode).toBe(403);
+    expect(JSON.stringify(replies[0].payload)).toContain('Device limit reached');
+    expect(internals.pairingCodes.has('code-1')).toBe(true);
+    expect(internals.config.devices).toHaveLength(1);
+
+    internals.config.devices[0].revokedAt = Date.now();
+    await internals.handlePairRequest({}, {});
+    expect(replies[1].statusCode).toBe(200);
+    expect(internals.pairingCodes.has('code-1')).toBe(false);
+    expect(service.getStatus().deviceCount).toBe(1);
+  });
+
+  it('falls back to the default cap for out-of-range values', async () => {
+    const appDir = await createTempAppDir();
+    const remoteDir = join(appDir, 'remote-access');
+    await mkdir(remoteDir, { recursive: true });
+    await writeFile(
+      join(remoteDir, 'config.json'),
+      JSON.stringify({ ...configWithMode('cloudflare'), maxDevices: 0 }, null, 2),
+      'utf8',
+    );
+
+    const service = new RemoteAccessService();
+    await service.initialize(appDir);
+    expect(service.getStatus().maxDevices).toBe(10);
+  });
+});
@@ -44,6 +44,8 @@ const INSTALL_TIMEOUT_MS = 10 * 60 * 1000;
 const CLOUDFLARE_START_TIMEOUT_MS = 20_000;
 const TUNNEL_HEALTH_REFRESH_COOLDOWN_MS = 60_000;
 const DIAGNOSTIC_LOG_LIMIT = 50;
+const DEFAULT_MAX_DEVICES = 10;
+const MAX_DEVICES_LIMIT = 100;
 const execFileAsync = promisify(execFile);
 
 interface PairingRecord {
@@ -166,11 +168,18 @@ function defaultConfig(): RemoteAccessConfig {
     tunnelDomain: null,
     tunnelVisibility: 'public',
     devices: [],
+    maxDevices: DEFAULT_MAX_DEVICES,
     createdAt: ts,
     updatedAt: ts,
   };
 }
 
+function normalizeMaxDevices(value: unknown): number {
+  return typeof value === 'number' && Number.isInteger(value) && value >= 1 && value <= MAX_DEVICES_LIMIT
+    ? value
+    : DEFAULT_MAX_DEVICES;
+}
+
 function toDeviceSummary(device: RemoteAccessDevice): RemoteAccessDeviceSummary {
   return {
     id: device.id,
@@ -424,6 +433,7 @@ export class RemoteAccessService {
             revokedAt: typeof device.revokedAt === 'number' ? device.revokedAt : undefined,
           }))
         : [],
+      maxDevices: normalizeMaxDevices(parsed.maxDevices),
       createdAt: typeof parsed.createdAt === 'number' ? parsed.createdAt : fallback.createdAt,
       updatedAt: typeof parsed.updatedAt === 'number' ? parsed.updatedAt : fallback.updatedAt,
     };
@@ -1333,8 +1343,11 @@ export class RemoteAccessService {
     this.pairingCodes.clear();
   }
 
+  private activeDeviceCount(): number {
+    return this.config.devices.filter((device) => !device.revokedAt && device.expiresAt > now()).length;
+  }
+
   getStatus(): RemoteAccessStatus {
-    const activeDevices = this.config.devices.filter((device) => !device.revokedAt && device.expiresAt > now());
     const configuredPublicUrl = this.resolvePublicBaseUrlForMode(
       this.config.tunnelMode,
       this.config.publicBaseUrl,
@@ -1366,7 +1379,8 @@ export class RemoteAccessService {
       lastOperation: this.lastOperation,
       lastOperationAt: this.lastOperationAt,
       diagnostics: this.diagnostics,
-      deviceCount: activeDevices.length,
+      deviceCount: this.activeDeviceCount(),
+      maxDevices: this.config.maxDevices,
       devices: this.listDevices(),
     };
   }
@@ -1462,11 +1476,18 @@ export class RemoteAccessService {
     return hints;
   }
 
-  async generatePairingQr(): Promise<PairingQrResult> {
+  async generatePairingQr(maxDevices?: number): Promise<PairingQrResult> {
     this.ensureInitialized();
     if (!this.config.enabled || !this.server) {
       throw new Error('Remote access must be enabled before generating pairing QR.');
     }
+    if (maxDevices !== undefined && normalizeMaxDevices(maxDevices) !== this.config.maxDevices) {
+      this.config.maxDevices = normalizeMaxDevices(maxDevices);
+      await this.persistConfig();
+    }
+    if (this.activeDeviceCount() >= this.config.maxDevices) {
+      throw new Error(`Device limit reached (${this.config.maxDevices} active devices).`);
+    }
 
     const endpoint =
       this.resolvePublicBaseUrlForMode(this.config.tunnelMode, this.config.publicBaseUrl, this.config.tunnelDomain) ||
@@ -1747,6 +1768,15 @@ export class RemoteAccessService {
       return;
     }
 
+    // Checked before the code is consumed, so it still works once a device
+    // is revoked.
+    if (this.activeDeviceCount() >= this.config.maxDevices) {
+      this.sendJson(response, 403, {
+        error: `Device limit reached (${this.config.maxDevices} active devices). Revoke a device before pairing another.`,
+      });
+      return;
+    }
+
     if (!this.consumePairingCode(pairingCode)) {
       this.sendJson(response, 400, { error: 'Invalid or expired pairing code' });
       return;
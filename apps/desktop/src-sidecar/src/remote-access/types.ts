@@ -38,6 +38,8 @@ export interface RemoteAccessConfig {
   tunnelDomain: string | null;
   tunnelVisibility: RemoteTunnelVisibility;
   devices: RemoteAccessDevice[];
+  /** Cap on active devices, checked when a pairing completes. */
+  maxDevices: number;
   createdAt: number;
   updatedAt: number;
 }
@@ -68,6 +70,7 @@ export interface RemoteAccessStatus {
   lastOperationAt: number | null;
   diagnostics: RemoteDiagnosticEntry[];
   deviceCount: number;
+  maxDevices: number;
   devices: RemoteAccessDeviceSummary[];
 }
 
@@ -32,6 +32,10 @@ function deriveTcpPort(): number {
 }
 
 export function resolveDefaultAppDataDir(): string {
+  const override = process.env.COWORK_DATA_DIR?.trim();
+  if (override) {
+    return override;
+  }
   return join(homedir(), '.cowork');
 }
 
@@ -2,6 +2,7 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 import type { Message, PermissionRequest, PermissionDecision, SessionType } from '@cowork/shared';
+import type { IPCErrorCode } from './ipc-errors.js';
 
 // Re-export types for use in persistence types
 export type { Message, SessionType };
@@ -22,6 +23,7 @@ export interface IPCResponse {
   success: boolean;
   result?: unknown;
   error?: string;
+  errorCode?: IPCErrorCode;
 }
 
 export interface IPCEvent {
@@ -126,10 +128,14 @@ export interface GetSessionParams {
   sessionId: string;
 }
 
+export type SessionSortKey = 'updated_at' | 'created_at' | 'last_accessed_at' | 'title';
+
 export interface ListSessionsPageParams {
   limit?: number;
   offset?: number;
   query?: string;
+  sortBy?: SessionSortKey;
+  order?: 'asc' | 'desc';
 }
 
 export interface GetSessionChunkParams extends GetSessionParams {
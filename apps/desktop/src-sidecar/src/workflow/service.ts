@@ -10,6 +10,7 @@ import type {
   UpdateWorkflowDraftInput,
   WorkflowDefinition,
   WorkflowEvent,
+  WorkflowNodeRun,
   WorkflowRun,
   WorkflowRunInput,
   WorkflowScheduledTaskSummary,
@@ -547,6 +548,70 @@ export class WorkflowService extends EventEmitter {
     return this.runRepository!.getByIdOrThrow(runId);
   }
 
+  /**
+   * Start a new run of a finished run's workflow version at `fromNodeId`.
+   * Upstream nodes that already succeeded are copied into the new run so the
+   * engine reuses their outputs instead of executing them again.
+   */
+  async retryRun(runId: string, fromNodeId: string): Promise<WorkflowRun> {
+    this.ensureInitialized();
+
+    const original = this.runRepository!.getByIdOrThrow(runId);
+    if (original.status === 'queued' || original.status === 'running' || original.status === 'paused') {
+      throw new Error(`Workflow run ${runId} is still ${original.status}; cancel it before retrying`);
+    }
+
+    const definition = this.workflowRepository!.getByVersion(original.workflowId, original.workflowVersion);
+    if (!definition) {
+      throw new Error(`Workflow not found: ${original.workflowId} v${original.workflowVersion}`);
+    }
+    if (!definition.nodes.some((node) => node.id === fromNodeId)) {
+      throw new Error(
+        `Node ${fromNodeId} does not exist in workflow ${original.workflowId} version ${original.workflowVersion}`,
+      );
+    }
+
+    const succeeded = new Map<string, WorkflowNodeRun>();
+    for (const nodeRun of this.runRepository!.getNodeRuns(runId)) {
+      if (nodeRun.status !== 'succeeded' || nodeRun.nodeId === fromNodeId) continue;
+      const existing = succeeded.get(nodeRun.nodeId);
+      if (!existing || existing.attempt <= nodeRun.attempt) {
+        succeeded.set(nodeRun.nodeId, nodeRun);
+      }
+    }
+
+    const run = this.runRepository!.create({
+      workflowId: original.workflowId,
+      workflowVersion: original.workflowVersion,
+      triggerType: 'retry',
+      triggerContext: {
+        ...original.triggerContext,
+        retryOfRunId: runId,
+        fromNodeId,
+      },
+      input: original.input,
+      status: 'queued',
+      correlationId: original.correlationId,
+    });
+    for (const nodeRun of succeeded.values()) {
+      this.runRepository!.createNodeRun({
+        runId: run.id,
+        nodeId: nodeRun.nodeId,
+        attempt: nodeRun.attempt,
+        status: nodeRun.status,
+        input: nodeRun.input,
+        output: nodeRun.output,
+        startedAt: nodeRun.startedAt,
+        completedAt: nodeRun.completedAt,
+        durationMs: nodeRun.durationMs,
+      });
+    }
+    this.runRepository!.updateStatus(run.id, { currentNodeId: fromNodeId });
+
+    void this.executeRunAsync(run.id);
+    return this.runRepository!.getByIdOrThrow(run.id);
+  }
+
   async backfillSchedule(workflowId: string, from: number, to: number): Promise<{ queued: number }> {
     this.ensureInitialized();
 
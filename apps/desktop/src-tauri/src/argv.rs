@@ -0,0 +1,192 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+//! Command lines for the daemon and sidecar. Their arguments are mostly
+//! paths, which may contain spaces, quotes and backslashes
+//! (`C:\Users\Jane Doe`), so every place that flattens argv into a single
+//! string quotes it here.
+
+/// Longest command line `CreateProcess` accepts, in UTF-16 units.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const WINDOWS_MAX_COMMAND_LINE: usize = 32_767;
+
+/// Reject arguments no command line can carry: NUL ends the string early and
+/// line breaks split service files and Scheduled Task commands.
+pub fn validate_arg(value: &str) -> Result<(), String> {
+    if let Some(ch) = value.chars().find(|ch| matches!(ch, '\0' | '\n' | '\r')) {
+        return Err(format!(
+            "Argument {:?} contains an unsupported character ({:?})",
+            value, ch
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_argv(program: &str, args: &[String]) -> Result<(), String> {
+    if program.trim().is_empty() {
+        return Err("Program path is empty".to_string());
+    }
+    validate_arg(program)?;
+    args.iter().try_for_each(|arg| validate_arg(arg))
+}
+
+/// Quote one argument the way the MSVC runtime (`CommandLineToArgvW`) splits
+/// it back: backslashes are literal except before a quote.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn windows_quote_arg(value: &str) -> String {
+    if value.is_empty() {
+        return "\"\"".to_string();
+    }
+
+    let needs_quotes = value
+        .chars()
+        .any(|ch| ch.is_ascii_whitespace() || ch == '"');
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut escaped = String::new();
+    let mut backslashes = 0;
+    for ch in value.chars() {
+        if ch == '\\' {
+            backslashes += 1;
+            continue;
+        }
+
+        if ch == '"' {
+            escaped.push_str(&"\\".repeat(backslashes * 2 + 1));
+            escaped.push('"');
+            backslashes = 0;
+            continue;
+        }
+
+        if backslashes > 0 {
+            escaped.push_str(&"\\".repeat(backslashes));
+            backslashes = 0;
+        }
+        escaped.push(ch);
+    }
+
+    if backslashes > 0 {
+        escaped.push_str(&"\\".repeat(backslashes * 2));
+    }
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn windows_command_line(program: &str, args: &[String]) -> Result<String, String> {
+    validate_argv(program, args)?;
+    let mut parts = Vec::with_capacity(args.len() + 1);
+    parts.push(windows_quote_arg(program));
+    for arg in args {
+        parts.push(windows_quote_arg(arg));
+    }
+    let command_line = parts.join(" ");
+
+    let length = command_line.encode_utf16().count();
+    if length > WINDOWS_MAX_COMMAND_LINE {
+        return Err(format!(
+            "Command line is {} characters; Windows allows at most {}",
+            length, WINDOWS_MAX_COMMAND_LINE
+        ));
+    }
+    Ok(command_line)
+}
+
+/// Quote a value for a systemd unit. `%` and `$` are escaped too, since
+/// systemd would otherwise expand them as specifiers and variables.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn systemd_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_quoting_leaves_plain_args_alone() {
+        assert_eq!(windows_quote_arg("--port"), "--port");
+        assert_eq!(
+            windows_quote_arg(r"C:\cowork\daemon.exe"),
+            r"C:\cowork\daemon.exe"
+        );
+        assert_eq!(windows_quote_arg(""), "\"\"");
+    }
+
+    #[test]
+    fn windows_quoting_handles_spaces() {
+        assert_eq!(
+            windows_quote_arg(r"C:\Users\Jane Doe\AppData"),
+            r#""C:\Users\Jane Doe\AppData""#
+        );
+        assert_eq!(windows_quote_arg("a\tb"), "\"a\tb\"");
+    }
+
+    #[test]
+    fn windows_quoting_escapes_quotes_and_preceding_backslashes() {
+        assert_eq!(windows_quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(windows_quote_arg(r#"a\"b c"#), r#""a\\\"b c""#);
+    }
+
+    #[test]
+    fn windows_quoting_doubles_trailing_backslashes() {
+        assert_eq!(
+            windows_quote_arg(r"C:\Users\Jane Doe\"),
+            r#""C:\Users\Jane Doe\\""#
+        );
+        assert_eq!(windows_quote_arg(r"dir name\\"), r#""dir name\\\\""#);
+    }
+
+    #[test]
+    fn windows_command_line_quotes_each_part() {
+        let args = vec![
+            "--app-data".to_string(),
+            r"C:\Users\Jane Doe\AppData\Roaming\cowork".to_string(),
+            String::new(),
+        ];
+        assert_eq!(
+            windows_command_line(r"C:\Program Files\Cowork\cowork-daemon.exe", &args).unwrap(),
+            r#""C:\Program Files\Cowork\cowork-daemon.exe" --app-data "C:\Users\Jane Doe\AppData\Roaming\cowork" """#
+        );
+    }
+
+    #[test]
+    fn windows_command_line_is_bounded() {
+        let args = vec!["x".repeat(WINDOWS_MAX_COMMAND_LINE)];
+        let error = windows_command_line("daemon.exe", &args).unwrap_err();
+        assert!(error.contains("Windows allows at most 32767"));
+
+        let error = windows_command_line("daemon.exe", &["a\nb".to_string()]).unwrap_err();
+        assert!(error.contains("unsupported character"));
+    }
+
+    #[test]
+    fn argv_rejects_empty_program_and_control_characters() {
+        assert_eq!(
+            validate_argv("  ", &[]).unwrap_err(),
+            "Program path is empty"
+        );
+        assert!(validate_argv("node", &["a\0b".to_string()]).is_err());
+        assert!(validate_argv("node\r", &[]).is_err());
+        assert!(validate_argv("/Users/Jane Doe/cowork", &[r#"it's "quoted""#.to_string()]).is_ok());
+    }
+
+    #[test]
+    fn systemd_quoting_escapes_specifiers_and_variables() {
+        assert_eq!(
+            systemd_quote("/home/Jane Doe/.local/share/cowork"),
+            "\"/home/Jane Doe/.local/share/cowork\""
+        );
+        assert_eq!(
+            systemd_quote(r#"a\b "c" 100% $HOME"#),
+            r#""a\\b \"c\" 100%% $$HOME""#
+        );
+    }
+}
@@ -1,8 +1,14 @@
 // Copyright (c) 2026 Naresh. All rights reserved.
 // Licensed under the MIT License. See LICENSE file for details.
 
-use crate::sidecar::{SidecarEvent, SidecarManager};
+use crate::commands::auth::ModelCheck;
+use crate::commands::{files, memory_watch};
+use crate::paths;
+use crate::sidecar::{SidecarEvent, SidecarInstallDiagnostics, SidecarManager};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::sync::Arc;
 use tauri::async_runtime::Mutex;
 use tauri::{AppHandle, Emitter, State};
@@ -29,6 +35,12 @@ pub struct SessionInfo {
     pub last_accessed_at: i64,
     #[serde(default)]
     pub message_count: u32,
+    /// Catalog entry for `model` when it is a known model of `provider`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_info: Option<crate::commands::auth::ModelInfo>,
+    /// Set when `model` is not among the provider's known models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_warning: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,6 +121,50 @@ pub struct SessionListPage {
     pub next_offset: Option<usize>,
 }
 
+/// `agent_list_sessions` result: the plain list unless paging was requested.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum SessionListResult {
+    All(Vec<SessionSummary>),
+    Page(SessionListPage),
+}
+
+/// One entry of a bulk session fetch; exactly one of `session` or `error` is set.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBulkEntry {
+    pub session_id: String,
+    pub session: Option<SessionSummary>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchFilters {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub execution_mode: Option<String>,
+    #[serde(default)]
+    pub updated_after: Option<i64>,
+    #[serde(default)]
+    pub updated_before: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchResult {
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub snippet: Option<String>,
+    #[serde(default)]
+    pub matched_field: Option<String>,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchSessionResult {
@@ -155,6 +211,115 @@ pub struct ActiveBranchSelectionResult {
     pub active_branch_id: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExportResult {
+    pub session_id: String,
+    pub format: String,
+    pub path: String,
+    pub bytes_written: u64,
+    pub item_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionImportResult {
+    pub session_id: String,
+    pub original_session_id: String,
+    pub remapped: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Returned instead of sending when a message would overflow the context window.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudgetWarning {
+    pub session_id: String,
+    pub used_tokens: i64,
+    pub estimated_message_tokens: i64,
+    pub projected_tokens: i64,
+    pub max_tokens: i64,
+    pub suggested_action: String,
+}
+
+/// A permission request still waiting for the user's decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPermission {
+    pub id: String,
+    #[serde(rename = "type", default)]
+    pub permission_type: String,
+    #[serde(default)]
+    pub resource: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    #[serde(default)]
+    pub risk_level: Option<String>,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingQuestionOption {
+    pub label: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// A question from the agent still waiting for an answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingQuestion {
+    pub id: String,
+    pub question: String,
+    #[serde(default)]
+    pub options: Option<Vec<PendingQuestionOption>>,
+    #[serde(default)]
+    pub multi_select: Option<bool>,
+    #[serde(default)]
+    pub header: Option<String>,
+    #[serde(default)]
+    pub allow_custom: Option<bool>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+/// Schema version stamped on JSON session exports.
+const SESSION_EXPORT_SCHEMA_VERSION: u32 = 1;
+const SESSION_REPLAY_BATCH_SIZE: usize = 100;
+const SESSION_REPLAY_DEFAULT_MAX_ITEMS: usize = 5000;
+const SESSION_EXPORT_DOCUMENT_FIELDS: [&str; 3] = ["schemaVersion", "exportedAt", "session"];
+const SESSION_DETAILS_FIELDS: [&str; 21] = [
+    "id",
+    "type",
+    "provider",
+    "executionMode",
+    "title",
+    "firstMessage",
+    "workingDirectory",
+    "model",
+    "messageCount",
+    "lastAccessedAt",
+    "messages",
+    "chatItems",
+    "tasks",
+    "artifacts",
+    "toolExecutions",
+    "contextUsage",
+    "hasMoreHistory",
+    "oldestLoadedSequence",
+    "runtime",
+    "createdAt",
+    "updatedAt",
+];
+
 fn default_provider() -> String {
     "google".to_string()
 }
@@ -163,11 +328,43 @@ fn default_execution_mode() -> String {
     "execute".to_string()
 }
 
+/// Approval modes understood by the sidecar (`ApprovalMode` in types.ts).
+const APPROVAL_MODES: [&str; 3] = ["auto", "read_only", "full"];
+/// Execution modes understood by the sidecar (`ExecutionMode` in types.ts).
+const EXECUTION_MODES: [&str; 2] = ["execute", "plan"];
+const COMPACTION_STRATEGIES: [&str; 3] = ["summarize", "truncate", "drop-tools"];
+/// How long a create-session client token keeps returning the same session.
+const SESSION_CLIENT_TOKEN_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+fn validate_mode(kind: &str, mode: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed.contains(&mode) {
+        return Ok(());
+    }
+    Err(format!(
+        "Unknown {} mode `{}`. Expected one of: {}",
+        kind,
+        mode,
+        allowed.join(", ")
+    ))
+}
+
+fn validate_approval_mode(mode: &str) -> Result<(), String> {
+    validate_mode("approval", mode, &APPROVAL_MODES)
+}
+
+fn validate_execution_mode(mode: &str) -> Result<(), String> {
+    validate_mode("execution", mode, &EXECUTION_MODES)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContextUsage {
+    // `get_context_usage` reports `used`/`total`/`percentage`.
+    #[serde(alias = "used")]
     pub used_tokens: i64,
+    #[serde(alias = "total")]
     pub max_tokens: i64,
+    #[serde(default, alias = "percentage")]
     pub percent_used: f64,
     #[serde(default)]
     pub last_updated: Option<i64>,
@@ -274,6 +471,185 @@ pub struct CommandSandboxSettingsPayload {
     pub max_output_bytes: i64,
 }
 
+const ATTACHMENT_MAX_BYTES_ENV_VAR: &str = "COWORK_ATTACHMENT_MAX_BYTES";
+const ATTACHMENT_TOTAL_MAX_BYTES_ENV_VAR: &str = "COWORK_ATTACHMENT_TOTAL_MAX_BYTES";
+const DEFAULT_ATTACHMENT_MAX_BYTES: u64 = 25 * 1024 * 1024;
+const DEFAULT_ATTACHMENT_TOTAL_MAX_BYTES: u64 = 50 * 1024 * 1024;
+
+fn env_u64(key: &str, default_value: u64) -> u64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(default_value)
+}
+
+/// Measure one attachment, checking that inline data decodes and that a
+/// referenced file exists and can be opened.
+fn attachment_size(attachment: &Attachment) -> Result<u64, String> {
+    if let Some(data) = attachment.data.as_deref() {
+        // Text attachments are sent as plain text rather than base64.
+        if attachment.attachment_type == "text" {
+            return Ok(data.len() as u64);
+        }
+        let decoded = BASE64_STANDARD.decode(data.trim().as_bytes()).map_err(|e| {
+            format!(
+                "Attachment `{}` has inline data that is not valid base64: {}",
+                attachment.name, e
+            )
+        })?;
+        return Ok(decoded.len() as u64);
+    }
+
+    if let Some(path) = attachment.path.as_deref() {
+        let metadata = std::fs::metadata(path).map_err(|e| {
+            format!("Attachment `{}` path {} is not accessible: {}", attachment.name, path, e)
+        })?;
+        if !metadata.is_file() {
+            return Err(format!(
+                "Attachment `{}` path {} is not a file",
+                attachment.name, path
+            ));
+        }
+        std::fs::File::open(path).map_err(|e| {
+            format!("Attachment `{}` path {} is not readable: {}", attachment.name, path, e)
+        })?;
+        return Ok(metadata.len());
+    }
+
+    Ok(attachment.size.unwrap_or(0))
+}
+
+fn validate_attachments_with_limits(
+    attachments: &[Attachment],
+    max_bytes: u64,
+    total_max_bytes: u64,
+) -> Result<(), String> {
+    let mut total: u64 = 0;
+    for attachment in attachments {
+        // Reject obviously oversized inline payloads before decoding them.
+        if let Some(data) = attachment.data.as_deref() {
+            let estimated = (data.len() as u64 / 4) * 3;
+            if estimated > max_bytes.saturating_add(3) {
+                return Err(format!(
+                    "Attachment `{}` is about {} bytes, over the per-attachment limit of {} bytes",
+                    attachment.name, estimated, max_bytes
+                ));
+            }
+        }
+
+        let size = attachment_size(attachment)?;
+        if size > max_bytes {
+            return Err(format!(
+                "Attachment `{}` is {} bytes, over the per-attachment limit of {} bytes",
+                attachment.name, size, max_bytes
+            ));
+        }
+
+        total = total.saturating_add(size);
+        if total > total_max_bytes {
+            return Err(format!(
+                "Attachment `{}` brings the total to {} bytes, over the limit of {} bytes per message",
+                attachment.name, total, total_max_bytes
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_attachments(attachments: Option<&Vec<Attachment>>) -> Result<(), String> {
+    match attachments {
+        Some(attachments) => validate_attachments_with_limits(
+            attachments,
+            env_u64(ATTACHMENT_MAX_BYTES_ENV_VAR, DEFAULT_ATTACHMENT_MAX_BYTES),
+            env_u64(
+                ATTACHMENT_TOTAL_MAX_BYTES_ENV_VAR,
+                DEFAULT_ATTACHMENT_TOTAL_MAX_BYTES,
+            ),
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Rough character-based estimate, matching the sidecar's ~4 chars per token.
+const CHARS_PER_TOKEN: usize = 4;
+const IMAGE_ATTACHMENT_TOKENS: i64 = 500;
+
+fn estimate_message_tokens(content: &str, attachments: Option<&Vec<Attachment>>) -> i64 {
+    let mut chars = content.chars().count();
+    let mut tokens: i64 = 0;
+    for attachment in attachments.into_iter().flatten() {
+        match attachment.attachment_type.as_str() {
+            "image" => tokens += IMAGE_ATTACHMENT_TOKENS,
+            "text" => chars += attachment.data.as_deref().map_or(0, |d| d.chars().count()),
+            _ => {}
+        }
+    }
+    tokens + chars.div_ceil(CHARS_PER_TOKEN) as i64
+}
+
+/// The warning to return instead of sending when the message would not fit
+/// in what is left of the context window.
+fn budget_warning(
+    session_id: &str,
+    usage: &ContextUsage,
+    estimated_message_tokens: i64,
+) -> Option<ContextBudgetWarning> {
+    if usage.max_tokens <= 0 {
+        return None;
+    }
+    let projected_tokens = usage.used_tokens.saturating_add(estimated_message_tokens);
+    (projected_tokens > usage.max_tokens).then(|| ContextBudgetWarning {
+        session_id: session_id.to_string(),
+        used_tokens: usage.used_tokens,
+        estimated_message_tokens,
+        projected_tokens,
+        max_tokens: usage.max_tokens,
+        suggested_action: "compact".to_string(),
+    })
+}
+
+async fn context_budget_warning(
+    state: &AgentState,
+    session_id: &str,
+    content: &str,
+    attachments: Option<&Vec<Attachment>>,
+) -> Result<Option<ContextBudgetWarning>, String> {
+    let usage = state
+        .manager
+        .send_command(
+            "get_context_usage",
+            serde_json::json!({ "sessionId": session_id }),
+        )
+        .await?;
+    let usage: ContextUsage = match serde_json::from_value(usage) {
+        Ok(usage) => usage,
+        Err(error) => {
+            eprintln!(
+                "[agent] Context usage response was not understood ({}); skipping budget check",
+                error
+            );
+            return Ok(None);
+        }
+    };
+    let estimated = estimate_message_tokens(content, attachments);
+    Ok(budget_warning(session_id, &usage, estimated))
+}
+
+/// Returned in place of the command result when a send is held back by the
+/// context budget check.
+fn context_budget_response(warning: ContextBudgetWarning) -> serde_json::Value {
+    serde_json::json!({ "contextBudgetWarning": warning })
+}
+
+#[derive(Deserialize)]
+struct QueuedMessage {
+    id: String,
+    content: String,
+    #[serde(default)]
+    attachments: Option<Vec<Attachment>>,
+}
+
 fn default_allow_process_spawn() -> bool {
     true
 }
@@ -292,11 +668,24 @@ pub struct AgentState {
     pub manager: Arc<SidecarManager>,
     bootstrap_state: Arc<Mutex<SidecarBootstrapState>>,
     bootstrap_lock: Arc<Mutex<()>>,
+    /// Source session ids whose in-flight duplication should stop.
+    duplicate_cancellations: Arc<Mutex<HashSet<String>>>,
+    /// Client token -> session created for it, so repeated submits reuse it.
+    session_client_tokens: Arc<Mutex<HashMap<String, SessionTokenSlot>>>,
 }
 
+/// Created-at time and the session (once created) for a client token. The
+/// inner lock makes a concurrent duplicate wait for the first attempt.
+type SessionTokenSlot = (std::time::Instant, Arc<Mutex<Option<SessionInfo>>>);
+
 struct SidecarBootstrapState {
     initialized: bool,
     initialized_app_data_dir: Option<String>,
+    /// Transport generation that was initialized; a respawned sidecar has a
+    /// newer one and must be initialized again.
+    initialized_generation: u64,
+    /// Error from the most recent `initialize` attempt, cleared on success.
+    last_init_error: Option<String>,
 }
 
 impl AgentState {
@@ -306,8 +695,12 @@ impl AgentState {
             bootstrap_state: Arc::new(Mutex::new(SidecarBootstrapState {
                 initialized: false,
                 initialized_app_data_dir: None,
+                initialized_generation: 0,
+                last_init_error: None,
             })),
             bootstrap_lock: Arc::new(Mutex::new(())),
+            duplicate_cancellations: Arc::new(Mutex::new(HashSet::new())),
+            session_client_tokens: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -319,13 +712,11 @@ impl Default for AgentState {
 }
 
 fn resolve_app_data_dir() -> Result<String, String> {
-    // Use home directory for persistence: ~/.cowork
-    // This provides consistent, user-accessible storage across platforms.
-    let home_dir = dirs::home_dir().ok_or("Failed to get home directory")?;
-    let cowork_dir = home_dir.join(".cowork");
+    // ~/.cowork by default, or COWORK_DATA_DIR when set.
+    let cowork_dir = paths::app_data_dir()?;
 
     std::fs::create_dir_all(&cowork_dir)
-        .map_err(|e| format!("Failed to create .cowork directory: {}", e))?;
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
 
     cowork_dir
         .to_str()
@@ -333,6 +724,14 @@ fn resolve_app_data_dir() -> Result<String, String> {
         .map(|value| value.to_string())
 }
 
+/// Where the app looked for the packaged sidecar binary, for "Unable to
+/// locate packaged binary" reports.
+#[tauri::command]
+pub async fn diagnose_sidecar_install() -> Result<SidecarInstallDiagnostics, String> {
+    let app_data_str = resolve_app_data_dir()?;
+    Ok(crate::sidecar::diagnose_sidecar_install(&app_data_str))
+}
+
 /// Ensure sidecar is started and set up event forwarding (public for use by other command modules)
 pub async fn ensure_sidecar_started_public(
     app: &AppHandle,
@@ -374,24 +773,37 @@ pub async fn ensure_sidecar_started(
         let bootstrap_state = state.bootstrap_state.lock().await;
         if !bootstrap_state.initialized
             || bootstrap_state.initialized_app_data_dir.as_deref() != Some(app_data_str.as_str())
+            || bootstrap_state.initialized_generation != manager.transport_generation()
         {
             should_initialize = true;
         }
     }
 
     if should_initialize {
+        manager.refresh_capabilities().await;
         let init_params = serde_json::json!({
             "appDataDir": app_data_str.clone()
         });
         if let Err(error) = manager.send_command("initialize", init_params).await {
-            let mut bootstrap_state = state.bootstrap_state.lock().await;
-            bootstrap_state.initialized = false;
-            return Err(format!("Failed to initialize sidecar services: {}", error));
+            let error = format!("Failed to initialize sidecar services: {}", error);
+            log::error!("{}", error);
+            {
+                let mut bootstrap_state = state.bootstrap_state.lock().await;
+                bootstrap_state.initialized = false;
+                bootstrap_state.last_init_error = Some(error.clone());
+            }
+            let _ = app.emit(
+                "agent:initialization_failed",
+                serde_json::json!({ "error": error }),
+            );
+            return Err(error);
         }
 
         let mut bootstrap_state = state.bootstrap_state.lock().await;
         bootstrap_state.initialized = true;
         bootstrap_state.initialized_app_data_dir = Some(app_data_str.clone());
+        bootstrap_state.initialized_generation = manager.transport_generation();
+        bootstrap_state.last_init_error = None;
     }
 
     // Always ensure API key is set on sidecar (handles startup race conditions
@@ -439,7 +851,9 @@ pub async fn agent_set_runtime_config(
         "config": config,
     });
 
-    manager.send_command("set_runtime_config", params).await
+    let result = manager.send_command("set_runtime_config", params).await?;
+    files::record_sandbox_paths(config.sandbox.as_ref());
+    Ok(result)
 }
 
 #[tauri::command]
@@ -451,14 +865,22 @@ pub async fn agent_get_capability_snapshot(
     ensure_sidecar_started(&app, &state).await?;
 
     let manager = &state.manager;
-    manager
+    let mut snapshot = manager
         .send_command(
             "get_capability_snapshot",
             serde_json::json!({
                 "sessionId": session_id,
             }),
         )
-        .await
+        .await?;
+    if let Some(map) = snapshot.as_object_mut() {
+        let capabilities = manager.capabilities().await;
+        map.insert(
+            "daemonCapabilities".to_string(),
+            serde_json::to_value(capabilities).unwrap_or_default(),
+        );
+    }
+    Ok(snapshot)
 }
 
 #[tauri::command]
@@ -496,7 +918,8 @@ pub async fn agent_set_stitch_api_key(
     Ok(())
 }
 
-/// Create a new session
+/// Create a new session. Calls repeating a `client_token` within a short
+/// window return the session created for it instead of a duplicate.
 #[tauri::command]
 pub async fn agent_create_session(
     app: AppHandle,
@@ -505,8 +928,75 @@ pub async fn agent_create_session(
     model: Option<String>,
     provider: Option<String>,
     execution_mode: Option<String>,
+    client_token: Option<String>,
 ) -> Result<SessionInfo, String> {
-    ensure_sidecar_started(&app, &state).await?;
+    if let Some(mode) = execution_mode.as_deref() {
+        validate_execution_mode(mode)?;
+    }
+
+    let Some(client_token) = client_token.filter(|token| !token.trim().is_empty()) else {
+        return create_session(
+            &app,
+            &state,
+            working_directory,
+            model,
+            provider,
+            execution_mode,
+        )
+        .await;
+    };
+
+    create_session_once(
+        &state.session_client_tokens,
+        client_token,
+        create_session(
+            &app,
+            &state,
+            working_directory,
+            model,
+            provider,
+            execution_mode,
+        ),
+    )
+    .await
+}
+
+/// Run `create` unless `client_token` already produced a session within
+/// `SESSION_CLIENT_TOKEN_TTL`, in which case that session is returned.
+async fn create_session_once(
+    tokens: &Mutex<HashMap<String, SessionTokenSlot>>,
+    client_token: String,
+    create: impl std::future::Future<Output = Result<SessionInfo, String>>,
+) -> Result<SessionInfo, String> {
+    let slot = {
+        let mut tokens = tokens.lock().await;
+        tokens.retain(|_, (created_at, _)| created_at.elapsed() < SESSION_CLIENT_TOKEN_TTL);
+        tokens
+            .entry(client_token)
+            .or_insert_with(|| (std::time::Instant::now(), Arc::new(Mutex::new(None))))
+            .1
+            .clone()
+    };
+
+    let mut created = slot.lock().await;
+    if let Some(session) = created.as_ref() {
+        return Ok(session.clone());
+    }
+    // A failed attempt leaves the slot empty so a retry can create it.
+    let session = create.await?;
+    *created = Some(session.clone());
+    Ok(session)
+}
+
+async fn create_session(
+    app: &AppHandle,
+    state: &State<'_, AgentState>,
+    working_directory: String,
+    model: Option<String>,
+    provider: Option<String>,
+    execution_mode: Option<String>,
+) -> Result<SessionInfo, String> {
+    ensure_sidecar_started(app, state).await?;
 
     let manager = &state.manager;
     let params = serde_json::json!({
@@ -517,7 +1007,102 @@ pub async fn agent_create_session(
     });
 
     let result = manager.send_command("create_session", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse session info: {}", e))
+    let mut session: SessionInfo = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse session info: {}", e))?;
+    memory_watch::watch_session(app, &session.id, &session.working_directory);
+    annotate_session_model(&mut session);
+    Ok(session)
+}
+
+/// Checks the resolved model against the provider's known models. Unknown
+/// ids only produce a warning so newly released models keep working.
+fn annotate_session_model(session: &mut SessionInfo) {
+    match crate::commands::auth::check_provider_model(&session.provider, &session.model) {
+        ModelCheck::Known(model) => session.model_info = Some(model),
+        ModelCheck::Unknown => {
+            let warning = format!(
+                "Model `{}` is not a known {} model; check the id if the first message fails",
+                session.model, session.provider
+            );
+            log::warn!("Session {}: {}", session.id, warning);
+            session.model_warning = Some(warning);
+        }
+        ModelCheck::Unchecked => {}
+    }
+}
+
+/// Whether the agent has work in flight, overall or for one session.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentBusy {
+    pub busy: bool,
+    pub session_id: Option<String>,
+    pub active_sessions: Vec<String>,
+    pub pending_requests: usize,
+    pub reason: Option<String>,
+}
+
+/// Busy state from generations in flight and unanswered sidecar requests.
+/// Scoped to a session, only that session's generation counts, since
+/// pending requests are not attributed to sessions.
+pub(crate) async fn agent_busy_state(
+    manager: &SidecarManager,
+    session_id: Option<&str>,
+) -> AgentBusy {
+    let activity = manager.activity().await;
+    let reason = match session_id {
+        Some(id) if activity.active_sessions.iter().any(|s| s == id) => {
+            Some(format!("Session {} is still generating a response", id))
+        }
+        Some(_) => None,
+        None if !activity.active_sessions.is_empty() => Some(format!(
+            "{} session(s) are still generating a response",
+            activity.active_sessions.len()
+        )),
+        None if activity.pending_requests > 0 => Some(format!(
+            "{} agent request(s) are still in flight",
+            activity.pending_requests
+        )),
+        None => None,
+    };
+    AgentBusy {
+        busy: reason.is_some(),
+        session_id: session_id.map(|id| id.to_string()),
+        active_sessions: activity.active_sessions,
+        pending_requests: activity.pending_requests,
+        reason,
+    }
+}
+
+/// Refuse a destructive `action` while the agent is busy unless `force` is
+/// set, in which case it goes ahead with a warning in the log.
+pub(crate) async fn ensure_agent_idle(
+    manager: &SidecarManager,
+    session_id: Option<&str>,
+    action: &str,
+    force: bool,
+) -> Result<(), String> {
+    let state = agent_busy_state(manager, session_id).await;
+    let Some(reason) = state.reason else {
+        return Ok(());
+    };
+    if force {
+        log::warn!("{} while busy (forced): {}", action, reason);
+        return Ok(());
+    }
+    Err(format!(
+        "Cannot {} right now: {}. Stop it first or pass force.",
+        action, reason
+    ))
+}
+
+/// Report whether the agent, or one session, is busy
+#[tauri::command]
+pub async fn agent_is_busy(
+    state: State<'_, AgentState>,
+    session_id: Option<String>,
+) -> Result<AgentBusy, String> {
+    Ok(agent_busy_state(&state.manager, session_id.as_deref()).await)
 }
 
 /// Send a message in a session
@@ -528,9 +1113,21 @@ pub async fn agent_send_message(
     session_id: String,
     content: String,
     attachments: Option<Vec<Attachment>>,
-) -> Result<(), String> {
+    check_context_budget: Option<bool>,
+) -> Result<Option<ContextBudgetWarning>, String> {
+    validate_attachments(attachments.as_ref())?;
     ensure_sidecar_started(&app, &state).await?;
 
+    // When requested, refuse to send a message that would overflow the
+    // context window and let the caller offer to compact instead.
+    if check_context_budget.unwrap_or(false) {
+        if let Some(warning) =
+            context_budget_warning(&state, &session_id, &content, attachments.as_ref()).await?
+        {
+            return Ok(Some(warning));
+        }
+    }
+
     let manager = &state.manager;
     let params = serde_json::json!({
         "sessionId": session_id,
@@ -538,11 +1135,17 @@ pub async fn agent_send_message(
         "attachments": attachments,
     });
 
-    manager.send_command("send_message", params).await?;
-    Ok(())
+    manager.mark_generation_started(&session_id).await;
+    if let Err(err) = manager.send_command("send_message", params).await {
+        manager.mark_generation_stopped(&session_id).await;
+        return Err(err);
+    }
+    Ok(None)
 }
 
-/// Send a message through the v2 run pipeline with run options.
+/// Send a message through the v2 run pipeline with run options. With
+/// `check_context_budget`, a message that would overflow the context window
+/// is not sent and `{ contextBudgetWarning }` is returned instead.
 #[tauri::command]
 pub async fn agent_send_message_v2(
     app: AppHandle,
@@ -551,9 +1154,19 @@ pub async fn agent_send_message_v2(
     message: String,
     run_options: Option<serde_json::Value>,
     attachments: Option<Vec<Attachment>>,
+    check_context_budget: Option<bool>,
 ) -> Result<serde_json::Value, String> {
+    validate_attachments(attachments.as_ref())?;
     ensure_sidecar_started(&app, &state).await?;
 
+    if check_context_budget.unwrap_or(false) {
+        if let Some(warning) =
+            context_budget_warning(&state, &session_id, &message, attachments.as_ref()).await?
+        {
+            return Ok(context_budget_response(warning));
+        }
+    }
+
     let manager = &state.manager;
     let params = serde_json::json!({
         "sessionId": session_id,
@@ -749,6 +1362,7 @@ pub async fn agent_set_approval_mode(
     session_id: String,
     mode: String,
 ) -> Result<(), String> {
+    validate_approval_mode(&mode)?;
     ensure_sidecar_started(&app, &state).await?;
 
     let manager = &state.manager;
@@ -769,6 +1383,7 @@ pub async fn agent_set_execution_mode(
     session_id: String,
     mode: String,
 ) -> Result<(), String> {
+    validate_execution_mode(&mode)?;
     ensure_sidecar_started(&app, &state).await?;
 
     let manager = &state.manager;
@@ -814,6 +1429,7 @@ pub async fn agent_stop_generation(
     });
 
     manager.send_command("stop_generation", params).await?;
+    manager.mark_generation_stopped(&session_id).await;
     Ok(())
 }
 
@@ -872,17 +1488,40 @@ pub async fn agent_reorder_queue(
     manager.send_command("reorder_queue", params).await
 }
 
-/// Send a queued message immediately
+/// Send a queued message immediately. `check_context_budget` works as for
+/// `agent_send_message_v2`.
 #[tauri::command]
 pub async fn agent_send_queued_immediately(
     app: AppHandle,
     state: State<'_, AgentState>,
     session_id: String,
     message_id: String,
+    check_context_budget: Option<bool>,
 ) -> Result<serde_json::Value, String> {
     ensure_sidecar_started(&app, &state).await?;
 
     let manager = &state.manager;
+    if check_context_budget.unwrap_or(false) {
+        let queue = manager
+            .send_command("get_queue", serde_json::json!({ "sessionId": session_id }))
+            .await?;
+        let queued = serde_json::from_value::<Vec<QueuedMessage>>(queue)
+            .ok()
+            .and_then(|queue| queue.into_iter().find(|message| message.id == message_id));
+        if let Some(queued) = queued {
+            let warning = context_budget_warning(
+                &state,
+                &session_id,
+                &queued.content,
+                queued.attachments.as_ref(),
+            )
+            .await?;
+            if let Some(warning) = warning {
+                return Ok(context_budget_response(warning));
+            }
+        }
+    }
+
     let params = serde_json::json!({
         "sessionId": session_id,
         "messageId": message_id,
@@ -912,19 +1551,298 @@ pub async fn agent_edit_queued_message(
     manager.send_command("edit_queued_message", params).await
 }
 
-/// List all sessions
+const SESSION_SORT_KEYS: [&str; 4] = ["updated_at", "created_at", "last_accessed_at", "title"];
+
+/// Resolve `sort_by` (default `updated_at`) and `order` (default `desc`)
+/// into a sort key and whether it is descending.
+fn session_sort_options<'a>(
+    sort_by: Option<&'a str>,
+    order: Option<&str>,
+) -> Result<(&'a str, bool), String> {
+    let sort_by = sort_by.unwrap_or("updated_at");
+    if !SESSION_SORT_KEYS.contains(&sort_by) {
+        return Err(format!(
+            "Invalid sort key: {}. Expected one of: {}",
+            sort_by,
+            SESSION_SORT_KEYS.join(", ")
+        ));
+    }
+    let descending = match order.unwrap_or("desc") {
+        "desc" => true,
+        "asc" => false,
+        other => {
+            return Err(format!(
+                "Invalid sort order: {}. Expected one of: asc, desc",
+                other
+            ))
+        }
+    };
+    Ok((sort_by, descending))
+}
+
+/// Sort sessions by `sort_by` in `order`. Ties fall back to the session id so
+/// the order never depends on what the sidecar returned.
+fn sort_sessions(
+    sessions: &mut [SessionSummary],
+    sort_by: Option<&str>,
+    order: Option<&str>,
+) -> Result<(), String> {
+    let (sort_by, descending) = session_sort_options(sort_by, order)?;
+    sessions.sort_by(|a, b| {
+        let primary = match sort_by {
+            "created_at" => a.created_at.cmp(&b.created_at),
+            "last_accessed_at" => a.last_accessed_at.cmp(&b.last_accessed_at),
+            "title" => {
+                let title = |s: &SessionSummary| s.title.as_deref().unwrap_or("").to_lowercase();
+                title(a).cmp(&title(b))
+            }
+            _ => a.updated_at.cmp(&b.updated_at),
+        };
+        let primary = if descending {
+            primary.reverse()
+        } else {
+            primary
+        };
+        primary.then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(())
+}
+
+/// List sessions. Passing `limit` or `offset` returns a page with a total
+/// count instead of the full list.
 #[tauri::command]
 pub async fn agent_list_sessions(
     app: AppHandle,
     state: State<'_, AgentState>,
-) -> Result<Vec<SessionSummary>, String> {
+    sort_by: Option<String>,
+    order: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<SessionListResult, String> {
+    let (sort_key, descending) = session_sort_options(sort_by.as_deref(), order.as_deref())?;
     ensure_sidecar_started(&app, &state).await?;
     let manager = &state.manager;
+
+    if limit.is_some() || offset.is_some() {
+        let result = manager
+            .send_command(
+                "list_sessions_page",
+                serde_json::json!({
+                    "limit": limit,
+                    "offset": offset,
+                    "sortBy": sort_key,
+                    "order": if descending { "desc" } else { "asc" },
+                }),
+            )
+            .await?;
+        let mut page: SessionListPage = serde_json::from_value(result)
+            .map_err(|e| format!("Failed to parse sessions page: {}", e))?;
+        // Daemons that predate sortBy/order still page in their own order, so
+        // keep at least each page in the requested order.
+        sort_sessions(&mut page.sessions, sort_by.as_deref(), order.as_deref())?;
+        return Ok(SessionListResult::Page(page));
+    }
+
     let result = manager
         .send_command("list_sessions", serde_json::json!({}))
         .await?;
+    let mut sessions: Vec<SessionSummary> =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse sessions: {}", e))?;
+    sort_sessions(&mut sessions, sort_by.as_deref(), order.as_deref())?;
+    Ok(SessionListResult::All(sessions))
+}
+
+const SESSION_SEARCH_SNIPPET_CONTEXT: usize = 40;
+
+/// Case-insensitive match of `query` in `text`, returning a snippet around it.
+fn session_search_snippet(text: &str, query: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let lowered: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let needle: Vec<char> = query.chars().flat_map(|c| c.to_lowercase()).collect();
+    if needle.is_empty() || needle.len() > lowered.len() {
+        return None;
+    }
+
+    let start = lowered.windows(needle.len()).position(|w| w == needle.as_slice())?;
+    let from = start.saturating_sub(SESSION_SEARCH_SNIPPET_CONTEXT);
+    let to = (start + needle.len() + SESSION_SEARCH_SNIPPET_CONTEXT).min(chars.len());
+    let mut snippet: String = chars[from..to].iter().collect();
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+fn session_matches_filters(session: &SessionSummary, filters: &SessionSearchFilters) -> bool {
+    if let Some(provider) = filters.provider.as_deref() {
+        if !session.provider.eq_ignore_ascii_case(provider) {
+            return false;
+        }
+    }
+    if let Some(mode) = filters.execution_mode.as_deref() {
+        if !session.execution_mode.eq_ignore_ascii_case(mode) {
+            return false;
+        }
+    }
+    if filters.updated_after.is_some_and(|after| session.updated_at < after) {
+        return false;
+    }
+    if filters.updated_before.is_some_and(|before| session.updated_at > before) {
+        return false;
+    }
+    true
+}
+
+/// Local search over session titles and first messages, used when the
+/// sidecar does not support `search_sessions`.
+fn search_session_summaries(
+    sessions: Vec<SessionSummary>,
+    query: &str,
+    filters: &SessionSearchFilters,
+) -> Vec<SessionSearchResult> {
+    let mut results: Vec<SessionSearchResult> = sessions
+        .into_iter()
+        .filter(|session| session_matches_filters(session, filters))
+        .filter_map(|session| {
+            let (matched_field, snippet) = [
+                ("title", session.title.as_deref()),
+                ("firstMessage", session.first_message.as_deref()),
+            ]
+            .into_iter()
+            .find_map(|(field, text)| {
+                session_search_snippet(text?, query).map(|snippet| (field, snippet))
+            })?;
+            Some(SessionSearchResult {
+                id: session.id,
+                title: session.title,
+                snippet: Some(snippet),
+                matched_field: Some(matched_field.to_string()),
+                updated_at: session.updated_at,
+            })
+        })
+        .collect();
+    results.sort_by_key(|result| std::cmp::Reverse(result.updated_at));
+    results
+}
+
+/// Search sessions by title and content, falling back to a local title and
+/// first-message scan when the sidecar lacks the command.
+#[tauri::command]
+pub async fn agent_search_sessions(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    query: String,
+    filters: Option<SessionSearchFilters>,
+) -> Result<Vec<SessionSearchResult>, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err("Search query must not be empty".to_string());
+    }
+    let filters = filters.unwrap_or_default();
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse sessions: {}", e))
+    ensure_sidecar_started(&app, &state).await?;
+    let manager = &state.manager;
+    match manager
+        .send_command(
+            "search_sessions",
+            serde_json::json!({
+                "query": query,
+                "filters": filters,
+            }),
+        )
+        .await
+    {
+        Ok(result) => serde_json::from_value(result)
+            .map_err(|e| format!("Failed to parse session search results: {}", e)),
+        Err(error) if error.contains("Unknown command") => {
+            let result = manager
+                .send_command("list_sessions", serde_json::json!({}))
+                .await?;
+            let sessions: Vec<SessionSummary> = serde_json::from_value(result)
+                .map_err(|e| format!("Failed to parse sessions: {}", e))?;
+            Ok(search_session_summaries(sessions, &query, &filters))
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Match requested ids against raw session values, parsing each one on its own
+/// so a single malformed session does not fail the whole batch.
+fn collect_session_bulk_entries(
+    session_ids: &[String],
+    values: Vec<serde_json::Value>,
+) -> Vec<SessionBulkEntry> {
+    let mut by_id: HashMap<String, serde_json::Value> = values
+        .into_iter()
+        .filter_map(|value| {
+            let id = value.get("id")?.as_str()?.to_string();
+            Some((id, value))
+        })
+        .collect();
+
+    session_ids
+        .iter()
+        .map(|session_id| {
+            let (session, error) = match by_id.remove(session_id) {
+                Some(value) => match serde_json::from_value::<SessionSummary>(value) {
+                    Ok(summary) => (Some(summary), None),
+                    Err(e) => (None, Some(format!("Failed to parse session: {}", e))),
+                },
+                None => (None, Some("Session not found".to_string())),
+            };
+            SessionBulkEntry {
+                session_id: session_id.clone(),
+                session,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Fetch summaries for many sessions in one round-trip.
+#[tauri::command]
+pub async fn agent_get_sessions_bulk(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    session_ids: Vec<String>,
+) -> Result<Vec<SessionBulkEntry>, String> {
+    if session_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    ensure_sidecar_started(&app, &state).await?;
+    let manager = &state.manager;
+    let result = match manager
+        .send_command(
+            "get_sessions_bulk",
+            serde_json::json!({ "sessionIds": session_ids }),
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(error) if error.contains("Unknown command") => {
+            manager
+                .send_command("list_sessions", serde_json::json!({}))
+                .await?
+        }
+        Err(error) => return Err(error),
+    };
+
+    let values = match result {
+        serde_json::Value::Array(values) => values,
+        serde_json::Value::Object(mut map) => match map.remove("sessions") {
+            Some(serde_json::Value::Array(values)) => values,
+            _ => Vec::new(),
+        },
+        _ => return Err("Failed to parse sessions: expected an array".to_string()),
+    };
+    Ok(collect_session_bulk_entries(&session_ids, values))
 }
 
 #[tauri::command]
@@ -966,7 +1884,12 @@ pub async fn agent_get_session(
     });
 
     let result = manager.send_command("get_session", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse session: {}", e))
+    let session: SessionDetails =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse session: {}", e))?;
+    if let Some(working_directory) = session.working_directory.as_deref() {
+        memory_watch::watch_session(&app, &session.id, working_directory);
+    }
+    Ok(session)
 }
 
 #[tauri::command]
@@ -987,7 +1910,14 @@ pub async fn agent_get_session_chunk(
     });
 
     let result = manager.send_command("get_session_chunk", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse session chunk: {}", e))
+    let session: SessionDetails = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse session chunk: {}", e))?;
+    if before_sequence.is_none() {
+        if let Some(working_directory) = session.working_directory.as_deref() {
+            memory_watch::watch_session(&app, &session.id, working_directory);
+        }
+    }
+    Ok(session)
 }
 
 #[tauri::command]
@@ -1034,21 +1964,31 @@ pub async fn agent_subscribe_events(
         .await
 }
 
-/// Delete a session
+/// Delete a session, refusing while it is generating unless `force` is set
 #[tauri::command]
 pub async fn agent_delete_session(
     app: AppHandle,
     state: State<'_, AgentState>,
     session_id: String,
+    force: Option<bool>,
 ) -> Result<(), String> {
     ensure_sidecar_started(&app, &state).await?;
 
     let manager = &state.manager;
+    ensure_agent_idle(
+        manager,
+        Some(&session_id),
+        "delete this session",
+        force.unwrap_or(false),
+    )
+    .await?;
     let params = serde_json::json!({
         "sessionId": session_id,
     });
 
     manager.send_command("delete_session", params).await?;
+    memory_watch::unwatch_session(&session_id);
+    files::abort_session_file_writes(&session_id);
     Ok(())
 }
 
@@ -1089,6 +2029,7 @@ pub async fn agent_update_session_working_directory(
     });
 
     manager.send_command("update_session_working_directory", params).await?;
+    memory_watch::move_session_watch(&app, &session_id, &working_directory);
     Ok(())
 }
 
@@ -1144,6 +2085,14 @@ pub async fn agent_save_memory(
     });
 
     manager.send_command("save_memory", params).await?;
+    memory_watch::mark_memory_seen(&working_directory);
+    Ok(())
+}
+
+/// Stop watching a session's memory file, e.g. when its view is closed
+#[tauri::command]
+pub async fn agent_stop_memory_watch(session_id: String) -> Result<(), String> {
+    memory_watch::unwatch_session(&session_id);
     Ok(())
 }
 
@@ -1164,6 +2113,29 @@ pub async fn agent_get_context_usage(
     manager.send_command("get_context_usage", params).await
 }
 
+/// Compact a session's history to free context and return the new usage.
+/// Refused while the session is generating, since the running turn would
+/// write back the history it started from.
+#[tauri::command]
+pub async fn agent_compact_session(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    session_id: String,
+    strategy: String,
+) -> Result<ContextUsage, String> {
+    validate_mode("compaction", &strategy, &COMPACTION_STRATEGIES)?;
+    ensure_sidecar_started(&app, &state).await?;
+
+    let manager = &state.manager;
+    ensure_agent_idle(manager, Some(&session_id), "compact this session", false).await?;
+    let params = serde_json::json!({
+        "sessionId": session_id,
+        "strategy": strategy,
+    });
+    let result = manager.send_command("compact_session", params).await?;
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse context usage: {}", e))
+}
+
 /// Sync MCP servers to sidecar
 #[tauri::command]
 pub async fn agent_set_mcp_servers(
@@ -1193,6 +2165,13 @@ pub async fn agent_set_skills(
         "skills": skills,
     });
     manager.send_command("set_skills", params).await?;
+    crate::commands::skills::invalidate_skill_eligibility_cache();
+    crate::commands::skills::record_enabled_skills(
+        skills
+            .iter()
+            .filter(|skill| skill.enabled.unwrap_or(true))
+            .map(|skill| skill.id.as_str()),
+    );
     Ok(())
 }
 
@@ -1272,18 +2251,171 @@ pub async fn agent_respond_question(
     Ok(())
 }
 
-/// Get initialization status for frontend coordination
+/// Ask the sidecar for a session's outstanding prompts, falling back to the
+/// ones tracked from events if it cannot answer.
+async fn list_pending_prompts<T: serde::de::DeserializeOwned>(
+    state: &State<'_, AgentState>,
+    command: &str,
+    session_id: &str,
+    tracked: Vec<serde_json::Value>,
+) -> Vec<T> {
+    let params = serde_json::json!({ "sessionId": session_id });
+    let error = match state.manager.send_command(command, params).await {
+        Ok(result) => match serde_json::from_value(result) {
+            Ok(prompts) => return prompts,
+            Err(e) => e.to_string(),
+        },
+        Err(e) => e,
+    };
+    log::warn!(
+        "{} failed for session {}, using tracked events: {}",
+        command,
+        session_id,
+        error
+    );
+    tracked
+        .into_iter()
+        .filter_map(|request| serde_json::from_value(request).ok())
+        .collect()
+}
+
+/// List permission requests awaiting a decision, e.g. after a UI reload
+#[tauri::command]
+pub async fn agent_list_pending_permissions(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    session_id: String,
+) -> Result<Vec<PendingPermission>, String> {
+    ensure_sidecar_started(&app, &state).await?;
+    let tracked = state.manager.outstanding_prompts(&session_id).await;
+    Ok(list_pending_prompts(
+        &state,
+        "list_pending_permissions",
+        &session_id,
+        tracked.permissions,
+    )
+    .await)
+}
+
+/// List questions awaiting an answer, e.g. after a UI reload
+#[tauri::command]
+pub async fn agent_list_pending_questions(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    session_id: String,
+) -> Result<Vec<PendingQuestion>, String> {
+    ensure_sidecar_started(&app, &state).await?;
+    let tracked = state.manager.outstanding_prompts(&session_id).await;
+    Ok(list_pending_prompts(
+        &state,
+        "list_pending_questions",
+        &session_id,
+        tracked.questions,
+    )
+    .await)
+}
+
+/// Get initialization status for frontend coordination. `state` is
+/// "ready", "initializing", or "failed" (with `error`) when `initialize`
+/// itself failed; each call retries a failed initialization.
 #[tauri::command]
 pub async fn agent_get_initialization_status(
     app: AppHandle,
     state: State<'_, AgentState>,
 ) -> Result<serde_json::Value, String> {
-    ensure_sidecar_started(&app, &state).await?;
+    if let Err(error) = ensure_sidecar_started(&app, &state).await {
+        let init_error = state.bootstrap_state.lock().await.last_init_error.clone();
+        return match init_error {
+            Some(init_error) => Ok(serde_json::json!({
+                "initialized": false,
+                "sessionCount": 0,
+                "state": "failed",
+                "error": init_error,
+            })),
+            None => Err(error),
+        };
+    }
+
     let manager = &state.manager;
-    manager.send_command("get_initialization_status", serde_json::json!({})).await
+    let mut status = manager
+        .send_command("get_initialization_status", serde_json::json!({}))
+        .await?;
+    let ready = status["initialized"].as_bool().unwrap_or(false);
+    if let Some(fields) = status.as_object_mut() {
+        fields.insert(
+            "state".to_string(),
+            serde_json::json!(if ready { "ready" } else { "initializing" }),
+        );
+        fields.insert("error".to_string(), serde_json::Value::Null);
+    }
+    Ok(status)
+}
+
+const AGENT_COMMAND_ALLOWLIST_ENV_VAR: &str = "COWORK_AGENT_COMMAND_ALLOWLIST";
+/// Commands the frontend itself sends through `agent_command_raw`.
+const DEFAULT_AGENT_COMMAND_ALLOWLIST: [&str; 3] =
+    ["souls_list", "souls_save_custom", "souls_delete_custom"];
+
+/// `None` unless `COWORK_AGENT_COMMAND_ALLOWLIST` turns the allowlist on.
+/// `on` allows the built-in commands; any other value is a comma-separated
+/// list of extra command names allowed alongside them.
+fn agent_command_allowlist() -> Option<HashSet<String>> {
+    let raw = std::env::var(AGENT_COMMAND_ALLOWLIST_ENV_VAR).ok()?;
+    let value = raw.trim();
+    let normalized = value.to_lowercase();
+    let disabled = matches!(
+        normalized.as_str(),
+        "0" | "false" | "no" | "off" | "disabled"
+    );
+    if value.is_empty() || disabled {
+        return None;
+    }
+
+    let mut allowed: HashSet<String> = DEFAULT_AGENT_COMMAND_ALLOWLIST
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    if !matches!(normalized.as_str(), "1" | "true" | "yes" | "on" | "enabled") {
+        allowed.extend(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+        );
+    }
+    Some(allowed)
+}
+
+/// Defense in depth for hardened deployments: a compromised webview could
+/// otherwise reach every sidecar handler through the generic commands.
+fn ensure_agent_command_allowed(command: &str) -> Result<(), String> {
+    let Some(allowed) = agent_command_allowlist() else {
+        return Ok(());
+    };
+    if allowed.contains(command) {
+        return Ok(());
+    }
+
+    let error = format!(
+        "Command `{}` is not allowed by {}",
+        command, AGENT_COMMAND_ALLOWLIST_ENV_VAR
+    );
+    log::warn!("Rejected generic agent command `{}`", command);
+    crate::commands::credentials::record_audit_event(
+        "agent_command",
+        command,
+        Some(&error),
+        serde_json::json!({ "allowlist": AGENT_COMMAND_ALLOWLIST_ENV_VAR }),
+    );
+    Err(error)
 }
 
-/// Generic command handler - forwards any command to the sidecar
+/// Generic command handler - forwards any command to the sidecar, subject to
+/// the optional allowlist.
+///
+/// Returns `{ "result": <sidecar result> }`, unlike every other command.
+/// Kept for existing callers; prefer `agent_command_raw`.
 #[tauri::command]
 pub async fn agent_command(
     app: AppHandle,
@@ -1291,10 +2423,428 @@ pub async fn agent_command(
     command: String,
     params: serde_json::Value,
 ) -> Result<serde_json::Value, String> {
+    let result = agent_command_raw(app, state, command, params).await?;
+    Ok(serde_json::json!({ "result": result }))
+}
+
+/// Generic command handler returning the sidecar result as-is
+#[tauri::command]
+pub async fn agent_command_raw(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    command: String,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    ensure_agent_command_allowed(&command)?;
     ensure_sidecar_started(&app, &state).await?;
     let manager = &state.manager;
-    let result = manager.send_command(&command, params).await?;
-    Ok(serde_json::json!({ "result": result }))
+    manager.send_command(&command, params).await
+}
+
+fn now_millis() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn message_content_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| match part["type"].as_str() {
+                Some("text") | None => part["text"].as_str().map(|text| text.to_string()),
+                Some(other) => Some(format!("[{}]", other)),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn write_collapsed_json(
+    writer: &mut impl Write,
+    summary: &str,
+    value: &serde_json::Value,
+) -> std::io::Result<()> {
+    let body = serde_json::to_string_pretty(value).unwrap_or_default();
+    writeln!(writer, "<details>\n<summary>{}</summary>\n", summary)?;
+    writeln!(writer, "```json\n{}\n```\n</details>\n", body)
+}
+
+fn write_chat_item_markdown(writer: &mut impl Write, item: &serde_json::Value) -> std::io::Result<()> {
+    match item["kind"].as_str().unwrap_or_default() {
+        "user_message" => {
+            writeln!(writer, "### User\n\n{}\n", message_content_text(&item["content"]))?;
+            if let Some(attachments) = item["attachments"].as_array() {
+                for attachment in attachments {
+                    writeln!(
+                        writer,
+                        "- Attachment: {}",
+                        attachment["name"].as_str().unwrap_or("unnamed")
+                    )?;
+                }
+                if !attachments.is_empty() {
+                    writeln!(writer)?;
+                }
+            }
+            Ok(())
+        }
+        "assistant_message" => {
+            writeln!(writer, "### Assistant\n\n{}\n", message_content_text(&item["content"]))
+        }
+        "system_message" => writeln!(
+            writer,
+            "> **System:** {}\n",
+            item["content"].as_str().unwrap_or_default()
+        ),
+        "tool_start" => write_collapsed_json(
+            writer,
+            &format!("Tool call: {}", item["name"].as_str().unwrap_or("tool")),
+            &item["args"],
+        ),
+        "tool_result" => {
+            let summary = format!(
+                "Tool result: {} ({})",
+                item["name"].as_str().unwrap_or("tool"),
+                item["status"].as_str().unwrap_or("unknown")
+            );
+            match item["error"].as_str() {
+                Some(error) => write_collapsed_json(writer, &summary, &serde_json::json!({ "error": error })),
+                None => write_collapsed_json(writer, &summary, &item["result"]),
+            }
+        }
+        "error" => writeln!(
+            writer,
+            "> **Error:** {}\n",
+            item["message"].as_str().unwrap_or_default()
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn write_session_markdown(writer: &mut impl Write, session: &SessionDetails) -> std::io::Result<usize> {
+    let title = session.title.as_deref().unwrap_or("Untitled session");
+    writeln!(writer, "# {}\n", title)?;
+    writeln!(writer, "- Session: `{}`", session.id)?;
+    writeln!(writer, "- Provider: {}", session.provider)?;
+    if let Some(model) = &session.model {
+        writeln!(writer, "- Model: {}", model)?;
+    }
+    if let Some(working_directory) = &session.working_directory {
+        writeln!(writer, "- Working directory: `{}`", working_directory)?;
+    }
+    writeln!(writer, "- Created: {}\n", session.created_at)?;
+    writeln!(writer, "## Conversation\n")?;
+
+    let item_count = match &session.chat_items {
+        Some(chat_items) => {
+            for item in chat_items {
+                write_chat_item_markdown(writer, item)?;
+            }
+            chat_items.len()
+        }
+        None => {
+            for message in &session.messages {
+                let role = match message["role"].as_str().unwrap_or("message") {
+                    "user" => "User",
+                    "assistant" | "model" => "Assistant",
+                    "system" => "System",
+                    other => other,
+                };
+                writeln!(writer, "### {}\n\n{}\n", role, message_content_text(&message["content"]))?;
+            }
+            session.messages.len()
+        }
+    };
+
+    if let Some(artifacts) = session.artifacts.as_ref().filter(|artifacts| !artifacts.is_empty()) {
+        writeln!(writer, "## Artifacts\n")?;
+        for artifact in artifacts {
+            writeln!(
+                writer,
+                "- `{}` ({})",
+                artifact["path"].as_str().unwrap_or("unknown"),
+                artifact["type"].as_str().unwrap_or("artifact")
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(item_count)
+}
+
+fn session_export_path(session_id: &str, extension: &str) -> std::path::PathBuf {
+    let safe_id: String = session_id
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' { ch } else { '_' })
+        .collect();
+    std::env::temp_dir().join(format!(
+        "cowork-session-{}-{}.{}",
+        safe_id,
+        now_millis(),
+        extension
+    ))
+}
+
+/// Export a session to Markdown or JSON. The rendered document is streamed to
+/// a temp file and its path is returned so large sessions never cross IPC.
+#[tauri::command]
+pub async fn agent_export_session(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    session_id: String,
+    format: String,
+) -> Result<SessionExportResult, String> {
+    let format = format.trim().to_lowercase();
+    let extension = match format.as_str() {
+        "markdown" | "md" => "md",
+        "json" => "json",
+        _ => return Err(format!("Unsupported export format: {}", format)),
+    };
+
+    let session = agent_get_session(app, state, session_id.clone()).await?;
+    let path = session_export_path(&session.id, extension);
+    let file = std::fs::File::create(&path)
+        .map_err(|e| format!("Failed to create export file {:?}: {}", path, e))?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    let item_count = if extension == "json" {
+        let document = serde_json::json!({
+            "schemaVersion": SESSION_EXPORT_SCHEMA_VERSION,
+            "exportedAt": now_millis(),
+            "session": &session,
+        });
+        serde_json::to_writer_pretty(&mut writer, &document)
+            .map_err(|e| format!("Failed to write session export: {}", e))?;
+        session
+            .chat_items
+            .as_ref()
+            .map(|items| items.len())
+            .unwrap_or(session.messages.len())
+    } else {
+        write_session_markdown(&mut writer, &session)
+            .map_err(|e| format!("Failed to write session export: {}", e))?
+    };
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write session export: {}", e))?;
+
+    let bytes_written = std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    Ok(SessionExportResult {
+        session_id,
+        format: extension.to_string(),
+        path: path.to_string_lossy().to_string(),
+        bytes_written,
+        item_count,
+    })
+}
+
+fn unknown_fields(value: &serde_json::Value, known: &[&str], scope: &str) -> Vec<String> {
+    value
+        .as_object()
+        .map(|map| {
+            map.keys()
+                .filter(|key| !known.contains(&key.as_str()))
+                .map(|key| format!("Ignoring unknown field `{}` in {}", key, scope))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Validate an exported session bundle and return the session plus warnings
+/// for fields this version does not understand.
+fn parse_session_export(document: &serde_json::Value) -> Result<(SessionDetails, Vec<String>), String> {
+    if !document.is_object() {
+        return Err("Session import document must be a JSON object".to_string());
+    }
+
+    let schema_version = document
+        .get("schemaVersion")
+        .ok_or("Session import document is missing `schemaVersion`")?;
+    match schema_version.as_u64() {
+        Some(version) if version == u64::from(SESSION_EXPORT_SCHEMA_VERSION) => {}
+        _ => {
+            return Err(format!(
+                "Unsupported session export schema version {}; this build reads version {}",
+                schema_version, SESSION_EXPORT_SCHEMA_VERSION
+            ))
+        }
+    }
+
+    let session_value = document
+        .get("session")
+        .ok_or("Session import document is missing `session`")?;
+    let mut warnings = unknown_fields(document, &SESSION_EXPORT_DOCUMENT_FIELDS, "the document");
+    warnings.extend(unknown_fields(session_value, &SESSION_DETAILS_FIELDS, "`session`"));
+
+    let session: SessionDetails = serde_json::from_value(session_value.clone())
+        .map_err(|e| format!("Invalid session in import document: {}", e))?;
+    if session.id.trim().is_empty() {
+        return Err("Session import document has an empty session id".to_string());
+    }
+    Ok((session, warnings))
+}
+
+/// Import a session previously produced by `agent_export_session` (JSON).
+#[tauri::command]
+pub async fn agent_import_session(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    document: serde_json::Value,
+) -> Result<SessionImportResult, String> {
+    let (mut session, warnings) = parse_session_export(&document)?;
+    for warning in &warnings {
+        log::warn!("Session import: {}", warning);
+    }
+
+    let existing =
+        match agent_list_sessions(app.clone(), state.clone(), None, None, None, None).await? {
+            SessionListResult::All(sessions) => sessions,
+            SessionListResult::Page(page) => page.sessions,
+        };
+    let original_session_id = session.id.clone();
+    let remapped = existing.iter().any(|summary| summary.id == session.id);
+    if remapped {
+        session.id = format!("{}-import-{}", original_session_id, now_millis());
+    }
+
+    let manager = &state.manager;
+    manager
+        .send_command(
+            "import_session",
+            serde_json::json!({
+                "session": session,
+                "originalSessionId": original_session_id,
+            }),
+        )
+        .await?;
+
+    Ok(SessionImportResult {
+        session_id: session.id,
+        original_session_id,
+        remapped,
+        warnings,
+    })
+}
+
+/// Duplicate a session: create a fresh session with the same settings,
+/// replay the source history into it in batches and copy the agent's
+/// checkpointed state, so the next turn has the same context. Only the
+/// displayed history is capped at `max_items` (most recent kept); the copy
+/// can be stopped with `agent_cancel_session_duplicate`.
+#[tauri::command]
+pub async fn agent_duplicate_session(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    session_id: String,
+    new_title: Option<String>,
+    max_items: Option<usize>,
+) -> Result<SessionInfo, String> {
+    let source = agent_get_session(app.clone(), state.clone(), session_id.clone()).await?;
+    let working_directory = source
+        .working_directory
+        .clone()
+        .ok_or_else(|| format!("Session {} has no working directory to duplicate", session_id))?;
+
+    let mut info = agent_create_session(
+        app,
+        state.clone(),
+        working_directory,
+        source.model.clone(),
+        Some(source.provider.clone()),
+        Some(source.execution_mode.clone()),
+        None,
+    )
+    .await?;
+
+    let title = new_title
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| {
+            format!("{} (copy)", source.title.as_deref().unwrap_or("Untitled session"))
+        });
+    let history = source.chat_items.unwrap_or_default();
+    let limit = max_items.unwrap_or(SESSION_REPLAY_DEFAULT_MAX_ITEMS);
+    let skipped = history.len().saturating_sub(limit);
+    if skipped > 0 {
+        log::info!(
+            "Duplicating session {}: replaying the latest {} of {} items",
+            session_id,
+            limit,
+            history.len()
+        );
+    }
+
+    let manager = &state.manager;
+    let copied: Result<(), String> = async {
+        manager
+            .send_command(
+                "update_session_title",
+                serde_json::json!({ "sessionId": info.id, "title": title }),
+            )
+            .await?;
+
+        state.duplicate_cancellations.lock().await.remove(&session_id);
+        for batch in history[skipped..].chunks(SESSION_REPLAY_BATCH_SIZE) {
+            if state.duplicate_cancellations.lock().await.remove(&session_id) {
+                return Err(format!("Duplicating session {} was cancelled", session_id));
+            }
+
+            manager
+                .send_command(
+                    "session_append_chat_items",
+                    serde_json::json!({
+                        "sessionId": info.id,
+                        "chatItems": batch,
+                    }),
+                )
+                .await?;
+        }
+
+        manager
+            .send_command(
+                "session_copy_agent_state",
+                serde_json::json!({
+                    "sourceSessionId": session_id,
+                    "targetSessionId": info.id,
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+    .await;
+
+    // Never leave a half-copied session behind.
+    if let Err(error) = copied {
+        if let Err(cleanup_error) = manager
+            .send_command("delete_session", serde_json::json!({ "sessionId": info.id }))
+            .await
+        {
+            log::warn!(
+                "Failed to remove partial duplicate {}: {}",
+                info.id,
+                cleanup_error
+            );
+        }
+        return Err(error);
+    }
+
+    info.title = Some(title);
+    info.message_count = source.message_count;
+    Ok(info)
+}
+
+/// Stop an in-flight `agent_duplicate_session` for the given source session.
+#[tauri::command]
+pub async fn agent_cancel_session_duplicate(
+    state: State<'_, AgentState>,
+    session_id: String,
+) -> Result<(), String> {
+    state.duplicate_cancellations.lock().await.insert(session_id);
+    Ok(())
 }
 
 fn normalize_log_level(value: &str) -> &str {
@@ -1326,13 +2876,7 @@ pub async fn agent_log_client_diagnostic(
     context_json: Option<String>,
     timestamp_ms: Option<i64>,
 ) -> Result<(), String> {
-    let ts = timestamp_ms.unwrap_or_else(|| {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|duration| duration.as_millis() as i64)
-            .unwrap_or(0)
-    });
+    let ts = timestamp_ms.unwrap_or_else(now_millis);
 
     let normalized_level = normalize_log_level(&level);
     let source_label = source.unwrap_or_else(|| "frontend".to_string());
@@ -1370,3 +2914,449 @@ pub async fn agent_log_client_diagnostic(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn export_document(session: &SessionDetails) -> serde_json::Value {
+        serde_json::json!({
+            "schemaVersion": SESSION_EXPORT_SCHEMA_VERSION,
+            "exportedAt": 1_700_000_000_000_i64,
+            "session": session,
+        })
+    }
+
+    fn sample_session() -> SessionDetails {
+        serde_json::from_value(serde_json::json!({
+            "id": "sess-1",
+            "type": "main",
+            "provider": "openai",
+            "executionMode": "plan",
+            "title": "Refactor parser",
+            "workingDirectory": "/tmp/project",
+            "model": "gpt-5.2",
+            "messageCount": 2,
+            "chatItems": [
+                { "id": "ci-1", "kind": "user_message", "content": "hi", "sequence": 0 },
+                { "id": "ci-2", "kind": "assistant_message", "content": "hello", "sequence": 1 }
+            ],
+            "tasks": [],
+            "artifacts": [],
+            "createdAt": 1,
+            "updatedAt": 2,
+            "lastAccessedAt": 3
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn session_export_round_trips_through_import() {
+        let session = sample_session();
+        let (imported, warnings) = parse_session_export(&export_document(&session)).unwrap();
+
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&session).unwrap()
+        );
+    }
+
+    #[test]
+    fn session_import_warns_about_unknown_fields() {
+        let mut document = export_document(&sample_session());
+        document["extra"] = serde_json::json!(true);
+        document["session"]["futureField"] = serde_json::json!(1);
+
+        let (_, warnings) = parse_session_export(&document).unwrap();
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn session_import_rejects_other_schema_versions() {
+        let mut document = export_document(&sample_session());
+        document["schemaVersion"] = serde_json::json!(SESSION_EXPORT_SCHEMA_VERSION + 1);
+        assert!(parse_session_export(&document).is_err());
+
+        document.as_object_mut().unwrap().remove("schemaVersion");
+        assert!(parse_session_export(&document).is_err());
+    }
+
+    fn inline_attachment(name: &str, bytes: usize) -> Attachment {
+        Attachment {
+            attachment_type: "image".to_string(),
+            name: name.to_string(),
+            path: None,
+            mime_type: Some("image/png".to_string()),
+            data: Some(BASE64_STANDARD.encode(vec![0u8; bytes])),
+            size: None,
+        }
+    }
+
+    fn path_attachment(name: &str, path: &std::path::Path) -> Attachment {
+        Attachment {
+            attachment_type: "file".to_string(),
+            name: name.to_string(),
+            path: Some(path.to_string_lossy().to_string()),
+            mime_type: None,
+            data: None,
+            size: None,
+        }
+    }
+
+    #[test]
+    fn attachments_within_limits_pass() {
+        let attachments = vec![
+            inline_attachment("a.png", 10),
+            inline_attachment("b.png", 10),
+        ];
+        assert!(validate_attachments_with_limits(&attachments, 16, 32).is_ok());
+    }
+
+    #[test]
+    fn attachment_over_the_per_attachment_cap_is_rejected() {
+        let attachments = vec![inline_attachment("big.png", 64)];
+        let error = validate_attachments_with_limits(&attachments, 16, 1024).unwrap_err();
+        assert!(error.contains("`big.png`"), "{}", error);
+        assert!(error.contains("per-attachment limit"), "{}", error);
+    }
+
+    #[test]
+    fn attachments_over_the_total_cap_are_rejected() {
+        let attachments = vec![
+            inline_attachment("one.png", 12),
+            inline_attachment("two.png", 12),
+        ];
+        let error = validate_attachments_with_limits(&attachments, 16, 20).unwrap_err();
+        assert!(error.contains("`two.png`"), "{}", error);
+        assert!(error.contains("per message"), "{}", error);
+    }
+
+    #[test]
+    fn attachment_with_invalid_base64_is_rejected() {
+        let mut attachment = inline_attachment("bad.png", 0);
+        attachment.data = Some("not base64!".to_string());
+        let error = validate_attachments_with_limits(&[attachment], 1024, 1024).unwrap_err();
+        assert!(error.contains("not valid base64"), "{}", error);
+    }
+
+    #[test]
+    fn attachment_with_missing_path_is_rejected() {
+        let missing = std::env::temp_dir().join(format!(
+            "cowork-missing-attachment-{}-{}",
+            std::process::id(),
+            now_millis()
+        ));
+        let error =
+            validate_attachments_with_limits(&[path_attachment("gone.txt", &missing)], 1024, 1024)
+                .unwrap_err();
+        assert!(error.contains("`gone.txt`"), "{}", error);
+        assert!(error.contains("not accessible"), "{}", error);
+    }
+
+    #[test]
+    fn attachment_path_is_measured_from_disk() {
+        let path = std::env::temp_dir().join(format!(
+            "cowork-attachment-{}-{}.txt",
+            std::process::id(),
+            now_millis()
+        ));
+        std::fs::write(&path, vec![b'x'; 40]).unwrap();
+        let attachment = path_attachment("notes.txt", &path);
+
+        assert_eq!(attachment_size(&attachment).unwrap(), 40);
+        assert!(
+            validate_attachments_with_limits(std::slice::from_ref(&attachment), 32, 1024).is_err()
+        );
+        assert!(validate_attachments_with_limits(&[attachment], 64, 1024).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn attachment_path_must_be_a_file() {
+        let error = attachment_size(&path_attachment("dir", &std::env::temp_dir())).unwrap_err();
+        assert!(error.contains("is not a file"), "{}", error);
+    }
+
+    #[test]
+    fn pairing_qr_data_url_is_a_valid_png_image_attachment() {
+        let data_url = crate::commands::remote_access::render_qr_png_data_url(
+            "cowork://pair?code=123456",
+            256,
+            4,
+            qrcode::EcLevel::M,
+        )
+        .unwrap();
+        let data = data_url.strip_prefix("data:image/png;base64,").unwrap();
+        let mut attachment = inline_attachment("pairing.png", 0);
+        attachment.data = Some(data.to_string());
+
+        let bytes = BASE64_STANDARD.decode(data).unwrap();
+        assert_eq!(attachment_size(&attachment).unwrap(), bytes.len() as u64);
+        assert!(validate_attachments_with_limits(&[attachment], 1024 * 1024, 1024 * 1024).is_ok());
+
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!(info.width, info.height);
+        assert!(info.width <= 256, "{}", info.width);
+        assert_eq!(info.color_type, png::ColorType::Grayscale);
+    }
+
+    #[test]
+    fn known_modes_are_accepted() {
+        for mode in APPROVAL_MODES {
+            assert!(validate_approval_mode(mode).is_ok(), "{}", mode);
+        }
+        for mode in EXECUTION_MODES {
+            assert!(validate_execution_mode(mode).is_ok(), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn unknown_modes_are_rejected_with_the_accepted_list() {
+        let error = validate_approval_mode("yolo").unwrap_err();
+        assert_eq!(
+            error,
+            "Unknown approval mode `yolo`. Expected one of: auto, read_only, full"
+        );
+
+        let error = validate_execution_mode("Plan").unwrap_err();
+        assert_eq!(
+            error,
+            "Unknown execution mode `Plan`. Expected one of: execute, plan"
+        );
+        assert!(validate_execution_mode("").is_err());
+    }
+
+    fn created_session(id: &str) -> SessionInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "title": null,
+            "workingDirectory": "/tmp/project",
+            "model": "gpt-5.2",
+            "createdAt": 1,
+            "updatedAt": 1
+        }))
+        .unwrap()
+    }
+
+    async fn counted_create(
+        calls: &AtomicUsize,
+        result: Result<&str, &str>,
+    ) -> Result<SessionInfo, String> {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        result
+            .map(|id| created_session(&format!("{}-{}", id, call)))
+            .map_err(str::to_string)
+    }
+
+    #[tokio::test]
+    async fn repeated_client_token_returns_first_session() {
+        let tokens = Mutex::new(HashMap::new());
+        let calls = AtomicUsize::new(0);
+
+        let first = create_session_once(&tokens, "tok".into(), counted_create(&calls, Ok("s")))
+            .await
+            .unwrap();
+        let second = create_session_once(&tokens, "tok".into(), counted_create(&calls, Ok("s")))
+            .await
+            .unwrap();
+        assert_eq!(first.id, "s-0");
+        assert_eq!(second.id, "s-0");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let other = create_session_once(&tokens, "other".into(), counted_create(&calls, Ok("s")))
+            .await
+            .unwrap();
+        assert_eq!(other.id, "s-1");
+    }
+
+    #[tokio::test]
+    async fn concurrent_duplicate_submit_creates_once() {
+        let tokens = Mutex::new(HashMap::new());
+        let calls = AtomicUsize::new(0);
+
+        let (first, second) = tokio::join!(
+            create_session_once(&tokens, "tok".into(), counted_create(&calls, Ok("s"))),
+            create_session_once(&tokens, "tok".into(), counted_create(&calls, Ok("s"))),
+        );
+        assert_eq!(first.unwrap().id, second.unwrap().id);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_create_lets_the_token_retry() {
+        let tokens = Mutex::new(HashMap::new());
+        let calls = AtomicUsize::new(0);
+
+        let error = create_session_once(
+            &tokens,
+            "tok".into(),
+            counted_create(&calls, Err("Transport is not running")),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, "Transport is not running");
+
+        let session = create_session_once(&tokens, "tok".into(), counted_create(&calls, Ok("s")))
+            .await
+            .unwrap();
+        assert_eq!(session.id, "s-1");
+    }
+
+    fn summary(
+        id: &str,
+        title: Option<&str>,
+        created: i64,
+        updated: i64,
+        accessed: i64,
+    ) -> SessionSummary {
+        SessionSummary {
+            id: id.to_string(),
+            r#type: None,
+            provider: default_provider(),
+            execution_mode: default_execution_mode(),
+            title: title.map(str::to_string),
+            first_message: None,
+            working_directory: None,
+            model: None,
+            message_count: 0,
+            created_at: created,
+            updated_at: updated,
+            last_accessed_at: accessed,
+        }
+    }
+
+    fn sorted_ids(sort_by: Option<&str>, order: Option<&str>) -> Vec<String> {
+        let mut sessions = vec![
+            summary("c", Some("beta"), 3, 20, 100),
+            summary("a", Some("Alpha"), 1, 30, 300),
+            summary("d", None, 2, 20, 300),
+            summary("b", Some("alpha"), 3, 10, 200),
+        ];
+        sort_sessions(&mut sessions, sort_by, order).unwrap();
+        sessions.into_iter().map(|session| session.id).collect()
+    }
+
+    #[test]
+    fn sessions_default_to_updated_at_desc() {
+        assert_eq!(sorted_ids(None, None), vec!["a", "c", "d", "b"]);
+        assert_eq!(
+            sorted_ids(Some("updated_at"), Some("asc")),
+            vec!["b", "c", "d", "a"]
+        );
+    }
+
+    #[test]
+    fn sessions_sort_by_created_at() {
+        assert_eq!(
+            sorted_ids(Some("created_at"), Some("asc")),
+            vec!["a", "d", "b", "c"]
+        );
+        assert_eq!(
+            sorted_ids(Some("created_at"), None),
+            vec!["b", "c", "d", "a"]
+        );
+    }
+
+    #[test]
+    fn sessions_sort_by_last_accessed_at() {
+        assert_eq!(
+            sorted_ids(Some("last_accessed_at"), None),
+            vec!["a", "d", "b", "c"]
+        );
+        assert_eq!(
+            sorted_ids(Some("last_accessed_at"), Some("asc")),
+            vec!["c", "b", "a", "d"]
+        );
+    }
+
+    #[test]
+    fn sessions_sort_by_title_ignoring_case() {
+        assert_eq!(
+            sorted_ids(Some("title"), Some("asc")),
+            vec!["d", "a", "b", "c"]
+        );
+        assert_eq!(sorted_ids(Some("title"), None), vec!["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn invalid_sort_options_are_rejected() {
+        let mut sessions = Vec::new();
+        assert_eq!(
+            sort_sessions(&mut sessions, Some("name"), None).unwrap_err(),
+            "Invalid sort key: name. Expected one of: updated_at, created_at, last_accessed_at, title"
+        );
+        assert_eq!(
+            sort_sessions(&mut sessions, None, Some("up")).unwrap_err(),
+            "Invalid sort order: up. Expected one of: asc, desc"
+        );
+    }
+
+    #[test]
+    fn context_usage_reads_the_sidecar_shape() {
+        let usage: ContextUsage = serde_json::from_value(serde_json::json!({
+            "used": 9_000,
+            "total": 10_000,
+            "percentage": 90.0,
+        }))
+        .unwrap();
+        assert_eq!((usage.used_tokens, usage.max_tokens), (9_000, 10_000));
+
+        let usage: ContextUsage = serde_json::from_value(serde_json::json!({
+            "usedTokens": 1,
+            "maxTokens": 2,
+        }))
+        .unwrap();
+        assert_eq!((usage.used_tokens, usage.max_tokens), (1, 2));
+        assert!(serde_json::from_value::<ContextUsage>(serde_json::json!({ "used": 1 })).is_err());
+    }
+
+    #[test]
+    fn budget_warning_only_when_the_message_overflows() {
+        let usage = ContextUsage {
+            used_tokens: 9_000,
+            max_tokens: 10_000,
+            percent_used: 90.0,
+            last_updated: None,
+        };
+        assert!(budget_warning("s1", &usage, 1_000).is_none());
+
+        let warning = budget_warning("s1", &usage, 1_001).unwrap();
+        assert_eq!(warning.projected_tokens, 10_001);
+        assert_eq!(warning.suggested_action, "compact");
+        assert_eq!(
+            context_budget_response(warning)["contextBudgetWarning"]["maxTokens"],
+            10_000
+        );
+
+        // An unknown window size never blocks a send.
+        let unknown = ContextUsage {
+            max_tokens: 0,
+            ..usage
+        };
+        assert!(budget_warning("s1", &unknown, i64::MAX).is_none());
+    }
+
+    #[test]
+    fn queued_messages_parse_from_the_queue_listing() {
+        let queue: Vec<QueuedMessage> = serde_json::from_value(serde_json::json!([
+            { "id": "m1", "content": "hello", "queuedAt": 1 },
+            {
+                "id": "m2",
+                "content": "see image",
+                "queuedAt": 2,
+                "attachments": [{ "type": "image", "name": "a.png" }],
+            },
+        ]))
+        .unwrap();
+        assert_eq!(queue[0].id, "m1");
+        assert_eq!(
+            estimate_message_tokens(&queue[1].content, queue[1].attachments.as_ref()),
+            IMAGE_ATTACHMENT_TOKENS + 3
+        );
+    }
+}
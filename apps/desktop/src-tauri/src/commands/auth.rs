@@ -1,8 +1,17 @@
 // Copyright (c) 2026 Naresh. All rights reserved.
 // Licensed under the MIT License. See LICENSE file for details.
 
+use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
 use crate::commands::credentials;
+use crate::error::{CommandError, ErrorCode};
+use crate::paths;
+use crate::redact;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
 
 const API_KEY_SERVICE: &str = "cowork";
 const LEGACY_API_KEY_ACCOUNT: &str = "api_key";
@@ -23,26 +32,99 @@ const PROVIDER_IDS: [&str; 8] = [
     "lmstudio",
 ];
 
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
     pub description: String,
     pub input_token_limit: u32,
     pub output_token_limit: u32,
+    /// True when a limit was missing upstream and filled with the provider
+    /// default, so context usage built on it is approximate.
+    #[serde(default)]
+    pub token_limits_estimated: bool,
+}
+
+/// The result of `fetch_provider_models`: the models plus where the list
+/// came from.
+#[derive(serde::Serialize)]
+pub struct FetchedModelList {
+    pub models: Vec<ModelInfo>,
+    /// The live fetch failed and this is the last successful list.
+    pub stale: bool,
+    /// The live fetch failed and this is the curated list shown in its place.
+    pub fallback: bool,
+    /// When the list was fetched from the provider, in ms since the epoch.
+    /// `None` for curated defaults.
+    pub fetched_at: Option<i64>,
+}
+
+#[derive(serde::Serialize)]
+pub struct LogoutPreview {
+    pub credential_accounts: Vec<String>,
+    pub data_dir_path: String,
+    pub data_dir_exists: bool,
+    /// Size of the entries logout would remove, not of the whole directory.
+    pub data_dir_size_bytes: u64,
+    /// Entries Cowork did not create; logout leaves them in place.
+    pub kept_entries: Vec<String>,
 }
 
 #[derive(serde::Serialize)]
 pub struct LogoutCleanupResult {
+    /// True only when nothing else was left in the directory.
     pub removed_data_dir: bool,
+    pub kept_entries: Vec<String>,
     pub data_dir_path: String,
     pub cleared_credential_accounts: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStepStatus {
+    Ok,
+    Failed,
+    Skipped,
+}
+
+/// Whether the provider accepted the API key, as far as the response shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderAuthStatus {
+    Accepted,
+    Rejected,
+    MissingKey,
+    RateLimited,
+    Unknown,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiagnosticStep {
+    pub name: String,
+    pub status: DiagnosticStepStatus,
+    pub detail: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiagnostics {
+    pub provider: String,
+    pub base_url: String,
+    pub steps: Vec<ProviderDiagnosticStep>,
+    pub http_status: Option<u16>,
+    pub auth: ProviderAuthStatus,
+    pub model_count: Option<usize>,
+    pub summary: String,
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SecurityPostureStatus {
     pub credential_backend: String,
+    pub credential_backend_source: String,
+    pub keychain_available: bool,
     pub secure_seed_available: bool,
     pub credentials_vault_present: bool,
     pub connector_vault_present: bool,
@@ -53,6 +135,14 @@ pub struct SecurityPostureStatus {
     pub auxiliary_keys_configured: usize,
     pub audit_log_present: bool,
     pub audit_log_size_bytes: u64,
+    pub undecryptable_credential_entries: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityPostureRepair {
+    pub repairs: Vec<String>,
+    pub posture: SecurityPostureStatus,
 }
 
 fn normalize_provider_id(provider_id: &str) -> Result<String, String> {
@@ -71,6 +161,12 @@ fn normalize_provider_id(provider_id: &str) -> Result<String, String> {
     }
 }
 
+/// `normalize_provider_id` for command arguments, where an unknown provider
+/// is the caller's mistake.
+fn command_provider_id(provider_id: &str) -> Result<String, CommandError> {
+    normalize_provider_id(provider_id).map_err(CommandError::validation)
+}
+
 fn provider_api_key_account(provider_id: &str) -> Result<String, String> {
     Ok(format!(
         "provider_api_key_{}",
@@ -93,6 +189,10 @@ fn default_base_url(provider_id: &str) -> Option<&'static str> {
 }
 
 fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
+    normalize_model_list(provider_id, curated_model_entries(provider_id))
+}
+
+fn curated_model_entries(provider_id: &str) -> Vec<ModelInfo> {
     match provider_id {
         "google" => vec![
             ModelInfo {
@@ -101,6 +201,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Latest fast preview model".to_string(),
                 input_token_limit: 1_048_576,
                 output_token_limit: 65_536,
+                ..Default::default()
             },
             ModelInfo {
                 id: "gemini-3-pro-preview".to_string(),
@@ -108,6 +209,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Latest reasoning-focused preview model".to_string(),
                 input_token_limit: 1_048_576,
                 output_token_limit: 65_536,
+                ..Default::default()
             },
         ],
         "openai" => vec![
@@ -117,6 +219,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Latest GPT model".to_string(),
                 input_token_limit: 400_000,
                 output_token_limit: 128_000,
+                ..Default::default()
             },
             ModelInfo {
                 id: "gpt-4.1".to_string(),
@@ -124,6 +227,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Broad compatibility fallback model".to_string(),
                 input_token_limit: 1_000_000,
                 output_token_limit: 32_768,
+                ..Default::default()
             },
         ],
         "anthropic" => vec![
@@ -133,6 +237,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Latest Claude flagship model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 8_192,
+                ..Default::default()
             },
             ModelInfo {
                 id: "claude-sonnet-4-5".to_string(),
@@ -140,6 +245,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Balanced reasoning and speed".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 8_192,
+                ..Default::default()
             },
         ],
         "openrouter" => vec![
@@ -149,6 +255,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Via OpenRouter".to_string(),
                 input_token_limit: 0,
                 output_token_limit: 0,
+                ..Default::default()
             },
             ModelInfo {
                 id: "anthropic/claude-opus-4.6".to_string(),
@@ -156,6 +263,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Via OpenRouter".to_string(),
                 input_token_limit: 0,
                 output_token_limit: 0,
+                ..Default::default()
             },
         ],
         "moonshot" => vec![
@@ -165,6 +273,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Moonshot latest reasoning-focused K2 model".to_string(),
                 input_token_limit: 262_144,
                 output_token_limit: 0,
+                ..Default::default()
             },
             ModelInfo {
                 id: "kimi-k2.5".to_string(),
@@ -172,6 +281,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Moonshot multimodal flagship model".to_string(),
                 input_token_limit: 262_144,
                 output_token_limit: 0,
+                ..Default::default()
             },
             ModelInfo {
                 id: "kimi-k2-0711-preview".to_string(),
@@ -179,6 +289,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Moonshot K2 preview model".to_string(),
                 input_token_limit: 131_072,
                 output_token_limit: 0,
+                ..Default::default()
             },
             ModelInfo {
                 id: "kimi-k2-turbo-preview".to_string(),
@@ -186,6 +297,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Moonshot high-speed K2 model".to_string(),
                 input_token_limit: 262_144,
                 output_token_limit: 0,
+                ..Default::default()
             },
             ModelInfo {
                 id: "kimi-k2-0905-preview".to_string(),
@@ -193,6 +305,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Moonshot K2 preview model".to_string(),
                 input_token_limit: 262_144,
                 output_token_limit: 0,
+                ..Default::default()
             },
             ModelInfo {
                 id: "kimi-k2-thinking-turbo".to_string(),
@@ -200,6 +313,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Moonshot high-speed reasoning K2 model".to_string(),
                 input_token_limit: 262_144,
                 output_token_limit: 0,
+                ..Default::default()
             },
         ],
         "glm" => vec![
@@ -209,6 +323,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM flagship model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.7-flashx".to_string(),
@@ -216,6 +331,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM fast flagship variant".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.6".to_string(),
@@ -223,6 +339,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM high-capability model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.5".to_string(),
@@ -230,6 +347,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM balanced model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.5-x".to_string(),
@@ -237,6 +355,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM premium high-reasoning model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.5-air".to_string(),
@@ -244,6 +363,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM lightweight model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.5-airx".to_string(),
@@ -251,6 +371,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM high-speed lightweight variant".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4-32b-0414-128k".to_string(),
@@ -258,6 +379,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM 32B 128K context model".to_string(),
                 input_token_limit: 131_072,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.7-flash".to_string(),
@@ -265,6 +387,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM free fast model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.5-flash".to_string(),
@@ -272,6 +395,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM free balanced model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.6v".to_string(),
@@ -279,6 +403,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM vision model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-ocr".to_string(),
@@ -286,6 +411,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM OCR model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.6v-flashx".to_string(),
@@ -293,6 +419,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM fast vision model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.5v".to_string(),
@@ -300,6 +427,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM vision-balanced model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
             ModelInfo {
                 id: "glm-4.6v-flash".to_string(),
@@ -307,6 +435,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "GLM free fast vision model".to_string(),
                 input_token_limit: 200_000,
                 output_token_limit: 131_072,
+                ..Default::default()
             },
         ],
         "deepseek" => vec![
@@ -316,6 +445,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "DeepSeek V3.2 non-thinking mode (max output 8K)".to_string(),
                 input_token_limit: 131_072,
                 output_token_limit: 8_192,
+                ..Default::default()
             },
             ModelInfo {
                 id: "deepseek-reasoner".to_string(),
@@ -323,6 +453,7 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "DeepSeek V3.2 thinking mode (max output 64K)".to_string(),
                 input_token_limit: 131_072,
                 output_token_limit: 65_536,
+                ..Default::default()
             },
         ],
         "lmstudio" => vec![
@@ -332,12 +463,74 @@ fn curated_models(provider_id: &str) -> Vec<ModelInfo> {
                 description: "Fallback local model entry when LM Studio /v1/models is unavailable.".to_string(),
                 input_token_limit: 0,
                 output_token_limit: 0,
+                ..Default::default()
             },
         ],
         _ => vec![],
     }
 }
 
+/// Fold `other` into `model`, keeping whichever side has each piece of metadata.
+fn merge_model_metadata(model: &mut ModelInfo, other: ModelInfo) {
+    if model.name.is_empty() || (model.name == model.id && other.name != other.id) {
+        model.name = other.name;
+    }
+    if other.description.len() > model.description.len() {
+        model.description = other.description;
+    }
+    model.input_token_limit = model.input_token_limit.max(other.input_token_limit);
+    model.output_token_limit = model.output_token_limit.max(other.output_token_limit);
+}
+
+/// Typical (input, output) limits used when a provider reports 0 for a model.
+fn default_token_limits(provider_id: &str) -> (u32, u32) {
+    match provider_id {
+        "google" => (1_048_576, 65_536),
+        "openai" => (128_000, 16_384),
+        "anthropic" => (200_000, 8_192),
+        "moonshot" => (131_072, 8_192),
+        "deepseek" => (64_000, 8_192),
+        // Local models are often loaded with a small context window.
+        "lmstudio" => (8_192, 2_048),
+        _ => (128_000, 8_192),
+    }
+}
+
+/// Replace unknown (zero) limits with provider defaults. A defaulted output
+/// limit is kept within the context window; a reported one is left as is.
+fn normalize_token_limits(provider_id: &str, model: &mut ModelInfo) {
+    let (default_input, default_output) = default_token_limits(provider_id);
+    if model.input_token_limit == 0 {
+        model.input_token_limit = default_input;
+        model.token_limits_estimated = true;
+    }
+    if model.output_token_limit == 0 {
+        model.output_token_limit = default_output.min(model.input_token_limit);
+        model.token_limits_estimated = true;
+    }
+}
+
+/// Drop duplicate ids, fill unknown token limits and order the list by
+/// display name, so fetched and curated lists look the same in the picker.
+fn normalize_model_list(provider_id: &str, models: Vec<ModelInfo>) -> Vec<ModelInfo> {
+    let mut by_id: HashMap<String, ModelInfo> = HashMap::new();
+    for model in models {
+        match by_id.get_mut(&model.id) {
+            Some(existing) => merge_model_metadata(existing, model),
+            None => {
+                by_id.insert(model.id.clone(), model);
+            }
+        }
+    }
+
+    let mut normalized: Vec<ModelInfo> = by_id.into_values().collect();
+    for model in &mut normalized {
+        normalize_token_limits(provider_id, model);
+    }
+    normalized.sort_by_cached_key(|model| (model.name.to_lowercase(), model.id.clone()));
+    normalized
+}
+
 async fn migrate_legacy_google_api_key_if_needed() -> Result<(), String> {
     let google_account = provider_api_key_account("google")?;
     let current = credentials::credentials_get(
@@ -392,6 +585,7 @@ fn parse_google_models(body: &serde_json::Value) -> Result<Vec<ModelInfo>, Strin
                 description: model["description"].as_str().unwrap_or("").to_string(),
                 input_token_limit: model["inputTokenLimit"].as_u64().unwrap_or(0) as u32,
                 output_token_limit: model["outputTokenLimit"].as_u64().unwrap_or(0) as u32,
+                ..Default::default()
             })
         })
         .collect();
@@ -435,6 +629,7 @@ fn parse_generic_models(body: &serde_json::Value) -> Result<Vec<ModelInfo>, Stri
                 description,
                 input_token_limit: input_limit,
                 output_token_limit: output_limit,
+                ..Default::default()
             })
         })
         .collect();
@@ -442,31 +637,39 @@ fn parse_generic_models(body: &serde_json::Value) -> Result<Vec<ModelInfo>, Stri
     Ok(models)
 }
 
-async fn provider_models_http(
-    provider_id: &str,
-    api_key: &str,
-    base_url: Option<&str>,
-) -> Result<Vec<ModelInfo>, String> {
-    let provider = normalize_provider_id(provider_id)?;
-    if provider == "glm" {
-        return Ok(curated_models(&provider));
-    }
+const MODEL_CACHE_DIR: &str = "models";
+const PROVIDER_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const PROVIDER_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// HTTP client for provider API calls, bounded so an unreachable host can't
+/// hang the settings UI.
+fn provider_http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(PROVIDER_CONNECT_TIMEOUT)
+        .timeout(PROVIDER_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
 
-    let resolved_base = base_url
+fn resolve_provider_base_url(provider: &str, base_url: Option<&str>) -> Result<String, String> {
+    base_url
         .filter(|value| !value.trim().is_empty())
         .map(|value| value.trim().trim_end_matches('/').to_string())
-        .or_else(|| default_base_url(&provider).map(|value| value.to_string()))
-        .ok_or_else(|| format!("No base URL configured for provider {}", provider))?;
-
-    let client = reqwest::Client::new();
+        .or_else(|| default_base_url(provider).map(|value| value.to_string()))
+        .ok_or_else(|| format!("No base URL configured for provider {}", provider))
+}
 
-    let mut request = match provider.as_str() {
+/// The model-listing request for a provider, or `None` when the provider has
+/// no listing endpoint and only curated models are available.
+fn provider_models_request(
+    client: &reqwest::Client,
+    provider: &str,
+    api_key: &str,
+    resolved_base: &str,
+) -> Option<reqwest::RequestBuilder> {
+    let request = match provider {
         "google" => {
-            let url = format!(
-                "{}/v1beta/models?key={}",
-                resolved_base,
-                api_key
-            );
+            let url = format!("{}/v1beta/models?key={}", resolved_base, api_key);
             client.get(url)
         }
         "openai" | "openrouter" | "moonshot" => {
@@ -493,19 +696,65 @@ async fn provider_models_http(
                 .header("x-api-key", api_key)
                 .header("anthropic-version", "2023-06-01")
         }
-        _ => {
-            return Ok(curated_models(&provider));
-        }
+        _ => return None,
     };
+    Some(request.header("content-type", "application/json"))
+}
+
+async fn provider_models_http(
+    provider_id: &str,
+    api_key: &str,
+    base_url: Option<&str>,
+) -> Result<Vec<ModelInfo>, CommandError> {
+    let provider = command_provider_id(provider_id)?;
+    Ok(live_provider_models(&provider, api_key, base_url)
+        .await?
+        .unwrap_or_else(|| curated_models(&provider)))
+}
+
+/// Fetch the provider's model list over HTTP. `None` means the provider has
+/// no usable listing (no endpoint, or an empty list) and curated models apply.
+/// A 401 or 403 is reported as an `auth` error, other failures as `network`.
+async fn live_provider_models(
+    provider: &str,
+    api_key: &str,
+    base_url: Option<&str>,
+) -> Result<Option<Vec<ModelInfo>>, CommandError> {
+    if provider == "glm" {
+        return Ok(None);
+    }
 
-    request = request.header("content-type", "application/json");
-    let response = request.send().await.map_err(|e| e.to_string())?;
+    let resolved_base =
+        resolve_provider_base_url(provider, base_url).map_err(CommandError::validation)?;
+    let client = provider_http_client()?;
+    let request = match provider_models_request(&client, provider, api_key, &resolved_base) {
+        Some(request) => request,
+        None => return Ok(None),
+    };
 
-    if !response.status().is_success() {
-        return Err(format!("Failed to fetch models: {}", response.status()));
+    let response = request.send().await.map_err(|e| {
+        CommandError::network(redact::redact_literal(
+            &e.without_url().to_string(),
+            api_key,
+        ))
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let details = serde_json::json!({ "status": status.as_u16() });
+        let error = match status {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                CommandError::auth(format!("Provider rejected the API key: {}", status))
+            }
+            _ => CommandError::network(format!("Failed to fetch models: {}", status)),
+        };
+        return Err(error.with_details(details));
     }
 
-    let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| CommandError::network(e.to_string()))?;
     let parsed = if provider == "google" {
         parse_google_models(&body)?
     } else {
@@ -513,15 +762,143 @@ async fn provider_models_http(
     };
 
     if parsed.is_empty() {
-        Ok(curated_models(&provider))
+        Ok(None)
     } else {
-        Ok(parsed)
+        Ok(Some(normalize_model_list(provider, parsed)))
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedModelList {
+    fetched_at: i64,
+    #[serde(default)]
+    base_url: String,
+    models: Vec<ModelInfo>,
+}
+
+/// Lists are cached per provider and endpoint, so a custom base URL (a proxy,
+/// a local server) never shows the models of another.
+fn model_cache_file_name(provider: &str, base_url: &str) -> String {
+    let digest = Sha256::digest(base_url.as_bytes());
+    let hash: String = digest[..6]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("{}-{}.json", provider, hash)
+}
+
+fn model_cache_dir() -> Result<PathBuf, String> {
+    Ok(paths::app_data_dir()?.join(MODEL_CACHE_DIR))
+}
+
+fn persist_model_list(provider: &str, base_url: &str, models: &[ModelInfo]) -> Result<i64, String> {
+    let dir = model_cache_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create model cache directory: {}", e))?;
+    let fetched_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    let record = serde_json::json!({
+        "fetchedAt": fetched_at,
+        "baseUrl": base_url,
+        "models": models,
+    });
+    let content = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("Failed to serialize model list: {}", e))?;
+    fs::write(dir.join(model_cache_file_name(provider, base_url)), content)
+        .map_err(|e| format!("Failed to write model cache: {}", e))?;
+    Ok(fetched_at)
+}
+
+fn read_persisted_model_list(path: &Path) -> Option<PersistedModelList> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str::<PersistedModelList>(&content)
+        .ok()
+        .filter(|record| !record.models.is_empty())
+}
+
+fn load_persisted_model_list(
+    dir: &Path,
+    provider: &str,
+    base_url: &str,
+) -> Option<PersistedModelList> {
+    read_persisted_model_list(&dir.join(model_cache_file_name(provider, base_url)))
+        .filter(|record| record.base_url == base_url)
+}
+
+/// The most recently fetched list for a provider, from whichever endpoint.
+fn latest_persisted_model_list(dir: &Path, provider: &str) -> Option<PersistedModelList> {
+    let prefix = format!("{}-", provider);
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .filter_map(|entry| read_persisted_model_list(&entry.path()))
+        .max_by_key(|record| record.fetched_at)
+}
+
+/// Result of checking a session's model id against the provider's known models.
+pub enum ModelCheck {
+    Known(ModelInfo),
+    /// Not in the known list. Still allowed, since providers ship models
+    /// before the cached or curated list catches up.
+    Unknown,
+    /// Nothing to check against: unsupported provider or no known models.
+    Unchecked,
+}
+
+/// Checks `model_id` against the last fetched model list for the provider,
+/// or the curated list when none was fetched. Never touches the network.
+pub fn check_provider_model(provider_id: &str, model_id: &str) -> ModelCheck {
+    let Ok(provider) = normalize_provider_id(provider_id) else {
+        return ModelCheck::Unchecked;
+    };
+    let models = model_cache_dir()
+        .ok()
+        .and_then(|dir| latest_persisted_model_list(&dir, &provider))
+        .map(|record| record.models)
+        .unwrap_or_else(|| curated_models(&provider));
+    match_known_model(models, model_id)
+}
+
+fn match_known_model(models: Vec<ModelInfo>, model_id: &str) -> ModelCheck {
+    if models.is_empty() {
+        return ModelCheck::Unchecked;
+    }
+
+    let wanted = model_id.trim();
+    let wanted = wanted.strip_prefix("models/").unwrap_or(wanted);
+    match models.into_iter().find(|model| model.id == wanted) {
+        Some(model) => ModelCheck::Known(model),
+        None => ModelCheck::Unknown,
+    }
+}
+
+impl FetchedModelList {
+    fn live(models: Vec<ModelInfo>, fetched_at: Option<i64>) -> Self {
+        Self {
+            models,
+            stale: false,
+            fallback: false,
+            fetched_at,
+        }
+    }
+
+    fn curated(models: Vec<ModelInfo>, fallback: bool) -> Self {
+        Self {
+            models,
+            stale: false,
+            fallback,
+            fetched_at: None,
+        }
     }
 }
 
 #[tauri::command]
-pub async fn get_provider_api_key(provider_id: String) -> Result<Option<String>, String> {
-    let provider = normalize_provider_id(&provider_id)?;
+pub async fn get_provider_api_key(provider_id: String) -> Result<Option<String>, CommandError> {
+    let provider = command_provider_id(&provider_id)?;
     if provider == "google" {
         migrate_legacy_google_api_key_if_needed().await?;
     }
@@ -531,10 +908,13 @@ pub async fn get_provider_api_key(provider_id: String) -> Result<Option<String>,
 }
 
 #[tauri::command]
-pub async fn set_provider_api_key(provider_id: String, api_key: String) -> Result<(), String> {
-    let provider = normalize_provider_id(&provider_id)?;
+pub async fn set_provider_api_key(
+    provider_id: String,
+    api_key: String,
+) -> Result<(), CommandError> {
+    let provider = command_provider_id(&provider_id)?;
     if api_key.trim().is_empty() {
-        return Err("API key cannot be empty".to_string());
+        return Err(CommandError::validation("API key cannot be empty"));
     }
 
     let account = provider_api_key_account(&provider)?;
@@ -547,14 +927,14 @@ pub async fn set_provider_api_key(provider_id: String, api_key: String) -> Resul
 }
 
 #[tauri::command]
-pub async fn delete_provider_api_key(provider_id: String) -> Result<(), String> {
-    let provider = normalize_provider_id(&provider_id)?;
+pub async fn delete_provider_api_key(provider_id: String) -> Result<(), CommandError> {
+    let provider = command_provider_id(&provider_id)?;
     let account = provider_api_key_account(&provider)?;
     credentials::credentials_delete(API_KEY_SERVICE.to_string(), account).await
 }
 
 #[tauri::command]
-pub async fn get_google_api_key() -> Result<Option<String>, String> {
+pub async fn get_google_api_key() -> Result<Option<String>, CommandError> {
     credentials::credentials_get(
         API_KEY_SERVICE.to_string(),
         GOOGLE_API_KEY_ACCOUNT.to_string(),
@@ -563,9 +943,9 @@ pub async fn get_google_api_key() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn set_google_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_google_api_key(api_key: String) -> Result<(), CommandError> {
     if api_key.trim().is_empty() {
-        return Err("Google API key cannot be empty".to_string());
+        return Err(CommandError::validation("Google API key cannot be empty"));
     }
 
     credentials::credentials_set(
@@ -577,7 +957,7 @@ pub async fn set_google_api_key(api_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_google_api_key() -> Result<(), String> {
+pub async fn delete_google_api_key() -> Result<(), CommandError> {
     credentials::credentials_delete(
         API_KEY_SERVICE.to_string(),
         GOOGLE_API_KEY_ACCOUNT.to_string(),
@@ -586,7 +966,7 @@ pub async fn delete_google_api_key() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_openai_api_key() -> Result<Option<String>, String> {
+pub async fn get_openai_api_key() -> Result<Option<String>, CommandError> {
     credentials::credentials_get(
         API_KEY_SERVICE.to_string(),
         OPENAI_API_KEY_ACCOUNT.to_string(),
@@ -595,9 +975,9 @@ pub async fn get_openai_api_key() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn set_openai_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_openai_api_key(api_key: String) -> Result<(), CommandError> {
     if api_key.trim().is_empty() {
-        return Err("OpenAI API key cannot be empty".to_string());
+        return Err(CommandError::validation("OpenAI API key cannot be empty"));
     }
 
     credentials::credentials_set(
@@ -609,7 +989,7 @@ pub async fn set_openai_api_key(api_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_openai_api_key() -> Result<(), String> {
+pub async fn delete_openai_api_key() -> Result<(), CommandError> {
     credentials::credentials_delete(
         API_KEY_SERVICE.to_string(),
         OPENAI_API_KEY_ACCOUNT.to_string(),
@@ -618,7 +998,7 @@ pub async fn delete_openai_api_key() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_fal_api_key() -> Result<Option<String>, String> {
+pub async fn get_fal_api_key() -> Result<Option<String>, CommandError> {
     credentials::credentials_get(
         API_KEY_SERVICE.to_string(),
         FAL_API_KEY_ACCOUNT.to_string(),
@@ -627,9 +1007,9 @@ pub async fn get_fal_api_key() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn set_fal_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_fal_api_key(api_key: String) -> Result<(), CommandError> {
     if api_key.trim().is_empty() {
-        return Err("Fal API key cannot be empty".to_string());
+        return Err(CommandError::validation("Fal API key cannot be empty"));
     }
 
     credentials::credentials_set(
@@ -641,7 +1021,7 @@ pub async fn set_fal_api_key(api_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_fal_api_key() -> Result<(), String> {
+pub async fn delete_fal_api_key() -> Result<(), CommandError> {
     credentials::credentials_delete(
         API_KEY_SERVICE.to_string(),
         FAL_API_KEY_ACCOUNT.to_string(),
@@ -650,7 +1030,7 @@ pub async fn delete_fal_api_key() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_exa_api_key() -> Result<Option<String>, String> {
+pub async fn get_exa_api_key() -> Result<Option<String>, CommandError> {
     credentials::credentials_get(
         API_KEY_SERVICE.to_string(),
         EXA_API_KEY_ACCOUNT.to_string(),
@@ -659,9 +1039,9 @@ pub async fn get_exa_api_key() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn set_exa_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_exa_api_key(api_key: String) -> Result<(), CommandError> {
     if api_key.trim().is_empty() {
-        return Err("Exa API key cannot be empty".to_string());
+        return Err(CommandError::validation("Exa API key cannot be empty"));
     }
 
     credentials::credentials_set(
@@ -673,7 +1053,7 @@ pub async fn set_exa_api_key(api_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_exa_api_key() -> Result<(), String> {
+pub async fn delete_exa_api_key() -> Result<(), CommandError> {
     credentials::credentials_delete(
         API_KEY_SERVICE.to_string(),
         EXA_API_KEY_ACCOUNT.to_string(),
@@ -682,7 +1062,7 @@ pub async fn delete_exa_api_key() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_tavily_api_key() -> Result<Option<String>, String> {
+pub async fn get_tavily_api_key() -> Result<Option<String>, CommandError> {
     credentials::credentials_get(
         API_KEY_SERVICE.to_string(),
         TAVILY_API_KEY_ACCOUNT.to_string(),
@@ -691,9 +1071,9 @@ pub async fn get_tavily_api_key() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn set_tavily_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_tavily_api_key(api_key: String) -> Result<(), CommandError> {
     if api_key.trim().is_empty() {
-        return Err("Tavily API key cannot be empty".to_string());
+        return Err(CommandError::validation("Tavily API key cannot be empty"));
     }
 
     credentials::credentials_set(
@@ -705,7 +1085,7 @@ pub async fn set_tavily_api_key(api_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_tavily_api_key() -> Result<(), String> {
+pub async fn delete_tavily_api_key() -> Result<(), CommandError> {
     credentials::credentials_delete(
         API_KEY_SERVICE.to_string(),
         TAVILY_API_KEY_ACCOUNT.to_string(),
@@ -714,7 +1094,7 @@ pub async fn delete_tavily_api_key() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_stitch_api_key() -> Result<Option<String>, String> {
+pub async fn get_stitch_api_key() -> Result<Option<String>, CommandError> {
     credentials::credentials_get(
         API_KEY_SERVICE.to_string(),
         STITCH_API_KEY_ACCOUNT.to_string(),
@@ -723,9 +1103,9 @@ pub async fn get_stitch_api_key() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn set_stitch_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_stitch_api_key(api_key: String) -> Result<(), CommandError> {
     if api_key.trim().is_empty() {
-        return Err("Stitch API key cannot be empty".to_string());
+        return Err(CommandError::validation("Stitch API key cannot be empty"));
     }
 
     credentials::credentials_set(
@@ -737,7 +1117,7 @@ pub async fn set_stitch_api_key(api_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn delete_stitch_api_key() -> Result<(), String> {
+pub async fn delete_stitch_api_key() -> Result<(), CommandError> {
     credentials::credentials_delete(
         API_KEY_SERVICE.to_string(),
         STITCH_API_KEY_ACCOUNT.to_string(),
@@ -745,8 +1125,9 @@ pub async fn delete_stitch_api_key() -> Result<(), String> {
     .await
 }
 
-#[tauri::command]
-pub async fn auth_logout_and_cleanup() -> Result<LogoutCleanupResult, String> {
+/// Every credential account a full logout clears; shared with the preview so
+/// the two can't drift apart.
+fn logout_credential_accounts() -> Result<Vec<String>, String> {
     let mut accounts_to_clear = vec![
         LEGACY_API_KEY_ACCOUNT.to_string(),
         GOOGLE_API_KEY_ACCOUNT.to_string(),
@@ -760,32 +1141,198 @@ pub async fn auth_logout_and_cleanup() -> Result<LogoutCleanupResult, String> {
     for provider_id in PROVIDER_IDS {
         accounts_to_clear.push(provider_api_key_account(provider_id)?);
     }
+    Ok(accounts_to_clear)
+}
+
+/// Top-level entries Cowork creates in its data directory. The directory
+/// can be relocated to a shared or user-chosen folder, so logout removes
+/// only these and never the directory wholesale.
+const COWORK_DATA_ENTRIES: &[&str] = &[
+    ".wwebjs_cache",
+    "checkpoints",
+    "commands",
+    "connectors",
+    "cron",
+    "daemon",
+    "data.db",
+    "data.db-shm",
+    "data.db-wal",
+    "diagnostics",
+    "integrations",
+    "logs",
+    MODEL_CACHE_DIR,
+    "remote-access",
+    "runtime",
+    "security",
+    "service",
+    "sessions",
+    "skills",
+    "souls",
+    "subagents",
+    "updater",
+    "workspaces",
+];
+
+/// Kept by logout: under `COWORK_DATA_DIR` this holds the credential vault,
+/// which is cleared account by account instead.
+const LOGOUT_KEPT_DATA_ENTRIES: &[&str] = &["config"];
+
+fn logout_data_dir() -> Result<PathBuf, String> {
+    paths::app_data_dir()
+}
+
+/// Split `data_dir` into the Cowork entries logout removes and the names of
+/// everything else, which it leaves alone.
+fn logout_data_entries(data_dir: &Path) -> Result<(Vec<PathBuf>, Vec<String>), String> {
+    let entries = fs::read_dir(data_dir)
+        .map_err(|e| format!("Failed to read {}: {}", data_dir.display(), e))?;
+    let mut owned = Vec::new();
+    let mut kept = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if COWORK_DATA_ENTRIES.contains(&name.as_str()) {
+            owned.push(entry.path());
+        } else if !LOGOUT_KEPT_DATA_ENTRIES.contains(&name.as_str()) {
+            kept.push(name);
+        }
+    }
+    owned.sort();
+    kept.sort();
+    Ok((owned, kept))
+}
+
+/// Remove Cowork's entries, then the directory itself if that left it empty.
+/// Returns whether the directory was removed and what was kept.
+fn remove_logout_data(data_dir: &Path) -> Result<(bool, Vec<String>), String> {
+    let (owned, kept) = logout_data_entries(data_dir)?;
+    for path in owned {
+        // Symlinks are unlinked, never followed.
+        let is_dir = fs::symlink_metadata(&path)
+            .map(|metadata| metadata.is_dir())
+            .unwrap_or(false);
+        let result = if is_dir {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        result.map_err(|error| format!("Failed to remove {}: {}", path.display(), error))?;
+    }
+    let removed_data_dir = kept.is_empty() && fs::remove_dir(data_dir).is_ok();
+    if !kept.is_empty() {
+        log::warn!(
+            "Logout left {} entries Cowork did not create in {}: {}",
+            kept.len(),
+            data_dir.display(),
+            kept.join(", ")
+        );
+    }
+    Ok((removed_data_dir, kept))
+}
+
+/// Total size of regular files under `path`. Symlinks are not followed.
+fn directory_size_bytes(path: &Path) -> u64 {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size_bytes(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Show what `auth_logout_and_cleanup` would delete without deleting anything.
+#[tauri::command]
+pub async fn auth_logout_preview() -> Result<LogoutPreview, String> {
+    let credential_accounts = logout_credential_accounts()?;
+    let data_dir = logout_data_dir()?;
+    let data_dir_exists = data_dir.exists();
+    let (data_dir_size_bytes, kept_entries) = if data_dir_exists {
+        let (owned, kept) = logout_data_entries(&data_dir)?;
+        let size = tauri::async_runtime::spawn_blocking(move || {
+            owned
+                .iter()
+                .map(|path| match fs::symlink_metadata(path) {
+                    Ok(metadata) if metadata.is_dir() => directory_size_bytes(path),
+                    Ok(metadata) if metadata.is_file() => metadata.len(),
+                    _ => 0,
+                })
+                .sum()
+        })
+        .await
+        .map_err(|e| format!("Failed to measure {}: {}", data_dir.display(), e))?;
+        (size, kept)
+    } else {
+        (0, Vec::new())
+    };
+
+    Ok(LogoutPreview {
+        credential_accounts,
+        data_dir_path: data_dir.to_string_lossy().to_string(),
+        data_dir_exists,
+        data_dir_size_bytes,
+        kept_entries,
+    })
+}
 
+#[tauri::command]
+pub async fn auth_logout_and_cleanup() -> Result<LogoutCleanupResult, String> {
+    let accounts_to_clear = logout_credential_accounts()?;
     for account in &accounts_to_clear {
         credentials::credentials_delete(API_KEY_SERVICE.to_string(), account.clone()).await?;
     }
 
-    let home_dir = dirs::home_dir().ok_or("Could not determine home directory".to_string())?;
-    let data_dir = home_dir.join(".cowork");
+    let data_dir = logout_data_dir()?;
     let data_dir_path = data_dir.to_string_lossy().to_string();
-    let removed_data_dir = if data_dir.exists() {
-        fs::remove_dir_all(&data_dir)
-            .map_err(|error| format!("Failed to remove {}: {}", data_dir_path, error))?;
-        true
+    let (removed_data_dir, kept_entries) = if data_dir.exists() {
+        remove_logout_data(&data_dir)?
     } else {
-        false
+        (false, Vec::new())
     };
 
     Ok(LogoutCleanupResult {
         removed_data_dir,
+        kept_entries,
         data_dir_path,
         cleared_credential_accounts: accounts_to_clear.len(),
     })
 }
 
+/// Delete the API keys of the given providers only, leaving app data and
+/// every other credential in place. Returns the number of providers cleared.
+#[tauri::command]
+pub async fn auth_clear_provider_keys(provider_ids: Vec<String>) -> Result<usize, String> {
+    // Validate everything up front so a typo doesn't leave a half-cleared set.
+    let mut providers = Vec::new();
+    for provider_id in &provider_ids {
+        let provider = normalize_provider_id(provider_id)?;
+        if !providers.contains(&provider) {
+            providers.push(provider);
+        }
+    }
+
+    for provider in &providers {
+        delete_provider_api_key(provider.clone()).await?;
+        if provider == "google" {
+            // Otherwise the legacy key would be migrated back on next read.
+            credentials::credentials_delete(
+                API_KEY_SERVICE.to_string(),
+                LEGACY_API_KEY_ACCOUNT.to_string(),
+            )
+            .await?;
+        }
+    }
+
+    Ok(providers.len())
+}
+
 #[tauri::command]
 pub async fn auth_get_security_posture() -> Result<SecurityPostureStatus, String> {
-    let config_root = dirs::config_dir().ok_or("Could not determine config directory".to_string())?;
+    let config_root = paths::config_root()?;
     let current_config_dir = config_root.join("cowork");
     let legacy_config_dir = config_root.join("cowork");
 
@@ -804,10 +1351,10 @@ pub async fn auth_get_security_posture() -> Result<SecurityPostureStatus, String
     let mut provider_keys_configured = 0usize;
     for provider_id in PROVIDER_IDS {
         let account = provider_api_key_account(provider_id)?;
-        if credentials::credentials_get(API_KEY_SERVICE.to_string(), account)
-            .await?
-            .is_some()
-        {
+        if matches!(
+            credentials::credentials_lookup(API_KEY_SERVICE.to_string(), account).await,
+            Ok(Some(_))
+        ) {
             provider_keys_configured += 1;
         }
     }
@@ -822,18 +1369,18 @@ pub async fn auth_get_security_posture() -> Result<SecurityPostureStatus, String
     ];
     let mut auxiliary_keys_configured = 0usize;
     for account in auxiliary_accounts {
-        if credentials::credentials_get(API_KEY_SERVICE.to_string(), account.to_string())
-            .await?
-            .is_some()
-        {
+        if matches!(
+            credentials::credentials_lookup(API_KEY_SERVICE.to_string(), account.to_string()).await,
+            Ok(Some(_))
+        ) {
             auxiliary_keys_configured += 1;
         }
     }
 
     let secure_seed_available = credentials::get_or_create_sidecar_connector_seed().is_ok();
+    let undecryptable_credential_entries = credentials::undecryptable_vault_entries()?;
 
-    let home_dir = dirs::home_dir().ok_or("Could not determine home directory".to_string())?;
-    let audit_log_path = home_dir.join(".cowork").join("security").join("audit.log");
+    let audit_log_path = paths::app_data_dir()?.join("security").join("audit.log");
     let (audit_log_present, audit_log_size_bytes) = match fs::metadata(&audit_log_path) {
         Ok(metadata) => (true, metadata.len()),
         Err(_) => (false, 0),
@@ -841,6 +1388,8 @@ pub async fn auth_get_security_posture() -> Result<SecurityPostureStatus, String
 
     Ok(SecurityPostureStatus {
         credential_backend: credentials::credential_backend_label().to_string(),
+        credential_backend_source: credentials::credential_backend_source().to_string(),
+        keychain_available: credentials::keychain_is_available(),
         secure_seed_available,
         credentials_vault_present,
         connector_vault_present,
@@ -851,16 +1400,70 @@ pub async fn auth_get_security_posture() -> Result<SecurityPostureStatus, String
         auxiliary_keys_configured,
         audit_log_present,
         audit_log_size_bytes,
+        undecryptable_credential_entries,
     })
 }
 
+/// Remove plaintext credential leftovers flagged by the security posture.
+/// Safe to run repeatedly; a clean posture is returned unchanged.
+#[tauri::command]
+pub async fn auth_repair_security_posture(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+) -> Result<SecurityPostureRepair, String> {
+    let before = auth_get_security_posture().await?;
+    let mut repairs = Vec::new();
+
+    let keychain_migrated = credentials::credentials_migrate_on_startup()?;
+    if before.plaintext_credentials_present {
+        repairs.push("Moved plaintext credentials into secure storage".to_string());
+    }
+    if keychain_migrated > 0 {
+        repairs.push(format!(
+            "Moved {} vault entries into the OS keychain",
+            keychain_migrated
+        ));
+    }
+
+    // Connector secrets are owned by the sidecar, which migrates them itself.
+    if before.plaintext_connector_secrets_present {
+        ensure_sidecar_started_public(&app, &state).await?;
+        let result = state
+            .manager
+            .send_command("migrate_connector_secrets", serde_json::json!({}))
+            .await
+            .map_err(|e| format!("Failed to migrate connector secrets: {}", e))?;
+        if result["migrated"].as_bool().unwrap_or(false) {
+            repairs.push("Moved plaintext connector secrets into the vault".to_string());
+        }
+    }
+
+    let posture = auth_get_security_posture().await?;
+    if !repairs.is_empty() {
+        log::info!("Security posture repaired: {}", repairs.join("; "));
+    }
+    Ok(SecurityPostureRepair { repairs, posture })
+}
+
+#[tauri::command]
+pub async fn auth_rekey_credentials(
+    previous_identity: Option<credentials::MachineIdentity>,
+) -> Result<credentials::CredentialRekeyResult, String> {
+    credentials::credentials_rekey(previous_identity).await
+}
+
+#[tauri::command]
+pub async fn auth_migrate_credentials_to_keychain() -> Result<usize, String> {
+    credentials::credentials_migrate_to_keychain()
+}
+
 #[tauri::command]
 pub async fn validate_provider_connection(
     provider_id: String,
     api_key: String,
     base_url: Option<String>,
-) -> Result<bool, String> {
-    let provider = normalize_provider_id(&provider_id)?;
+) -> Result<bool, CommandError> {
+    let provider = command_provider_id(&provider_id)?;
     if api_key.trim().is_empty() && provider != "lmstudio" {
         return Ok(false);
     }
@@ -879,14 +1482,7 @@ pub async fn validate_provider_connection(
         let result = provider_models_http(&provider, api_key.trim(), base_url.as_deref()).await;
         return match result {
             Ok(_) => Ok(true),
-            Err(error) => {
-                let lower = error.to_lowercase();
-                let auth_failed = lower.contains("401")
-                    || lower.contains("403")
-                    || lower.contains("unauthorized")
-                    || lower.contains("forbidden");
-                Ok(!auth_failed)
-            }
+            Err(error) => Ok(error.code() != ErrorCode::Auth),
         };
     }
 
@@ -894,28 +1490,362 @@ pub async fn validate_provider_connection(
     Ok(result.is_ok())
 }
 
+fn diagnostic_step(
+    name: &str,
+    started: Instant,
+    result: Result<Option<String>, String>,
+) -> ProviderDiagnosticStep {
+    let (status, detail) = match result {
+        Ok(detail) => (DiagnosticStepStatus::Ok, detail),
+        Err(detail) => (DiagnosticStepStatus::Failed, Some(detail)),
+    };
+    ProviderDiagnosticStep {
+        name: name.to_string(),
+        status,
+        detail,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn skipped_step(name: &str, reason: &str) -> ProviderDiagnosticStep {
+    ProviderDiagnosticStep {
+        name: name.to_string(),
+        status: DiagnosticStepStatus::Skipped,
+        detail: Some(reason.to_string()),
+        duration_ms: 0,
+    }
+}
+
+/// What a model listing response says about the key, and the summary to show.
+/// `model_count` is only read for successful responses.
+fn classify_provider_response(
+    provider: &str,
+    status: u16,
+    has_key: bool,
+    model_count: Option<usize>,
+) -> (ProviderAuthStatus, String) {
+    let rejected = if has_key {
+        ProviderAuthStatus::Rejected
+    } else {
+        ProviderAuthStatus::MissingKey
+    };
+    let missing_or_unknown = if has_key || provider == "lmstudio" {
+        ProviderAuthStatus::Unknown
+    } else {
+        ProviderAuthStatus::MissingKey
+    };
+    match status {
+        200..=299 => (
+            ProviderAuthStatus::Accepted,
+            match model_count {
+                Some(count) => format!("Connected; {} model(s) available.", count),
+                None => "Connected, but the model list could not be parsed.".to_string(),
+            },
+        ),
+        401 | 403 => (rejected, "The provider rejected the API key.".to_string()),
+        // Google reports an invalid key as 400.
+        400 if provider == "google" => (rejected, "The provider rejected the API key.".to_string()),
+        404 => (
+            missing_or_unknown,
+            "The models endpoint was not found. Check the base URL.".to_string(),
+        ),
+        429 => (
+            ProviderAuthStatus::RateLimited,
+            "The provider is rate limiting requests. Try again later.".to_string(),
+        ),
+        500..=599 => (
+            missing_or_unknown,
+            "The provider returned a server error; it may be having an outage.".to_string(),
+        ),
+        _ => (
+            missing_or_unknown,
+            format!("Unexpected response from the provider: {}", status),
+        ),
+    }
+}
+
+/// Walk the path to a provider step by step (DNS, TCP, TLS, HTTP, auth) and
+/// report where it breaks. The API key never appears in the report.
+#[tauri::command]
+pub async fn diagnose_provider(
+    provider_id: String,
+    api_key: Option<String>,
+    base_url: Option<String>,
+) -> Result<ProviderDiagnostics, String> {
+    let provider = normalize_provider_id(&provider_id)?;
+    let api_key = match api_key.filter(|key| !key.trim().is_empty()) {
+        Some(key) => key.trim().to_string(),
+        None => {
+            let account = provider_api_key_account(&provider)?;
+            credentials::credentials_get(API_KEY_SERVICE.to_string(), account)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        }
+    };
+    let has_key = !api_key.is_empty();
+    let resolved_base = resolve_provider_base_url(&provider, base_url.as_deref())?;
+    let mut report = ProviderDiagnostics {
+        provider: provider.clone(),
+        base_url: resolved_base.clone(),
+        steps: Vec::new(),
+        http_status: None,
+        auth: if has_key || provider == "lmstudio" {
+            ProviderAuthStatus::Unknown
+        } else {
+            ProviderAuthStatus::MissingKey
+        },
+        model_count: None,
+        summary: String::new(),
+    };
+
+    let url = match reqwest::Url::parse(&resolved_base) {
+        Ok(url) => url,
+        Err(error) => {
+            report.summary = format!("Base URL is not a valid URL: {}", error);
+            return Ok(report);
+        }
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let is_https = url.scheme() == "https";
+
+    // DNS
+    let started = Instant::now();
+    let lookup = tokio::time::timeout(
+        PROVIDER_CONNECT_TIMEOUT,
+        tokio::net::lookup_host((host.as_str(), port)),
+    )
+    .await;
+    let addresses: Vec<std::net::SocketAddr> = match lookup {
+        Ok(Ok(addresses)) => addresses.collect(),
+        Ok(Err(error)) => {
+            report
+                .steps
+                .push(diagnostic_step("dns", started, Err(error.to_string())));
+            report.summary = format!(
+                "Could not resolve {}. Check the base URL and your network.",
+                host
+            );
+            return Ok(report);
+        }
+        Err(_) => {
+            report.steps.push(diagnostic_step(
+                "dns",
+                started,
+                Err("Timed out".to_string()),
+            ));
+            report.summary = format!("DNS lookup for {} timed out.", host);
+            return Ok(report);
+        }
+    };
+    report.steps.push(diagnostic_step(
+        "dns",
+        started,
+        Ok(Some(format!("{} address(es)", addresses.len()))),
+    ));
+
+    // TCP connect
+    let started = Instant::now();
+    let mut connect_error = "No addresses to connect to".to_string();
+    let mut connected = None;
+    for address in &addresses {
+        match tokio::time::timeout(
+            PROVIDER_CONNECT_TIMEOUT,
+            tokio::net::TcpStream::connect(address),
+        )
+        .await
+        {
+            Ok(Ok(stream)) => {
+                connected = Some((*address, stream));
+                break;
+            }
+            Ok(Err(error)) => connect_error = error.to_string(),
+            Err(_) => connect_error = format!("Timed out connecting to {}", address),
+        }
+    }
+    let stream = match connected {
+        Some((address, stream)) => {
+            report.steps.push(diagnostic_step(
+                "connect",
+                started,
+                Ok(Some(address.to_string())),
+            ));
+            stream
+        }
+        None => {
+            report
+                .steps
+                .push(diagnostic_step("connect", started, Err(connect_error)));
+            report.summary = format!(
+                "Could not connect to {}:{}. The service may be down or blocked by a firewall or proxy.",
+                host, port
+            );
+            return Ok(report);
+        }
+    };
+
+    // TLS handshake on the connection just opened, so a certificate or
+    // interception problem is told apart from a slow or failing API.
+    if is_https {
+        let started = Instant::now();
+        let handshake = match native_tls::TlsConnector::new() {
+            Ok(connector) => tokio::time::timeout(
+                PROVIDER_CONNECT_TIMEOUT,
+                tokio_native_tls::TlsConnector::from(connector).connect(&host, stream),
+            )
+            .await
+            .map_err(|_| "Timed out".to_string())
+            .and_then(|result| result.map_err(|error| error.to_string())),
+            Err(error) => Err(error.to_string()),
+        };
+        match handshake {
+            Ok(_) => report.steps.push(diagnostic_step("tls", started, Ok(None))),
+            Err(detail) => {
+                report
+                    .steps
+                    .push(diagnostic_step("tls", started, Err(detail)));
+                report.summary =
+                    "TLS handshake failed. A proxy or antivirus may be intercepting HTTPS traffic."
+                        .to_string();
+                return Ok(report);
+            }
+        }
+    } else {
+        drop(stream);
+        report
+            .steps
+            .push(skipped_step("tls", "Base URL uses plain HTTP"));
+    }
+
+    let client = provider_http_client()?;
+    let request = match provider_models_request(&client, &provider, &api_key, &resolved_base) {
+        Some(request) => request,
+        None => {
+            report.steps.push(skipped_step(
+                "http",
+                "Provider has no model listing endpoint",
+            ));
+            report.summary =
+                "Host is reachable; this provider has no endpoint to verify the API key against."
+                    .to_string();
+            return Ok(report);
+        }
+    };
+
+    let started = Instant::now();
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(error) => {
+            let timed_out = error.is_timeout();
+            let detail = redact::redact_literal(&error.without_url().to_string(), &api_key);
+            report
+                .steps
+                .push(diagnostic_step("http", started, Err(detail)));
+            report.summary = if timed_out {
+                "The provider did not respond in time.".to_string()
+            } else {
+                "The request to the provider failed.".to_string()
+            };
+            return Ok(report);
+        }
+    };
+    let status = response.status();
+    report.http_status = Some(status.as_u16());
+    report.steps.push(diagnostic_step(
+        "http",
+        started,
+        if status.is_success() {
+            Ok(Some(status.to_string()))
+        } else {
+            Err(status.to_string())
+        },
+    ));
+
+    if status.is_success() {
+        let body: Result<serde_json::Value, _> = response.json().await;
+        report.model_count = body
+            .ok()
+            .and_then(|body| {
+                if provider == "google" {
+                    parse_google_models(&body).ok()
+                } else {
+                    parse_generic_models(&body).ok()
+                }
+            })
+            .as_ref()
+            .map(Vec::len);
+    }
+    let (auth, summary) =
+        classify_provider_response(&provider, status.as_u16(), has_key, report.model_count);
+    report.auth = auth;
+    report.summary = summary;
+    Ok(report)
+}
+
+/// Built-in model list for a provider, available before any key is entered.
+/// Purely local; never touches the network.
+#[tauri::command]
+pub async fn get_curated_models(provider_id: String) -> Result<Vec<ModelInfo>, CommandError> {
+    let provider = command_provider_id(&provider_id)?;
+    Ok(curated_models(&provider))
+}
+
+/// Fetch a provider's live model list. If the fetch fails, the last list
+/// fetched from the same base URL (`stale`) or the curated one (`fallback`) is
+/// returned instead, unless `strict` is set.
 #[tauri::command]
 pub async fn fetch_provider_models(
     provider_id: String,
     api_key: String,
     base_url: Option<String>,
-) -> Result<Vec<ModelInfo>, String> {
-    let provider = normalize_provider_id(&provider_id)?;
+    strict: Option<bool>,
+) -> Result<FetchedModelList, CommandError> {
+    let provider = command_provider_id(&provider_id)?;
     if api_key.trim().is_empty() && provider != "lmstudio" {
-        return Ok(curated_models(&provider));
+        return Ok(FetchedModelList::curated(curated_models(&provider), false));
     }
-
-    match provider_models_http(&provider, api_key.trim(), base_url.as_deref()).await {
-        Ok(models) => Ok(models),
+    let cache_base_url =
+        resolve_provider_base_url(&provider, base_url.as_deref()).unwrap_or_default();
+
+    match live_provider_models(&provider, api_key.trim(), base_url.as_deref()).await {
+        Ok(Some(models)) => {
+            let fetched_at = match persist_model_list(&provider, &cache_base_url, &models) {
+                Ok(fetched_at) => Some(fetched_at),
+                Err(error) => {
+                    log::warn!("Failed to persist models for {}: {}", provider, error);
+                    None
+                }
+            };
+            Ok(FetchedModelList::live(models, fetched_at))
+        }
+        Ok(None) => Ok(FetchedModelList::curated(curated_models(&provider), false)),
+        Err(error) if strict.unwrap_or(false) => Err(error),
         Err(error) => {
-            if provider == "moonshot" || provider == "deepseek" || provider == "lmstudio" {
-                eprintln!(
-                    "[auth::fetch_provider_models] Falling back to curated models for {}: {}",
-                    provider, error
+            let cached = model_cache_dir()
+                .ok()
+                .and_then(|dir| load_persisted_model_list(&dir, &provider, &cache_base_url));
+            if let Some(record) = cached {
+                log::warn!(
+                    "Using models last fetched for {} at {}: {}",
+                    provider,
+                    record.fetched_at,
+                    error
                 );
-                return Ok(curated_models(&provider));
+                return Ok(FetchedModelList {
+                    models: record.models,
+                    stale: true,
+                    fallback: false,
+                    fetched_at: Some(record.fetched_at),
+                });
             }
-            Err(error)
+            let curated = curated_models(&provider);
+            if curated.is_empty() {
+                return Err(error);
+            }
+            log::warn!("Falling back to curated models for {}: {}", provider, error);
+            Ok(FetchedModelList::curated(curated, true))
         }
     }
 }
@@ -925,26 +1855,403 @@ pub async fn fetch_provider_models(
 // ---------------------------------------------------------------------------
 
 #[tauri::command]
-pub async fn get_api_key() -> Result<Option<String>, String> {
+pub async fn get_api_key() -> Result<Option<String>, CommandError> {
     get_provider_api_key("google".to_string()).await
 }
 
 #[tauri::command]
-pub async fn set_api_key(api_key: String) -> Result<(), String> {
+pub async fn set_api_key(api_key: String) -> Result<(), CommandError> {
     set_provider_api_key("google".to_string(), api_key).await
 }
 
 #[tauri::command]
-pub async fn delete_api_key() -> Result<(), String> {
+pub async fn delete_api_key() -> Result<(), CommandError> {
     delete_provider_api_key("google".to_string()).await
 }
 
 #[tauri::command]
-pub async fn validate_api_key(api_key: String) -> Result<bool, String> {
+pub async fn validate_api_key(api_key: String) -> Result<bool, CommandError> {
     validate_provider_connection("google".to_string(), api_key, None).await
 }
 
 #[tauri::command]
-pub async fn fetch_models(api_key: String) -> Result<Vec<ModelInfo>, String> {
-    fetch_provider_models("google".to_string(), api_key, None).await
+pub async fn fetch_models(
+    api_key: String,
+    strict: Option<bool>,
+) -> Result<FetchedModelList, CommandError> {
+    fetch_provider_models("google".to_string(), api_key, None, strict).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(models: &[ModelInfo]) -> Vec<&str> {
+        models.iter().map(|model| model.id.as_str()).collect()
+    }
+
+    #[test]
+    fn generic_duplicates_keep_richest_metadata() {
+        let body = json!({
+            "data": [
+                { "id": "openai/gpt-4o", "context_window": 128000 },
+                { "id": "anthropic/claude-sonnet", "name": "Claude Sonnet" },
+                {
+                    "id": "openai/gpt-4o",
+                    "name": "GPT-4o",
+                    "description": "Multimodal flagship",
+                    "max_output_tokens": 16384
+                },
+                { "id": "anthropic/claude-sonnet", "description": "Short" }
+            ]
+        });
+        let models = normalize_model_list("openrouter", parse_generic_models(&body).unwrap());
+
+        assert_eq!(
+            ids(&models),
+            vec!["anthropic/claude-sonnet", "openai/gpt-4o"]
+        );
+        let gpt = &models[1];
+        assert_eq!(gpt.name, "GPT-4o");
+        assert_eq!(gpt.description, "Multimodal flagship");
+        assert_eq!(gpt.input_token_limit, 128000);
+        assert_eq!(gpt.output_token_limit, 16384);
+        assert_eq!(models[0].name, "Claude Sonnet");
+        assert_eq!(models[0].description, "Short");
+    }
+
+    #[test]
+    fn google_duplicates_are_folded_and_sorted_by_name() {
+        let body = json!({
+            "models": [
+                {
+                    "name": "models/gemini-2.5-pro",
+                    "displayName": "Gemini 2.5 Pro",
+                    "supportedGenerationMethods": ["generateContent"],
+                    "inputTokenLimit": 1048576
+                },
+                {
+                    "name": "models/gemini-2.5-flash",
+                    "displayName": "Gemini 2.5 Flash",
+                    "supportedGenerationMethods": ["generateContent"]
+                },
+                {
+                    "name": "models/gemini-2.5-pro",
+                    "supportedGenerationMethods": ["generateContent"],
+                    "outputTokenLimit": 65536
+                },
+                {
+                    "name": "models/text-embedding-004",
+                    "supportedGenerationMethods": ["embedContent"]
+                }
+            ]
+        });
+        let models = normalize_model_list("google", parse_google_models(&body).unwrap());
+
+        assert_eq!(ids(&models), vec!["gemini-2.5-flash", "gemini-2.5-pro"]);
+        assert_eq!(models[1].name, "Gemini 2.5 Pro");
+        assert_eq!(models[1].input_token_limit, 1048576);
+        assert_eq!(models[1].output_token_limit, 65536);
+    }
+
+    #[test]
+    fn curated_models_are_unique_and_sorted() {
+        for provider in ["google", "openai", "anthropic", "openrouter", "deepseek"] {
+            let models = curated_models(provider);
+            let mut unique = ids(&models);
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), models.len(), "{}", provider);
+
+            let names: Vec<String> = models.iter().map(|m| m.name.to_lowercase()).collect();
+            let mut sorted = names.clone();
+            sorted.sort();
+            assert_eq!(names, sorted, "{}", provider);
+        }
+    }
+
+    #[test]
+    fn model_check_distinguishes_known_unknown_and_unchecked() {
+        let models = curated_models("google");
+        match match_known_model(models.clone(), " models/gemini-3-pro-preview ") {
+            ModelCheck::Known(model) => assert_eq!(model.name, "Gemini 3 Pro Preview"),
+            _ => panic!("curated google model should be known"),
+        }
+        assert!(matches!(
+            match_known_model(models, "gemini-9-ultra"),
+            ModelCheck::Unknown
+        ));
+        assert!(matches!(
+            match_known_model(Vec::new(), "gemini-3-pro-preview"),
+            ModelCheck::Unchecked
+        ));
+        assert!(matches!(
+            check_provider_model("not-a-provider", "gpt-5.2"),
+            ModelCheck::Unchecked
+        ));
+    }
+
+    fn logout_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-logout-{}-{}-{}",
+            name,
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos())
+                .unwrap_or(0)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn logout_removes_only_cowork_entries() {
+        let dir = logout_dir("shared");
+        fs::create_dir_all(dir.join("sessions").join("s1")).unwrap();
+        fs::write(dir.join("sessions").join("s1").join("chat.json"), "{}").unwrap();
+        fs::write(dir.join("data.db"), "db").unwrap();
+        fs::create_dir_all(dir.join("config").join("cowork")).unwrap();
+        fs::write(dir.join("config").join("cowork").join("vault"), "v").unwrap();
+        fs::create_dir_all(dir.join("Documents")).unwrap();
+        fs::write(dir.join("notes.txt"), "mine").unwrap();
+
+        let (removed, kept) = remove_logout_data(&dir).unwrap();
+        assert!(!removed);
+        assert_eq!(kept, vec!["Documents".to_string(), "notes.txt".to_string()]);
+        assert!(!dir.join("sessions").exists());
+        assert!(!dir.join("data.db").exists());
+        assert!(dir.join("config").join("cowork").join("vault").exists());
+        assert!(dir.join("notes.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn logout_removes_a_directory_holding_only_cowork_entries() {
+        let dir = logout_dir("owned");
+        fs::create_dir_all(dir.join("logs")).unwrap();
+        fs::write(dir.join("logs").join("app.log"), "line").unwrap();
+        assert_eq!(remove_logout_data(&dir).unwrap(), (true, Vec::new()));
+        assert!(!dir.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn logout_unlinks_symlinked_entries_without_following_them() {
+        let dir = logout_dir("link");
+        let target = logout_dir("link-target");
+        fs::write(target.join("keep.txt"), "keep").unwrap();
+        std::os::unix::fs::symlink(&target, dir.join("skills")).unwrap();
+
+        assert_eq!(remove_logout_data(&dir).unwrap(), (true, Vec::new()));
+        assert!(target.join("keep.txt").exists());
+        fs::remove_dir_all(target).unwrap();
+    }
+
+    #[test]
+    fn provider_responses_are_classified() {
+        assert_eq!(
+            classify_provider_response("openai", 200, true, Some(12)),
+            (
+                ProviderAuthStatus::Accepted,
+                "Connected; 12 model(s) available.".to_string()
+            )
+        );
+        assert_eq!(
+            classify_provider_response("openai", 200, true, None).1,
+            "Connected, but the model list could not be parsed."
+        );
+        assert_eq!(
+            classify_provider_response("openai", 401, true, None).0,
+            ProviderAuthStatus::Rejected
+        );
+        assert_eq!(
+            classify_provider_response("anthropic", 403, false, None).0,
+            ProviderAuthStatus::MissingKey
+        );
+        assert_eq!(
+            classify_provider_response("google", 400, true, None).0,
+            ProviderAuthStatus::Rejected
+        );
+        assert_eq!(
+            classify_provider_response("openai", 400, true, None).0,
+            ProviderAuthStatus::Unknown
+        );
+        assert_eq!(
+            classify_provider_response("openai", 429, true, None).0,
+            ProviderAuthStatus::RateLimited
+        );
+        assert_eq!(
+            classify_provider_response("openai", 503, false, None).0,
+            ProviderAuthStatus::MissingKey
+        );
+        assert_eq!(
+            classify_provider_response("lmstudio", 404, false, None),
+            (
+                ProviderAuthStatus::Unknown,
+                "The models endpoint was not found. Check the base URL.".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn diagnostic_steps_serialize_typed_statuses() {
+        let failed = diagnostic_step("tls", Instant::now(), Err("bad certificate".to_string()));
+        let skipped = skipped_step("http", "Provider has no model listing endpoint");
+        let ok = diagnostic_step("dns", Instant::now(), Ok(None));
+
+        assert_eq!(serde_json::to_value(&failed).unwrap()["status"], "failed");
+        assert_eq!(serde_json::to_value(&skipped).unwrap()["status"], "skipped");
+        assert_eq!(serde_json::to_value(&ok).unwrap()["status"], "ok");
+        assert_eq!(
+            serde_json::to_value(ProviderAuthStatus::RateLimited).unwrap(),
+            "rate_limited"
+        );
+        assert_eq!(skipped.duration_ms, 0);
+    }
+
+    fn model(id: &str, input: u32, output: u32) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            input_token_limit: input,
+            output_token_limit: output,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unknown_token_limits_are_filled_with_provider_defaults() {
+        let mut unknown = model("claude-x", 0, 0);
+        normalize_token_limits("anthropic", &mut unknown);
+        assert_eq!(
+            (unknown.input_token_limit, unknown.output_token_limit),
+            (200_000, 8_192)
+        );
+        assert!(unknown.token_limits_estimated);
+
+        // A defaulted output limit never exceeds a small reported context.
+        let mut small = model("tiny", 4_096, 0);
+        normalize_token_limits("openai", &mut small);
+        assert_eq!(small.output_token_limit, 4_096);
+        assert!(small.token_limits_estimated);
+    }
+
+    #[test]
+    fn reported_token_limits_are_kept() {
+        let mut known = model("gpt-x", 128_000, 16_384);
+        normalize_token_limits("openai", &mut known);
+        assert_eq!(
+            (known.input_token_limit, known.output_token_limit),
+            (128_000, 16_384)
+        );
+        assert!(!known.token_limits_estimated);
+
+        // A reported output limit is not clamped to an estimated context.
+        let mut output_only = model("local", 0, 32_768);
+        normalize_token_limits("lmstudio", &mut output_only);
+        assert_eq!(
+            (
+                output_only.input_token_limit,
+                output_only.output_token_limit
+            ),
+            (8_192, 32_768)
+        );
+        assert!(output_only.token_limits_estimated);
+    }
+
+    #[test]
+    fn only_curated_models_missing_a_limit_are_estimated() {
+        for provider in ["google", "openai", "anthropic", "moonshot", "deepseek"] {
+            let raw = curated_model_entries(provider);
+            for model in curated_models(provider) {
+                let entry = raw.iter().find(|entry| entry.id == model.id).unwrap();
+                let missing = entry.input_token_limit == 0 || entry.output_token_limit == 0;
+                assert_eq!(model.token_limits_estimated, missing, "{}", model.id);
+                assert!(model.output_token_limit <= model.input_token_limit);
+            }
+        }
+    }
+
+    #[test]
+    fn cached_models_without_the_estimated_flag_still_load() {
+        let cached: ModelInfo = serde_json::from_value(serde_json::json!({
+            "id": "gemini-old",
+            "name": "Gemini Old",
+            "description": "",
+            "input_token_limit": 32_768,
+            "output_token_limit": 8_192,
+        }))
+        .unwrap();
+        assert!(!cached.token_limits_estimated);
+    }
+
+    fn write_cached_list(dir: &Path, provider: &str, base_url: &str, fetched_at: i64, id: &str) {
+        let record = json!({
+            "fetchedAt": fetched_at,
+            "baseUrl": base_url,
+            "models": [model(id, 1_000, 100)],
+        });
+        fs::write(
+            dir.join(model_cache_file_name(provider, base_url)),
+            record.to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn model_lists_are_cached_per_base_url() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-model-cache-{}-{}",
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let official = "https://api.openai.com/v1";
+        let proxy = "http://localhost:4000/v1";
+        assert_ne!(
+            model_cache_file_name("openai", official),
+            model_cache_file_name("openai", proxy)
+        );
+
+        write_cached_list(&dir, "openai", official, 10, "gpt-official");
+        write_cached_list(&dir, "openai", proxy, 20, "gpt-proxy");
+        write_cached_list(
+            &dir,
+            "deepseek",
+            "https://api.deepseek.com",
+            30,
+            "deepseek-chat",
+        );
+
+        let cached = load_persisted_model_list(&dir, "openai", official).unwrap();
+        assert_eq!(ids(&cached.models), vec!["gpt-official"]);
+        assert_eq!(cached.fetched_at, 10);
+        assert!(load_persisted_model_list(&dir, "openai", "https://other.example/v1").is_none());
+
+        let latest = latest_persisted_model_list(&dir, "openai").unwrap();
+        assert_eq!(ids(&latest.models), vec!["gpt-proxy"]);
+        assert!(latest_persisted_model_list(&dir, "anthropic").is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn fetched_list_metadata_is_serialized_once() {
+        let list = FetchedModelList::live(vec![model("a", 10, 5), model("b", 10, 5)], Some(42));
+        let value = serde_json::to_value(&list).unwrap();
+        assert_eq!(value["stale"], false);
+        assert_eq!(value["fetched_at"], 42);
+        assert_eq!(value["models"].as_array().unwrap().len(), 2);
+        assert!(value["models"][0].get("stale").is_none());
+
+        let fallback = serde_json::to_value(FetchedModelList::curated(Vec::new(), true)).unwrap();
+        assert_eq!(fallback["fallback"], true);
+        assert!(fallback["fetched_at"].is_null());
+    }
 }
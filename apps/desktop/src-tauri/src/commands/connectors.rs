@@ -2,8 +2,22 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 use crate::commands::agent::AgentState;
+use crate::error::ErrorCode;
+use crate::sidecar::{SidecarEvent, SidecarManager};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, State};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Notify, Semaphore};
+use tokio::task::JoinSet;
 
 // ============================================================================
 // Connector Types
@@ -215,13 +229,32 @@ pub async fn connect_connector(
     connector_id: String,
 ) -> Result<ConnectorCapabilities, String> {
     ensure_sidecar(&app, &state).await?;
+    connect_connector_with_retry(state.manager.clone(), connector_id).await
+}
 
-    let manager = &state.manager;
+/// Connect one connector. Transient failures schedule an auto reconnect;
+/// config and auth failures are only reported, since retrying cannot help.
+async fn connect_connector_with_retry(
+    manager: Arc<SidecarManager>,
+    connector_id: String,
+) -> Result<ConnectorCapabilities, String> {
     let params = serde_json::json!({
         "connectorId": connector_id,
     });
 
-    let result = manager.send_command("connect_connector", params).await?;
+    let result = manager
+        .send_command_typed("connect_connector", params)
+        .await;
+    invalidate_connector_tools();
+    let result = match result {
+        Ok(result) => result,
+        Err(error) => {
+            if is_retryable_connector_error(error.code()) {
+                schedule_connector_reconnect(manager, connector_id);
+            }
+            return Err(error.into());
+        }
+    };
     serde_json::from_value(result).map_err(|e| format!("Failed to parse capabilities: {}", e))
 }
 
@@ -233,13 +266,16 @@ pub async fn disconnect_connector(
     connector_id: String,
 ) -> Result<(), String> {
     ensure_sidecar(&app, &state).await?;
+    cancel_connector_reconnect(&connector_id);
 
     let manager = &state.manager;
     let params = serde_json::json!({
         "connectorId": connector_id,
     });
 
-    manager.send_command("disconnect_connector", params).await?;
+    let result = manager.send_command("disconnect_connector", params).await;
+    invalidate_connector_tools();
+    result?;
     Ok(())
 }
 
@@ -257,8 +293,9 @@ pub async fn reconnect_connector(
         "connectorId": connector_id,
     });
 
-    let result = manager.send_command("reconnect_connector", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse capabilities: {}", e))
+    let result = manager.send_command("reconnect_connector", params).await;
+    invalidate_connector_tools();
+    serde_json::from_value(result?).map_err(|e| format!("Failed to parse capabilities: {}", e))
 }
 
 /// Configure secrets for a connector
@@ -356,25 +393,101 @@ pub async fn connector_call_tool(
     manager.send_command("connector_call_tool", params).await
 }
 
-/// Get all tools from all connected connectors
-#[tauri::command]
-pub async fn get_all_connector_tools(
-    app: AppHandle,
-    state: State<'_, AgentState>,
-) -> Result<Vec<MCPTool>, String> {
-    ensure_sidecar(&app, &state).await?;
+/// Tool lists younger than this are served without asking the sidecar.
+const CONNECTOR_TOOLS_FRESH_TTL: Duration = Duration::from_secs(15);
+/// Older lists are still served while a background refresh runs.
+const CONNECTOR_TOOLS_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// Cached tool catalog. `generation` changes whenever the set of connected
+/// connectors may have changed, which discards the cached list.
+#[derive(Default)]
+struct ConnectorToolCache {
+    generation: u64,
+    tools: Option<(Vec<MCPTool>, Instant)>,
+    refreshing: bool,
+}
 
-    let manager = &state.manager;
-    let params = serde_json::json!({});
+static CONNECTOR_TOOL_CACHE: OnceLock<StdMutex<ConnectorToolCache>> = OnceLock::new();
+
+fn connector_tool_cache() -> &'static StdMutex<ConnectorToolCache> {
+    CONNECTOR_TOOL_CACHE.get_or_init(|| StdMutex::new(ConnectorToolCache::default()))
+}
+
+fn invalidate_connector_tools() {
+    if let Ok(mut cache) = connector_tool_cache().lock() {
+        cache.generation = cache.generation.wrapping_add(1);
+        cache.tools = None;
+    }
+}
+
+/// Fetch tools from the sidecar and cache them unless connections changed
+/// while the request was in flight.
+async fn refresh_connector_tools(manager: &SidecarManager) -> Result<Vec<MCPTool>, String> {
+    let generation = connector_tool_cache()
+        .lock()
+        .map(|cache| cache.generation)
+        .unwrap_or_default();
 
+    let params = serde_json::json!({});
     let result = manager.send_command("get_all_connector_tools", params).await?;
     let tools = result
         .get("tools")
         .and_then(|t| t.as_array())
         .ok_or("Invalid response format: missing tools array")?;
+    let tools: Vec<MCPTool> = serde_json::from_value(serde_json::Value::Array(tools.clone()))
+        .map_err(|e| format!("Failed to parse tools: {}", e))?;
+
+    if let Ok(mut cache) = connector_tool_cache().lock() {
+        if cache.generation == generation {
+            cache.tools = Some((tools.clone(), Instant::now()));
+        }
+    }
+    Ok(tools)
+}
+
+/// Get all tools from all connected connectors
+#[tauri::command]
+pub async fn get_all_connector_tools(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+) -> Result<Vec<MCPTool>, String> {
+    ensure_sidecar(&app, &state).await?;
 
-    serde_json::from_value(serde_json::Value::Array(tools.clone()))
-        .map_err(|e| format!("Failed to parse tools: {}", e))
+    let cached = {
+        let mut cache = connector_tool_cache()
+            .lock()
+            .map_err(|_| "Connector tool cache is unavailable".to_string())?;
+        match cache.tools.as_ref() {
+            Some((tools, fetched_at)) if fetched_at.elapsed() < CONNECTOR_TOOLS_FRESH_TTL => {
+                return Ok(tools.clone());
+            }
+            Some((tools, fetched_at)) if fetched_at.elapsed() < CONNECTOR_TOOLS_MAX_AGE => {
+                let tools = tools.clone();
+                let start_refresh = !cache.refreshing;
+                cache.refreshing = true;
+                Some((tools, start_refresh))
+            }
+            _ => None,
+        }
+    };
+
+    match cached {
+        Some((tools, start_refresh)) => {
+            if start_refresh {
+                let manager = state.manager.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = refresh_connector_tools(&manager).await {
+                        eprintln!("[connectors] Background tool refresh failed: {}", e);
+                    }
+                    if let Ok(mut cache) = connector_tool_cache().lock() {
+                        cache.refreshing = false;
+                    }
+                });
+            }
+            Ok(tools)
+        }
+        None => refresh_connector_tools(&state.manager).await,
+    }
 }
 
 /// Get all connector states
@@ -391,21 +504,97 @@ pub async fn get_all_connector_states(
     manager.send_command("get_all_connector_states", params).await
 }
 
-/// Connect all enabled connectors
+const CONNECT_ALL_DEFAULT_CONCURRENCY: usize = 4;
+const CONNECT_ALL_MAX_CONCURRENCY: usize = 16;
+const CONNECT_ALL_DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Outcome of connecting one connector in a batch
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectorConnectResult {
+    pub connector_id: String,
+    pub ok: bool,
+    pub capabilities: Option<ConnectorCapabilities>,
+    pub error: Option<String>,
+}
+
+/// Connect all enabled connectors in parallel, reporting each one separately
 #[tauri::command]
 pub async fn connect_all_connectors(
     app: AppHandle,
     state: State<'_, AgentState>,
     connector_ids: Vec<String>,
-) -> Result<serde_json::Value, String> {
+    concurrency: Option<usize>,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<ConnectorConnectResult>, String> {
     ensure_sidecar(&app, &state).await?;
 
-    let manager = &state.manager;
-    let params = serde_json::json!({
-        "connectorIds": connector_ids,
-    });
-
-    manager.send_command("connect_all_connectors", params).await
+    let mut seen = HashSet::new();
+    let connector_ids: Vec<String> = connector_ids
+        .into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect();
+    let timeout_secs = timeout_secs.unwrap_or(CONNECT_ALL_DEFAULT_TIMEOUT_SECS).max(1);
+    let semaphore = Arc::new(Semaphore::new(
+        concurrency
+            .unwrap_or(CONNECT_ALL_DEFAULT_CONCURRENCY)
+            .clamp(1, CONNECT_ALL_MAX_CONCURRENCY),
+    ));
+
+    let mut tasks = JoinSet::new();
+    for connector_id in connector_ids.iter().cloned() {
+        let manager = state.manager.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let result = match semaphore.acquire_owned().await {
+                Ok(_permit) => connect_connector_with_retry(manager, connector_id.clone()).await,
+                Err(_) => Err("Connection batch was cancelled".to_string()),
+            };
+            (connector_id, result)
+        });
+    }
+
+    let mut outcomes: HashMap<String, Result<ConnectorCapabilities, String>> = HashMap::new();
+    let deadline = tokio::time::sleep(Duration::from_secs(timeout_secs));
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            joined = tasks.join_next() => match joined {
+                Some(Ok((connector_id, result))) => {
+                    outcomes.insert(connector_id, result);
+                }
+                Some(Err(e)) => eprintln!("[connectors] Connect task failed: {}", e),
+                None => break,
+            },
+            _ = &mut deadline => {
+                tasks.abort_all();
+                break;
+            }
+        }
+    }
+
+    Ok(connector_ids
+        .into_iter()
+        .map(|connector_id| {
+            let outcome = outcomes
+                .remove(&connector_id)
+                .unwrap_or_else(|| Err(format!("Timed out after {}s", timeout_secs)));
+            match outcome {
+                Ok(capabilities) => ConnectorConnectResult {
+                    connector_id,
+                    ok: true,
+                    capabilities: Some(capabilities),
+                    error: None,
+                },
+                Err(error) => ConnectorConnectResult {
+                    connector_id,
+                    ok: false,
+                    capabilities: None,
+                    error: Some(error),
+                },
+            }
+        })
+        .collect())
 }
 
 /// Disconnect all connectors
@@ -419,10 +608,443 @@ pub async fn disconnect_all_connectors(
     let manager = &state.manager;
     let params = serde_json::json!({});
 
-    manager.send_command("disconnect_all_connectors", params).await?;
+    if let Ok(mut reconnects) = connector_reconnects().lock() {
+        reconnects.clear();
+    }
+    let result = manager.send_command("disconnect_all_connectors", params).await;
+    invalidate_connector_tools();
+    result?;
     Ok(())
 }
 
+// ============================================================================
+// Connector Health Polling
+// ============================================================================
+
+const CONNECTOR_HEALTH_DEFAULT_INTERVAL_SECS: u64 = 30;
+const CONNECTOR_HEALTH_MIN_INTERVAL_SECS: u64 = 5;
+const CONNECTOR_HEALTH_MAX_BACKOFF_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectorHealthPollingStatus {
+    pub running: bool,
+    pub paused: bool,
+    pub interval_secs: u64,
+    pub consecutive_failures: u32,
+}
+
+struct ConnectorHealthPoller {
+    running: AtomicBool,
+    paused: AtomicBool,
+    interval_secs: AtomicU64,
+    consecutive_failures: AtomicU32,
+    /// Wakes the poller early so interval changes apply immediately.
+    wake: Notify,
+}
+
+static CONNECTOR_HEALTH_POLLER: OnceLock<ConnectorHealthPoller> = OnceLock::new();
+
+fn connector_health_poller() -> &'static ConnectorHealthPoller {
+    CONNECTOR_HEALTH_POLLER.get_or_init(|| ConnectorHealthPoller {
+        running: AtomicBool::new(false),
+        paused: AtomicBool::new(true),
+        interval_secs: AtomicU64::new(CONNECTOR_HEALTH_DEFAULT_INTERVAL_SECS),
+        consecutive_failures: AtomicU32::new(0),
+        wake: Notify::new(),
+    })
+}
+
+impl ConnectorHealthPoller {
+    fn status(&self) -> ConnectorHealthPollingStatus {
+        ConnectorHealthPollingStatus {
+            running: self.running.load(Ordering::SeqCst),
+            paused: self.paused.load(Ordering::SeqCst),
+            interval_secs: self.interval_secs.load(Ordering::SeqCst),
+            consecutive_failures: self.consecutive_failures.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Poll interval, doubled for each consecutive failure up to a cap.
+    fn next_delay(&self) -> Duration {
+        let interval = self.interval_secs.load(Ordering::SeqCst);
+        let failures = self.consecutive_failures.load(Ordering::SeqCst);
+        let delay = if failures == 0 {
+            interval
+        } else {
+            interval
+                .saturating_mul(1u64 << failures.min(6))
+                .min(CONNECTOR_HEALTH_MAX_BACKOFF_SECS)
+                .max(interval)
+        };
+        Duration::from_secs(delay)
+    }
+}
+
+/// Connector status and error keyed by id, from `get_all_connector_states`.
+type ConnectorStatusMap = HashMap<String, (String, Option<String>)>;
+
+fn parse_connector_states(value: &serde_json::Value) -> ConnectorStatusMap {
+    value
+        .get("states")
+        .and_then(|states| states.as_object())
+        .map(|states| {
+            states
+                .iter()
+                .filter_map(|(id, state)| {
+                    let status = state.get("status")?.as_str()?.to_string();
+                    let error = state
+                        .get("error")
+                        .and_then(|e| e.as_str())
+                        .map(|e| e.to_string());
+                    Some((id.clone(), (status, error)))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn dispatch_connector_status_changed(
+    manager: &SidecarManager,
+    connector_id: &str,
+    previous_status: &str,
+    status: &str,
+    error: Option<&str>,
+) {
+    manager
+        .dispatch_event(SidecarEvent {
+            event_type: "connector:status_changed".to_string(),
+            session_id: None,
+            data: serde_json::json!({
+                "connectorId": connector_id,
+                "previousStatus": previous_status,
+                "status": status,
+                "error": error,
+            }),
+        })
+        .await;
+}
+
+async fn run_connector_health_poller(manager: Arc<SidecarManager>) {
+    let poller = connector_health_poller();
+    let mut last_states: Option<ConnectorStatusMap> = None;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(poller.next_delay()) => {}
+            _ = poller.wake.notified() => {}
+        }
+        if poller.paused.load(Ordering::SeqCst) || !manager.is_running().await {
+            continue;
+        }
+
+        let result = manager
+            .send_command("get_all_connector_states", serde_json::json!({}))
+            .await;
+        let states = match result {
+            Ok(value) => {
+                poller.consecutive_failures.store(0, Ordering::SeqCst);
+                parse_connector_states(&value)
+            }
+            Err(e) => {
+                let failures = poller.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
+                eprintln!(
+                    "[connectors] Health poll failed ({} in a row): {}",
+                    failures, e
+                );
+                continue;
+            }
+        };
+
+        if let Some(previous) = &last_states {
+            for (connector_id, (status, error)) in &states {
+                let previous_status = previous
+                    .get(connector_id)
+                    .map(|(status, _)| status.as_str())
+                    .unwrap_or("available");
+                if previous_status != status {
+                    invalidate_connector_tools();
+                    dispatch_connector_status_changed(
+                        &manager,
+                        connector_id,
+                        previous_status,
+                        status,
+                        error.as_deref(),
+                    )
+                    .await;
+                    if previous_status == "connected" && status == "error" {
+                        // It was connected, so its config was fine; the
+                        // drop itself is worth retrying.
+                        schedule_connector_reconnect(manager.clone(), connector_id.clone());
+                    }
+                }
+            }
+            // Connections the sidecar no longer tracks report as available.
+            for (connector_id, (status, _)) in previous {
+                if !states.contains_key(connector_id) && status != "available" {
+                    invalidate_connector_tools();
+                    dispatch_connector_status_changed(
+                        &manager,
+                        connector_id,
+                        status,
+                        "available",
+                        None,
+                    )
+                    .await;
+                }
+            }
+        }
+        last_states = Some(states);
+    }
+}
+
+// ============================================================================
+// Connector Auto Reconnect
+// ============================================================================
+
+/// How dropped or failed connections are retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectorReconnectPolicy {
+    pub enabled: bool,
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for ConnectorReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: 5,
+            initial_delay_ms: 1_000,
+            max_delay_ms: 60_000,
+        }
+    }
+}
+
+const CONNECTOR_RECONNECT_MAX_ATTEMPTS: u32 = 20;
+const CONNECTOR_RECONNECT_MIN_DELAY_MS: u64 = 100;
+
+static CONNECTOR_RECONNECT_POLICY: OnceLock<StdMutex<ConnectorReconnectPolicy>> = OnceLock::new();
+/// Connector ids with a reconnect loop in flight.
+static CONNECTOR_RECONNECTS: OnceLock<StdMutex<HashSet<String>>> = OnceLock::new();
+
+fn connector_reconnect_policy() -> ConnectorReconnectPolicy {
+    CONNECTOR_RECONNECT_POLICY
+        .get_or_init(|| StdMutex::new(ConnectorReconnectPolicy::default()))
+        .lock()
+        .map(|policy| policy.clone())
+        .unwrap_or_default()
+}
+
+fn connector_reconnects() -> &'static StdMutex<HashSet<String>> {
+    CONNECTOR_RECONNECTS.get_or_init(|| StdMutex::new(HashSet::new()))
+}
+
+/// Auth, validation and missing-connector errors need the user to act, so
+/// retrying them only loops. Everything else, including untyped errors from
+/// older sidecars, is treated as transient.
+fn is_retryable_connector_error(code: ErrorCode) -> bool {
+    !matches!(
+        code,
+        ErrorCode::Auth | ErrorCode::Validation | ErrorCode::NotFound
+    )
+}
+
+fn reconnect_in_flight(connector_id: &str) -> bool {
+    connector_reconnects()
+        .lock()
+        .map(|reconnects| reconnects.contains(connector_id))
+        .unwrap_or(false)
+}
+
+fn cancel_connector_reconnect(connector_id: &str) {
+    if let Ok(mut reconnects) = connector_reconnects().lock() {
+        reconnects.remove(connector_id);
+    }
+}
+
+async fn dispatch_connector_reconnect_event(
+    manager: &SidecarManager,
+    connector_id: &str,
+    data: serde_json::Value,
+) {
+    let mut data = data;
+    data["connectorId"] = serde_json::json!(connector_id);
+    manager
+        .dispatch_event(SidecarEvent {
+            event_type: "connector:reconnect".to_string(),
+            session_id: None,
+            data,
+        })
+        .await;
+}
+
+/// Start a reconnect loop for a connector unless the policy is disabled or a
+/// loop is already running.
+fn schedule_connector_reconnect(manager: Arc<SidecarManager>, connector_id: String) {
+    let policy = connector_reconnect_policy();
+    if !policy.enabled {
+        return;
+    }
+    let Ok(mut reconnects) = connector_reconnects().lock() else {
+        return;
+    };
+    if !reconnects.insert(connector_id.clone()) {
+        return;
+    }
+    drop(reconnects);
+
+    tauri::async_runtime::spawn(run_connector_reconnect(manager, connector_id, policy));
+}
+
+async fn run_connector_reconnect(
+    manager: Arc<SidecarManager>,
+    connector_id: String,
+    policy: ConnectorReconnectPolicy,
+) {
+    let mut delay_ms = policy.initial_delay_ms;
+    let mut last_error: Option<String> = None;
+    let mut retryable = true;
+
+    for attempt in 1..=policy.max_attempts {
+        dispatch_connector_reconnect_event(
+            &manager,
+            &connector_id,
+            serde_json::json!({
+                "phase": "retrying",
+                "attempt": attempt,
+                "maxAttempts": policy.max_attempts,
+                "delayMs": delay_ms,
+            }),
+        )
+        .await;
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+
+        // A manual disconnect while waiting cancels the loop.
+        if !reconnect_in_flight(&connector_id) {
+            return;
+        }
+
+        match manager
+            .send_command_typed(
+                "reconnect_connector",
+                serde_json::json!({ "connectorId": connector_id }),
+            )
+            .await
+        {
+            Ok(_) => {
+                cancel_connector_reconnect(&connector_id);
+                invalidate_connector_tools();
+                dispatch_connector_reconnect_event(
+                    &manager,
+                    &connector_id,
+                    serde_json::json!({ "phase": "reconnected", "attempt": attempt }),
+                )
+                .await;
+                return;
+            }
+            Err(error) => {
+                retryable = is_retryable_connector_error(error.code());
+                last_error = Some(error.into());
+                if !retryable {
+                    break;
+                }
+            }
+        }
+        delay_ms = delay_ms.saturating_mul(2).min(policy.max_delay_ms);
+    }
+
+    cancel_connector_reconnect(&connector_id);
+    eprintln!(
+        "[connectors] Giving up reconnecting {}: {}",
+        connector_id,
+        last_error.as_deref().unwrap_or("unknown error")
+    );
+    dispatch_connector_reconnect_event(
+        &manager,
+        &connector_id,
+        serde_json::json!({
+            "phase": "gave_up",
+            "status": "error",
+            "retryable": retryable,
+            "error": last_error,
+        }),
+    )
+    .await;
+}
+
+/// Get the connector auto-reconnect policy
+#[tauri::command]
+pub async fn get_connector_reconnect_policy() -> Result<ConnectorReconnectPolicy, String> {
+    Ok(connector_reconnect_policy())
+}
+
+/// Update the connector auto-reconnect policy
+#[tauri::command]
+pub async fn set_connector_reconnect_policy(
+    policy: ConnectorReconnectPolicy,
+) -> Result<ConnectorReconnectPolicy, String> {
+    if policy.max_attempts == 0 || policy.max_attempts > CONNECTOR_RECONNECT_MAX_ATTEMPTS {
+        return Err(format!(
+            "maxAttempts must be between 1 and {}",
+            CONNECTOR_RECONNECT_MAX_ATTEMPTS
+        ));
+    }
+    if policy.initial_delay_ms < CONNECTOR_RECONNECT_MIN_DELAY_MS {
+        return Err(format!(
+            "initialDelayMs must be at least {}",
+            CONNECTOR_RECONNECT_MIN_DELAY_MS
+        ));
+    }
+    if policy.max_delay_ms < policy.initial_delay_ms {
+        return Err("maxDelayMs must not be less than initialDelayMs".to_string());
+    }
+
+    let mut current = CONNECTOR_RECONNECT_POLICY
+        .get_or_init(|| StdMutex::new(ConnectorReconnectPolicy::default()))
+        .lock()
+        .map_err(|_| "Connector reconnect policy is unavailable".to_string())?;
+    *current = policy.clone();
+    Ok(policy)
+}
+
+/// Enable, pause, or retune background connector health polling. Status
+/// transitions are emitted as `connector:status_changed` events.
+#[tauri::command]
+pub async fn set_connector_health_polling(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    enabled: bool,
+    interval_secs: Option<u64>,
+) -> Result<ConnectorHealthPollingStatus, String> {
+    let poller = connector_health_poller();
+    if let Some(interval_secs) = interval_secs {
+        poller.interval_secs.store(
+            interval_secs.max(CONNECTOR_HEALTH_MIN_INTERVAL_SECS),
+            Ordering::SeqCst,
+        );
+    }
+    poller.paused.store(!enabled, Ordering::SeqCst);
+
+    if enabled && !poller.running.load(Ordering::SeqCst) {
+        ensure_sidecar(&app, &state).await?;
+        if !poller.running.swap(true, Ordering::SeqCst) {
+            tauri::async_runtime::spawn(run_connector_health_poller(state.manager.clone()));
+        }
+    }
+    poller.wake.notify_one();
+
+    Ok(poller.status())
+}
+
+/// Current state of background connector health polling
+#[tauri::command]
+pub async fn get_connector_health_polling() -> Result<ConnectorHealthPollingStatus, String> {
+    Ok(connector_health_poller().status())
+}
+
 // ============================================================================
 // OAuth Commands
 // ============================================================================
@@ -437,24 +1059,376 @@ pub struct OAuthFlowResult {
     pub user_code: Option<String>,
     pub verification_url: Option<String>,
     pub expires_in: Option<i32>,
+    /// Set when the redirect is captured by the Rust loopback server.
+    #[serde(default)]
+    pub redirect_uri: Option<String>,
+}
+
+const OAUTH_CALLBACK_PATH: &str = "/oauth/callback";
+const OAUTH_LOOPBACK_DEFAULT_TIMEOUT_SECS: u64 = 300;
+const OAUTH_LOOPBACK_MAX_TIMEOUT_SECS: u64 = 900;
+const OAUTH_CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const OAUTH_CALLBACK_MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Active loopback servers keyed by connector id, with the flow state that
+/// owns each entry and the sender that cancels it.
+type OAuthLoopbackRegistry = HashMap<String, (String, oneshot::Sender<()>)>;
+
+static OAUTH_LOOPBACKS: OnceLock<StdMutex<OAuthLoopbackRegistry>> = OnceLock::new();
+
+/// A pending loopback authorization-code flow.
+struct OAuthLoopbackFlow {
+    connector_id: String,
+    state: String,
+    redirect_uri: String,
+}
+
+fn oauth_loopbacks() -> &'static StdMutex<OAuthLoopbackRegistry> {
+    OAUTH_LOOPBACKS.get_or_init(|| StdMutex::new(HashMap::new()))
+}
+
+fn random_url_safe_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// PKCE code verifiers for loopback flows, keyed by connector id with an
+/// expiry. They are never persisted.
+static PKCE_VERIFIERS: OnceLock<StdMutex<HashMap<String, (String, Instant)>>> = OnceLock::new();
+
+fn pkce_verifiers() -> &'static StdMutex<HashMap<String, (String, Instant)>> {
+    PKCE_VERIFIERS.get_or_init(|| StdMutex::new(HashMap::new()))
+}
+
+/// S256 code challenge for a verifier (RFC 7636 section 4.2).
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn store_pkce_verifier(connector_id: &str, verifier: String, ttl: Duration) {
+    if let Ok(mut verifiers) = pkce_verifiers().lock() {
+        let now = Instant::now();
+        verifiers.retain(|_, (_, expires_at)| *expires_at > now);
+        verifiers.insert(connector_id.to_string(), (verifier, now + ttl));
+    }
+}
+
+/// Remove and return the verifier for a connector if it has not expired.
+fn take_pkce_verifier(connector_id: &str) -> Option<String> {
+    let (verifier, expires_at) = pkce_verifiers().lock().ok()?.remove(connector_id)?;
+    (expires_at > Instant::now()).then_some(verifier)
+}
+
+enum OAuthCallback {
+    Code(String),
+    Denied(String),
+    InvalidState,
+    NotFound,
+}
+
+/// Interpret a callback request target such as `/oauth/callback?code=..&state=..`.
+fn parse_oauth_callback(target: &str, expected_state: &str) -> OAuthCallback {
+    let Ok(url) = reqwest::Url::parse(&format!("http://127.0.0.1{}", target)) else {
+        return OAuthCallback::NotFound;
+    };
+    if url.path() != OAUTH_CALLBACK_PATH {
+        return OAuthCallback::NotFound;
+    }
+
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    if query.get("state").map(String::as_str) != Some(expected_state) {
+        return OAuthCallback::InvalidState;
+    }
+    if let Some(error) = query.get("error") {
+        let description = query.get("error_description").unwrap_or(error);
+        return OAuthCallback::Denied(description.clone());
+    }
+    match query.get("code") {
+        Some(code) if !code.is_empty() => OAuthCallback::Code(code.clone()),
+        _ => OAuthCallback::InvalidState,
+    }
 }
 
-/// Start OAuth flow for a connector
+/// Read the request head and return the target of a GET request.
+async fn read_request_target(stream: &mut TcpStream) -> Result<String, String> {
+    let mut buffer = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read OAuth callback: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if buffer.windows(4).any(|w| w == b"\r\n\r\n")
+            || buffer.len() >= OAUTH_CALLBACK_MAX_REQUEST_BYTES
+        {
+            break;
+        }
+    }
+
+    let head = String::from_utf8_lossy(&buffer);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Ok(target.to_string()),
+        _ => Err("Unsupported OAuth callback request".to_string()),
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+async fn write_callback_page(stream: &mut TcpStream, status: &str, title: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html><html><head><title>{title}</title>\
+         <style>body {{ font-family: system-ui; padding: 40px; text-align: center; background: #1a1a1a; color: #fff; }} p {{ color: #9ca3af; }}</style>\
+         </head><body><h1>{title}</h1><p>{message}</p></body></html>",
+        title = escape_html(title),
+        message = escape_html(message),
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn write_callback_text(stream: &mut TcpStream, status: &str, message: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        message.len(),
+        message
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Serve the loopback redirect until a valid callback arrives, the flow times
+/// out, or it is cancelled, then report the outcome to the frontend.
+async fn run_oauth_loopback(
+    app: AppHandle,
+    manager: Arc<SidecarManager>,
+    listener: TcpListener,
+    flow: OAuthLoopbackFlow,
+    timeout: Duration,
+    mut cancel_rx: oneshot::Receiver<()>,
+) {
+    let OAuthLoopbackFlow {
+        connector_id,
+        state: expected_state,
+        redirect_uri,
+    } = flow;
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+
+    let outcome: Result<(), String> = loop {
+        let mut stream = tokio::select! {
+            _ = &mut deadline => break Err("Timed out waiting for the OAuth redirect".to_string()),
+            _ = &mut cancel_rx => break Err("OAuth flow was cancelled".to_string()),
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => break Err(format!("OAuth callback server failed: {}", e)),
+            },
+        };
+
+        let target =
+            match tokio::time::timeout(OAUTH_CALLBACK_READ_TIMEOUT, read_request_target(&mut stream))
+                .await
+            {
+                Ok(Ok(target)) => target,
+                Ok(Err(e)) => {
+                    write_callback_text(&mut stream, "400 Bad Request", &e).await;
+                    continue;
+                }
+                Err(_) => continue,
+            };
+
+        match parse_oauth_callback(&target, &expected_state) {
+            OAuthCallback::NotFound => {
+                write_callback_text(&mut stream, "404 Not Found", "Not found").await;
+            }
+            OAuthCallback::InvalidState => {
+                write_callback_text(&mut stream, "400 Bad Request", "Invalid callback parameters")
+                    .await;
+            }
+            OAuthCallback::Denied(error) => {
+                write_callback_page(
+                    &mut stream,
+                    "400 Bad Request",
+                    "Authorization Failed",
+                    &format!("{} You can close this window and try again.", error),
+                )
+                .await;
+                break Err(error);
+            }
+            OAuthCallback::Code(code) => {
+                let result = match take_pkce_verifier(&connector_id) {
+                    Some(code_verifier) => {
+                        let params = serde_json::json!({
+                            "connectorId": connector_id,
+                            "code": code,
+                            "state": expected_state,
+                            "redirectUri": redirect_uri,
+                            "codeVerifier": code_verifier,
+                        });
+                        manager
+                            .send_command("complete_connector_oauth_flow", params)
+                            .await
+                            .map(|_| ())
+                    }
+                    None => Err(
+                        "The PKCE verifier for this flow has expired. Start the OAuth flow again."
+                            .to_string(),
+                    ),
+                };
+                match &result {
+                    Ok(()) => {
+                        write_callback_page(
+                            &mut stream,
+                            "200 OK",
+                            "Authorization Successful",
+                            "You can close this window and return to Cowork.",
+                        )
+                        .await
+                    }
+                    Err(e) => {
+                        write_callback_page(
+                            &mut stream,
+                            "500 Internal Server Error",
+                            "Authorization Failed",
+                            &format!("{} You can close this window and try again.", e),
+                        )
+                        .await
+                    }
+                }
+                break result;
+            }
+        }
+    };
+
+    if let Ok(mut loopbacks) = oauth_loopbacks().lock() {
+        if loopbacks
+            .get(&connector_id)
+            .is_some_and(|(state, _)| state == &expected_state)
+        {
+            loopbacks.remove(&connector_id);
+            let _ = take_pkce_verifier(&connector_id);
+        }
+    }
+
+    if let Err(error) = &outcome {
+        log::warn!("OAuth loopback for {} ended: {}", connector_id, error);
+    }
+    let _ = app.emit(
+        "connector:oauth_complete",
+        serde_json::json!({
+            "connectorId": connector_id,
+            "success": outcome.is_ok(),
+            "error": outcome.err(),
+        }),
+    );
+}
+
+/// Start OAuth flow for a connector.
+///
+/// Authorization-code flows redirect to a short-lived server on 127.0.0.1
+/// that captures the code and completes the flow, using a PKCE verifier
+/// generated here and held in memory until the exchange.
 #[tauri::command]
 pub async fn start_connector_oauth_flow(
     app: AppHandle,
     state: State<'_, AgentState>,
     connector_id: String,
+    timeout_secs: Option<u64>,
 ) -> Result<OAuthFlowResult, String> {
     ensure_sidecar(&app, &state).await?;
 
     let manager = &state.manager;
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to start OAuth callback server: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read OAuth callback server address: {}", e))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, OAUTH_CALLBACK_PATH);
+    let oauth_state = random_url_safe_token();
+    let code_verifier = random_url_safe_token();
+
     let params = serde_json::json!({
         "connectorId": connector_id,
+        "redirectUri": redirect_uri,
+        "state": oauth_state,
+        "codeChallenge": pkce_challenge(&code_verifier),
+        "codeChallengeMethod": "S256",
     });
-
     let result = manager.send_command("start_connector_oauth_flow", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse OAuth flow result: {}", e))
+    let mut flow: OAuthFlowResult = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse OAuth flow result: {}", e))?;
+    if flow.flow_type != "browser" {
+        // Device-code flows have no redirect to capture.
+        return Ok(flow);
+    }
+
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    let previous = oauth_loopbacks()
+        .lock()
+        .map_err(|_| "OAuth loopback registry is unavailable".to_string())?
+        .insert(connector_id.clone(), (oauth_state.clone(), cancel_tx));
+    if let Some((_, previous_cancel)) = previous {
+        let _ = previous_cancel.send(());
+    }
+
+    let timeout = Duration::from_secs(
+        timeout_secs
+            .unwrap_or(OAUTH_LOOPBACK_DEFAULT_TIMEOUT_SECS)
+            .clamp(1, OAUTH_LOOPBACK_MAX_TIMEOUT_SECS),
+    );
+    store_pkce_verifier(&connector_id, code_verifier, timeout);
+    tauri::async_runtime::spawn(run_oauth_loopback(
+        app.clone(),
+        state.manager.clone(),
+        listener,
+        OAuthLoopbackFlow {
+            connector_id,
+            state: oauth_state,
+            redirect_uri: redirect_uri.clone(),
+        },
+        timeout,
+        cancel_rx,
+    ));
+
+    flow.redirect_uri = Some(redirect_uri);
+    Ok(flow)
+}
+
+/// Stop a pending loopback OAuth flow. Returns false when none was running.
+#[tauri::command]
+pub async fn cancel_connector_oauth_flow(connector_id: String) -> Result<bool, String> {
+    let cancel = oauth_loopbacks()
+        .lock()
+        .map_err(|_| "OAuth loopback registry is unavailable".to_string())?
+        .remove(&connector_id);
+    let _ = take_pkce_verifier(&connector_id);
+    match cancel {
+        Some((_, cancel_tx)) => {
+            let _ = cancel_tx.send(());
+            Ok(true)
+        }
+        None => Ok(false),
+    }
 }
 
 /// Poll OAuth device code for completion
@@ -614,3 +1588,66 @@ pub async fn call_connector_app_tool(
 
     manager.send_command("call_connector_app_tool", params).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7636 Appendix B.
+    const RFC7636_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const RFC7636_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn pkce_challenge_matches_rfc7636_appendix_b() {
+        assert_eq!(pkce_challenge(RFC7636_VERIFIER), RFC7636_CHALLENGE);
+    }
+
+    #[test]
+    fn pkce_verifier_has_valid_length_and_charset() {
+        for _ in 0..32 {
+            let verifier = random_url_safe_token();
+            // RFC 7636 section 4.1: 43-128 unreserved characters.
+            assert!((43..=128).contains(&verifier.len()), "{}", verifier);
+            assert!(
+                verifier
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')),
+                "{}",
+                verifier
+            );
+            assert_eq!(pkce_challenge(&verifier).len(), 43);
+        }
+        assert_ne!(random_url_safe_token(), random_url_safe_token());
+    }
+
+    #[test]
+    fn pkce_verifier_is_taken_once_and_expires() {
+        store_pkce_verifier(
+            "test-connector-once",
+            "verifier".to_string(),
+            Duration::from_secs(60),
+        );
+        assert_eq!(
+            take_pkce_verifier("test-connector-once").as_deref(),
+            Some("verifier")
+        );
+        assert_eq!(take_pkce_verifier("test-connector-once"), None);
+
+        store_pkce_verifier(
+            "test-connector-expired",
+            "verifier".to_string(),
+            Duration::ZERO,
+        );
+        assert_eq!(take_pkce_verifier("test-connector-expired"), None);
+    }
+
+    #[test]
+    fn only_transient_connector_errors_are_retried() {
+        assert!(is_retryable_connector_error(ErrorCode::Network));
+        assert!(is_retryable_connector_error(ErrorCode::Transport));
+        assert!(is_retryable_connector_error(ErrorCode::Internal));
+        assert!(!is_retryable_connector_error(ErrorCode::Auth));
+        assert!(!is_retryable_connector_error(ErrorCode::Validation));
+        assert!(!is_retryable_connector_error(ErrorCode::NotFound));
+    }
+}
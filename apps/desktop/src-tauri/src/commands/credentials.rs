@@ -1,9 +1,13 @@
 // Copyright (c) 2026 Naresh. All rights reserved.
 // Licensed under the MIT License. See LICENSE file for details.
 
+use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
+use crate::error::CommandError;
+use crate::paths;
 use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Nonce};
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use hmac::{Hmac, Mac};
 use keyring::{Entry, Error as KeyringError};
 use rand::rngs::OsRng;
 use rand::RngCore;
@@ -11,7 +15,12 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
 
 const APP_DIR_NAME: &str = "cowork";
 const LEGACY_APP_DIR_NAME: &str = "cowork";
@@ -20,6 +29,25 @@ const ENCRYPTED_VAULT_FILE: &str = "credentials.vault.json";
 const CONNECTOR_SECRET_SERVICE: &str = "cowork.connector-secrets";
 const CONNECTOR_SECRET_ACCOUNT: &str = "sidecar-master-key";
 const CREDENTIAL_BACKEND_ENV_VAR: &str = "COWORK_CREDENTIAL_BACKEND";
+const KEYCHAIN_PROBE_SERVICE: &str = "cowork.keychain-probe";
+const AUDIT_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+const AUDIT_READ_DEFAULT_LIMIT: usize = 200;
+const AUDIT_READ_MAX_LIMIT: usize = 5000;
+/// Current on-disk format of the encrypted vault. Version 0 is the legacy
+/// unversioned layout that carried no integrity MAC.
+const VAULT_FORMAT_VERSION: u32 = 1;
+const VAULT_MAC_CONTEXT: &[u8] = b"cowork.credentials.vault.mac.v1";
+/// Keychain entry recording that a MAC-sealed vault was written on this
+/// machine. It lives outside the vault file, so stripping the MAC and
+/// downgrading the version cannot pass the file off as a legacy vault.
+const VAULT_SEALED_MARKER_SERVICE: &str = "cowork.credentials-vault";
+const VAULT_SEALED_MARKER_ACCOUNT: &str = "integrity-sealed";
+const VAULT_INTEGRITY_ERROR: &str = "Credential vault integrity check failed: the vault file is corrupted, was modified outside Cowork, or this machine's identity changed (re-key the vault with the previous identity)";
+
+type HmacSha256 = Hmac<Sha256>;
+
+static KEYCHAIN_PROBE: OnceLock<Result<(), String>> = OnceLock::new();
+static CONNECTOR_SEED_ROTATING: AtomicBool = AtomicBool::new(false);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum CredentialBackend {
@@ -34,25 +62,214 @@ struct PlaintextCredentialStore {
 
 #[derive(Serialize, Deserialize, Default)]
 struct EncryptedCredentialStore {
+    #[serde(default)]
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mac: Option<String>,
     credentials: HashMap<String, String>,
 }
 
+/// Machine-bound inputs the fallback vault key is derived from. Callers pass
+/// the previous values explicitly when re-keying a vault whose host identity
+/// has since changed.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MachineIdentity {
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub computer_name: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub home_dir: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainProbeResult {
+    pub available: bool,
+    pub error: Option<String>,
+    /// Backend in effect for this run, decided by the startup probe.
+    pub backend: String,
+    /// Set when credentials are going to the encrypted vault because the
+    /// keychain failed its probe.
+    pub warning: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRekeyResult {
+    pub rekeyed_entries: usize,
+    pub used_previous_identity: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectorSeedRotationResult {
+    pub rotated_entries: usize,
+    pub from_key_version: String,
+    pub to_key_version: String,
+}
+
+/// Why a credential lookup could not produce a value. A missing credential is
+/// not an error and is reported as `Ok(None)`.
+#[derive(Debug, Clone)]
+pub enum CredentialLookupError {
+    /// The entry exists but no longer decrypts under the current vault key,
+    /// usually because the machine identity changed. Re-keying recovers it.
+    DecryptFailed(String),
+    /// Neither the keychain nor the vault could be read.
+    BackendUnavailable(String),
+    /// The keychain is locked and the vault has no copy. The entry most
+    /// likely still exists and becomes readable once the user unlocks it.
+    KeychainLocked(String),
+}
+
+impl std::fmt::Display for CredentialLookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DecryptFailed(detail) => write!(
+                f,
+                "Stored credential could not be decrypted and the vault needs to be re-keyed: {}",
+                detail
+            ),
+            Self::BackendUnavailable(detail) => {
+                write!(f, "Credential storage is unavailable: {}", detail)
+            }
+            Self::KeychainLocked(detail) => write!(
+                f,
+                "The system keychain is locked; unlock it and try again: {}",
+                detail
+            ),
+        }
+    }
+}
+
+impl From<CredentialLookupError> for String {
+    fn from(error: CredentialLookupError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<CredentialLookupError> for CommandError {
+    fn from(error: CredentialLookupError) -> Self {
+        let message = error.to_string();
+        let (error, reason) = match error {
+            CredentialLookupError::DecryptFailed(_) => {
+                (CommandError::auth(message), "decrypt_failed")
+            }
+            CredentialLookupError::KeychainLocked(_) => {
+                (CommandError::auth(message), "keychain_locked")
+            }
+            CredentialLookupError::BackendUnavailable(_) => {
+                (CommandError::internal(message), "backend_unavailable")
+            }
+        };
+        error.with_details(serde_json::json!({ "reason": reason }))
+    }
+}
+
+/// Why a keychain read failed. `Locked` is transient and must not be treated
+/// like a missing entry.
+#[derive(Debug)]
+enum KeychainReadError {
+    Locked(String),
+    Failed(String),
+}
+
+impl From<KeychainReadError> for String {
+    fn from(error: KeychainReadError) -> Self {
+        match error {
+            KeychainReadError::Locked(detail) | KeychainReadError::Failed(detail) => detail,
+        }
+    }
+}
+
+impl From<KeychainReadError> for CredentialLookupError {
+    fn from(error: KeychainReadError) -> Self {
+        match error {
+            KeychainReadError::Locked(detail) => Self::KeychainLocked(detail),
+            KeychainReadError::Failed(detail) => Self::BackendUnavailable(detail),
+        }
+    }
+}
+
 fn config_root() -> Result<PathBuf, String> {
-    dirs::config_dir().ok_or("Could not determine config directory".to_string())
+    paths::config_root()
+}
+
+fn credential_backend_override() -> Option<CredentialBackend> {
+    let value = std::env::var(CREDENTIAL_BACKEND_ENV_VAR).ok()?;
+    let normalized = value.trim().to_lowercase();
+    match normalized.as_str() {
+        "keychain" | "keychain_with_encrypted_fallback" => {
+            Some(CredentialBackend::KeychainWithFallback)
+        }
+        _ => Some(CredentialBackend::VaultOnly),
+    }
 }
 
 fn credential_backend() -> CredentialBackend {
-    match std::env::var(CREDENTIAL_BACKEND_ENV_VAR) {
-        Ok(value) => {
-            let normalized = value.trim().to_lowercase();
-            match normalized.as_str() {
-                "keychain" | "keychain_with_encrypted_fallback" => {
-                    CredentialBackend::KeychainWithFallback
-                }
-                _ => CredentialBackend::VaultOnly,
+    match credential_backend_override() {
+        Some(backend) => backend,
+        None if keychain_available() => CredentialBackend::KeychainWithFallback,
+        None => CredentialBackend::VaultOnly,
+    }
+}
+
+/// Round-trips a throwaway entry through the OS keychain. The sentinel is
+/// deleted whether or not the write and read succeeded.
+fn probe_keychain() -> Result<(), String> {
+    let account = format!("probe-{:016x}", OsRng.next_u64());
+    let value = format!("{:016x}", OsRng.next_u64());
+    let round_trip = keychain_set(KEYCHAIN_PROBE_SERVICE, &account, &value)
+        .and_then(|_| keychain_get(KEYCHAIN_PROBE_SERVICE, &account).map_err(String::from))
+        .and_then(|stored| match stored {
+            Some(stored) if stored == value => Ok(()),
+            Some(_) => Err("Keychain returned a different value than was written".to_string()),
+            None => Err("Keychain did not return the value that was written".to_string()),
+        });
+    // A single retry covers keychains that briefly lock the entry after a read.
+    let cleanup = keychain_delete(KEYCHAIN_PROBE_SERVICE, &account)
+        .or_else(|_| keychain_delete(KEYCHAIN_PROBE_SERVICE, &account));
+
+    match (round_trip, cleanup) {
+        (Ok(()), Ok(())) => Ok(()),
+        (Err(error), Ok(())) => Err(error),
+        (Ok(()), Err(cleanup_error)) => Err(cleanup_error),
+        (Err(error), Err(cleanup_error)) => Err(format!("{}; {}", error, cleanup_error)),
+    }
+}
+
+/// The startup probe result, cached for the life of the process so the
+/// backend never changes underneath stored credentials.
+fn keychain_available() -> bool {
+    KEYCHAIN_PROBE
+        .get_or_init(|| {
+            let result = probe_keychain();
+            if let Err(error) = &result {
+                log::warn!(
+                    "OS keychain unavailable, using the encrypted vault: {}",
+                    error
+                );
             }
-        }
-        Err(_) => CredentialBackend::VaultOnly,
+            result
+        })
+        .is_ok()
+}
+
+pub fn keychain_is_available() -> bool {
+    keychain_available()
+}
+
+pub fn credential_backend_source() -> &'static str {
+    if credential_backend_override().is_some() {
+        "env_override"
+    } else {
+        "auto_detected"
     }
 }
 
@@ -71,6 +288,13 @@ fn app_dir_path() -> Result<PathBuf, String> {
     Ok(app_dir)
 }
 
+/// Config directory and vault file locations, without creating anything.
+pub fn credential_storage_paths() -> Result<(PathBuf, PathBuf), String> {
+    let app_dir = config_root()?.join(APP_DIR_NAME);
+    let vault = app_dir.join(ENCRYPTED_VAULT_FILE);
+    Ok((app_dir, vault))
+}
+
 fn get_encrypted_store_path() -> Result<PathBuf, String> {
     Ok(app_dir_path()?.join(ENCRYPTED_VAULT_FILE))
 }
@@ -96,32 +320,56 @@ fn ensure_secure_permissions(path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-fn fallback_cipher_key() -> [u8; 32] {
+impl MachineIdentity {
+    fn current() -> Self {
+        Self {
+            hostname: std::env::var("HOSTNAME").ok(),
+            computer_name: std::env::var("COMPUTERNAME").ok(),
+            username: std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .ok(),
+            home_dir: dirs::home_dir().map(|path| path.to_string_lossy().to_string()),
+        }
+    }
+}
+
+fn fallback_cipher_key(identity: &MachineIdentity, salt: Option<&str>) -> Result<[u8; 32], String> {
     let mut hasher = Sha256::new();
     hasher.update(APP_DIR_NAME.as_bytes());
 
-    if let Ok(hostname) = std::env::var("HOSTNAME") {
+    if let Some(hostname) = &identity.hostname {
         hasher.update(hostname.as_bytes());
     }
-    if let Ok(computer_name) = std::env::var("COMPUTERNAME") {
+    if let Some(computer_name) = &identity.computer_name {
         hasher.update(computer_name.as_bytes());
     }
-    if let Ok(username) = std::env::var("USER").or_else(|_| std::env::var("USERNAME")) {
+    if let Some(username) = &identity.username {
         hasher.update(username.as_bytes());
     }
-    if let Some(home_dir) = dirs::home_dir() {
-        hasher.update(home_dir.to_string_lossy().as_bytes());
+    if let Some(home_dir) = &identity.home_dir {
+        hasher.update(home_dir.as_bytes());
+    }
+    // Vaults written before re-keying existed carry no salt and keep the
+    // original derivation so they stay readable.
+    if let Some(salt) = salt {
+        let salt_bytes = BASE64_STANDARD
+            .decode(salt.as_bytes())
+            .map_err(|e| format!("Failed to decode credential vault salt: {}", e))?;
+        hasher.update(&salt_bytes);
     }
 
     let digest = hasher.finalize();
     let mut key = [0_u8; 32];
     key.copy_from_slice(&digest[..32]);
-    key
+    Ok(key)
 }
 
-fn encrypt_secret(plain_text: &str) -> Result<String, String> {
-    let key = fallback_cipher_key();
-    let cipher = Aes256Gcm::new_from_slice(&key)
+fn store_cipher_key(store: &EncryptedCredentialStore) -> Result<[u8; 32], String> {
+    fallback_cipher_key(&MachineIdentity::current(), store.salt.as_deref())
+}
+
+fn encrypt_secret(key: &[u8; 32], plain_text: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(key)
         .map_err(|e| format!("Failed to create fallback cipher: {}", e))?;
     let mut nonce_bytes = [0_u8; 12];
     OsRng.fill_bytes(&mut nonce_bytes);
@@ -135,7 +383,7 @@ fn encrypt_secret(plain_text: &str) -> Result<String, String> {
     Ok(BASE64_STANDARD.encode(payload))
 }
 
-fn decrypt_secret(cipher_text: &str) -> Result<String, String> {
+fn decrypt_secret(key: &[u8; 32], cipher_text: &str) -> Result<Zeroizing<String>, String> {
     let payload = BASE64_STANDARD
         .decode(cipher_text.as_bytes())
         .map_err(|e| format!("Failed to decode fallback credential: {}", e))?;
@@ -144,15 +392,125 @@ fn decrypt_secret(cipher_text: &str) -> Result<String, String> {
     }
 
     let (nonce_bytes, encrypted_bytes) = payload.split_at(12);
-    let key = fallback_cipher_key();
-    let cipher = Aes256Gcm::new_from_slice(&key)
+    let cipher = Aes256Gcm::new_from_slice(key)
         .map_err(|e| format!("Failed to create fallback cipher: {}", e))?;
     let nonce = Nonce::from_slice(nonce_bytes);
-    let plain_bytes = cipher
-        .decrypt(nonce, encrypted_bytes)
-        .map_err(|e| format!("Failed to decrypt fallback credential: {}", e))?;
+    let plain_bytes = Zeroizing::new(
+        cipher
+            .decrypt(nonce, encrypted_bytes)
+            .map_err(|e| format!("Failed to decrypt fallback credential: {}", e))?,
+    );
+
+    let plain_text = std::str::from_utf8(&plain_bytes)
+        .map_err(|e| format!("Fallback credential is not valid UTF-8: {}", e))?;
+    Ok(Zeroizing::new(plain_text.to_string()))
+}
+
+fn vault_mac(
+    key: &[u8; 32],
+    version: u32,
+    credentials: &HashMap<String, String>,
+) -> Result<HmacSha256, String> {
+    let mut key_hasher = Sha256::new();
+    key_hasher.update(VAULT_MAC_CONTEXT);
+    key_hasher.update(key);
+    let mac_key = key_hasher.finalize();
+
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(&mac_key)
+        .map_err(|e| format!("Failed to create credential vault MAC: {}", e))?;
+    mac.update(&version.to_be_bytes());
+
+    let mut keys: Vec<&String> = credentials.keys().collect();
+    keys.sort();
+    for key in keys {
+        mac.update(key.as_bytes());
+        mac.update(&[0]);
+        mac.update(credentials[key].as_bytes());
+        mac.update(&[0]);
+    }
+    Ok(mac)
+}
+
+fn verify_encrypted_store(store: &EncryptedCredentialStore, key: &[u8; 32]) -> Result<(), String> {
+    let encoded = store.mac.as_deref().ok_or(VAULT_INTEGRITY_ERROR)?;
+    let expected = BASE64_STANDARD
+        .decode(encoded.as_bytes())
+        .map_err(|_| VAULT_INTEGRITY_ERROR.to_string())?;
+    vault_mac(key, store.version, &store.credentials)?
+        .verify_slice(&expected)
+        .map_err(|_| VAULT_INTEGRITY_ERROR.to_string())
+}
+
+/// Whether this machine has written a MAC-sealed vault. Without a usable
+/// keychain there is nowhere to keep the marker, so nothing is known.
+fn vault_sealed_marker_present() -> Result<bool, String> {
+    if credential_backend() == CredentialBackend::VaultOnly {
+        return Ok(false);
+    }
+    match keychain_get(VAULT_SEALED_MARKER_SERVICE, VAULT_SEALED_MARKER_ACCOUNT) {
+        Ok(marker) => Ok(marker.is_some()),
+        Err(KeychainReadError::Locked(detail)) => Err(format!(
+            "The system keychain is locked; unlock it before opening the credential vault: {}",
+            detail
+        )),
+        // A keychain that can't be read can't hold the marker either.
+        Err(KeychainReadError::Failed(_)) => Ok(false),
+    }
+}
 
-    String::from_utf8(plain_bytes).map_err(|e| format!("Fallback credential is not valid UTF-8: {}", e))
+fn record_vault_sealed_marker() {
+    if credential_backend() == CredentialBackend::VaultOnly {
+        return;
+    }
+    if let Err(error) = keychain_set(
+        VAULT_SEALED_MARKER_SERVICE,
+        VAULT_SEALED_MARKER_ACCOUNT,
+        &VAULT_FORMAT_VERSION.to_string(),
+    ) {
+        log::warn!(
+            "Failed to record credential vault integrity marker: {}",
+            error
+        );
+    }
+}
+
+/// A vault older than the current format is only accepted as a legacy vault
+/// if it is genuinely pre-MAC: it carries no `mac` and no sealed vault was
+/// ever written here. Anything else is a downgraded, tampered file.
+fn check_legacy_vault(store: &EncryptedCredentialStore, sealed_before: bool) -> Result<(), String> {
+    if store.mac.is_some() || sealed_before {
+        return Err(format!(
+            "{} (format version {} was downgraded from a sealed vault)",
+            VAULT_INTEGRITY_ERROR, store.version
+        ));
+    }
+    Ok(())
+}
+
+fn migrate_encrypted_store(
+    mut store: EncryptedCredentialStore,
+) -> Result<EncryptedCredentialStore, String> {
+    check_legacy_vault(&store, vault_sealed_marker_present()?)?;
+    // Legacy vaults predate the MAC, so there is nothing to verify; upgrade
+    // them in place so every subsequent read is integrity-checked.
+    store.version = VAULT_FORMAT_VERSION;
+    write_encrypted_store(&store)?;
+    Ok(store)
+}
+
+fn parse_encrypted_store(path: &PathBuf) -> Result<EncryptedCredentialStore, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read encrypted credential vault: {}", e))?;
+    let store: EncryptedCredentialStore = serde_json::from_str(&data)
+        .map_err(|e| format!("Failed to parse encrypted credential vault: {}", e))?;
+
+    if store.version > VAULT_FORMAT_VERSION {
+        return Err(format!(
+            "Credential vault format version {} is newer than supported version {}; update Cowork to read it",
+            store.version, VAULT_FORMAT_VERSION
+        ));
+    }
+    Ok(store)
 }
 
 fn read_encrypted_store() -> Result<EncryptedCredentialStore, String> {
@@ -161,38 +519,86 @@ fn read_encrypted_store() -> Result<EncryptedCredentialStore, String> {
         return Ok(EncryptedCredentialStore::default());
     }
 
-    let data = fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read encrypted credential vault: {}", e))?;
-    serde_json::from_str(&data)
-        .map_err(|e| format!("Failed to parse encrypted credential vault: {}", e))
+    let store = parse_encrypted_store(&path)?;
+    if store.version < VAULT_FORMAT_VERSION {
+        return migrate_encrypted_store(store);
+    }
+
+    verify_encrypted_store(&store, &store_cipher_key(&store)?)?;
+    Ok(store)
 }
 
 fn write_encrypted_store(store: &EncryptedCredentialStore) -> Result<(), String> {
     let path = get_encrypted_store_path()?;
-    let data = serde_json::to_string_pretty(store)
+    let key = store_cipher_key(store)?;
+    let mac = vault_mac(&key, VAULT_FORMAT_VERSION, &store.credentials)?.finalize();
+    let sealed = EncryptedCredentialStore {
+        version: VAULT_FORMAT_VERSION,
+        salt: store.salt.clone(),
+        mac: Some(BASE64_STANDARD.encode(mac.into_bytes())),
+        credentials: store.credentials.clone(),
+    };
+    let data = serde_json::to_string_pretty(&sealed)
         .map_err(|e| format!("Failed to serialize encrypted credential vault: {}", e))?;
-    fs::write(&path, data)
+
+    // Write to a sibling file and rename over the vault so a crash mid-write
+    // never leaves a truncated vault behind.
+    let staging_path = path.with_extension("json.tmp");
+    fs::write(&staging_path, data)
         .map_err(|e| format!("Failed to write encrypted credential vault: {}", e))?;
-    ensure_secure_permissions(&path)
+    ensure_secure_permissions(&staging_path)?;
+    fs::rename(&staging_path, &path)
+        .map_err(|e| format!("Failed to replace encrypted credential vault: {}", e))?;
+    record_vault_sealed_marker();
+    Ok(())
 }
 
 fn fallback_key(service: &str, account: &str) -> String {
     format!("{}.{}", service, account)
 }
 
-fn fallback_get_secret(service: &str, account: &str) -> Result<Option<String>, String> {
+fn fallback_get_secret(
+    service: &str,
+    account: &str,
+) -> Result<Option<Zeroizing<String>>, CredentialLookupError> {
     let key = fallback_key(service, account);
-    let store = read_encrypted_store()?;
+    let store = read_encrypted_store().map_err(CredentialLookupError::BackendUnavailable)?;
     match store.credentials.get(&key) {
-        Some(encrypted) => Ok(Some(decrypt_secret(encrypted)?)),
+        Some(encrypted) => {
+            let cipher_key =
+                store_cipher_key(&store).map_err(CredentialLookupError::BackendUnavailable)?;
+            decrypt_secret(&cipher_key, encrypted)
+                .map(Some)
+                .map_err(|error| CredentialLookupError::DecryptFailed(format!("{}: {}", key, error)))
+        }
         None => Ok(None),
     }
 }
 
+/// Lists vault entries that fail to decrypt under the current machine key.
+pub fn undecryptable_vault_entries() -> Result<Vec<String>, String> {
+    let path = get_encrypted_store_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let store = parse_encrypted_store(&path)?;
+    let key = store_cipher_key(&store)?;
+    let mut entries: Vec<String> = store
+        .credentials
+        .iter()
+        .filter(|(_, encrypted)| decrypt_secret(&key, encrypted).is_err())
+        .map(|(vault_key, _)| vault_key.clone())
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
 fn fallback_set_secret(service: &str, account: &str, value: &str) -> Result<(), String> {
     let key = fallback_key(service, account);
     let mut store = read_encrypted_store()?;
-    store.credentials.insert(key, encrypt_secret(value)?);
+    let encrypted = encrypt_secret(&store_cipher_key(&store)?, value)?;
+    store.credentials.insert(key, encrypted);
     write_encrypted_store(&store)
 }
 
@@ -210,12 +616,34 @@ fn keyring_entry(service: &str, account: &str) -> Result<Entry, String> {
         .map_err(|e| format!("Failed to create keychain entry: {}", e))
 }
 
-fn keychain_get(service: &str, account: &str) -> Result<Option<String>, String> {
-    let entry = keyring_entry(service, account)?;
+/// Secret Service reports a locked collection as `NoStorageAccess`; macOS
+/// reports a locked login keychain as errSecInteractionNotAllowed (-25308).
+fn keychain_error_is_locked(error: &KeyringError) -> bool {
+    match error {
+        KeyringError::NoStorageAccess(_) => true,
+        KeyringError::PlatformFailure(inner) => {
+            let detail = inner.to_string().to_lowercase();
+            detail.contains("-25308")
+                || detail.contains("interaction is not allowed")
+                || detail.contains("locked")
+        }
+        _ => false,
+    }
+}
+
+fn keychain_get(service: &str, account: &str) -> Result<Option<String>, KeychainReadError> {
+    let entry = keyring_entry(service, account).map_err(KeychainReadError::Failed)?;
     match entry.get_password() {
         Ok(value) => Ok(Some(value)),
         Err(KeyringError::NoEntry) => Ok(None),
-        Err(error) => Err(format!("Keychain read failed: {}", error)),
+        Err(error) => {
+            let detail = format!("Keychain read failed: {}", error);
+            if keychain_error_is_locked(&error) {
+                Err(KeychainReadError::Locked(detail))
+            } else {
+                Err(KeychainReadError::Failed(detail))
+            }
+        }
     }
 }
 
@@ -234,6 +662,76 @@ fn keychain_delete(service: &str, account: &str) -> Result<(), String> {
     }
 }
 
+/// Per-item outcome of `keychain_each`.
+#[derive(Debug)]
+enum KeychainItemOutcome<T> {
+    Done(T),
+    Failed(String),
+    /// Not attempted because an earlier item found the keychain itself
+    /// unreachable (locked, denied, or the platform service is down).
+    Skipped,
+}
+
+fn keychain_unreachable(error: &KeyringError) -> bool {
+    matches!(
+        error,
+        KeyringError::PlatformFailure(_) | KeyringError::NoStorageAccess(_)
+    )
+}
+
+/// Runs `op` for every item in order. The keyring has no batch API, so this
+/// is still one keychain call per item; what it saves is the calls after a
+/// failure. Item-level errors are recorded and the loop continues; once the
+/// keychain reports itself unreachable the rest are skipped rather than each
+/// failing (or prompting) on its own.
+fn keychain_each<I, T>(
+    items: &[I],
+    action: &str,
+    mut op: impl FnMut(&I) -> Result<T, KeyringError>,
+) -> Vec<KeychainItemOutcome<T>> {
+    let mut unreachable = false;
+    items
+        .iter()
+        .map(|item| {
+            if unreachable {
+                return KeychainItemOutcome::Skipped;
+            }
+            match op(item) {
+                Ok(value) => KeychainItemOutcome::Done(value),
+                Err(error) => {
+                    unreachable = keychain_unreachable(&error);
+                    KeychainItemOutcome::Failed(format!("Keychain {} failed: {}", action, error))
+                }
+            }
+        })
+        .collect()
+}
+
+fn keychain_set_many(items: &[(&str, &str, &str)]) -> Vec<KeychainItemOutcome<()>> {
+    keychain_each(items, "write", |(service, account, value)| {
+        Entry::new(service, account)?.set_password(value)
+    })
+}
+
+fn keychain_get_many(items: &[(&str, &str)]) -> Vec<KeychainItemOutcome<Option<String>>> {
+    keychain_each(items, "read", |(service, account)| {
+        match Entry::new(service, account)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(KeyringError::NoEntry) => Ok(None),
+            Err(error) => Err(error),
+        }
+    })
+}
+
+fn keychain_delete_many(items: &[(&str, &str)]) -> Vec<KeychainItemOutcome<()>> {
+    keychain_each(items, "delete", |(service, account)| {
+        match Entry::new(service, account)?.delete_password() {
+            Ok(_) | Err(KeyringError::NoEntry) => Ok(()),
+            Err(error) => Err(error),
+        }
+    })
+}
+
 fn parse_plaintext_store(path: &PathBuf) -> Result<PlaintextCredentialStore, String> {
     let data = fs::read_to_string(path)
         .map_err(|e| format!("Failed to read legacy credentials {}: {}", path.display(), e))?;
@@ -257,17 +755,32 @@ fn migrate_plaintext_store(path: &PathBuf) -> Result<(), String> {
     }
 
     let store = parse_plaintext_store(path)?;
-    let backend = credential_backend();
-    for (key, value) in store.credentials {
-        if let Some((service, account)) = split_legacy_key(&key) {
-            match backend {
-                CredentialBackend::VaultOnly => {
-                    fallback_set_secret(&service, &account, &value)?;
-                }
-                CredentialBackend::KeychainWithFallback => {
-                    if keychain_set(&service, &account, &value).is_err() {
-                        fallback_set_secret(&service, &account, &value)?;
-                    }
+    let entries: Vec<(String, String, &String)> = store
+        .credentials
+        .iter()
+        .filter_map(|(key, value)| {
+            split_legacy_key(key).map(|(service, account)| (service, account, value))
+        })
+        .collect();
+
+    match credential_backend() {
+        CredentialBackend::VaultOnly => {
+            for (service, account, value) in &entries {
+                fallback_set_secret(service, account, value)?;
+            }
+        }
+        CredentialBackend::KeychainWithFallback => {
+            let items: Vec<(&str, &str, &str)> = entries
+                .iter()
+                .map(|(service, account, value)| {
+                    (service.as_str(), account.as_str(), value.as_str())
+                })
+                .collect();
+            let results = keychain_set_many(&items);
+            // Anything the keychain did not take goes to the vault instead.
+            for ((service, account, value), result) in items.iter().zip(results) {
+                if !matches!(result, KeychainItemOutcome::Done(())) {
+                    fallback_set_secret(service, account, value)?;
                 }
             }
         }
@@ -291,8 +804,146 @@ fn migrate_plaintext_stores_if_needed() -> Result<(), String> {
     Ok(())
 }
 
-pub fn credentials_migrate_on_startup() -> Result<(), String> {
-    migrate_plaintext_stores_if_needed()
+fn split_vault_key(key: &str) -> Option<(String, String)> {
+    // Service names may contain dots (e.g. `cowork.connector-secrets`) while
+    // account names never do, so split on the last separator.
+    let (service, account) = key.rsplit_once('.')?;
+    if service.is_empty() || account.is_empty() {
+        return None;
+    }
+    Some((service.to_string(), account.to_string()))
+}
+
+/// Moves every decryptable vault entry into the OS keychain when the keychain
+/// backend is active. Entries are removed from the vault only after the
+/// keychain returns the same value; anything that fails stays in the vault.
+pub fn credentials_migrate_to_keychain() -> Result<usize, String> {
+    if credential_backend() != CredentialBackend::KeychainWithFallback {
+        return Ok(0);
+    }
+
+    let mut store = read_encrypted_store()?;
+    if store.credentials.is_empty() {
+        return Ok(0);
+    }
+
+    let key = store_cipher_key(&store)?;
+    let mut pending = Vec::new();
+    for (vault_key, encrypted) in &store.credentials {
+        let (service, account) = match split_vault_key(vault_key) {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let value = match decrypt_secret(&key, encrypted) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        pending.push((vault_key.clone(), service, account, value));
+    }
+
+    // The vault was the effective store under the previous default, so its
+    // values win over anything already sitting in the keychain.
+    let writes: Vec<(&str, &str, &str)> = pending
+        .iter()
+        .map(|(_, service, account, value)| (service.as_str(), account.as_str(), value.as_str()))
+        .collect();
+    let written: Vec<_> = pending
+        .iter()
+        .zip(keychain_set_many(&writes))
+        .filter_map(|(entry, result)| match result {
+            KeychainItemOutcome::Done(()) => Some(entry),
+            _ => None,
+        })
+        .collect();
+
+    let reads: Vec<(&str, &str)> = written
+        .iter()
+        .map(|(_, service, account, _)| (service.as_str(), account.as_str()))
+        .collect();
+    let mut migrated = Vec::new();
+    let mut mismatched = Vec::new();
+    for ((vault_key, service, account, value), result) in
+        written.iter().zip(keychain_get_many(&reads))
+    {
+        match result {
+            KeychainItemOutcome::Done(Some(stored)) if stored == **value => {
+                migrated.push(vault_key.clone())
+            }
+            // A bad read-back would shadow the vault value on lookup.
+            KeychainItemOutcome::Done(_) => mismatched.push((service.as_str(), account.as_str())),
+            KeychainItemOutcome::Failed(_) | KeychainItemOutcome::Skipped => {}
+        }
+    }
+    for ((service, account), result) in mismatched.iter().zip(keychain_delete_many(&mismatched)) {
+        if let KeychainItemOutcome::Failed(error) = result {
+            log::warn!(
+                "Failed to remove unverified keychain copy of {}.{}: {}",
+                service,
+                account,
+                error
+            );
+        }
+    }
+
+    if migrated.is_empty() {
+        return Ok(0);
+    }
+    for vault_key in &migrated {
+        store.credentials.remove(vault_key);
+    }
+    write_encrypted_store(&store)?;
+    Ok(migrated.len())
+}
+
+/// Returns how many vault entries were moved into the OS keychain.
+pub fn credentials_migrate_on_startup() -> Result<usize, String> {
+    migrate_plaintext_stores_if_needed()?;
+    match credentials_migrate_to_keychain() {
+        Ok(0) => Ok(0),
+        Ok(count) => {
+            log::info!("Migrated {} vault entries into the OS keychain", count);
+            Ok(count)
+        }
+        Err(error) => {
+            log::warn!("Deferred vault-to-keychain migration: {}", error);
+            Ok(0)
+        }
+    }
+}
+
+fn generate_connector_seed() -> Zeroizing<String> {
+    let mut seed_bytes = Zeroizing::new([0_u8; 32]);
+    OsRng.fill_bytes(seed_bytes.as_mut());
+    Zeroizing::new(BASE64_STANDARD.encode(seed_bytes.as_ref()))
+}
+
+/// Returns the connector master seed. Intermediate buffers are zeroized; the
+/// returned `String` is handed to the sidecar environment and is not.
+/// Find the stored connector seed from the keychain read and, failing that,
+/// the vault. `Ok(None)` means a new seed may be minted; an error means one
+/// may exist but can't be read right now.
+fn existing_connector_seed(
+    keychain: Result<Option<String>, KeychainReadError>,
+    vault: impl FnOnce() -> Result<Option<Zeroizing<String>>, CredentialLookupError>,
+) -> Result<Option<String>, CredentialLookupError> {
+    let keychain_error = match keychain {
+        Ok(Some(seed)) => return Ok(Some(seed)),
+        Ok(None) => None,
+        Err(error) => Some(error),
+    };
+
+    match vault() {
+        Ok(Some(seed)) => return Ok(Some(seed.to_string())),
+        Err(error @ CredentialLookupError::DecryptFailed(_)) => return Err(error),
+        Ok(None) | Err(_) => {}
+    }
+
+    // A locked keychain most likely still holds the seed; minting a new one
+    // would orphan every connector secret sealed with it.
+    match keychain_error {
+        Some(error @ KeychainReadError::Locked(_)) => Err(error.into()),
+        _ => Ok(None),
+    }
 }
 
 pub fn get_or_create_sidecar_connector_seed() -> Result<String, String> {
@@ -301,83 +952,203 @@ pub fn get_or_create_sidecar_connector_seed() -> Result<String, String> {
 
     match backend {
         CredentialBackend::VaultOnly => {
-            if let Ok(Some(seed)) = fallback_get_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT)
-            {
-                return Ok(seed);
+            // Never replace a seed that exists but fails to decrypt: doing so
+            // would orphan every connector secret sealed with it.
+            if let Some(seed) = fallback_get_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT)? {
+                return Ok(seed.to_string());
             }
 
-            let mut seed_bytes = [0_u8; 32];
-            OsRng.fill_bytes(&mut seed_bytes);
-            let seed = BASE64_STANDARD.encode(seed_bytes);
+            let seed = generate_connector_seed();
             fallback_set_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT, &seed)?;
-            Ok(seed)
+            Ok(seed.to_string())
         }
         CredentialBackend::KeychainWithFallback => {
-            match keychain_get(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT) {
-                Ok(Some(seed)) => return Ok(seed),
-                Ok(None) | Err(_) => {}
-            }
-
-            match fallback_get_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT) {
-                Ok(Some(seed)) => return Ok(seed),
-                Ok(None) | Err(_) => {}
+            if let Some(seed) = existing_connector_seed(
+                keychain_get(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT),
+                || fallback_get_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT),
+            )? {
+                return Ok(seed);
             }
 
-            let mut seed_bytes = [0_u8; 32];
-            OsRng.fill_bytes(&mut seed_bytes);
-            let seed = BASE64_STANDARD.encode(seed_bytes);
+            let seed = generate_connector_seed();
 
             match keychain_set(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT, &seed) {
                 Ok(_) => {
                     let _ = fallback_delete_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT);
-                    Ok(seed)
+                    Ok(seed.to_string())
                 }
                 Err(_) => {
                     fallback_set_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT, &seed)?;
-                    Ok(seed)
+                    Ok(seed.to_string())
                 }
             }
         }
     }
 }
 
-pub async fn credentials_get(service: String, account: String) -> Result<Option<String>, String> {
-    migrate_plaintext_stores_if_needed()?;
+/// Replaces the stored connector seed, making sure no stale copy in the other
+/// backend can shadow it on the next lookup.
+fn store_sidecar_connector_seed(seed: &str) -> Result<(), String> {
     match credential_backend() {
-        CredentialBackend::VaultOnly => fallback_get_secret(&service, &account),
+        CredentialBackend::VaultOnly => {
+            fallback_set_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT, seed)
+        }
+        CredentialBackend::KeychainWithFallback => {
+            match keychain_set(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT, seed) {
+                Ok(_) => {
+                    let _ =
+                        fallback_delete_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT);
+                    Ok(())
+                }
+                Err(error) => {
+                    // The keychain is read first, so an old seed left there
+                    // would win over the vault copy.
+                    keychain_delete(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT)
+                        .map_err(|delete_error| format!("{}; {}", error, delete_error))?;
+                    fallback_set_secret(CONNECTOR_SECRET_SERVICE, CONNECTOR_SECRET_ACCOUNT, seed)
+                }
+            }
+        }
+    }
+}
+
+/// Clears the rotation flag however the rotation ends.
+struct ConnectorSeedRotationGuard;
+
+impl ConnectorSeedRotationGuard {
+    fn acquire() -> Result<Self, String> {
+        CONNECTOR_SEED_ROTATING
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| Self)
+            .map_err(|_| "A connector seed rotation is already in progress".to_string())
+    }
+}
+
+impl Drop for ConnectorSeedRotationGuard {
+    fn drop(&mut self) {
+        CONNECTOR_SEED_ROTATING.store(false, Ordering::Release);
+    }
+}
+
+async fn rotate_sidecar_connector_secrets(
+    state: &State<'_, AgentState>,
+    seed: &str,
+) -> Result<ConnectorSeedRotationResult, String> {
+    let result = state
+        .manager
+        .send_command(
+            "rotate_connector_secrets",
+            serde_json::json!({ "seed": seed }),
+        )
+        .await?;
+    Ok(ConnectorSeedRotationResult {
+        rotated_entries: result["rotatedEntries"].as_u64().unwrap_or(0) as usize,
+        from_key_version: result["fromKeyVersion"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        to_key_version: result["toKeyVersion"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+/// Generates a new connector seed and has the sidecar re-encrypt its
+/// connector secrets under it. The new seed is stored only once the sidecar
+/// has rotated; if storing fails the sidecar is rotated back, so the stored
+/// seed and the connector vault never disagree.
+#[tauri::command]
+pub async fn credentials_rotate_connector_seed(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+) -> Result<ConnectorSeedRotationResult, String> {
+    let _guard = ConnectorSeedRotationGuard::acquire()?;
+    let previous_seed = Zeroizing::new(get_or_create_sidecar_connector_seed()?);
+    let next_seed = generate_connector_seed();
+
+    ensure_sidecar_started_public(&app, &state).await?;
+    let result = rotate_sidecar_connector_secrets(&state, &next_seed)
+        .await
+        .map_err(|e| format!("Failed to re-encrypt connector secrets: {}", e))?;
+
+    if let Err(error) = store_sidecar_connector_seed(&next_seed) {
+        if let Err(rollback_error) = rotate_sidecar_connector_secrets(&state, &previous_seed).await
+        {
+            log::error!(
+                "Connector secrets were rotated but the new seed could not be stored or rolled back: {}",
+                rollback_error
+            );
+        }
+        record_credential_access(
+            "rotate",
+            CONNECTOR_SECRET_SERVICE,
+            CONNECTOR_SECRET_ACCOUNT,
+            Err(&error),
+        );
+        return Err(format!("Failed to store the new connector seed: {}", error));
+    }
+
+    record_credential_access(
+        "rotate",
+        CONNECTOR_SECRET_SERVICE,
+        CONNECTOR_SECRET_ACCOUNT,
+        Ok(None),
+    );
+    log::info!(
+        "Rotated connector secret seed ({} entries re-encrypted)",
+        result.rotated_entries
+    );
+    Ok(result)
+}
+
+/// Looks up a credential. Decrypted vault values are zeroized internally, but
+/// the returned `String` crosses the Tauri boundary, where IPC serialization
+/// makes copies this module cannot scrub.
+pub async fn credentials_lookup(
+    service: String,
+    account: String,
+) -> Result<Option<String>, CredentialLookupError> {
+    migrate_plaintext_stores_if_needed().map_err(CredentialLookupError::BackendUnavailable)?;
+    let vault_value = |service: &str, account: &str| {
+        fallback_get_secret(service, account).map(|value| value.map(|secret| secret.to_string()))
+    };
+    match credential_backend() {
+        CredentialBackend::VaultOnly => vault_value(&service, &account),
         CredentialBackend::KeychainWithFallback => match keychain_get(&service, &account) {
             Ok(Some(value)) => Ok(Some(value)),
-            Ok(None) => fallback_get_secret(&service, &account),
-            Err(_) => fallback_get_secret(&service, &account),
+            Ok(None) => vault_value(&service, &account),
+            // A keychain failure is only masked when the vault actually holds
+            // the value; otherwise "not found" would hide the real problem.
+            Err(keychain_error) => match vault_value(&service, &account)? {
+                Some(value) => Ok(Some(value)),
+                None => Err(keychain_error.into()),
+            },
         },
     }
 }
 
-pub async fn credentials_set(
-    service: String,
-    account: String,
-    value: String,
-) -> Result<(), String> {
+fn store_credential(service: &str, account: &str, value: &str) -> Result<(), String> {
     migrate_plaintext_stores_if_needed()?;
     match credential_backend() {
-        CredentialBackend::VaultOnly => fallback_set_secret(&service, &account, &value),
-        CredentialBackend::KeychainWithFallback => match keychain_set(&service, &account, &value) {
+        CredentialBackend::VaultOnly => fallback_set_secret(service, account, value),
+        CredentialBackend::KeychainWithFallback => match keychain_set(service, account, value) {
             Ok(_) => {
-                let _ = fallback_delete_secret(&service, &account);
+                let _ = fallback_delete_secret(service, account);
                 Ok(())
             }
-            Err(_) => fallback_set_secret(&service, &account, &value),
+            Err(_) => fallback_set_secret(service, account, value),
         },
     }
 }
 
-pub async fn credentials_delete(service: String, account: String) -> Result<(), String> {
+fn remove_credential(service: &str, account: &str) -> Result<(), String> {
     migrate_plaintext_stores_if_needed()?;
     match credential_backend() {
-        CredentialBackend::VaultOnly => fallback_delete_secret(&service, &account),
+        CredentialBackend::VaultOnly => fallback_delete_secret(service, account),
         CredentialBackend::KeychainWithFallback => {
-            let keychain_result = keychain_delete(&service, &account);
-            let fallback_result = fallback_delete_secret(&service, &account);
+            let keychain_result = keychain_delete(service, account);
+            let fallback_result = fallback_delete_secret(service, account);
 
             match (keychain_result, fallback_result) {
                 (Ok(_), Ok(_)) => Ok(()),
@@ -391,3 +1162,508 @@ pub async fn credentials_delete(service: String, account: String) -> Result<(),
         }
     }
 }
+
+pub async fn credentials_get(
+    service: String,
+    account: String,
+) -> Result<Option<String>, CommandError> {
+    let result = credentials_lookup(service.clone(), account.clone())
+        .await
+        .map_err(CommandError::from);
+    record_credential_access(
+        "get",
+        &service,
+        &account,
+        result
+            .as_ref()
+            .map(|value| Some(value.is_some()))
+            .map_err(CommandError::message),
+    );
+    result
+}
+
+pub async fn credentials_set(
+    service: String,
+    account: String,
+    value: String,
+) -> Result<(), CommandError> {
+    let result = store_credential(&service, &account, &value).map_err(CommandError::internal);
+    record_credential_access(
+        "set",
+        &service,
+        &account,
+        result.as_ref().map(|_| None).map_err(CommandError::message),
+    );
+    result
+}
+
+pub async fn credentials_delete(service: String, account: String) -> Result<(), CommandError> {
+    let result = remove_credential(&service, &account).map_err(CommandError::internal);
+    record_credential_access(
+        "delete",
+        &service,
+        &account,
+        result.as_ref().map(|_| None).map_err(CommandError::message),
+    );
+    result
+}
+
+/// Decrypt every entry with `source_key` and re-encrypt it under
+/// `target_key` in a new store with `salt`. An entry that fails to decrypt
+/// aborts the whole re-key, so the vault on disk is never half converted.
+fn rekey_store(
+    store: &EncryptedCredentialStore,
+    source_key: &[u8; 32],
+    target_key: &[u8; 32],
+    salt: String,
+) -> Result<EncryptedCredentialStore, String> {
+    let mut plain_entries = Vec::with_capacity(store.credentials.len());
+    for (key, encrypted) in &store.credentials {
+        let value = decrypt_secret(source_key, encrypted).map_err(|error| {
+            format!(
+                "Cannot re-key credential vault: entry {} did not decrypt with the supplied identity ({}); nothing was changed",
+                key, error
+            )
+        })?;
+        plain_entries.push((key.clone(), value));
+    }
+
+    let mut rekeyed = EncryptedCredentialStore {
+        version: VAULT_FORMAT_VERSION,
+        salt: Some(salt),
+        mac: None,
+        credentials: HashMap::with_capacity(plain_entries.len()),
+    };
+    for (key, value) in plain_entries {
+        let encrypted = encrypt_secret(target_key, &value)?;
+        rekeyed.credentials.insert(key, encrypted);
+    }
+    Ok(rekeyed)
+}
+
+pub async fn credentials_rekey(
+    previous_identity: Option<MachineIdentity>,
+) -> Result<CredentialRekeyResult, String> {
+    let used_previous_identity = previous_identity.is_some();
+    let path = get_encrypted_store_path()?;
+    if !path.exists() {
+        return Ok(CredentialRekeyResult {
+            rekeyed_entries: 0,
+            used_previous_identity,
+        });
+    }
+
+    let store = parse_encrypted_store(&path)?;
+    let source_identity = previous_identity.unwrap_or_else(MachineIdentity::current);
+    let source_key = fallback_cipher_key(&source_identity, store.salt.as_deref())?;
+    if store.version >= VAULT_FORMAT_VERSION {
+        verify_encrypted_store(&store, &source_key)?;
+    } else {
+        check_legacy_vault(&store, vault_sealed_marker_present()?)?;
+    }
+
+    let mut salt_bytes = [0_u8; 16];
+    OsRng.fill_bytes(&mut salt_bytes);
+    let salt = BASE64_STANDARD.encode(salt_bytes);
+    let target_key = fallback_cipher_key(&MachineIdentity::current(), Some(&salt))?;
+    let rekeyed = rekey_store(&store, &source_key, &target_key, salt)?;
+
+    write_encrypted_store(&rekeyed)?;
+    Ok(CredentialRekeyResult {
+        rekeyed_entries: rekeyed.credentials.len(),
+        used_previous_identity,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Credential access audit log
+// ---------------------------------------------------------------------------
+
+/// Shared with the sidecar's security audit log so both processes append to
+/// the file `auth_get_security_posture` reports on.
+fn audit_log_path() -> Result<PathBuf, String> {
+    Ok(paths::app_data_dir()?.join("security").join("audit.log"))
+}
+
+fn rotate_audit_log_if_needed(path: &PathBuf) -> Result<(), String> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.len() >= AUDIT_LOG_MAX_BYTES => {
+            fs::rename(path, path.with_extension("log.1"))
+                .map_err(|e| format!("Failed to rotate audit log: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn append_audit_entry(entry: &serde_json::Value) -> Result<(), String> {
+    let path = audit_log_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create audit log directory: {}", e))?;
+    }
+    rotate_audit_log_if_needed(&path)?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    ensure_secure_permissions(&path)?;
+    writeln!(file, "{}", entry).map_err(|e| format!("Failed to append audit log entry: {}", e))
+}
+
+/// Records a credential operation. Only the service and account are logged,
+/// never the value. Failures to write are ignored so auditing can never
+/// break credential access.
+fn record_credential_access(
+    operation: &str,
+    service: &str,
+    account: &str,
+    outcome: Result<Option<bool>, &str>,
+) {
+    let (found, error) = match outcome {
+        Ok(found) => (found, None),
+        Err(error) => (None, Some(error)),
+    };
+    record_audit_event(
+        "credentials",
+        operation,
+        error,
+        serde_json::json!({
+            "service": service,
+            "account": account,
+            "found": found,
+        }),
+    );
+}
+
+/// Appends an entry in the sidecar's audit format. Write failures are ignored.
+pub(crate) fn record_audit_event(
+    category: &str,
+    command: &str,
+    error: Option<&str>,
+    metadata: serde_json::Value,
+) {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0);
+    let outcome_label = if error.is_some() { "failed" } else { "success" };
+
+    let entry = serde_json::json!({
+        "timestamp": timestamp,
+        "category": category,
+        "command": command,
+        "outcome": outcome_label,
+        "sessionId": null,
+        "connectorId": null,
+        "runId": null,
+        "provider": null,
+        "metadata": metadata,
+        "error": error,
+    });
+    let _ = append_audit_entry(&entry);
+}
+
+/// Returns the most recent audit entries in chronological order.
+#[tauri::command]
+pub async fn credentials_read_audit(limit: Option<usize>) -> Result<Vec<serde_json::Value>, String> {
+    let path = audit_log_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let limit = limit
+        .unwrap_or(AUDIT_READ_DEFAULT_LIMIT)
+        .clamp(1, AUDIT_READ_MAX_LIMIT);
+    let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read audit log: {}", e))?;
+    let mut entries: Vec<serde_json::Value> = data
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line.trim()).ok())
+        .take(limit)
+        .collect();
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Runs a fresh keychain probe. The active backend stays as decided at
+/// startup; a changed result takes effect on the next launch.
+#[tauri::command]
+pub async fn keychain_probe() -> Result<KeychainProbeResult, String> {
+    let result = tauri::async_runtime::spawn_blocking(probe_keychain)
+        .await
+        .map_err(|e| format!("Keychain probe failed to run: {}", e))?;
+
+    let backend = credential_backend();
+    let warning = match (backend, &result) {
+        (CredentialBackend::VaultOnly, _) if credential_backend_override().is_some() => None,
+        (CredentialBackend::VaultOnly, Err(_)) => Some(
+            "The OS keychain is not working on this machine. Credentials are stored in the encrypted vault instead."
+                .to_string(),
+        ),
+        (CredentialBackend::VaultOnly, Ok(())) => Some(
+            "The OS keychain is available now but was not at startup. Restart Cowork to store credentials in the keychain."
+                .to_string(),
+        ),
+        (CredentialBackend::KeychainWithFallback, _) => None,
+    };
+
+    Ok(KeychainProbeResult {
+        available: result.is_ok(),
+        error: result.err(),
+        backend: credential_backend_label().to_string(),
+        warning,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: [u8; 32] = [7; 32];
+
+    fn sealed_store(credentials: &[(&str, &str)]) -> EncryptedCredentialStore {
+        let credentials: HashMap<String, String> = credentials
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let mac = vault_mac(&TEST_KEY, VAULT_FORMAT_VERSION, &credentials)
+            .unwrap()
+            .finalize();
+        EncryptedCredentialStore {
+            version: VAULT_FORMAT_VERSION,
+            salt: None,
+            mac: Some(BASE64_STANDARD.encode(mac.into_bytes())),
+            credentials,
+        }
+    }
+
+    #[test]
+    fn sealed_vault_verifies() {
+        let store = sealed_store(&[("svc:a", "cipher-a"), ("svc:b", "cipher-b")]);
+        assert!(verify_encrypted_store(&store, &TEST_KEY).is_ok());
+    }
+
+    #[test]
+    fn tampered_vault_fails_integrity_check() {
+        let mut store = sealed_store(&[("svc:a", "cipher-a")]);
+        store
+            .credentials
+            .insert("svc:a".to_string(), "attacker".to_string());
+        let error = verify_encrypted_store(&store, &TEST_KEY).unwrap_err();
+        assert!(error.contains("integrity check failed"));
+    }
+
+    #[test]
+    fn downgraded_vault_with_mac_is_rejected() {
+        let mut store = sealed_store(&[("svc:a", "cipher-a")]);
+        store
+            .credentials
+            .insert("svc:a".to_string(), "attacker".to_string());
+        store.version = 0;
+
+        let error = check_legacy_vault(&store, false).unwrap_err();
+        assert!(error.contains("integrity check failed"));
+        assert!(error.contains("downgraded"));
+    }
+
+    #[test]
+    fn downgraded_vault_with_stripped_mac_is_rejected_once_sealed() {
+        let mut store = sealed_store(&[("svc:a", "cipher-a")]);
+        store.version = 0;
+        store.mac = None;
+
+        assert!(check_legacy_vault(&store, true).is_err());
+    }
+
+    #[test]
+    fn genuine_legacy_vault_is_migrated() {
+        let store = EncryptedCredentialStore {
+            version: 0,
+            salt: None,
+            mac: None,
+            credentials: HashMap::from([("svc:a".to_string(), "cipher-a".to_string())]),
+        };
+        assert!(check_legacy_vault(&store, false).is_ok());
+    }
+
+    #[test]
+    fn version_is_covered_by_the_mac() {
+        let mut store = sealed_store(&[("svc:a", "cipher-a")]);
+        store.version = VAULT_FORMAT_VERSION + 1;
+        assert!(verify_encrypted_store(&store, &TEST_KEY).is_err());
+    }
+
+    #[derive(Debug)]
+    struct PlatformMessage(&'static str);
+
+    impl std::fmt::Display for PlatformMessage {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl std::error::Error for PlatformMessage {}
+
+    fn no_vault_seed() -> Result<Option<Zeroizing<String>>, CredentialLookupError> {
+        Ok(None)
+    }
+
+    #[test]
+    fn locked_keychain_errors_are_recognised() {
+        let locked =
+            KeyringError::NoStorageAccess(Box::new(PlatformMessage("collection is locked")));
+        assert!(keychain_error_is_locked(&locked));
+        let mac_locked = KeyringError::PlatformFailure(Box::new(PlatformMessage(
+            "User interaction is not allowed. (-25308)",
+        )));
+        assert!(keychain_error_is_locked(&mac_locked));
+
+        let other = KeyringError::PlatformFailure(Box::new(PlatformMessage("dbus timeout")));
+        assert!(!keychain_error_is_locked(&other));
+        assert!(!keychain_error_is_locked(&KeyringError::NoEntry));
+    }
+
+    #[test]
+    fn missing_seed_can_be_minted() {
+        assert_eq!(
+            existing_connector_seed(Ok(None), no_vault_seed).unwrap(),
+            None
+        );
+        // A keychain that fails for good reasons other than a lock does not
+        // hold a seed we could get back later.
+        let failed = Err(KeychainReadError::Failed(
+            "Keychain read failed: dbus".into(),
+        ));
+        assert_eq!(
+            existing_connector_seed(failed, no_vault_seed).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn locked_keychain_refuses_to_mint_a_seed() {
+        let locked = Err(KeychainReadError::Locked(
+            "Keychain read failed: locked".into(),
+        ));
+        let error = existing_connector_seed(locked, no_vault_seed).unwrap_err();
+        assert!(matches!(error, CredentialLookupError::KeychainLocked(_)));
+        assert!(String::from(error).starts_with("The system keychain is locked"));
+    }
+
+    #[test]
+    fn stored_seeds_are_reused() {
+        let seed = existing_connector_seed(Ok(Some("keychain-seed".into())), || {
+            panic!("vault should not be read when the keychain has the seed")
+        })
+        .unwrap();
+        assert_eq!(seed.as_deref(), Some("keychain-seed"));
+
+        // The vault copy still works while the keychain is locked.
+        let locked = Err(KeychainReadError::Locked("locked".into()));
+        let seed =
+            existing_connector_seed(locked, || Ok(Some(Zeroizing::new("vault-seed".into()))))
+                .unwrap();
+        assert_eq!(seed.as_deref(), Some("vault-seed"));
+
+        let error = existing_connector_seed(Ok(None), || {
+            Err(CredentialLookupError::DecryptFailed("bad key".into()))
+        })
+        .unwrap_err();
+        assert!(matches!(error, CredentialLookupError::DecryptFailed(_)));
+    }
+
+    #[test]
+    fn keychain_each_skips_the_rest_once_the_keychain_is_unreachable() {
+        let mut attempted = Vec::new();
+        let outcomes = keychain_each(&[1, 2, 3, 4, 5], "read", |item| {
+            attempted.push(*item);
+            match item {
+                2 => Err(KeyringError::Invalid("account".into(), "empty".into())),
+                4 => Err(KeyringError::NoStorageAccess("locked".into())),
+                _ => Ok(*item * 10),
+            }
+        });
+
+        // An item-level error does not stop the loop; an unreachable keychain does.
+        assert_eq!(attempted, vec![1, 2, 3, 4]);
+        assert!(matches!(outcomes[0], KeychainItemOutcome::Done(10)));
+        match &outcomes[1] {
+            KeychainItemOutcome::Failed(error) => {
+                assert!(error.starts_with("Keychain read failed"))
+            }
+            other => panic!("expected a failure, got {:?}", other),
+        }
+        assert!(matches!(outcomes[2], KeychainItemOutcome::Done(30)));
+        assert!(matches!(outcomes[3], KeychainItemOutcome::Failed(_)));
+        assert!(matches!(outcomes[4], KeychainItemOutcome::Skipped));
+    }
+
+    fn encrypted_store(key: &[u8; 32], entries: &[(&str, &str)]) -> EncryptedCredentialStore {
+        EncryptedCredentialStore {
+            version: VAULT_FORMAT_VERSION,
+            salt: Some("b2xkLXNhbHQ=".to_string()),
+            mac: None,
+            credentials: entries
+                .iter()
+                .map(|(key_name, value)| {
+                    (key_name.to_string(), encrypt_secret(key, value).unwrap())
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn rekey_moves_every_entry_from_the_previous_identity_to_the_new_key() {
+        let previous = MachineIdentity {
+            hostname: Some("old-laptop".to_string()),
+            computer_name: None,
+            username: Some("me".to_string()),
+            home_dir: Some("/Users/me".to_string()),
+        };
+        let source_key = fallback_cipher_key(&previous, Some("b2xkLXNhbHQ=")).unwrap();
+        let target_key =
+            fallback_cipher_key(&MachineIdentity::current(), Some("bmV3LXNhbHQ=")).unwrap();
+        assert_ne!(source_key, target_key);
+        let store = encrypted_store(
+            &source_key,
+            &[("api/google", "g-key"), ("api/openai", "o-key")],
+        );
+
+        let rekeyed =
+            rekey_store(&store, &source_key, &target_key, "bmV3LXNhbHQ=".to_string()).unwrap();
+        assert_eq!(rekeyed.salt.as_deref(), Some("bmV3LXNhbHQ="));
+        assert_eq!(rekeyed.version, VAULT_FORMAT_VERSION);
+        assert_eq!(rekeyed.credentials.len(), 2);
+        for (key, value) in [("api/google", "g-key"), ("api/openai", "o-key")] {
+            let encrypted = &rekeyed.credentials[key];
+            assert_eq!(
+                decrypt_secret(&target_key, encrypted).unwrap().as_str(),
+                value
+            );
+            assert!(decrypt_secret(&source_key, encrypted).is_err());
+        }
+    }
+
+    #[test]
+    fn rekey_fails_as_a_whole_when_any_entry_does_not_decrypt() {
+        let source_key = [1_u8; 32];
+        let target_key = [2_u8; 32];
+        let mut store = encrypted_store(&source_key, &[("api/google", "g-key")]);
+        store.credentials.insert(
+            "api/openai".to_string(),
+            encrypt_secret(&[3_u8; 32], "o-key").unwrap(),
+        );
+        let original = store.credentials.clone();
+
+        let Err(error) = rekey_store(&store, &source_key, &target_key, "bmV3LXNhbHQ=".to_string())
+        else {
+            panic!("re-key should fail");
+        };
+        assert!(error.contains("api/openai"), "{}", error);
+        assert!(error.contains("nothing was changed"), "{}", error);
+        // The source store is not modified, so nothing needs rolling back.
+        assert_eq!(store.credentials, original);
+    }
+}
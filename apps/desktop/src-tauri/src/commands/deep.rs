@@ -6,6 +6,7 @@
 
 use crate::commands::agent::{AgentState, ensure_sidecar_started};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tauri::{AppHandle, State};
 
 // ============================================================================
@@ -47,10 +48,87 @@ pub struct UpdateMemoryInput {
     pub tags: Option<Vec<String>>,
 }
 
+/// Schema version stamped on memory export documents.
+const MEMORY_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryExportDocument {
+    pub schema_version: u32,
+    pub exported_at: i64,
+    #[serde(default)]
+    pub group: Option<String>,
+    pub memories: Vec<Memory>,
+}
+
+/// A memory as read from an import document; only the fields needed to
+/// recreate it are required.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportedMemory {
+    id: String,
+    title: String,
+    content: String,
+    group: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryImportResult {
+    pub imported: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+    /// Maps ids from the document to the ids assigned on import.
+    pub id_map: HashMap<String, String>,
+    pub warnings: Vec<String>,
+}
+
 // ============================================================================
 // Memory Commands
 // ============================================================================
 
+async fn list_memories(
+    state: &AgentState,
+    working_directory: &str,
+    group: Option<&str>,
+) -> Result<Vec<Memory>, String> {
+    let manager = &state.manager;
+    let params = serde_json::json!({
+        "workingDirectory": working_directory,
+        "group": group,
+    });
+
+    let result = manager.send_command("deep_memory_list", params).await?;
+    // Handler returns { memories: [...] }
+    let wrapper: serde_json::Value = serde_json::from_value(result).map_err(|e| format!("Failed to parse: {}", e))?;
+    let memories = wrapper.get("memories").cloned().unwrap_or(serde_json::json!([]));
+    serde_json::from_value(memories).map_err(|e| format!("Failed to parse memories: {}", e))
+}
+
+async fn create_memory(
+    state: &AgentState,
+    working_directory: &str,
+    input: CreateMemoryInput,
+) -> Result<Memory, String> {
+    let manager = &state.manager;
+    let params = serde_json::json!({
+        "workingDirectory": working_directory,
+        "input": input.clone(),
+        "title": input.title,
+        "content": input.content,
+        "group": input.group,
+        "tags": input.tags.unwrap_or_default(),
+        "source": input.source.unwrap_or_else(|| "manual".to_string()),
+    });
+
+    let result = manager.send_command("deep_memory_create", params).await?;
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse memory: {}", e))
+}
+
 /// Initialize memory service for a working directory
 #[tauri::command]
 pub async fn deep_memory_init(
@@ -78,18 +156,7 @@ pub async fn deep_memory_list(
     group: Option<String>,
 ) -> Result<Vec<Memory>, String> {
     ensure_sidecar_started(&app, &state).await?;
-
-    let manager = &state.manager;
-    let params = serde_json::json!({
-        "workingDirectory": working_directory,
-        "group": group,
-    });
-
-    let result = manager.send_command("deep_memory_list", params).await?;
-    // Handler returns { memories: [...] }
-    let wrapper: serde_json::Value = serde_json::from_value(result).map_err(|e| format!("Failed to parse: {}", e))?;
-    let memories = wrapper.get("memories").cloned().unwrap_or(serde_json::json!([]));
-    serde_json::from_value(memories).map_err(|e| format!("Failed to parse memories: {}", e))
+    list_memories(&state, &working_directory, group.as_deref()).await
 }
 
 /// Create a new memory
@@ -101,20 +168,7 @@ pub async fn deep_memory_create(
     input: CreateMemoryInput,
 ) -> Result<Memory, String> {
     ensure_sidecar_started(&app, &state).await?;
-
-    let manager = &state.manager;
-    let params = serde_json::json!({
-        "workingDirectory": working_directory,
-        "input": input.clone(),
-        "title": input.title,
-        "content": input.content,
-        "group": input.group,
-        "tags": input.tags.unwrap_or_default(),
-        "source": input.source.unwrap_or_else(|| "manual".to_string()),
-    });
-
-    let result = manager.send_command("deep_memory_create", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse memory: {}", e))
+    create_memory(&state, &working_directory, input).await
 }
 
 /// Read a memory by ID
@@ -194,21 +248,114 @@ pub async fn deep_memory_search(
     working_directory: String,
     query: String,
     limit: Option<i32>,
+    tags: Option<Vec<String>>,
+    group: Option<String>,
 ) -> Result<Vec<Memory>, String> {
     ensure_sidecar_started(&app, &state).await?;
 
+    let limit = limit.unwrap_or(20);
+    let filtered = tags.as_ref().is_some_and(|tags| !tags.is_empty()) || group.is_some();
     let manager = &state.manager;
     let params = serde_json::json!({
         "workingDirectory": working_directory,
         "query": query,
-        "limit": limit.unwrap_or(20),
+        "limit": memory_search_fetch_limit(limit, filtered),
+        "tags": tags,
+        "group": group,
     });
 
     let result = manager.send_command("deep_memory_search", params).await?;
     // Handler returns { memories: [...] }
     let wrapper: serde_json::Value = serde_json::from_value(result).map_err(|e| format!("Failed to parse: {}", e))?;
     let memories = wrapper.get("memories").cloned().unwrap_or(serde_json::json!([]));
-    serde_json::from_value(memories).map_err(|e| format!("Failed to parse memories: {}", e))
+    let memories: Vec<Memory> =
+        serde_json::from_value(memories).map_err(|e| format!("Failed to parse memories: {}", e))?;
+
+    // Older daemons ignore the filters, so apply them here as well.
+    let mut memories =
+        filter_and_rank_memories(memories, &query, tags.as_deref(), group.as_deref());
+    memories.truncate(limit.max(0) as usize);
+    Ok(memories)
+}
+
+const MEMORY_SEARCH_OVERFETCH_FACTOR: i32 = 5;
+const MEMORY_SEARCH_MIN_FILTERED_FETCH: i32 = 100;
+
+/// The daemon applies `limit` before the tag and group filters run here, so
+/// filtered searches ask for a wider window to still fill the page.
+fn memory_search_fetch_limit(limit: i32, filtered: bool) -> i32 {
+    if !filtered {
+        return limit;
+    }
+    limit
+        .saturating_mul(MEMORY_SEARCH_OVERFETCH_FACTOR)
+        .max(MEMORY_SEARCH_MIN_FILTERED_FETCH)
+}
+
+/// How well a memory matches the query terms: title hits outweigh tag hits,
+/// which outweigh content hits.
+fn memory_relevance(memory: &Memory, query: &str) -> u32 {
+    let title = memory.title.to_lowercase();
+    let content = memory.content.to_lowercase();
+    let tags: Vec<String> = memory.tags.iter().map(|tag| tag.to_lowercase()).collect();
+    query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .map(|term| {
+            let mut score = 0;
+            if title.contains(&term) {
+                score += 3;
+            }
+            if tags.iter().any(|tag| tag.contains(&term)) {
+                score += 2;
+            }
+            if content.contains(&term) {
+                score += 1;
+            }
+            score
+        })
+        .sum()
+}
+
+/// Keep memories in `group` carrying any of `tags`, then order by relevance
+/// to `query`, with confidence and access count only breaking ties. The sort
+/// is stable, so full ties keep the daemon's ranking.
+fn filter_and_rank_memories(
+    memories: Vec<Memory>,
+    query: &str,
+    tags: Option<&[String]>,
+    group: Option<&str>,
+) -> Vec<Memory> {
+    let tags: Vec<String> = tags
+        .unwrap_or_default()
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    let memories: Vec<Memory> = memories
+        .into_iter()
+        .filter(|memory| group.is_none_or(|group| memory.group == group))
+        .filter(|memory| {
+            tags.is_empty()
+                || memory
+                    .tags
+                    .iter()
+                    .any(|tag| tags.contains(&tag.trim().to_lowercase()))
+        })
+        .collect();
+
+    let mut scored: Vec<(u32, Memory)> = memories
+        .into_iter()
+        .map(|memory| (memory_relevance(&memory, query), memory))
+        .collect();
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| b.confidence.total_cmp(&a.confidence))
+            .then_with(|| b.access_count.cmp(&a.access_count))
+    });
+    scored.into_iter().map(|(_, memory)| memory).collect()
 }
 
 #[tauri::command]
@@ -323,6 +470,184 @@ pub async fn deep_memory_get_migration_report(
         .await
 }
 
+/// Export memories, optionally limited to one group, as a versioned document
+#[tauri::command]
+pub async fn deep_memory_export(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    working_directory: String,
+    group: Option<String>,
+) -> Result<MemoryExportDocument, String> {
+    ensure_sidecar_started(&app, &state).await?;
+
+    let memories = list_memories(&state, &working_directory, group.as_deref()).await?;
+    let exported_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    Ok(MemoryExportDocument {
+        schema_version: MEMORY_EXPORT_SCHEMA_VERSION,
+        exported_at,
+        group,
+        memories,
+    })
+}
+
+fn parse_memory_export(
+    document: &serde_json::Value,
+) -> Result<(Vec<ImportedMemory>, Vec<String>), String> {
+    let schema_version = document
+        .get("schemaVersion")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Memory export is missing schemaVersion".to_string())?;
+    if schema_version == 0 || schema_version > MEMORY_EXPORT_SCHEMA_VERSION as u64 {
+        return Err(format!(
+            "Unsupported memory export schema version {} (expected {})",
+            schema_version, MEMORY_EXPORT_SCHEMA_VERSION
+        ));
+    }
+    let entries = document
+        .get("memories")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Memory export is missing a memories array".to_string())?;
+
+    let mut memories = Vec::with_capacity(entries.len());
+    let mut warnings = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        match serde_json::from_value::<ImportedMemory>(entry.clone()) {
+            Ok(memory) if memory.title.trim().is_empty() || memory.group.trim().is_empty() => {
+                warnings.push(format!("Skipped memory {}: title and group are required", index));
+            }
+            Ok(memory) => memories.push(memory),
+            Err(e) => warnings.push(format!("Skipped memory {}: {}", index, e)),
+        }
+    }
+    Ok((memories, warnings))
+}
+
+/// Import memories from an exported document, recreating each one with a new id
+#[tauri::command]
+pub async fn deep_memory_import(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    working_directory: String,
+    document: serde_json::Value,
+    conflict_strategy: Option<String>,
+) -> Result<MemoryImportResult, String> {
+    let overwrite = match conflict_strategy.as_deref().unwrap_or("skip") {
+        "skip" => false,
+        "overwrite" => true,
+        other => {
+            return Err(format!(
+                "Unknown conflict strategy `{}`. Expected skip or overwrite",
+                other
+            ))
+        }
+    };
+    let (memories, warnings) = parse_memory_export(&document)?;
+
+    ensure_sidecar_started(&app, &state).await?;
+
+    // Collisions are detected on title + group, which is what users see.
+    let existing: HashMap<(String, String), String> =
+        list_memories(&state, &working_directory, None)
+            .await?
+            .into_iter()
+            .map(|memory| ((memory.title, memory.group), memory.id))
+            .collect();
+
+    let working_directory = working_directory.as_str();
+    let state = &state;
+    apply_memory_import(
+        memories,
+        existing,
+        overwrite,
+        warnings,
+        |input| create_memory(state, working_directory, input),
+        |memory_id| async move {
+            state
+                .manager
+                .send_command(
+                    "deep_memory_delete",
+                    serde_json::json!({
+                        "workingDirectory": working_directory,
+                        "memoryId": memory_id,
+                    }),
+                )
+                .await
+                .map(|_| ())
+        },
+    )
+    .await
+}
+
+/// Apply parsed memories against the existing title + group index. On
+/// overwrite the replacement is created before the old memory is removed, so
+/// a failed create leaves the existing memory in place.
+async fn apply_memory_import<C, CF, D, DF>(
+    memories: Vec<ImportedMemory>,
+    mut existing: HashMap<(String, String), String>,
+    overwrite: bool,
+    warnings: Vec<String>,
+    mut create: C,
+    mut remove: D,
+) -> Result<MemoryImportResult, String>
+where
+    C: FnMut(CreateMemoryInput) -> CF,
+    CF: std::future::Future<Output = Result<Memory, String>>,
+    D: FnMut(String) -> DF,
+    DF: std::future::Future<Output = Result<(), String>>,
+{
+    let mut result = MemoryImportResult {
+        imported: 0,
+        overwritten: 0,
+        skipped: 0,
+        id_map: HashMap::new(),
+        warnings,
+    };
+
+    for memory in memories {
+        let key = (memory.title.clone(), memory.group.clone());
+        let replaced = existing.get(&key).cloned();
+        if let Some(existing_id) = &replaced {
+            if !overwrite {
+                result.skipped += 1;
+                result.id_map.insert(memory.id, existing_id.clone());
+                continue;
+            }
+        }
+
+        let created = create(CreateMemoryInput {
+            title: memory.title,
+            content: memory.content,
+            group: memory.group,
+            tags: Some(memory.tags),
+            source: memory.source,
+        })
+        .await?;
+
+        match replaced {
+            Some(existing_id) => {
+                // The replacement already exists, so a failed removal only
+                // leaves a duplicate behind rather than losing the memory.
+                if let Err(e) = remove(existing_id.clone()).await {
+                    result.warnings.push(format!(
+                        "Imported {} but could not remove the memory it replaces ({}): {}",
+                        created.id, existing_id, e
+                    ));
+                }
+                result.overwritten += 1;
+            }
+            None => result.imported += 1,
+        }
+        existing.insert(key, created.id.clone());
+        result.id_map.insert(memory.id, created.id);
+    }
+
+    Ok(result)
+}
+
 /// List memory groups
 #[tauri::command]
 pub async fn deep_memory_list_groups(
@@ -559,3 +884,248 @@ pub async fn deep_command_create(
         .map(|s| s.to_string())
         .ok_or_else(|| "Invalid response".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(id: &str, group: &str, tags: &[&str], confidence: f64, access_count: i32) -> Memory {
+        Memory {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            group: group.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            source: "manual".to_string(),
+            confidence,
+            created_at: String::new(),
+            updated_at: String::new(),
+            access_count,
+            last_accessed_at: None,
+        }
+    }
+
+    fn ids(memories: &[Memory]) -> Vec<&str> {
+        memories.iter().map(|memory| memory.id.as_str()).collect()
+    }
+
+    #[test]
+    fn tags_match_any_of_case_insensitively() {
+        let memories = vec![
+            memory("a", "work", &["Rust"], 0.5, 0),
+            memory("b", "work", &["python"], 0.5, 0),
+            memory("c", "work", &["go"], 0.5, 0),
+        ];
+        let tags = vec![" rust ".to_string(), "PYTHON".to_string()];
+        let filtered = filter_and_rank_memories(memories, "", Some(&tags), None);
+        assert_eq!(ids(&filtered), ["a", "b"]);
+    }
+
+    #[test]
+    fn group_must_match_exactly() {
+        let memories = vec![
+            memory("a", "work", &[], 0.5, 0),
+            memory("b", "Work", &[], 0.5, 0),
+            memory("c", "home", &[], 0.5, 0),
+        ];
+        let filtered = filter_and_rank_memories(memories, "", None, Some("work"));
+        assert_eq!(ids(&filtered), ["a"]);
+    }
+
+    #[test]
+    fn empty_filters_keep_everything() {
+        let memories = vec![
+            memory("a", "work", &["x"], 0.5, 0),
+            memory("b", "home", &[], 0.5, 0),
+        ];
+        let blank = vec!["  ".to_string()];
+        assert_eq!(
+            filter_and_rank_memories(memories, "", Some(&blank), None).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn equal_relevance_sorts_by_confidence_then_access_count() {
+        let memories = vec![
+            memory("low", "g", &[], 0.2, 50),
+            memory("high-rare", "g", &[], 0.9, 1),
+            memory("high-popular", "g", &[], 0.9, 10),
+        ];
+        let ranked = filter_and_rank_memories(memories, "", None, None);
+        assert_eq!(ids(&ranked), ["high-popular", "high-rare", "low"]);
+    }
+
+    #[test]
+    fn relevance_outranks_confidence() {
+        let mut title_hit = memory("title-hit", "g", &[], 0.1, 0);
+        title_hit.title = "Deploy checklist".to_string();
+        let mut content_hit = memory("content-hit", "g", &[], 0.9, 0);
+        content_hit.content = "Remember to deploy on Fridays".to_string();
+        let mut tag_hit = memory("tag-hit", "g", &["deploy"], 0.5, 0);
+        tag_hit.title = "Release".to_string();
+        let unrelated = memory("unrelated", "g", &[], 1.0, 0);
+
+        let ranked = filter_and_rank_memories(
+            vec![unrelated, content_hit, tag_hit, title_hit],
+            "deploy",
+            None,
+            None,
+        );
+        assert_eq!(
+            ids(&ranked),
+            ["title-hit", "tag-hit", "content-hit", "unrelated"]
+        );
+    }
+
+    #[test]
+    fn confidence_breaks_relevance_ties() {
+        let mut low = memory("low", "g", &[], 0.2, 0);
+        low.title = "Rust notes".to_string();
+        let mut high = memory("high", "g", &[], 0.8, 0);
+        high.title = "Rust tips".to_string();
+
+        let ranked = filter_and_rank_memories(vec![low, high], "rust", None, None);
+        assert_eq!(ids(&ranked), ["high", "low"]);
+    }
+
+    #[test]
+    fn filtered_searches_fetch_a_wider_window() {
+        assert_eq!(memory_search_fetch_limit(20, false), 20);
+        assert_eq!(
+            memory_search_fetch_limit(5, true),
+            MEMORY_SEARCH_MIN_FILTERED_FETCH
+        );
+        assert_eq!(memory_search_fetch_limit(50, true), 250);
+        assert_eq!(memory_search_fetch_limit(i32::MAX, true), i32::MAX);
+    }
+
+    #[test]
+    fn ties_keep_the_daemon_order() {
+        let memories = vec![
+            memory("first", "g", &[], 0.5, 3),
+            memory("second", "g", &[], 0.5, 3),
+            memory("third", "g", &[], 0.5, 3),
+        ];
+        let ranked = filter_and_rank_memories(memories, "", None, None);
+        assert_eq!(ids(&ranked), ["first", "second", "third"]);
+    }
+
+    fn imported(id: &str, title: &str) -> ImportedMemory {
+        ImportedMemory {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: format!("{} content", title),
+            group: "notes".to_string(),
+            tags: Vec::new(),
+            source: None,
+        }
+    }
+
+    fn existing_index(entries: &[(&str, &str)]) -> HashMap<(String, String), String> {
+        entries
+            .iter()
+            .map(|(title, id)| ((title.to_string(), "notes".to_string()), id.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn skip_keeps_existing_memories_and_maps_their_ids() {
+        let created = std::cell::RefCell::new(Vec::new());
+        let removed = std::cell::RefCell::new(Vec::new());
+        let result = apply_memory_import(
+            vec![imported("old-1", "Taken"), imported("old-2", "Fresh")],
+            existing_index(&[("Taken", "live-1")]),
+            false,
+            Vec::new(),
+            |input: CreateMemoryInput| {
+                created.borrow_mut().push(input.title.clone());
+                let id = format!("new-{}", input.title);
+                async move { Ok(memory(&id, &input.group, &[], 0.5, 0)) }
+            },
+            |id: String| {
+                removed.borrow_mut().push(id);
+                async { Ok(()) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            (result.imported, result.skipped, result.overwritten),
+            (1, 1, 0)
+        );
+        assert_eq!(created.into_inner(), ["Fresh"]);
+        assert!(removed.into_inner().is_empty());
+        assert_eq!(result.id_map["old-1"], "live-1");
+        assert_eq!(result.id_map["old-2"], "new-Fresh");
+    }
+
+    #[tokio::test]
+    async fn overwrite_creates_the_replacement_before_removing() {
+        let calls = std::cell::RefCell::new(Vec::new());
+        let result = apply_memory_import(
+            vec![imported("old-1", "Taken")],
+            existing_index(&[("Taken", "live-1")]),
+            true,
+            Vec::new(),
+            |input: CreateMemoryInput| {
+                calls.borrow_mut().push(format!("create {}", input.title));
+                async move { Ok(memory("new-1", &input.group, &[], 0.5, 0)) }
+            },
+            |id: String| {
+                calls.borrow_mut().push(format!("remove {}", id));
+                async { Ok(()) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(calls.into_inner(), ["create Taken", "remove live-1"]);
+        assert_eq!(result.overwritten, 1);
+        assert_eq!(result.id_map["old-1"], "new-1");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_create_leaves_the_existing_memory_in_place() {
+        let removed = std::cell::RefCell::new(Vec::new());
+        let error = apply_memory_import(
+            vec![imported("old-1", "Taken")],
+            existing_index(&[("Taken", "live-1")]),
+            true,
+            Vec::new(),
+            |_input: CreateMemoryInput| async { Err::<Memory, _>("disk full".to_string()) },
+            |id: String| {
+                removed.borrow_mut().push(id);
+                async { Ok(()) }
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error, "disk full");
+        assert!(removed.into_inner().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_removal_after_overwrite_is_a_warning() {
+        let result = apply_memory_import(
+            vec![imported("old-1", "Taken")],
+            existing_index(&[("Taken", "live-1")]),
+            true,
+            Vec::new(),
+            |input: CreateMemoryInput| async move {
+                Ok(memory("new-1", &input.group, &[], 0.5, 0))
+            },
+            |_id: String| async { Err("daemon busy".to_string()) },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.overwritten, 1);
+        assert_eq!(result.id_map["old-1"], "new-1");
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("live-1"));
+    }
+}
@@ -1,10 +1,25 @@
 // Copyright (c) 2026 Naresh. All rights reserved.
 // Licensed under the MIT License. See LICENSE file for details.
 
+use crate::commands::agent::CommandSandboxSettingsPayload;
+use crate::commands::policy::glob_match;
 use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::UNIX_EPOCH;
+
+/// Default cap for `read_file`; callers may pass a smaller or larger limit
+/// up to `MAX_READ_FILE_BYTES_CEILING`.
+const DEFAULT_MAX_READ_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_READ_FILE_BYTES_CEILING: u64 = 100 * 1024 * 1024;
+const DEFAULT_DIRECTORY_PAGE_SIZE: usize = 500;
+const MAX_DIRECTORY_PAGE_SIZE: usize = 5_000;
 
 #[derive(Serialize, Deserialize)]
 pub struct FileInfo {
@@ -12,6 +27,16 @@ pub struct FileInfo {
     path: String,
     is_dir: bool,
     size: u64,
+    /// Last modification time in milliseconds since the epoch, when available.
+    modified: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DirectoryListing {
+    entries: Vec<FileInfo>,
+    total: usize,
+    offset: usize,
+    has_more: bool,
 }
 
 /// Get blocked system paths based on current platform
@@ -153,14 +178,258 @@ fn validate_path_for_write(path: &str) -> Result<PathBuf, String> {
     Ok(path_buf)
 }
 
+/// Extra allowed and denied roots from the last runtime config sync. They
+/// apply to file commands called with a `sandbox_root`.
+#[derive(Default)]
+struct SandboxPaths {
+    allowed: Vec<String>,
+    denied: Vec<String>,
+}
+
+static SANDBOX_PATHS: OnceLock<Mutex<SandboxPaths>> = OnceLock::new();
+
+fn sandbox_paths() -> MutexGuard<'static, SandboxPaths> {
+    let lock = SANDBOX_PATHS.get_or_init(|| Mutex::new(SandboxPaths::default()));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+pub(crate) fn record_sandbox_paths(settings: Option<&CommandSandboxSettingsPayload>) {
+    let mut paths = sandbox_paths();
+    paths.allowed = settings
+        .map(|s| s.allowed_paths.clone())
+        .unwrap_or_default();
+    paths.denied = settings.map(|s| s.denied_paths.clone()).unwrap_or_default();
+}
+
+/// Resolve a sandbox list entry the way the command sandbox does: `~` is the
+/// home directory and relative entries are relative to the root.
+fn resolve_sandbox_entry(entry: &str, root: &Path) -> PathBuf {
+    let expanded = match (entry.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(entry),
+    };
+    let absolute = root.join(expanded);
+    absolute.canonicalize().unwrap_or(absolute)
+}
+
+/// Canonicalize a path that may not exist yet: the deepest existing ancestor
+/// is resolved (following symlinks) and the missing tail is appended. The
+/// tail cannot contain symlinks, but `..` in it is rejected, since it could
+/// climb anywhere once the missing directories were created.
+fn canonicalize_for_sandbox(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut tail = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            let mut resolved = canonical;
+            for component in tail.iter().rev() {
+                resolved.push(component);
+            }
+            return Ok(resolved);
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(Component::Normal(name))) => {
+                tail.push(name.to_os_string());
+                existing = parent;
+            }
+            (_, Some(Component::ParentDir)) => {
+                return Err(format!(
+                    "Access denied: path outside allowed roots: {}",
+                    path.display()
+                ))
+            }
+            _ => return Err(format!("Failed to resolve path: {}", path.display())),
+        }
+    }
+}
+
+/// Resolve `path` against `root` and check it stays inside the root or one
+/// of the configured allowed paths, and outside every denied path. Symlinks
+/// are resolved first, so a link inside the root cannot point out of it.
+fn resolve_in_sandbox(path: &str, root: &str) -> Result<PathBuf, String> {
+    let root = Path::new(root)
+        .canonicalize()
+        .map_err(|e| format!("Invalid sandbox root {}: {}", root, e))?;
+    let target = canonicalize_for_sandbox(&root.join(path))?;
+
+    let paths = sandbox_paths();
+    if let Some(denied) = paths
+        .denied
+        .iter()
+        .map(|entry| resolve_sandbox_entry(entry, &root))
+        .find(|denied| target.starts_with(denied))
+    {
+        return Err(format!(
+            "Access denied: {} is inside denied path {}",
+            target.display(),
+            denied.display()
+        ));
+    }
+
+    let allowed = target.starts_with(&root)
+        || paths
+            .allowed
+            .iter()
+            .any(|entry| target.starts_with(resolve_sandbox_entry(entry, &root)));
+    if !allowed {
+        return Err(format!(
+            "Access denied: path outside allowed roots: {}",
+            target.display()
+        ));
+    }
+    Ok(target)
+}
+
+/// Apply the sandbox when a root is given; other paths pass through for the
+/// usual validation.
+fn sandboxed_path(path: String, sandbox_root: Option<&str>) -> Result<String, String> {
+    match sandbox_root {
+        Some(root) => Ok(resolve_in_sandbox(&path, root)?
+            .to_string_lossy()
+            .to_string()),
+        None => Ok(path),
+    }
+}
+
+fn resolve_read_limit(max_bytes: Option<u64>) -> Result<u64, String> {
+    match max_bytes {
+        None => Ok(DEFAULT_MAX_READ_FILE_BYTES),
+        Some(0) => Err("maxBytes must be greater than 0".to_string()),
+        Some(limit) if limit > MAX_READ_FILE_BYTES_CEILING => Err(format!(
+            "maxBytes cannot exceed {} bytes",
+            MAX_READ_FILE_BYTES_CEILING
+        )),
+        Some(limit) => Ok(limit),
+    }
+}
+
+fn file_too_large_error(size: u64, limit: u64) -> String {
+    format!(
+        "File too large: {} bytes exceeds the {} byte read limit",
+        size, limit
+    )
+}
+
+fn modified_ms(metadata: &fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+}
+
+/// Random sibling path for staging a write to `path`.
+fn temp_path_for(path: &Path) -> Result<PathBuf, String> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+    Ok(parent.join(format!(
+        ".{}.{:016x}.tmp",
+        file_name.to_string_lossy(),
+        OsRng.next_u64()
+    )))
+}
+
+fn create_temp_file(temp_path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)
+        .map_err(|e| format!("Failed to create temp file: {}", e))
+}
+
+/// Flush a fully written temp file and rename it over `path`.
+fn finish_temp_file(file: File, temp_path: &Path, path: &Path) -> Result<(), String> {
+    file.sync_all()
+        .map_err(|e| format!("Failed to flush file: {}", e))?;
+    drop(file);
+
+    // Keep the permissions of the file being replaced.
+    if let Ok(metadata) = fs::metadata(path) {
+        let _ = fs::set_permissions(temp_path, metadata.permissions());
+    }
+    fs::rename(temp_path, path).map_err(|e| format!("Failed to replace file: {}", e))
+}
+
+/// A rename would replace a symlink with a regular file, so writes go to the
+/// file the link points to instead, as a plain `fs::write` would. The
+/// resolved target is validated like any other write path.
+fn resolve_write_target(path: &Path) -> Result<PathBuf, String> {
+    let is_symlink = fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+    if !is_symlink {
+        return Ok(path.to_path_buf());
+    }
+    let target = fs::canonicalize(path)
+        .map_err(|e| format!("Failed to resolve symlink {}: {}", path.display(), e))?;
+    validate_path_for_write(&target.to_string_lossy())
+}
+
+/// Write to a sibling temp file and rename it over the target, so readers and
+/// crashes never observe a partially written file.
+fn write_atomically(path: &Path, content: &[u8]) -> Result<(), String> {
+    let path = &resolve_write_target(path)?;
+    let temp_path = temp_path_for(path)?;
+
+    let result = (|| {
+        let mut file = create_temp_file(&temp_path)?;
+        file.write_all(content)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        finish_temp_file(file, &temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
 #[tauri::command]
-pub async fn read_file(path: String) -> Result<String, String> {
+pub async fn read_file(
+    path: String,
+    max_bytes: Option<u64>,
+    sandbox_root: Option<String>,
+) -> Result<String, String> {
+    let path = sandboxed_path(path, sandbox_root.as_deref())?;
     let validated_path = validate_path(&path)?;
-    fs::read_to_string(&validated_path).map_err(|e| format!("Failed to read file: {}", e))
+    let limit = resolve_read_limit(max_bytes)?;
+
+    let file = File::open(&validated_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+    if size > limit {
+        return Err(file_too_large_error(size, limit));
+    }
+
+    // The file may grow between the size check and the read; never buffer past the limit.
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    if bytes.len() as u64 > limit {
+        return Err(file_too_large_error(bytes.len() as u64, limit));
+    }
+
+    String::from_utf8(bytes).map_err(|e| format!("Failed to read file: {}", e))
 }
 
 #[tauri::command]
-pub async fn write_file(path: String, content: String) -> Result<(), String> {
+pub async fn write_file(
+    path: String,
+    content: String,
+    sandbox_root: Option<String>,
+) -> Result<(), String> {
+    let path = sandboxed_path(path, sandbox_root.as_deref())?;
     let validated_path = validate_path_for_write(&path)?;
 
     // Ensure parent directory exists
@@ -168,38 +437,331 @@ pub async fn write_file(path: String, content: String) -> Result<(), String> {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
     }
 
-    fs::write(&validated_path, content).map_err(|e| format!("Failed to write file: {}", e))
+    write_atomically(&validated_path, content.as_bytes())
+}
+
+/// One `.gitignore` line, or a caller-supplied ignore pattern.
+struct IgnoreRule {
+    /// Directory the pattern is relative to.
+    base: PathBuf,
+    pattern: String,
+    negated: bool,
+    directory_only: bool,
+    /// Patterns containing a `/` match the path below `base`; the rest match
+    /// the entry name at any depth.
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str, base: &Path) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (directory_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = line.strip_prefix('/').unwrap_or(line);
+        if pattern.is_empty() {
+            return None;
+        }
+        Some(Self {
+            base: base.to_path_buf(),
+            pattern: pattern.to_string(),
+            negated,
+            directory_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, relative: &str, name: &str, is_dir: bool) -> bool {
+        if self.directory_only && !is_dir {
+            return false;
+        }
+        let text = if self.anchored { relative } else { name };
+        glob_match(text, &self.pattern)
+    }
+}
+
+fn read_ignore_file(path: &Path, base: &Path, rules: &mut Vec<IgnoreRule>) {
+    if let Ok(contents) = fs::read_to_string(path) {
+        rules.extend(
+            contents
+                .lines()
+                .filter_map(|line| IgnoreRule::parse(line, base)),
+        );
+    }
+}
+
+/// Rules from `.git/info/exclude` and every `.gitignore` between the
+/// repository root and `dir`, shallowest first so deeper files win.
+fn load_gitignore_rules(dir: &Path) -> Vec<IgnoreRule> {
+    let Some(root) = dir
+        .ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+    else {
+        return Vec::new();
+    };
+
+    let mut rules = Vec::new();
+    read_ignore_file(
+        &root.join(".git").join("info").join("exclude"),
+        root,
+        &mut rules,
+    );
+    let mut bases: Vec<&Path> = dir
+        .ancestors()
+        .take_while(|ancestor| *ancestor != root)
+        .collect();
+    bases.push(root);
+    for base in bases.into_iter().rev() {
+        read_ignore_file(&base.join(".gitignore"), base, &mut rules);
+    }
+    rules
+}
+
+/// Git semantics: the last matching rule decides, and `!` re-includes.
+/// Only the entry itself is checked, so browsing into an ignored directory
+/// still lists its contents.
+fn is_ignored(rules: &[IgnoreRule], entry_path: &Path, name: &str, is_dir: bool) -> bool {
+    let mut ignored = false;
+    for rule in rules {
+        let Ok(relative) = entry_path.strip_prefix(&rule.base) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if rule.matches(&relative, name, is_dir) {
+            ignored = !rule.negated;
+        }
+    }
+    ignored
+}
+
+/// A chunked write in progress. Chunks are appended to `temp_path`, which is
+/// only renamed over `target` on commit.
+struct PendingWrite {
+    session_id: Option<String>,
+    target: PathBuf,
+    temp_path: PathBuf,
+    file: File,
+    bytes_written: u64,
+}
+
+/// Open chunked writes keyed by handle, so large artifacts can be streamed to
+/// disk instead of being sent to `write_file` in one piece.
+static PENDING_WRITES: OnceLock<Mutex<HashMap<String, PendingWrite>>> = OnceLock::new();
+
+fn pending_writes() -> MutexGuard<'static, HashMap<String, PendingWrite>> {
+    let lock = PENDING_WRITES.get_or_init(|| Mutex::new(HashMap::new()));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn discard_pending_write(pending: PendingWrite) {
+    drop(pending.file);
+    let _ = fs::remove_file(&pending.temp_path);
+}
+
+fn unknown_write_handle(handle: &str) -> String {
+    format!("Unknown or already finished write handle: {}", handle)
+}
+
+/// Drop every chunked write opened for a session, e.g. when it is deleted.
+pub(crate) fn abort_session_file_writes(session_id: &str) {
+    let aborted: Vec<PendingWrite> = {
+        let mut writes = pending_writes();
+        let handles: Vec<String> = writes
+            .iter()
+            .filter(|(_, pending)| pending.session_id.as_deref() == Some(session_id))
+            .map(|(handle, _)| handle.clone())
+            .collect();
+        handles
+            .iter()
+            .filter_map(|handle| writes.remove(handle))
+            .collect()
+    };
+    for pending in aborted {
+        log::info!(
+            "Aborting chunked write to {} for deleted session {}",
+            pending.target.display(),
+            session_id
+        );
+        discard_pending_write(pending);
+    }
+}
+
+/// Start a chunked write to `path` and return a handle for
+/// `file_write_chunk`, `file_write_commit` and `file_write_abort`.
+#[tauri::command]
+pub async fn file_write_begin(
+    path: String,
+    session_id: Option<String>,
+    sandbox_root: Option<String>,
+) -> Result<String, String> {
+    let path = sandboxed_path(path, sandbox_root.as_deref())?;
+    let validated_path = validate_path_for_write(&path)?;
+
+    if let Some(parent) = validated_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let validated_path = resolve_write_target(&validated_path)?;
+    let temp_path = temp_path_for(&validated_path)?;
+    let file = create_temp_file(&temp_path)?;
+    let handle = format!("{:016x}{:016x}", OsRng.next_u64(), OsRng.next_u64());
+    pending_writes().insert(
+        handle.clone(),
+        PendingWrite {
+            session_id,
+            target: validated_path,
+            temp_path,
+            file,
+            bytes_written: 0,
+        },
+    );
+    Ok(handle)
+}
+
+/// Append a base64-encoded chunk and return the total bytes written so far.
+/// A failed write aborts the whole transfer.
+#[tauri::command]
+pub async fn file_write_chunk(handle: String, data: String) -> Result<u64, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&data)
+        .map_err(|e| format!("Failed to decode chunk: {}", e))?;
+
+    let mut writes = pending_writes();
+    let pending = writes
+        .get_mut(&handle)
+        .ok_or_else(|| unknown_write_handle(&handle))?;
+    if let Err(e) = pending.file.write_all(&bytes) {
+        if let Some(pending) = writes.remove(&handle) {
+            discard_pending_write(pending);
+        }
+        return Err(format!("Failed to write file: {}", e));
+    }
+    pending.bytes_written += bytes.len() as u64;
+    Ok(pending.bytes_written)
+}
+
+/// Flush the staged file and atomically move it into place. Returns the
+/// number of bytes written.
+#[tauri::command]
+pub async fn file_write_commit(handle: String) -> Result<u64, String> {
+    let pending = pending_writes()
+        .remove(&handle)
+        .ok_or_else(|| unknown_write_handle(&handle))?;
+
+    let PendingWrite {
+        target,
+        temp_path,
+        file,
+        bytes_written,
+        ..
+    } = pending;
+    if let Err(e) = finish_temp_file(file, &temp_path, &target) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    Ok(bytes_written)
+}
+
+/// Cancel a chunked write and remove its temp file. Unknown handles are
+/// ignored so callers can abort unconditionally while cleaning up.
+#[tauri::command]
+pub async fn file_write_abort(handle: String) -> Result<(), String> {
+    if let Some(pending) = pending_writes().remove(&handle) {
+        discard_pending_write(pending);
+    }
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn list_directory(path: String) -> Result<Vec<FileInfo>, String> {
+pub async fn list_directory(
+    path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    ignore: Option<Vec<String>>,
+    respect_gitignore: Option<bool>,
+) -> Result<DirectoryListing, String> {
     let validated_path = validate_path(&path)?;
+    let offset = offset.unwrap_or(0);
+    let limit = limit
+        .unwrap_or(DEFAULT_DIRECTORY_PAGE_SIZE)
+        .clamp(1, MAX_DIRECTORY_PAGE_SIZE);
     let entries = fs::read_dir(&validated_path).map_err(|e| format!("Failed to read directory: {}", e))?;
 
-    let mut files = Vec::new();
+    // Caller patterns come last so they override .gitignore, `!` included.
+    let respect_gitignore = respect_gitignore.unwrap_or(false);
+    let mut rules = if respect_gitignore {
+        load_gitignore_rules(&validated_path)
+    } else {
+        Vec::new()
+    };
+    rules.extend(
+        ignore
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|pattern| IgnoreRule::parse(pattern, &validated_path)),
+    );
 
+    // Sorting needs every name, but full metadata is only fetched for the
+    // requested page so huge directories stay cheap. Symlinks are the
+    // exception: they are followed so linked directories sort as directories.
+    let mut names = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
-        let metadata = entry.metadata().map_err(|e| e.to_string())?;
-
-        files.push(FileInfo {
-            name: entry.file_name().to_string_lossy().to_string(),
-            path: entry.path().to_string_lossy().to_string(),
-            is_dir: metadata.is_dir(),
-            size: metadata.len(),
-        });
+        let is_dir = match entry.file_type() {
+            Ok(kind) if kind.is_symlink() => fs::metadata(entry.path())
+                .map(|metadata| metadata.is_dir())
+                .unwrap_or(false),
+            Ok(kind) => kind.is_dir(),
+            Err(_) => false,
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        let entry_path = entry.path();
+        if respect_gitignore && name == ".git" {
+            continue;
+        }
+        if is_ignored(&rules, &entry_path, &name, is_dir) {
+            continue;
+        }
+        names.push((is_dir, name, entry_path));
     }
 
-    files.sort_by(|a, b| {
+    names.sort_by_cached_key(|(is_dir, name, _)| {
         // Directories first, then alphabetically
-        match (a.is_dir, b.is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
+        (!*is_dir, name.to_lowercase())
     });
 
-    Ok(files)
+    let total = names.len();
+    let mut files = Vec::new();
+    for (is_dir, name, entry_path) in names.into_iter().skip(offset).take(limit) {
+        // Entries can disappear while listing; report what is still there.
+        let metadata = fs::metadata(&entry_path).ok();
+        files.push(FileInfo {
+            name,
+            path: entry_path.to_string_lossy().to_string(),
+            is_dir: metadata.as_ref().map(|m| m.is_dir()).unwrap_or(is_dir),
+            size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+            modified: metadata.as_ref().and_then(modified_ms),
+        });
+    }
+
+    Ok(DirectoryListing {
+        has_more: offset.saturating_add(files.len()) < total,
+        entries: files,
+        total,
+        offset,
+    })
 }
 
 #[tauri::command]
@@ -239,3 +801,323 @@ pub async fn open_file_preview(name: String, data: String) -> Result<(), String>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-files-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    fn rule(line: &str) -> IgnoreRule {
+        IgnoreRule::parse(line, Path::new("/repo")).unwrap()
+    }
+
+    #[test]
+    fn ignore_rule_parsing() {
+        assert!(IgnoreRule::parse("", Path::new("/repo")).is_none());
+        assert!(IgnoreRule::parse("# comment", Path::new("/repo")).is_none());
+        assert!(IgnoreRule::parse("/", Path::new("/repo")).is_none());
+        assert!(IgnoreRule::parse("!", Path::new("/repo")).is_none());
+
+        let negated = rule("!keep.log  ");
+        assert!(negated.negated);
+        assert_eq!(negated.pattern, "keep.log");
+        assert!(!negated.anchored);
+
+        let directory = rule("build/");
+        assert!(directory.directory_only);
+        assert!(!directory.anchored);
+        assert_eq!(directory.pattern, "build");
+
+        let rooted = rule("/dist");
+        assert!(rooted.anchored);
+        assert_eq!(rooted.pattern, "dist");
+
+        let nested = rule("docs/*.md");
+        assert!(nested.anchored);
+        assert_eq!(nested.pattern, "docs/*.md");
+    }
+
+    #[test]
+    fn ignore_rule_matching() {
+        assert!(rule("*.log").matches("a/b/debug.log", "debug.log", false));
+        assert!(!rule("*.log").matches("debug.txt", "debug.txt", false));
+
+        assert!(rule("build/").matches("src/build", "build", true));
+        assert!(!rule("build/").matches("src/build", "build", false));
+
+        assert!(rule("/dist").matches("dist", "dist", true));
+        assert!(!rule("/dist").matches("app/dist", "dist", true));
+
+        assert!(rule("docs/*.md").matches("docs/intro.md", "intro.md", false));
+        assert!(!rule("docs/*.md").matches("docs/api/intro.md", "intro.md", false));
+        assert!(rule("docs/**/*.md").matches("docs/api/intro.md", "intro.md", false));
+        assert!(rule("*.{png,jpg}").matches("logo.jpg", "logo.jpg", false));
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let base = Path::new("/repo");
+        let rules: Vec<IgnoreRule> = ["*.log", "!keep.log"]
+            .iter()
+            .filter_map(|line| IgnoreRule::parse(line, base))
+            .collect();
+        assert!(is_ignored(
+            &rules,
+            &base.join("debug.log"),
+            "debug.log",
+            false
+        ));
+        assert!(!is_ignored(
+            &rules,
+            &base.join("keep.log"),
+            "keep.log",
+            false
+        ));
+
+        let rules: Vec<IgnoreRule> = ["!keep.log", "*.log"]
+            .iter()
+            .filter_map(|line| IgnoreRule::parse(line, base))
+            .collect();
+        assert!(is_ignored(
+            &rules,
+            &base.join("keep.log"),
+            "keep.log",
+            false
+        ));
+
+        // Rules only apply below their own base directory.
+        let nested = IgnoreRule::parse("*.tmp", &base.join("sub")).unwrap();
+        assert!(!is_ignored(&[nested], &base.join("x.tmp"), "x.tmp", false));
+    }
+
+    #[test]
+    fn gitignore_precedence_runs_exclude_then_root_then_nested_then_caller() {
+        let root = scratch_dir("gitignore");
+        let nested = root.join("app");
+        fs::create_dir_all(root.join(".git").join("info")).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join(".git/info/exclude"), "*.secret\n").unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n!root.secret\ncache/\n").unwrap();
+        fs::write(nested.join(".gitignore"), "!keep.log\n/out\n").unwrap();
+
+        let mut rules = load_gitignore_rules(&nested);
+        let ignored = |rules: &[IgnoreRule], name: &str, is_dir: bool| {
+            is_ignored(rules, &nested.join(name), name, is_dir)
+        };
+        assert!(ignored(&rules, "debug.log", false));
+        assert!(!ignored(&rules, "keep.log", false));
+        assert!(ignored(&rules, "api.secret", false));
+        assert!(!ignored(&rules, "root.secret", false));
+        assert!(ignored(&rules, "cache", true));
+        assert!(!ignored(&rules, "cache", false));
+        assert!(ignored(&rules, "out", true));
+        assert!(!ignored(&rules, "main.rs", false));
+
+        // `list_directory` appends caller patterns last, so they override
+        // .gitignore in both directions.
+        rules.extend(
+            ["!debug.log", "*.rs"]
+                .iter()
+                .filter_map(|pattern| IgnoreRule::parse(pattern, &nested)),
+        );
+        assert!(!ignored(&rules, "debug.log", false));
+        assert!(ignored(&rules, "main.rs", false));
+
+        // Outside a repository there is nothing to load.
+        let plain = scratch_dir("no-repo");
+        fs::write(plain.join(".gitignore"), "*\n").unwrap();
+        assert!(load_gitignore_rules(&plain).is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+        fs::remove_dir_all(plain).unwrap();
+    }
+
+    fn set_sandbox_paths(allowed: &[&Path], denied: &[&Path]) {
+        let to_strings = |paths: &[&Path]| -> Vec<String> {
+            paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect()
+        };
+        let mut paths = sandbox_paths();
+        paths.allowed = to_strings(allowed);
+        paths.denied = to_strings(denied);
+    }
+
+    // One test, since the allowed and denied lists are process-wide.
+    #[test]
+    fn sandbox_resolution() {
+        let base = scratch_dir("sandbox");
+        let root = base.join("root");
+        let outside = base.join("outside");
+        let extra = base.join("extra");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("secrets")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::create_dir_all(&extra).unwrap();
+        let root_str = root.to_string_lossy().to_string();
+        set_sandbox_paths(&[], &[]);
+
+        // Relative paths, including ones that do not exist yet.
+        assert_eq!(
+            resolve_in_sandbox("src/main.rs", &root_str).unwrap(),
+            root.join("src").join("main.rs")
+        );
+        assert_eq!(
+            resolve_in_sandbox("new/dir/file.txt", &root_str).unwrap(),
+            root.join("new").join("dir").join("file.txt")
+        );
+
+        // `..` through existing directories resolves and is then checked.
+        assert_eq!(
+            resolve_in_sandbox("src/../README.md", &root_str).unwrap(),
+            root.join("README.md")
+        );
+        let error = resolve_in_sandbox("../outside/file.txt", &root_str).unwrap_err();
+        assert!(error.contains("path outside allowed roots"), "{}", error);
+
+        // `..` in the missing tail is rejected outright.
+        let error = resolve_in_sandbox("missing/../../outside", &root_str).unwrap_err();
+        assert!(error.contains("path outside allowed roots"), "{}", error);
+
+        // Absolute paths are only accepted inside the root.
+        let inside = root.join("src").join("lib.rs");
+        assert_eq!(
+            resolve_in_sandbox(&inside.to_string_lossy(), &root_str).unwrap(),
+            inside
+        );
+        let error =
+            resolve_in_sandbox(&outside.join("a.txt").to_string_lossy(), &root_str).unwrap_err();
+        assert!(error.contains("path outside allowed roots"), "{}", error);
+
+        // Allowed paths widen the sandbox; denied paths win over both.
+        set_sandbox_paths(&[&extra], &[&root.join("secrets"), &extra.join("private")]);
+        let shared = extra.join("shared.txt");
+        assert_eq!(
+            resolve_in_sandbox(&shared.to_string_lossy(), &root_str).unwrap(),
+            shared
+        );
+        let error = resolve_in_sandbox("secrets/key.pem", &root_str).unwrap_err();
+        assert!(error.contains("inside denied path"), "{}", error);
+        let error = resolve_in_sandbox(&extra.join("private/key").to_string_lossy(), &root_str)
+            .unwrap_err();
+        assert!(error.contains("inside denied path"), "{}", error);
+        set_sandbox_paths(&[], &[]);
+
+        // A symlink inside the root cannot point out of it.
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, root.join("escape")).unwrap();
+            let error = resolve_in_sandbox("escape/file.txt", &root_str).unwrap_err();
+            assert!(error.contains("path outside allowed roots"), "{}", error);
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn read_limit_defaults_and_bounds() {
+        assert_eq!(
+            resolve_read_limit(None).unwrap(),
+            DEFAULT_MAX_READ_FILE_BYTES
+        );
+        assert_eq!(resolve_read_limit(Some(1)).unwrap(), 1);
+        assert_eq!(
+            resolve_read_limit(Some(MAX_READ_FILE_BYTES_CEILING)).unwrap(),
+            MAX_READ_FILE_BYTES_CEILING
+        );
+        assert!(resolve_read_limit(Some(0)).is_err());
+        assert!(resolve_read_limit(Some(MAX_READ_FILE_BYTES_CEILING + 1)).is_err());
+    }
+
+    #[test]
+    fn atomic_writes_replace_content_and_leave_no_temp_files() {
+        let dir = scratch_dir("atomic");
+        let path = dir.join("notes.txt");
+
+        write_atomically(&path, b"first").unwrap();
+        write_atomically(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        let names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["notes.txt"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_writes_keep_permissions_and_follow_symlinks() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = scratch_dir("atomic-link");
+        let target = dir.join("target.txt");
+        let link = dir.join("link.txt");
+        fs::write(&target, "old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        symlink(&target, &link).unwrap();
+
+        write_atomically(&link, b"new").unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        let mode = fs::metadata(&target).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o640);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    fn listed_names(listing: &DirectoryListing) -> Vec<&str> {
+        listing
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn directory_listing_pages_with_directories_first() {
+        let dir = scratch_dir("listing");
+        fs::create_dir(dir.join("src")).unwrap();
+        for name in ["b.txt", "A.txt", "c.txt"] {
+            fs::write(dir.join(name), name).unwrap();
+        }
+        std::os::unix::fs::symlink(dir.join("src"), dir.join("linked")).unwrap();
+        let path = dir.to_string_lossy().to_string();
+
+        let first = list_directory(path.clone(), None, Some(2), None, None)
+            .await
+            .unwrap();
+        let rest = list_directory(path, Some(2), Some(10), None, None)
+            .await
+            .unwrap();
+
+        // The symlinked directory sorts with the directories.
+        assert_eq!(listed_names(&first), ["linked", "src"]);
+        assert!(first.entries.iter().all(|entry| entry.is_dir));
+        assert_eq!((first.total, first.offset, first.has_more), (5, 0, true));
+        assert_eq!(listed_names(&rest), ["A.txt", "b.txt", "c.txt"]);
+        assert_eq!((rest.total, rest.offset, rest.has_more), (5, 2, false));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
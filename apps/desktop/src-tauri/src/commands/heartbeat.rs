@@ -5,6 +5,15 @@ use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
 
+/// Queued events kept before the oldest are evicted.
+const DEFAULT_MAX_QUEUED_EVENTS: u32 = 1000;
+const MAX_QUEUED_EVENTS_LIMIT: u32 = 10_000;
+/// Queued events older than this are dropped (one day).
+const DEFAULT_EVENT_RETENTION_MS: i64 = 24 * 60 * 60 * 1000;
+/// Wake requests within this window are merged into one cycle.
+const DEFAULT_WAKE_DEBOUNCE_MS: u64 = 250;
+const MAX_WAKE_DEBOUNCE_MS: u64 = 60_000;
+
 // ============================================================================
 // Heartbeat Types
 // ============================================================================
@@ -16,6 +25,46 @@ pub struct HeartbeatConfig {
     pub interval_ms: i64,
     pub system_events_enabled: bool,
     pub cron_enabled: bool,
+    #[serde(default = "default_max_queued_events")]
+    pub max_queued_events: u32,
+    /// Maximum age of a queued event in ms; 0 disables the age limit.
+    #[serde(default = "default_event_retention_ms")]
+    pub event_retention_ms: i64,
+    #[serde(default = "default_wake_debounce_ms")]
+    pub wake_debounce_ms: u64,
+}
+
+fn default_max_queued_events() -> u32 {
+    DEFAULT_MAX_QUEUED_EVENTS
+}
+
+fn default_event_retention_ms() -> i64 {
+    DEFAULT_EVENT_RETENTION_MS
+}
+
+fn default_wake_debounce_ms() -> u64 {
+    DEFAULT_WAKE_DEBOUNCE_MS
+}
+
+impl HeartbeatConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.max_queued_events == 0 || self.max_queued_events > MAX_QUEUED_EVENTS_LIMIT {
+            return Err(format!(
+                "maxQueuedEvents must be between 1 and {}",
+                MAX_QUEUED_EVENTS_LIMIT
+            ));
+        }
+        if self.event_retention_ms < 0 {
+            return Err("eventRetentionMs must not be negative".to_string());
+        }
+        if self.wake_debounce_ms > MAX_WAKE_DEBOUNCE_MS {
+            return Err(format!(
+                "wakeDebounceMs must be at most {}",
+                MAX_WAKE_DEBOUNCE_MS
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +74,12 @@ pub struct HeartbeatStatus {
     pub last_heartbeat: Option<i64>,
     pub next_heartbeat: Option<i64>,
     pub event_queue_size: u32,
+    #[serde(default)]
+    pub event_queue_capacity: u32,
+    #[serde(default)]
+    pub evicted_events: u64,
+    #[serde(default)]
+    pub coalesced_wakes: u64,
     pub is_processing: bool,
 }
 
@@ -98,6 +153,7 @@ pub async fn heartbeat_set_config(
     state: State<'_, AgentState>,
     config: HeartbeatConfig,
 ) -> Result<HeartbeatConfig, String> {
+    config.validate()?;
     ensure_sidecar_started_public(&app, &state).await?;
 
     let manager = &state.manager;
@@ -140,7 +196,7 @@ pub async fn heartbeat_stop(
     Ok(())
 }
 
-/// Trigger immediate heartbeat wake
+/// Trigger immediate heartbeat wake; bursts within the debounce window run once
 #[tauri::command]
 pub async fn heartbeat_wake(
     app: AppHandle,
@@ -180,17 +236,26 @@ pub async fn heartbeat_queue_event(
         .ok_or_else(|| "Failed to get event ID from response".to_string())
 }
 
-/// Get queued events
+/// Get queued events, optionally only those scheduled at or after `since`
 #[tauri::command]
 pub async fn heartbeat_get_events(
     app: AppHandle,
     state: State<'_, AgentState>,
+    since: Option<i64>,
+    limit: Option<u32>,
 ) -> Result<Vec<SystemEvent>, String> {
     ensure_sidecar_started_public(&app, &state).await?;
 
     let manager = &state.manager;
+    let mut params = serde_json::Map::new();
+    if let Some(since) = since {
+        params.insert("since".to_string(), serde_json::json!(since));
+    }
+    if let Some(limit) = limit {
+        params.insert("limit".to_string(), serde_json::json!(limit));
+    }
     let result = manager
-        .send_command("heartbeat_get_events", serde_json::json!({}))
+        .send_command("heartbeat_get_events", serde_json::Value::Object(params))
         .await?;
 
     serde_json::from_value(result).map_err(|e| format!("Failed to parse events: {}", e))
@@ -0,0 +1,34 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+use crate::logging;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSettings {
+    pub level: String,
+    pub log_file: Option<String>,
+}
+
+fn current_settings() -> LogSettings {
+    LogSettings {
+        level: log::max_level().to_string().to_lowercase(),
+        log_file: logging::log_file_path().map(|path| path.to_string_lossy().to_string()),
+    }
+}
+
+/// Get the active log level and log file location
+#[tauri::command]
+pub async fn get_log_settings() -> Result<LogSettings, String> {
+    Ok(current_settings())
+}
+
+/// Change the log level for the rest of this run
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<LogSettings, String> {
+    let level = logging::parse_level(&level)?;
+    log::set_max_level(level);
+    log::info!("Log level set to {}", level);
+    Ok(current_settings())
+}
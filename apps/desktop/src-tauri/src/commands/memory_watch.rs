@@ -0,0 +1,306 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+//! Keeps the in-app memory view in sync with external edits to a working
+//! directory's GEMINI.md by watching it and emitting `memory:changed`.
+//!
+//! Only the session being viewed is watched: opening another session moves
+//! the watch, and closing or deleting the session ends it.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+const MEMORY_FILE_NAME: &str = "GEMINI.md";
+const MEMORY_CHANGED_EVENT: &str = "memory:changed";
+/// Editors often write in several steps, so changes are only reported once
+/// the file has been quiet for this long.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryChangedPayload {
+    pub working_directory: String,
+    pub path: String,
+    pub exists: bool,
+}
+
+/// What the watcher compares between reports. `None` means the file is absent.
+type FileSignature = Option<(SystemTime, u64)>;
+
+struct MemoryWatch {
+    session_id: String,
+    working_directory: String,
+    /// Last signature reported, so saves made through the app can be marked
+    /// as seen.
+    reported: Arc<StdMutex<FileSignature>>,
+    // Dropping the watcher stops its OS subscription.
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl Drop for MemoryWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Default)]
+struct MemoryWatchRegistry {
+    active: Option<MemoryWatch>,
+}
+
+static MEMORY_WATCHES: OnceLock<StdMutex<MemoryWatchRegistry>> = OnceLock::new();
+
+fn registry() -> std::sync::MutexGuard<'static, MemoryWatchRegistry> {
+    let lock = MEMORY_WATCHES.get_or_init(|| StdMutex::new(MemoryWatchRegistry::default()));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn lock_signature(signature: &StdMutex<FileSignature>) -> std::sync::MutexGuard<'_, FileSignature> {
+    match signature.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn memory_file_path(working_directory: &str) -> PathBuf {
+    Path::new(working_directory).join(MEMORY_FILE_NAME)
+}
+
+fn file_signature(path: &Path) -> FileSignature {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The directory is watched rather than the file so that creating, deleting
+/// or atomically replacing GEMINI.md is still seen.
+fn touches_memory_file(event: &notify::Event) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| {
+            path.file_name()
+                .is_some_and(|name| name == MEMORY_FILE_NAME)
+        })
+}
+
+fn start_watch<F>(session_id: &str, working_directory: &str, emit: F) -> Result<MemoryWatch, String>
+where
+    F: Fn(MemoryChangedPayload) + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<()>();
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+            Ok(event) if touches_memory_file(&event) => {
+                let _ = tx.send(());
+            }
+            Ok(_) => {}
+            Err(error) => log::warn!("Memory watcher error: {}", error),
+        })
+        .map_err(|e| format!("Failed to create memory watcher: {}", e))?;
+    watcher
+        .watch(Path::new(working_directory), RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", working_directory, e))?;
+
+    let path = memory_file_path(working_directory);
+    let reported = Arc::new(StdMutex::new(file_signature(&path)));
+    let task_reported = Arc::clone(&reported);
+    let task_directory = working_directory.to_string();
+    let task = tauri::async_runtime::spawn(async move {
+        while rx.recv().await.is_some() {
+            // Wait for the burst of events from a single save to settle.
+            loop {
+                match tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                    Ok(Some(())) => continue,
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+
+            let current = file_signature(&path);
+            {
+                let mut reported = lock_signature(&task_reported);
+                if *reported == current {
+                    continue;
+                }
+                *reported = current;
+            }
+            emit(MemoryChangedPayload {
+                working_directory: task_directory.clone(),
+                path: path.to_string_lossy().to_string(),
+                exists: current.is_some(),
+            });
+        }
+    });
+
+    Ok(MemoryWatch {
+        session_id: session_id.to_string(),
+        working_directory: working_directory.to_string(),
+        reported,
+        _watcher: watcher,
+        task,
+    })
+}
+
+fn watch_session_with<F>(session_id: &str, working_directory: &str, emit: F)
+where
+    F: Fn(MemoryChangedPayload) + Send + 'static,
+{
+    if working_directory.trim().is_empty() {
+        return;
+    }
+
+    let mut registry = registry();
+    if let Some(watch) = &registry.active {
+        if watch.session_id == session_id && watch.working_directory == working_directory {
+            return;
+        }
+    }
+    // Viewing another session (or moving this one) ends the current watch.
+    registry.active = None;
+
+    match start_watch(session_id, working_directory, emit) {
+        Ok(watch) => registry.active = Some(watch),
+        Err(error) => log::warn!("{}", error),
+    }
+}
+
+/// Watch the memory file of the session being viewed. Calling this again for
+/// the same session and directory is a no-op; any other session's watch is
+/// stopped.
+pub fn watch_session(app: &AppHandle, session_id: &str, working_directory: &str) {
+    let app = app.clone();
+    watch_session_with(session_id, working_directory, move |payload| {
+        if let Err(error) = app.emit(MEMORY_CHANGED_EVENT, payload) {
+            log::warn!("Failed to emit {}: {}", MEMORY_CHANGED_EVENT, error);
+        }
+    });
+}
+
+/// Follow a session to a new working directory if it is the one being watched.
+pub fn move_session_watch(app: &AppHandle, session_id: &str, working_directory: &str) {
+    let watching = registry()
+        .active
+        .as_ref()
+        .is_some_and(|watch| watch.session_id == session_id);
+    if watching {
+        watch_session(app, session_id, working_directory);
+    }
+}
+
+/// Stop the watch if it belongs to this session.
+pub fn unwatch_session(session_id: &str) {
+    let mut registry = registry();
+    if registry
+        .active
+        .as_ref()
+        .is_some_and(|watch| watch.session_id == session_id)
+    {
+        registry.active = None;
+    }
+}
+
+/// Treat the memory file's current contents as already seen, so a save made
+/// through the app is not echoed back as an external change.
+pub fn mark_memory_seen(working_directory: &str) {
+    let registry = registry();
+    if let Some(watch) = registry
+        .active
+        .as_ref()
+        .filter(|watch| watch.working_directory == working_directory)
+    {
+        *lock_signature(&watch.reported) = file_signature(&memory_file_path(working_directory));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+    use std::sync::mpsc as std_mpsc;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-memory-watch-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_changes_to_the_memory_file_count() {
+        let memory = PathBuf::from("/work/GEMINI.md");
+        let other = PathBuf::from("/work/notes.md");
+
+        let modified =
+            notify::Event::new(EventKind::Modify(ModifyKind::Any)).add_path(memory.clone());
+        let created =
+            notify::Event::new(EventKind::Create(CreateKind::File)).add_path(memory.clone());
+        let read = notify::Event::new(EventKind::Access(AccessKind::Any)).add_path(memory);
+        let unrelated = notify::Event::new(EventKind::Modify(ModifyKind::Any)).add_path(other);
+
+        assert!(touches_memory_file(&modified));
+        assert!(touches_memory_file(&created));
+        assert!(!touches_memory_file(&read));
+        assert!(!touches_memory_file(&unrelated));
+    }
+
+    // The registry is process-wide, so its lifecycle is covered in one test.
+    #[test]
+    fn watch_follows_the_viewed_session() {
+        let first = temp_dir("first");
+        let second = temp_dir("second");
+        let first_dir = first.to_string_lossy().to_string();
+        let second_dir = second.to_string_lossy().to_string();
+        let (tx, rx) = std_mpsc::channel();
+
+        let emit = {
+            let tx = tx.clone();
+            move |payload| {
+                let _ = tx.send(payload);
+            }
+        };
+        watch_session_with("s1", &first_dir, emit);
+        fs::write(first.join(MEMORY_FILE_NAME), "# Memory").unwrap();
+        let payload = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(payload.working_directory, first_dir);
+        assert!(payload.exists);
+
+        // Re-opening the same session keeps the existing watch.
+        watch_session_with("s1", &first_dir, |_| panic!("watch was restarted"));
+        fs::remove_file(first.join(MEMORY_FILE_NAME)).unwrap();
+        let payload = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(!payload.exists);
+
+        // Switching sessions stops the first watch.
+        let emit = move |payload| {
+            let _ = tx.send(payload);
+        };
+        watch_session_with("s2", &second_dir, emit);
+        fs::write(first.join(MEMORY_FILE_NAME), "# Ignored").unwrap();
+        assert!(rx.recv_timeout(DEBOUNCE * 3).is_err());
+
+        unwatch_session("s1");
+        assert!(registry().active.is_some());
+        unwatch_session("s2");
+        assert!(registry().active.is_none());
+
+        let _ = fs::remove_dir_all(first);
+        let _ = fs::remove_dir_all(second);
+    }
+}
@@ -10,9 +10,13 @@ pub mod deep;
 pub mod files;
 pub mod heartbeat;
 pub mod integrations;
+pub mod logging;
+pub mod memory_watch;
 pub mod policy;
 pub mod remote_access;
 pub mod service;
 pub mod skills;
+pub mod storage;
 pub mod subagent;
+pub mod updater;
 pub mod workflow;
@@ -3,6 +3,9 @@
 
 use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex as StdMutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, State};
 
 // ============================================================================
@@ -75,6 +78,11 @@ pub struct ToolEvaluationResult {
     pub reason: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub matched_rule: Option<ToolRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<String>,
+    /// Priority of the rule that decided the outcome, if a rule did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,6 +96,34 @@ pub struct ToolCallContext {
     pub arguments: serde_json::Value,
 }
 
+/// One entry of a `policy_evaluate_batch` dry run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolEvaluationRequest {
+    pub tool_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default = "default_evaluation_session_type")]
+    pub session_type: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+fn default_evaluation_session_type() -> String {
+    "main".to_string()
+}
+
+impl From<ToolEvaluationRequest> for ToolCallContext {
+    fn from(request: ToolEvaluationRequest) -> Self {
+        Self {
+            tool_name: request.tool_name,
+            provider: request.provider,
+            session_type: request.session_type,
+            arguments: request.arguments,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdatePolicyInput {
@@ -116,6 +152,1457 @@ pub struct AddRuleInput {
     pub conditions: Option<ToolRuleConditions>,
 }
 
+// ============================================================================
+// Evaluation Cache
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyCacheConfig {
+    /// Maximum cached evaluations; 0 disables the cache.
+    pub max_entries: usize,
+    pub ttl_secs: u64,
+}
+
+impl Default for PolicyCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 512,
+            ttl_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyCacheStats {
+    pub config: PolicyCacheConfig,
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+const MAX_POLICY_CACHE_ENTRIES: usize = 10_000;
+const MAX_POLICY_CACHE_TTL_SECS: u64 = 3600;
+
+struct CachedEvaluation {
+    result: ToolEvaluationResult,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+/// LRU of evaluation results. `generation` bumps on every policy change so
+/// evaluations that were in flight during a change are not stored.
+#[derive(Default)]
+struct PolicyEvaluationCache {
+    config: PolicyCacheConfig,
+    entries: HashMap<String, CachedEvaluation>,
+    /// Last policy document fetched from the sidecar, for local evaluation.
+    policy: Option<(ToolPolicy, Instant)>,
+    generation: u64,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl PolicyEvaluationCache {
+    fn get(&mut self, key: &str) -> Option<ToolEvaluationResult> {
+        let ttl = Duration::from_secs(self.config.ttl_secs);
+        self.clock += 1;
+        let clock = self.clock;
+        let fresh = match self.entries.get_mut(key) {
+            Some(entry) if entry.inserted_at.elapsed() < ttl => {
+                entry.last_used = clock;
+                Some(entry.result.clone())
+            }
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        };
+        if fresh.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        fresh
+    }
+
+    fn insert(&mut self, key: String, result: ToolEvaluationResult, generation: u64) {
+        if generation != self.generation || self.config.max_entries == 0 {
+            return;
+        }
+        while self.entries.len() >= self.config.max_entries {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        self.clock += 1;
+        self.entries.insert(
+            key,
+            CachedEvaluation {
+                result,
+                inserted_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn invalidate(&mut self) {
+        self.entries.clear();
+        self.policy = None;
+        self.generation += 1;
+    }
+
+    /// The cached policy document and whether it is still within the TTL.
+    fn policy(&self) -> Option<(ToolPolicy, bool)> {
+        let ttl = Duration::from_secs(self.config.ttl_secs);
+        self.policy
+            .as_ref()
+            .map(|(policy, fetched_at)| (policy.clone(), fetched_at.elapsed() < ttl))
+    }
+
+    fn store_policy(&mut self, policy: &ToolPolicy, generation: u64) {
+        if generation == self.generation {
+            self.policy = Some((policy.clone(), Instant::now()));
+        }
+    }
+}
+
+static POLICY_EVALUATION_CACHE: OnceLock<StdMutex<PolicyEvaluationCache>> = OnceLock::new();
+
+fn policy_cache() -> std::sync::MutexGuard<'static, PolicyEvaluationCache> {
+    let cache =
+        POLICY_EVALUATION_CACHE.get_or_init(|| StdMutex::new(PolicyEvaluationCache::default()));
+    match cache.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn invalidate_policy_cache() {
+    policy_cache().invalidate();
+}
+
+/// Invalidate cached evaluations and keep the sidecar's new policy document.
+fn replace_cached_policy(policy: &ToolPolicy) {
+    let mut cache = policy_cache();
+    cache.invalidate();
+    let generation = cache.generation;
+    cache.store_policy(policy, generation);
+}
+
+/// JSON with object keys sorted, so equal arguments give equal cache keys
+/// regardless of key order.
+fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            let body: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        serde_json::Value::String(key.clone()),
+                        canonical_json(&fields[key])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", body.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            let body: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", body.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Cache key covering everything rule conditions can match on: the tool,
+/// provider, session type, and the call arguments (paths, commands).
+fn policy_cache_key(context: &ToolCallContext) -> String {
+    format!(
+        "{}\u{1f}{}\u{1f}{}\u{1f}{}",
+        context.tool_name,
+        context.provider.as_deref().unwrap_or_default(),
+        context.session_type,
+        canonical_json(&context.arguments)
+    )
+}
+
+// ============================================================================
+// Local Evaluator
+// ============================================================================
+
+// Decides most tool calls without a round-trip to the sidecar's
+// ToolPolicyService.evaluate. The sidecar stays the source of truth for the
+// policy document, and anything the local tables can't decide falls back
+// to it.
+
+/// Tool groups, kept in sync with TOOL_GROUPS in the sidecar's tool-policy.ts.
+const TOOL_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "group:fs",
+        &[
+            "read_file",
+            "write_file",
+            "edit_file",
+            "glob",
+            "ls",
+            "delete_file",
+            "move_file",
+            "copy_file",
+            "Read",
+            "Write",
+            "Edit",
+            "Glob",
+            "LS",
+        ],
+    ),
+    ("group:shell", &["execute", "Bash", "run_command", "shell"]),
+    (
+        "group:network",
+        &[
+            "fetch",
+            "WebFetch",
+            "web_fetch",
+            "http_request",
+            "google_grounded_search",
+            "web_search",
+            "WebSearch",
+        ],
+    ),
+    ("group:research", &["deep_research", "research"]),
+    (
+        "group:media",
+        &[
+            "generate_image",
+            "generate_video",
+            "text_to_speech",
+            "speech_to_text",
+        ],
+    ),
+    (
+        "group:computer",
+        &["computer_use", "screenshot", "mouse_click", "keyboard_type"],
+    ),
+    (
+        "group:tasks",
+        &[
+            "write_todos",
+            "TaskCreate",
+            "TaskUpdate",
+            "TaskList",
+            "TaskGet",
+            "TodoWrite",
+        ],
+    ),
+    (
+        "group:memory",
+        &[
+            "read_memory",
+            "write_memory",
+            "memory_search",
+            "deep_memory_create",
+            "deep_memory_read",
+            "deep_memory_update",
+            "deep_memory_delete",
+            "deep_memory_list",
+            "deep_memory_search",
+            "deep_memory_get_relevant",
+            "deep_memory_list_groups",
+            "deep_memory_create_group",
+            "deep_memory_delete_group",
+            "deep_memory_build_prompt",
+        ],
+    ),
+    (
+        "group:agents_md",
+        &[
+            "agents_md_load",
+            "agents_md_generate",
+            "agents_md_to_prompt",
+            "agents_md_update_section",
+            "agents_md_validate",
+            "agents_md_scan_project",
+        ],
+    ),
+    (
+        "group:commands",
+        &[
+            "command_list",
+            "command_get",
+            "command_execute",
+            "command_search",
+            "command_list_by_category",
+        ],
+    ),
+];
+
+/// Populated at runtime from connected MCP servers; only the sidecar knows it.
+const DYNAMIC_TOOL_GROUP: &str = "group:mcp";
+
+/// Profile allow/deny defaults, kept in sync with PROFILES in tool-policy.ts.
+const PROFILE_DEFAULTS: &[(&str, &[&str], &[&str])] = &[
+    (
+        "minimal",
+        &[
+            "read_file",
+            "Read",
+            "glob",
+            "Glob",
+            "ls",
+            "LS",
+            "grep",
+            "Grep",
+        ],
+        &[
+            "group:shell",
+            "group:network",
+            "group:media",
+            "write_file",
+            "Write",
+            "edit_file",
+            "Edit",
+            "delete_file",
+        ],
+    ),
+    (
+        "readonly",
+        &[
+            "group:fs",
+            "grep",
+            "Grep",
+            "web_search",
+            "google_grounded_search",
+            "web_fetch",
+        ],
+        &[
+            "write_file",
+            "Write",
+            "edit_file",
+            "Edit",
+            "delete_file",
+            "execute",
+            "Bash",
+            "group:media",
+        ],
+    ),
+    (
+        "coding",
+        &[
+            "group:fs",
+            "group:tasks",
+            "group:memory",
+            "group:agents_md",
+            "group:commands",
+            "grep",
+            "Grep",
+            "web_search",
+            "google_grounded_search",
+            "web_fetch",
+            "Bash",
+        ],
+        &["group:media", "deep_research", "deep_memory_delete"],
+    ),
+    (
+        "messaging",
+        &["group:network", "read_file", "Read", "glob", "Glob"],
+        &[
+            "group:shell",
+            "group:media",
+            "write_file",
+            "Write",
+            "edit_file",
+            "Edit",
+            "delete_file",
+        ],
+    ),
+    (
+        "research",
+        &[
+            "group:network",
+            "group:fs",
+            "group:memory",
+            "group:agents_md",
+            "deep_research",
+            "web_search",
+            "google_grounded_search",
+            "web_fetch",
+        ],
+        &[
+            "group:shell",
+            "group:media",
+            "write_file",
+            "Write",
+            "edit_file",
+            "Edit",
+            "delete_file",
+            "deep_memory_delete",
+            "deep_memory_create",
+            "deep_memory_update",
+        ],
+    ),
+    (
+        "enterprise_balanced",
+        &[
+            "group:fs",
+            "group:network",
+            "group:tasks",
+            "group:memory",
+            "web_search",
+            "web_fetch",
+            "group:agents_md",
+        ],
+        &[
+            "group:media",
+            "group:computer",
+            "delete_file",
+            "deep_memory_delete",
+        ],
+    ),
+    (
+        "enterprise_strict",
+        &[
+            "read_file",
+            "Read",
+            "glob",
+            "Glob",
+            "ls",
+            "LS",
+            "grep",
+            "Grep",
+            "web_search",
+        ],
+        &[
+            "group:shell",
+            "group:network",
+            "group:media",
+            "group:computer",
+            "write_file",
+            "Write",
+            "edit_file",
+            "Edit",
+            "delete_file",
+            "deep_research",
+        ],
+    ),
+    ("full", &["*"], &[]),
+    ("custom", &[], &[]),
+];
+
+const PATH_ARGUMENT_KEYS: [&str; 6] = [
+    "path",
+    "file_path",
+    "filePath",
+    "target",
+    "source",
+    "destination",
+];
+const COMMAND_ARGUMENT_KEYS: [&str; 3] = ["command", "cmd", "script"];
+const READ_ONLY_TOOLS: [&str; 8] = [
+    "read_file",
+    "Read",
+    "glob",
+    "Glob",
+    "ls",
+    "LS",
+    "grep",
+    "Grep",
+];
+const FILE_MUTATION_TOOLS: [&str; 7] = [
+    "write_file",
+    "Write",
+    "edit_file",
+    "Edit",
+    "delete_file",
+    "move_file",
+    "copy_file",
+];
+const SYSTEM_PATH_PREFIXES: [&str; 3] = ["/System", "/etc", "/usr"];
+
+/// Expand `{a,b}` alternatives, including nested ones.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let bytes = pattern.as_bytes();
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let mut depth = 0;
+    let mut splits = Vec::new();
+    let mut close = None;
+    for (index, &byte) in bytes.iter().enumerate().skip(open) {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(index);
+                    break;
+                }
+            }
+            b',' if depth == 1 => splits.push(index),
+            _ => {}
+        }
+    }
+    let Some(close) = close.filter(|_| !splits.is_empty()) else {
+        return vec![pattern.to_string()];
+    };
+
+    let prefix = &pattern[..open];
+    let suffix = &pattern[close + 1..];
+    let mut bounds = vec![open];
+    bounds.extend(&splits);
+    bounds.push(close);
+    bounds
+        .windows(2)
+        .flat_map(|window| {
+            let option = &pattern[window[0] + 1..window[1]];
+            expand_braces(&format!("{}{}{}", prefix, option, suffix))
+        })
+        .collect()
+}
+
+/// Match one `[...]` class at the start of `pattern` against `c`. Returns
+/// the match result and the class length, or `None` if the class is unclosed.
+fn match_char_class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut index = 1;
+    let negated = matches!(pattern.get(index), Some('!') | Some('^'));
+    if negated {
+        index += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    while index < pattern.len() {
+        let current = pattern[index];
+        if current == ']' && !first {
+            return Some((matched != negated, index + 1));
+        }
+        first = false;
+        if pattern.get(index + 1) == Some(&'-')
+            && pattern.get(index + 2).is_some_and(|end| *end != ']')
+        {
+            if (current..=pattern[index + 2]).contains(&c) {
+                matched = true;
+            }
+            index += 3;
+        } else {
+            if current == c {
+                matched = true;
+            }
+            index += 1;
+        }
+    }
+    None
+}
+
+/// Glob matching with micromatch's defaults: `*` and `?` stay within a
+/// path segment, `**` as a whole segment spans segments, and wildcards
+/// don't match a leading `.` in a segment.
+fn glob_match_chars(pattern: &[char], text: &[char], segment_start: bool) -> bool {
+    let Some(&first) = pattern.first() else {
+        return text.is_empty();
+    };
+
+    if first == '*' && pattern.get(1) == Some(&'*') && segment_start {
+        let rest = &pattern[2..];
+        if rest.is_empty() {
+            return !text.is_empty()
+                && text
+                    .split(|c| *c == '/')
+                    .all(|segment| segment.first() != Some(&'.'));
+        }
+        if rest[0] == '/' {
+            let mut position = 0;
+            loop {
+                if glob_match_chars(&rest[1..], &text[position..], true) {
+                    return true;
+                }
+                if text.get(position) == Some(&'.') {
+                    return false;
+                }
+                match text[position..].iter().position(|c| *c == '/') {
+                    Some(offset) => position += offset + 1,
+                    None => return false,
+                }
+            }
+        }
+    }
+
+    match first {
+        '*' => {
+            let rest = {
+                let mut rest = &pattern[1..];
+                while rest.first() == Some(&'*') {
+                    rest = &rest[1..];
+                }
+                rest
+            };
+            if segment_start && text.first() == Some(&'.') {
+                return glob_match_chars(rest, text, segment_start);
+            }
+            let mut consumed = 0;
+            loop {
+                if glob_match_chars(rest, &text[consumed..], segment_start && consumed == 0) {
+                    return true;
+                }
+                match text.get(consumed) {
+                    Some(c) if *c != '/' => consumed += 1,
+                    _ => return false,
+                }
+            }
+        }
+        '?' => match text.first() {
+            Some(c) if *c != '/' && !(segment_start && *c == '.') => {
+                glob_match_chars(&pattern[1..], &text[1..], false)
+            }
+            _ => false,
+        },
+        '[' => {
+            let Some(&c) = text.first() else {
+                return false;
+            };
+            match match_char_class(pattern, c) {
+                Some((matched, length)) => {
+                    matched && c != '/' && glob_match_chars(&pattern[length..], &text[1..], false)
+                }
+                None => c == '[' && glob_match_chars(&pattern[1..], &text[1..], false),
+            }
+        }
+        '\\' if pattern.len() > 1 => match text.first() {
+            Some(c) if *c == pattern[1] => glob_match_chars(&pattern[2..], &text[1..], *c == '/'),
+            _ => false,
+        },
+        literal => match text.first() {
+            Some(c) if *c == literal => glob_match_chars(&pattern[1..], &text[1..], literal == '/'),
+            _ => false,
+        },
+    }
+}
+
+pub(crate) fn glob_match(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    expand_braces(pattern).iter().any(|pattern| {
+        let pattern: Vec<char> = pattern.chars().collect();
+        glob_match_chars(&pattern, &text, true)
+    })
+}
+
+fn tool_group(name: &str) -> &'static [&'static str] {
+    TOOL_GROUPS
+        .iter()
+        .find(|(group, _)| *group == name)
+        .map(|(_, tools)| *tools)
+        .unwrap_or(&[])
+}
+
+/// Whether `tool` matches an allow/deny list entry. `None` means the answer
+/// depends on the runtime MCP tool group.
+fn tool_matches_entry(tool: &str, entry: &str) -> Option<bool> {
+    if entry == "*" || entry == tool {
+        return Some(true);
+    }
+    if entry == DYNAMIC_TOOL_GROUP {
+        return None;
+    }
+    if entry.starts_with("group:") && tool_group(entry).contains(&tool) {
+        return Some(true);
+    }
+    Some(entry.contains('*') && glob_match(tool, entry))
+}
+
+fn tool_matches_list(tool: &str, list: &[String]) -> Option<bool> {
+    let mut undecided = false;
+    for entry in list {
+        match tool_matches_entry(tool, entry) {
+            Some(true) => return Some(true),
+            Some(false) => {}
+            None => undecided = true,
+        }
+    }
+    if undecided {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+fn rule_matches_tool(rule: &ToolRule, tool: &str) -> Option<bool> {
+    if rule.tool == tool {
+        return Some(true);
+    }
+    if rule.tool == DYNAMIC_TOOL_GROUP {
+        return None;
+    }
+    if rule.tool.starts_with("group:") {
+        return Some(tool_group(&rule.tool).contains(&tool));
+    }
+    Some(rule.tool.contains('*') && glob_match(tool, &rule.tool))
+}
+
+fn string_argument<'a>(arguments: &'a serde_json::Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| arguments.get(*key).and_then(|value| value.as_str()))
+        .filter(|value| !value.is_empty())
+}
+
+fn command_matches(command: &str, pattern: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix(" *") {
+        return command == prefix || command.starts_with(&format!("{} ", prefix));
+    }
+    pattern == command || glob_match(command, pattern)
+}
+
+/// Risk of a tool call, matching the sidecar's assessRiskLevel: reads are
+/// low, writes to system directories are high, everything else is medium.
+fn assess_risk_level(context: &ToolCallContext) -> &'static str {
+    let tool = context.tool_name.as_str();
+    if READ_ONLY_TOOLS.contains(&tool) {
+        return "low";
+    }
+    if FILE_MUTATION_TOOLS.contains(&tool) {
+        let path = string_argument(&context.arguments, &PATH_ARGUMENT_KEYS).unwrap_or_default();
+        if SYSTEM_PATH_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        {
+            return "high";
+        }
+    }
+    "medium"
+}
+
+struct ConditionOutcome {
+    matches: bool,
+    reason: String,
+}
+
+fn condition(matches: bool, reason: impl Into<String>) -> ConditionOutcome {
+    ConditionOutcome {
+        matches,
+        reason: reason.into(),
+    }
+}
+
+fn evaluate_rule_conditions(rule: &ToolRule, context: &ToolCallContext) -> ConditionOutcome {
+    let Some(conditions) = &rule.conditions else {
+        return condition(true, format!("Rule matches tool \"{}\"", context.tool_name));
+    };
+
+    if let Some(session_types) = conditions
+        .session_types
+        .as_ref()
+        .filter(|types| !types.is_empty())
+    {
+        if !session_types.contains(&context.session_type) {
+            return condition(false, "Session type mismatch");
+        }
+    }
+
+    if let Some(path) = string_argument(&context.arguments, &PATH_ARGUMENT_KEYS) {
+        for pattern in conditions.exclude_paths.iter().flatten() {
+            if glob_match(path, pattern) {
+                return condition(
+                    true,
+                    format!("Path \"{}\" matches exclude pattern \"{}\"", path, pattern),
+                );
+            }
+        }
+        if let Some(patterns) = conditions.path_patterns.as_ref().filter(|p| !p.is_empty()) {
+            if !patterns.iter().any(|pattern| glob_match(path, pattern)) {
+                return condition(false, "Path does not match allowed patterns");
+            }
+        }
+    }
+
+    if let Some(command) = string_argument(&context.arguments, &COMMAND_ARGUMENT_KEYS) {
+        for pattern in conditions.denied_commands.iter().flatten() {
+            if command_matches(command, pattern) {
+                return condition(
+                    true,
+                    format!("Command matches denied pattern \"{}\"", pattern),
+                );
+            }
+        }
+        if let Some(patterns) = conditions
+            .allowed_commands
+            .as_ref()
+            .filter(|p| !p.is_empty())
+        {
+            if !patterns
+                .iter()
+                .any(|pattern| command_matches(command, pattern))
+            {
+                return condition(false, "Command does not match allowed patterns");
+            }
+        }
+    }
+
+    if let Some(providers) = conditions.providers.as_ref().filter(|p| !p.is_empty()) {
+        if !context
+            .provider
+            .as_ref()
+            .is_some_and(|provider| providers.contains(provider))
+        {
+            return condition(false, "Provider mismatch");
+        }
+    }
+
+    if let Some(max_risk_level) = &conditions.max_risk_level {
+        let risk_level = assess_risk_level(context);
+        let rank = |level: &str| RISK_LEVELS.iter().position(|known| *known == level);
+        if rank(max_risk_level).is_none_or(|max| rank(risk_level) > Some(max)) {
+            return condition(
+                false,
+                format!(
+                    "Risk level \"{}\" exceeds maximum \"{}\"",
+                    risk_level, max_risk_level
+                ),
+            );
+        }
+    }
+
+    condition(true, "All conditions satisfied")
+}
+
+fn evaluation(
+    allowed: bool,
+    action: &str,
+    reason: String,
+    reason_code: Option<&str>,
+    matched_rule: Option<ToolRule>,
+) -> ToolEvaluationResult {
+    ToolEvaluationResult {
+        allowed,
+        action: action.to_string(),
+        reason: Some(reason),
+        priority: matched_rule.as_ref().map(|rule| rule.priority),
+        matched_rule,
+        reason_code: reason_code.map(str::to_string),
+    }
+}
+
+/// Evaluate a tool call in the sidecar's order: global deny, global allow,
+/// provider settings, rules by descending priority, profile defaults, then
+/// ask. Returns `None` when the outcome depends on the runtime MCP tool group.
+fn evaluate_policy_locally(
+    policy: &ToolPolicy,
+    context: &ToolCallContext,
+) -> Option<ToolEvaluationResult> {
+    let tool = context.tool_name.as_str();
+
+    if tool_matches_list(tool, &policy.global_deny)? {
+        return Some(evaluation(
+            false,
+            "deny",
+            format!("Tool \"{}\" is globally denied", tool),
+            Some("global_deny"),
+            None,
+        ));
+    }
+    if tool_matches_list(tool, &policy.global_allow)? {
+        return Some(evaluation(
+            true,
+            "allow",
+            format!("Tool \"{}\" is globally allowed", tool),
+            Some("global_allow"),
+            None,
+        ));
+    }
+
+    let provider_settings = context
+        .provider
+        .as_ref()
+        .filter(|p| !p.is_empty())
+        .and_then(|provider| {
+            policy
+                .provider_settings
+                .as_ref()
+                .and_then(|settings| settings.get(provider))
+                .map(|settings| (provider, settings))
+        });
+    if let Some((provider, settings)) = provider_settings {
+        if !settings.enabled {
+            return Some(evaluation(
+                false,
+                "deny",
+                format!("MCP provider \"{}\" is disabled", provider),
+                Some("provider_disabled"),
+                None,
+            ));
+        }
+        if settings
+            .denied_tools
+            .as_ref()
+            .is_some_and(|denied| denied.iter().any(|name| name == tool))
+        {
+            return Some(evaluation(
+                false,
+                "deny",
+                format!("Tool \"{}\" is denied for provider \"{}\"", tool, provider),
+                Some("provider_tool_denied"),
+                None,
+            ));
+        }
+        if settings
+            .allowed_tools
+            .as_ref()
+            .is_some_and(|allowed| !allowed.iter().any(|name| name == tool))
+        {
+            return Some(evaluation(
+                false,
+                "deny",
+                format!(
+                    "Tool \"{}\" is not in allowed list for provider \"{}\"",
+                    tool, provider
+                ),
+                Some("provider_allowlist_miss"),
+                None,
+            ));
+        }
+    }
+
+    let mut rules: Vec<&ToolRule> = policy.rules.iter().collect();
+    rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+    for rule in rules {
+        if !rule_matches_tool(rule, tool)? {
+            continue;
+        }
+        let outcome = evaluate_rule_conditions(rule, context);
+        if outcome.matches {
+            let reason_code = match rule.action.as_str() {
+                "allow" => Some("rule_allow"),
+                "deny" => Some("rule_deny"),
+                _ => None,
+            };
+            return Some(evaluation(
+                rule.action == "allow",
+                &rule.action,
+                outcome.reason,
+                reason_code,
+                Some(rule.clone()),
+            ));
+        }
+    }
+
+    if let Some((_, allow, deny)) = PROFILE_DEFAULTS
+        .iter()
+        .find(|(profile, _, _)| *profile == policy.profile)
+    {
+        let to_strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        if tool_matches_list(tool, &to_strings(deny))? {
+            return Some(evaluation(
+                false,
+                "deny",
+                format!("Tool \"{}\" denied by profile \"{}\"", tool, policy.profile),
+                Some("profile_deny"),
+                None,
+            ));
+        }
+        if tool_matches_list(tool, &to_strings(allow))? {
+            return Some(evaluation(
+                true,
+                "allow",
+                format!(
+                    "Tool \"{}\" allowed by profile \"{}\"",
+                    tool, policy.profile
+                ),
+                Some("profile_allow"),
+                None,
+            ));
+        }
+    }
+
+    Some(evaluation(
+        false,
+        "ask",
+        format!("No policy rule found for tool \"{}\"", tool),
+        Some("default_ask"),
+        None,
+    ))
+}
+
+// ============================================================================
+// Import / Export
+// ============================================================================
+
+const POLICY_EXPORT_FORMAT_VERSION: u32 = 1;
+const MAX_POLICY_BATCH_SIZE: usize = 200;
+const RULE_ACTIONS: [&str; 3] = ["allow", "deny", "ask"];
+const SESSION_TYPES: [&str; 5] = ["main", "isolated", "cron", "ephemeral", "integration"];
+const RISK_LEVELS: [&str; 3] = ["low", "medium", "high"];
+const CONDITION_LIST_FIELDS: [&str; 6] = [
+    "pathPatterns",
+    "excludePaths",
+    "allowedCommands",
+    "deniedCommands",
+    "providers",
+    "sessionTypes",
+];
+
+/// Versioned policy backup produced by `policy_export`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyExportDocument {
+    pub format_version: u32,
+    pub exported_at: i64,
+    pub policies: Vec<ToolPolicy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyImportResult {
+    pub policy: ToolPolicy,
+    pub conflict_strategy: String,
+    pub rules_imported: usize,
+    pub rules_skipped: usize,
+}
+
+/// How an import treats rules that target the same tool as an existing rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PolicyConflictStrategy {
+    /// Replace the current policy entirely.
+    Overwrite,
+    /// Keep existing rules, but imported rules win on conflicts.
+    Merge,
+    /// Keep existing rules, and skip imported rules that conflict.
+    KeepExisting,
+}
+
+impl PolicyConflictStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Overwrite => "overwrite",
+            Self::Merge => "merge",
+            Self::KeepExisting => "keep_existing",
+        }
+    }
+}
+
+fn parse_conflict_strategy(value: Option<&str>) -> Result<PolicyConflictStrategy, String> {
+    match value.map(str::trim).unwrap_or("overwrite") {
+        "" | "overwrite" => Ok(PolicyConflictStrategy::Overwrite),
+        "merge" => Ok(PolicyConflictStrategy::Merge),
+        "keep_existing" => Ok(PolicyConflictStrategy::KeepExisting),
+        other => Err(format!(
+            "Unknown conflict strategy \"{}\". Expected overwrite, merge, or keep_existing",
+            other
+        )),
+    }
+}
+
+fn validate_profile_name(profile: &str) -> Result<(), String> {
+    if PROFILE_DEFAULTS.iter().any(|(name, _, _)| *name == profile) {
+        return Ok(());
+    }
+    let known: Vec<&str> = PROFILE_DEFAULTS.iter().map(|(name, _, _)| *name).collect();
+    Err(format!(
+        "Unknown policy profile \"{}\". Expected one of: {}",
+        profile,
+        known.join(", ")
+    ))
+}
+
+fn validate_string_list(value: &serde_json::Value, path: &str, errors: &mut Vec<String>) {
+    match value.as_array() {
+        Some(items) => {
+            for (index, item) in items.iter().enumerate() {
+                if !item.is_string() {
+                    errors.push(format!("{}[{}] must be a string", path, index));
+                }
+            }
+        }
+        None => errors.push(format!("{} must be an array of strings", path)),
+    }
+}
+
+fn validate_rule_conditions(value: &serde_json::Value, path: &str, errors: &mut Vec<String>) {
+    let Some(conditions) = value.as_object() else {
+        errors.push(format!("{} must be an object", path));
+        return;
+    };
+    for (key, field) in conditions {
+        let field_path = format!("{}.{}", path, key);
+        if key == "maxRiskLevel" {
+            if !field
+                .as_str()
+                .is_some_and(|level| RISK_LEVELS.contains(&level))
+            {
+                errors.push(format!(
+                    "{} must be one of {}",
+                    field_path,
+                    RISK_LEVELS.join(", ")
+                ));
+            }
+        } else if CONDITION_LIST_FIELDS.contains(&key.as_str()) {
+            validate_string_list(field, &field_path, errors);
+            if key == "sessionTypes" {
+                for session_type in field.as_array().into_iter().flatten() {
+                    if let Some(name) = session_type.as_str() {
+                        if !SESSION_TYPES.contains(&name) {
+                            errors.push(format!(
+                                "{} contains unknown session type \"{}\"",
+                                field_path, name
+                            ));
+                        }
+                    }
+                }
+            }
+        } else {
+            errors.push(format!("{} is not a supported condition", field_path));
+        }
+    }
+}
+
+fn validate_rule(value: &serde_json::Value, path: &str, errors: &mut Vec<String>) {
+    let Some(rule) = value.as_object() else {
+        errors.push(format!("{} must be an object", path));
+        return;
+    };
+    if rule
+        .get("tool")
+        .and_then(|tool| tool.as_str())
+        .is_none_or(|tool| tool.trim().is_empty())
+    {
+        errors.push(format!("{}.tool must be a non-empty string", path));
+    }
+    if !rule
+        .get("action")
+        .and_then(|action| action.as_str())
+        .is_some_and(|action| RULE_ACTIONS.contains(&action))
+    {
+        errors.push(format!(
+            "{}.action must be one of {}",
+            path,
+            RULE_ACTIONS.join(", ")
+        ));
+    }
+    if !rule
+        .get("priority")
+        .is_some_and(|priority| priority.is_i64())
+    {
+        errors.push(format!("{}.priority must be an integer", path));
+    }
+    if let Some(conditions) = rule.get("conditions").filter(|c| !c.is_null()) {
+        validate_rule_conditions(conditions, &format!("{}.conditions", path), errors);
+    }
+}
+
+fn validate_policy_value(value: &serde_json::Value, path: &str, errors: &mut Vec<String>) {
+    let Some(policy) = value.as_object() else {
+        errors.push(format!("{} must be an object", path));
+        return;
+    };
+    if !policy
+        .get("name")
+        .and_then(|name| name.as_str())
+        .is_some_and(|name| !name.trim().is_empty() && name.chars().count() <= 100)
+    {
+        errors.push(format!("{}.name must be 1-100 characters", path));
+    }
+    match policy.get("profile").and_then(|profile| profile.as_str()) {
+        Some(profile) => {
+            if let Err(e) = validate_profile_name(profile) {
+                errors.push(format!("{}.profile: {}", path, e));
+            }
+        }
+        None => errors.push(format!("{}.profile must be a string", path)),
+    }
+    for key in ["globalAllow", "globalDeny"] {
+        match policy.get(key) {
+            Some(list) => validate_string_list(list, &format!("{}.{}", path, key), errors),
+            None => errors.push(format!("{}.{} is required", path, key)),
+        }
+    }
+    match policy.get("rules").and_then(|rules| rules.as_array()) {
+        Some(rules) => {
+            for (index, rule) in rules.iter().enumerate() {
+                validate_rule(rule, &format!("{}.rules[{}]", path, index), errors);
+            }
+        }
+        None => errors.push(format!("{}.rules must be an array", path)),
+    }
+    if let Some(settings) = policy.get("providerSettings").filter(|s| !s.is_null()) {
+        match settings.as_object() {
+            Some(providers) => {
+                for (provider, entry) in providers {
+                    let entry_path = format!("{}.providerSettings.{}", path, provider);
+                    if !entry
+                        .get("enabled")
+                        .is_some_and(|enabled| enabled.is_boolean())
+                    {
+                        errors.push(format!("{}.enabled must be a boolean", entry_path));
+                    }
+                    for key in ["allowedTools", "deniedTools"] {
+                        if let Some(list) = entry.get(key).filter(|list| !list.is_null()) {
+                            validate_string_list(list, &format!("{}.{}", entry_path, key), errors);
+                        }
+                    }
+                }
+            }
+            None => errors.push(format!("{}.providerSettings must be an object", path)),
+        }
+    }
+}
+
+/// Validate an exported document and return the single policy it carries.
+fn parse_policy_document(document: serde_json::Value) -> Result<ToolPolicy, String> {
+    let version = document
+        .get("formatVersion")
+        .and_then(|version| version.as_u64())
+        .ok_or_else(|| "Policy document is missing formatVersion".to_string())?;
+    if version != u64::from(POLICY_EXPORT_FORMAT_VERSION) {
+        return Err(format!(
+            "Unsupported policy document version {} (expected {})",
+            version, POLICY_EXPORT_FORMAT_VERSION
+        ));
+    }
+
+    let policies = document
+        .get("policies")
+        .and_then(|policies| policies.as_array())
+        .ok_or_else(|| "Policy document is missing a policies array".to_string())?;
+    if policies.len() != 1 {
+        return Err(format!(
+            "Policy document contains {} policies; exactly one can be imported",
+            policies.len()
+        ));
+    }
+
+    let mut errors = Vec::new();
+    validate_policy_value(&policies[0], "policies[0]", &mut errors);
+    if !errors.is_empty() {
+        return Err(format!("Invalid policy document: {}", errors.join("; ")));
+    }
+
+    let mut policy = policies[0].clone();
+    if let Some(fields) = policy.as_object_mut() {
+        // Identity and timestamps belong to the policy being imported into.
+        for (key, placeholder) in [
+            ("id", serde_json::json!("")),
+            ("isDefault", serde_json::json!(false)),
+            ("createdAt", serde_json::json!(0)),
+            ("updatedAt", serde_json::json!(0)),
+        ] {
+            fields.entry(key).or_insert(placeholder);
+        }
+    }
+    serde_json::from_value(policy).map_err(|e| format!("Invalid policy document: {}", e))
+}
+
+fn union_tool_lists(first: &[String], second: &[String]) -> Vec<String> {
+    let mut merged = first.to_vec();
+    for tool in second {
+        if !merged.contains(tool) {
+            merged.push(tool.clone());
+        }
+    }
+    merged
+}
+
+fn rules_by_priority(rules: &[ToolRule]) -> Vec<ToolRule> {
+    let mut sorted = rules.to_vec();
+    sorted.sort_by_key(|rule| rule.priority);
+    sorted
+}
+
+/// The whole resulting policy, rules included with their priorities, so it
+/// is applied in a single `policy_update`.
+struct PolicyImportPlan {
+    update: UpdatePolicyInput,
+    /// How many of the document's rules made it into `update.rules`.
+    imported: usize,
+}
+
+fn plan_policy_import(
+    current: &ToolPolicy,
+    imported: &ToolPolicy,
+    strategy: PolicyConflictStrategy,
+) -> PolicyImportPlan {
+    let current_providers = current.provider_settings.clone().unwrap_or_default();
+    let imported_providers = imported.provider_settings.clone().unwrap_or_default();
+
+    // Lower-priority rules first; conflicts are resolved against `winner`.
+    let (base, winner, base_providers, winner_providers, source) = match strategy {
+        PolicyConflictStrategy::Overwrite => {
+            return PolicyImportPlan {
+                update: UpdatePolicyInput {
+                    name: Some(imported.name.clone()),
+                    description: imported.description.clone(),
+                    profile: Some(imported.profile.clone()),
+                    global_allow: Some(imported.global_allow.clone()),
+                    global_deny: Some(imported.global_deny.clone()),
+                    rules: Some(rules_by_priority(&imported.rules)),
+                    provider_settings: Some(imported_providers),
+                },
+                imported: imported.rules.len(),
+            };
+        }
+        PolicyConflictStrategy::Merge => (
+            current,
+            imported,
+            current_providers,
+            imported_providers,
+            imported,
+        ),
+        PolicyConflictStrategy::KeepExisting => (
+            imported,
+            current,
+            imported_providers,
+            current_providers,
+            current,
+        ),
+    };
+
+    let winning_tools: Vec<&str> = winner.rules.iter().map(|rule| rule.tool.as_str()).collect();
+    let kept: Vec<ToolRule> = rules_by_priority(&base.rules)
+        .into_iter()
+        .filter(|rule| !winning_tools.contains(&rule.tool.as_str()))
+        .collect();
+    let imported_count = if strategy == PolicyConflictStrategy::Merge {
+        imported.rules.len()
+    } else {
+        kept.len()
+    };
+
+    let mut providers = base_providers;
+    providers.extend(winner_providers);
+
+    let mut rules = kept;
+    rules.extend(rules_by_priority(&winner.rules));
+
+    PolicyImportPlan {
+        update: UpdatePolicyInput {
+            name: Some(source.name.clone()),
+            description: source.description.clone(),
+            profile: Some(source.profile.clone()),
+            global_allow: Some(union_tool_lists(
+                &current.global_allow,
+                &imported.global_allow,
+            )),
+            global_deny: Some(union_tool_lists(
+                &current.global_deny,
+                &imported.global_deny,
+            )),
+            rules: Some(rules),
+            provider_settings: Some(providers),
+        },
+        imported: imported_count,
+    }
+}
+
+/// Materialize a built-in profile into concrete policy fields. Its defaults
+/// become the lowest-priority rules, deny above allow as in the profile, so
+/// they still yield to provider settings and to rules added later; putting
+/// them in the global lists would override both.
+fn policy_template(profile: &str) -> Result<UpdatePolicyInput, String> {
+    validate_profile_name(profile)?;
+    let (_, allow, deny) = PROFILE_DEFAULTS
+        .iter()
+        .find(|(name, _, _)| *name == profile)
+        .ok_or_else(|| format!("Unknown policy profile \"{}\"", profile))?;
+    let rules = allow
+        .iter()
+        .map(|tool| (tool, "allow"))
+        .chain(deny.iter().map(|tool| (tool, "deny")))
+        .zip(1..)
+        .map(|((tool, action), priority)| ToolRule {
+            tool: tool.to_string(),
+            action: action.to_string(),
+            conditions: None,
+            priority,
+        })
+        .collect();
+    Ok(UpdatePolicyInput {
+        name: None,
+        description: None,
+        profile: Some(profile.to_string()),
+        global_allow: Some(Vec::new()),
+        global_deny: Some(Vec::new()),
+        rules: Some(rules),
+        provider_settings: None,
+    })
+}
+
+async fn fetch_policy_document(
+    app: &AppHandle,
+    state: &State<'_, AgentState>,
+) -> Result<ToolPolicy, String> {
+    ensure_sidecar_started_public(app, state).await?;
+    let result = state
+        .manager
+        .send_command("policy_get", serde_json::json!({}))
+        .await?;
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))
+}
+
+/// Evaluate through the result cache, then the local engine, then the sidecar.
+async fn evaluate_tool_call(
+    app: &AppHandle,
+    state: &State<'_, AgentState>,
+    context: &ToolCallContext,
+) -> Result<ToolEvaluationResult, String> {
+    let key = policy_cache_key(context);
+    let generation = {
+        let mut cache = policy_cache();
+        if cache.config.max_entries > 0 {
+            if let Some(cached) = cache.get(&key) {
+                return Ok(cached);
+            }
+        }
+        cache.generation
+    };
+
+    // A stale document could disagree with the sidecar, so a failed refresh
+    // is an error rather than a reason to evaluate it anyway.
+    let cached_policy = policy_cache().policy();
+    let policy = match cached_policy {
+        Some((policy, true)) => policy,
+        _ => {
+            let policy = fetch_policy_document(app, state)
+                .await
+                .map_err(|e| format!("Failed to refresh policy document: {}", e))?;
+            policy_cache().store_policy(&policy, generation);
+            policy
+        }
+    };
+
+    if let Some(evaluation) = evaluate_policy_locally(&policy, context) {
+        policy_cache().insert(key, evaluation.clone(), generation);
+        return Ok(evaluation);
+    }
+
+    // The decision depends on runtime state only the sidecar has (MCP tools).
+    ensure_sidecar_started_public(app, state).await?;
+
+    let mut params =
+        serde_json::to_value(context).map_err(|e| format!("Failed to serialize context: {}", e))?;
+    if let Some(fields) = params.as_object_mut() {
+        fields
+            .entry("sessionId")
+            .or_insert_with(|| serde_json::json!("policy-preview"));
+    }
+    let result = state
+        .manager
+        .send_command("policy_evaluate", params)
+        .await?;
+
+    let mut evaluation: ToolEvaluationResult = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse evaluation result: {}", e))?;
+    if evaluation.priority.is_none() {
+        evaluation.priority = evaluation.matched_rule.as_ref().map(|rule| rule.priority);
+    }
+    policy_cache().insert(key, evaluation.clone(), generation);
+    Ok(evaluation)
+}
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
@@ -128,12 +1615,16 @@ pub async fn policy_get(
 ) -> Result<ToolPolicy, String> {
     ensure_sidecar_started_public(&app, &state).await?;
 
+    let generation = policy_cache().generation;
     let manager = &state.manager;
     let result = manager
         .send_command("policy_get", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))
+    let policy: ToolPolicy =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    policy_cache().store_policy(&policy, generation);
+    Ok(policy)
 }
 
 /// Update tool policy
@@ -148,9 +1639,14 @@ pub async fn policy_update(
     let manager = &state.manager;
     let params = serde_json::to_value(&input)
         .map_err(|e| format!("Failed to serialize input: {}", e))?;
-    let result = manager.send_command("policy_update", params).await?;
+    let result = manager.send_command("policy_update", params).await;
+    invalidate_policy_cache();
+    let result = result?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))
+    let policy: ToolPolicy =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    replace_cached_policy(&policy);
+    Ok(policy)
 }
 
 /// Set policy profile (resets rules to profile defaults)
@@ -164,9 +1660,14 @@ pub async fn policy_set_profile(
 
     let manager = &state.manager;
     let params = serde_json::json!({ "profile": profile });
-    let result = manager.send_command("policy_set_profile", params).await?;
+    let result = manager.send_command("policy_set_profile", params).await;
+    invalidate_policy_cache();
+    let result = result?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))
+    let policy: ToolPolicy =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    replace_cached_policy(&policy);
+    Ok(policy)
 }
 
 /// Add a custom rule to the policy
@@ -181,7 +1682,9 @@ pub async fn policy_add_rule(
     let manager = &state.manager;
     let params = serde_json::to_value(&input)
         .map_err(|e| format!("Failed to serialize input: {}", e))?;
-    let result = manager.send_command("policy_add_rule", params).await?;
+    let result = manager.send_command("policy_add_rule", params).await;
+    invalidate_policy_cache();
+    let result = result?;
 
     serde_json::from_value(result).map_err(|e| format!("Failed to parse rule: {}", e))
 }
@@ -197,7 +1700,9 @@ pub async fn policy_remove_rule(
 
     let manager = &state.manager;
     let params = serde_json::json!({ "index": index });
-    manager.send_command("policy_remove_rule", params).await?;
+    let result = manager.send_command("policy_remove_rule", params).await;
+    invalidate_policy_cache();
+    result?;
 
     Ok(())
 }
@@ -209,14 +1714,74 @@ pub async fn policy_evaluate(
     state: State<'_, AgentState>,
     context: ToolCallContext,
 ) -> Result<ToolEvaluationResult, String> {
-    ensure_sidecar_started_public(&app, &state).await?;
+    evaluate_tool_call(&app, &state, &context).await
+}
 
-    let manager = &state.manager;
-    let params = serde_json::to_value(&context)
-        .map_err(|e| format!("Failed to serialize context: {}", e))?;
-    let result = manager.send_command("policy_evaluate", params).await?;
+/// Dry-run a batch of tool calls against the current policy
+#[tauri::command]
+pub async fn policy_evaluate_batch(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    requests: Vec<ToolEvaluationRequest>,
+) -> Result<Vec<ToolEvaluationResult>, String> {
+    if requests.len() > MAX_POLICY_BATCH_SIZE {
+        return Err(format!(
+            "At most {} tool calls can be evaluated at once",
+            MAX_POLICY_BATCH_SIZE
+        ));
+    }
+
+    let mut results = Vec::with_capacity(requests.len());
+    for (index, request) in requests.into_iter().enumerate() {
+        let context = ToolCallContext::from(request);
+        let evaluation = evaluate_tool_call(&app, &state, &context)
+            .await
+            .map_err(|e| format!("Request {} ({}): {}", index, context.tool_name, e))?;
+        results.push(evaluation);
+    }
+    Ok(results)
+}
+
+/// Get policy evaluation cache settings and hit/miss counters
+#[tauri::command]
+pub async fn policy_get_cache_stats() -> Result<PolicyCacheStats, String> {
+    let cache = policy_cache();
+    Ok(PolicyCacheStats {
+        config: cache.config.clone(),
+        entries: cache.entries.len(),
+        hits: cache.hits,
+        misses: cache.misses,
+    })
+}
+
+/// Configure the policy evaluation cache; clears cached results
+#[tauri::command]
+pub async fn policy_set_cache_config(
+    max_entries: Option<usize>,
+    ttl_secs: Option<u64>,
+) -> Result<PolicyCacheConfig, String> {
+    if max_entries.is_some_and(|max| max > MAX_POLICY_CACHE_ENTRIES) {
+        return Err(format!(
+            "maxEntries must be at most {}",
+            MAX_POLICY_CACHE_ENTRIES
+        ));
+    }
+    if ttl_secs.is_some_and(|ttl| ttl == 0 || ttl > MAX_POLICY_CACHE_TTL_SECS) {
+        return Err(format!(
+            "ttlSecs must be between 1 and {}",
+            MAX_POLICY_CACHE_TTL_SECS
+        ));
+    }
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse evaluation result: {}", e))
+    let mut cache = policy_cache();
+    if let Some(max_entries) = max_entries {
+        cache.config.max_entries = max_entries;
+    }
+    if let Some(ttl_secs) = ttl_secs {
+        cache.config.ttl_secs = ttl_secs;
+    }
+    cache.invalidate();
+    Ok(cache.config.clone())
 }
 
 /// Reset policy to defaults
@@ -230,9 +1795,90 @@ pub async fn policy_reset(
     let manager = &state.manager;
     let result = manager
         .send_command("policy_reset", serde_json::json!({}))
-        .await?;
+        .await;
+    invalidate_policy_cache();
+    let result = result?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))
+    let policy: ToolPolicy =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    replace_cached_policy(&policy);
+    Ok(policy)
+}
+
+/// Export the current policy as a versioned JSON document
+#[tauri::command]
+pub async fn policy_export(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    id: Option<String>,
+) -> Result<PolicyExportDocument, String> {
+    let policy = fetch_policy_document(&app, &state).await?;
+    if let Some(id) = id.filter(|id| *id != policy.id) {
+        return Err(format!("Policy not found: {}", id));
+    }
+
+    Ok(PolicyExportDocument {
+        format_version: POLICY_EXPORT_FORMAT_VERSION,
+        exported_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0),
+        policies: vec![policy],
+    })
+}
+
+/// Import a policy document produced by `policy_export`
+#[tauri::command]
+pub async fn policy_import(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    document: serde_json::Value,
+    conflict_strategy: Option<String>,
+) -> Result<PolicyImportResult, String> {
+    let strategy = parse_conflict_strategy(conflict_strategy.as_deref())?;
+    let imported = parse_policy_document(document)?;
+    let document_rules = imported.rules.len();
+    let current = fetch_policy_document(&app, &state).await?;
+    let plan = plan_policy_import(&current, &imported, strategy);
+
+    let params = serde_json::to_value(&plan.update)
+        .map_err(|e| format!("Failed to serialize input: {}", e))?;
+    let result = state.manager.send_command("policy_update", params).await;
+    invalidate_policy_cache();
+    let result = result?;
+
+    let policy: ToolPolicy =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    replace_cached_policy(&policy);
+    Ok(PolicyImportResult {
+        policy,
+        conflict_strategy: strategy.as_str().to_string(),
+        rules_imported: plan.imported,
+        rules_skipped: document_rules - plan.imported,
+    })
+}
+
+/// Replace the policy with a built-in profile's defaults as a starting point
+#[tauri::command]
+pub async fn policy_apply_template(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    profile: String,
+) -> Result<ToolPolicy, String> {
+    let template = policy_template(profile.trim())?;
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    let manager = &state.manager;
+    let params =
+        serde_json::to_value(&template).map_err(|e| format!("Failed to serialize input: {}", e))?;
+    let result = manager.send_command("policy_update", params).await;
+    invalidate_policy_cache();
+    let result = result?;
+
+    let policy: ToolPolicy =
+        serde_json::from_value(result).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    replace_cached_policy(&policy);
+    Ok(policy)
 }
 
 /// Get available tool profiles
@@ -262,3 +1908,520 @@ pub async fn policy_get_groups(
         .send_command("policy_get_groups", serde_json::json!({}))
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(fields: serde_json::Value) -> ToolPolicy {
+        let mut value = json!({
+            "id": "policy-test",
+            "name": "Test",
+            "profile": "custom",
+            "globalAllow": [],
+            "globalDeny": [],
+            "rules": [],
+            "isDefault": false,
+            "createdAt": 0,
+            "updatedAt": 0
+        });
+        for (key, field) in fields.as_object().unwrap() {
+            value[key] = field.clone();
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn call(tool: &str, provider: Option<&str>, arguments: serde_json::Value) -> ToolCallContext {
+        ToolCallContext {
+            tool_name: tool.to_string(),
+            provider: provider.map(str::to_string),
+            session_type: "main".to_string(),
+            arguments,
+        }
+    }
+
+    fn decide(policy: &ToolPolicy, context: &ToolCallContext) -> (String, Option<String>) {
+        let result = evaluate_policy_locally(policy, context).expect("decided locally");
+        assert_eq!(result.allowed, result.action == "allow");
+        (result.action, result.reason_code)
+    }
+
+    fn decision(action: &str, reason_code: &str) -> (String, Option<String>) {
+        (action.to_string(), Some(reason_code.to_string()))
+    }
+
+    #[test]
+    fn global_deny_beats_everything() {
+        let policy = policy(json!({
+            "profile": "full",
+            "globalAllow": ["*"],
+            "globalDeny": ["Bash"],
+            "rules": [{ "tool": "Bash", "action": "allow", "priority": 100 }]
+        }));
+        assert_eq!(
+            decide(&policy, &call("Bash", None, json!({}))),
+            decision("deny", "global_deny")
+        );
+    }
+
+    #[test]
+    fn global_allow_beats_rules_and_provider_settings() {
+        let policy = policy(json!({
+            "globalAllow": ["group:fs"],
+            "rules": [{ "tool": "read_file", "action": "deny", "priority": 100 }],
+            "providerSettings": { "github": { "enabled": false } }
+        }));
+        assert_eq!(
+            decide(&policy, &call("read_file", Some("github"), json!({}))),
+            decision("allow", "global_allow")
+        );
+    }
+
+    #[test]
+    fn rules_apply_in_descending_priority() {
+        let policy = policy(json!({
+            "rules": [
+                { "tool": "Bash", "action": "allow", "priority": 1 },
+                { "tool": "group:shell", "action": "deny", "priority": 10 },
+                { "tool": "Bash", "action": "ask", "priority": 5 }
+            ]
+        }));
+        let result = evaluate_policy_locally(&policy, &call("Bash", None, json!({}))).unwrap();
+        assert_eq!(result.action, "deny");
+        assert_eq!(result.reason_code.as_deref(), Some("rule_deny"));
+        assert_eq!(result.priority, Some(10));
+        assert_eq!(result.matched_rule.unwrap().tool, "group:shell");
+    }
+
+    // Pinned to ToolPolicyService.evaluate in the sidecar, which checks
+    // provider settings before custom rules.
+    #[test]
+    fn provider_settings_beat_rules() {
+        let policy = policy(json!({
+            "rules": [
+                { "tool": "create_issue", "action": "allow", "priority": 100 },
+                { "tool": "delete_repo", "action": "allow", "priority": 100 },
+                { "tool": "list_issues", "action": "deny", "priority": 1 }
+            ],
+            "providerSettings": {
+                "github": { "enabled": false },
+                "gitlab": { "enabled": true, "deniedTools": ["delete_repo"] }
+            }
+        }));
+        assert_eq!(
+            decide(&policy, &call("create_issue", Some("github"), json!({}))),
+            decision("deny", "provider_disabled")
+        );
+        assert_eq!(
+            decide(&policy, &call("delete_repo", Some("gitlab"), json!({}))),
+            decision("deny", "provider_tool_denied")
+        );
+        // Settings that don't object leave the decision to the rules.
+        assert_eq!(
+            decide(&policy, &call("list_issues", Some("gitlab"), json!({}))),
+            decision("deny", "rule_deny")
+        );
+        assert_eq!(
+            decide(&policy, &call("create_issue", None, json!({}))),
+            decision("allow", "rule_allow")
+        );
+    }
+
+    #[test]
+    fn provider_settings_filter_tools() {
+        let policy = policy(json!({
+            "profile": "full",
+            "providerSettings": {
+                "github": { "enabled": true, "deniedTools": ["delete_repo"] },
+                "slack": { "enabled": true, "allowedTools": ["post_message"] }
+            }
+        }));
+        assert_eq!(
+            decide(&policy, &call("delete_repo", Some("github"), json!({}))),
+            decision("deny", "provider_tool_denied")
+        );
+        assert_eq!(
+            decide(&policy, &call("list_repos", Some("github"), json!({}))),
+            decision("allow", "profile_allow")
+        );
+        assert_eq!(
+            decide(&policy, &call("archive_channel", Some("slack"), json!({}))),
+            decision("deny", "provider_allowlist_miss")
+        );
+        assert_eq!(
+            decide(&policy, &call("archive_channel", None, json!({}))),
+            decision("allow", "profile_allow")
+        );
+    }
+
+    #[test]
+    fn profile_defaults_then_ask() {
+        let coding = policy(json!({ "profile": "coding" }));
+        assert_eq!(
+            decide(&coding, &call("generate_image", None, json!({}))),
+            decision("deny", "profile_deny")
+        );
+        assert_eq!(
+            decide(&coding, &call("edit_file", None, json!({}))),
+            decision("allow", "profile_allow")
+        );
+        assert_eq!(
+            decide(&coding, &call("computer_use", None, json!({}))),
+            decision("ask", "default_ask")
+        );
+
+        let custom = policy(json!({}));
+        assert_eq!(
+            decide(&custom, &call("read_file", None, json!({}))),
+            decision("ask", "default_ask")
+        );
+    }
+
+    #[test]
+    fn mcp_group_defers_to_sidecar() {
+        let context = call("create_issue", Some("github"), json!({}));
+        let deny = policy(json!({ "globalDeny": ["group:mcp"] }));
+        assert!(evaluate_policy_locally(&deny, &context).is_none());
+        let allow = policy(json!({ "globalAllow": ["group:mcp"] }));
+        assert!(evaluate_policy_locally(&allow, &context).is_none());
+        let rule = policy(json!({
+            "rules": [{ "tool": "group:mcp", "action": "allow", "priority": 1 }]
+        }));
+        assert!(evaluate_policy_locally(&rule, &context).is_none());
+
+        // A definite match earlier in the same list still decides locally.
+        let listed = policy(json!({ "globalDeny": ["create_issue", "group:mcp"] }));
+        assert_eq!(decide(&listed, &context), decision("deny", "global_deny"));
+        // A higher-priority rule decides before the MCP rule is reached.
+        let ordered = policy(json!({
+            "rules": [
+                { "tool": "group:mcp", "action": "allow", "priority": 1 },
+                { "tool": "create_*", "action": "deny", "priority": 5 }
+            ]
+        }));
+        assert_eq!(decide(&ordered, &context), decision("deny", "rule_deny"));
+    }
+
+    #[test]
+    fn glob_match_follows_micromatch_defaults() {
+        assert!(glob_match("src/main.rs", "src/*.rs"));
+        assert!(!glob_match("src/commands/policy.rs", "src/*.rs"));
+        assert!(glob_match("src/commands/policy.rs", "src/**/*.rs"));
+        assert!(glob_match("src/main.rs", "src/**/*.rs"));
+        assert!(glob_match("src/a/b/c", "src/**"));
+        assert!(glob_match("file7.txt", "file[0-9].txt"));
+        assert!(!glob_match("fileA.txt", "file[!A-Z].txt"));
+        assert!(glob_match("ab", "a?"));
+        assert!(!glob_match("a/", "a?"));
+        assert!(glob_match("[x", "[x"));
+    }
+
+    #[test]
+    fn glob_match_expands_braces() {
+        assert!(glob_match("app.tsx", "*.{ts,tsx}"));
+        assert!(glob_match("app.ts", "*.{ts,tsx}"));
+        assert!(!glob_match("app.js", "*.{ts,tsx}"));
+        assert!(glob_match("lib/b.rs", "{src,lib/{a,b}}.rs"));
+        assert_eq!(
+            expand_braces("{src,lib/{a,b}}.rs"),
+            vec!["src.rs", "lib/a.rs", "lib/b.rs"]
+        );
+        assert_eq!(expand_braces("{single}.rs"), vec!["{single}.rs"]);
+    }
+
+    #[test]
+    fn glob_wildcards_skip_dotfiles() {
+        assert!(!glob_match(".env", "*"));
+        assert!(glob_match(".env.local", ".env*"));
+        assert!(!glob_match(".env", "?env"));
+        assert!(!glob_match("src/.hidden/a.rs", "src/**/*.rs"));
+        assert!(!glob_match("home/.ssh/id_rsa", "home/**"));
+        assert!(glob_match("home/.ssh/id_rsa", "home/.ssh/*"));
+    }
+
+    #[test]
+    fn command_patterns_match_prefixes() {
+        assert!(command_matches("git status", "git *"));
+        assert!(command_matches("git", "git *"));
+        assert!(!command_matches("gitk --all", "git *"));
+        assert!(command_matches("npm run build", "npm run*"));
+        assert!(command_matches("ls", "ls"));
+        assert!(!command_matches("ls -la", "ls"));
+    }
+
+    #[test]
+    fn path_conditions_scope_rules() {
+        let policy = policy(json!({
+            "rules": [{
+                "tool": "write_file",
+                "action": "allow",
+                "priority": 1,
+                "conditions": {
+                    "pathPatterns": ["/work/**"],
+                    "excludePaths": ["/work/secrets/**"]
+                }
+            }]
+        }));
+        assert_eq!(
+            decide(
+                &policy,
+                &call("write_file", None, json!({ "path": "/work/a.txt" }))
+            ),
+            decision("allow", "rule_allow")
+        );
+        assert_eq!(
+            decide(
+                &policy,
+                &call("write_file", None, json!({ "file_path": "/tmp/a.txt" }))
+            ),
+            decision("ask", "default_ask")
+        );
+        let result = evaluate_policy_locally(
+            &policy,
+            &call("write_file", None, json!({ "path": "/work/secrets/key" })),
+        )
+        .unwrap();
+        assert_eq!(
+            result.reason.as_deref(),
+            Some("Path \"/work/secrets/key\" matches exclude pattern \"/work/secrets/**\"")
+        );
+    }
+
+    #[test]
+    fn command_conditions_scope_rules() {
+        let policy = policy(json!({
+            "rules": [
+                {
+                    "tool": "Bash",
+                    "action": "allow",
+                    "priority": 10,
+                    "conditions": { "allowedCommands": ["git *", "cargo *"] }
+                },
+                {
+                    "tool": "Bash",
+                    "action": "deny",
+                    "priority": 1,
+                    "conditions": { "deniedCommands": ["rm *"] }
+                }
+            ]
+        }));
+        assert_eq!(
+            decide(
+                &policy,
+                &call("Bash", None, json!({ "command": "cargo test" }))
+            ),
+            decision("allow", "rule_allow")
+        );
+        let result = evaluate_policy_locally(
+            &policy,
+            &call("Bash", None, json!({ "cmd": "rm -rf target" })),
+        )
+        .unwrap();
+        assert_eq!(result.action, "deny");
+        assert_eq!(
+            result.reason.as_deref(),
+            Some("Command matches denied pattern \"rm *\"")
+        );
+        let result = evaluate_policy_locally(
+            &policy,
+            &call("Bash", None, json!({ "command": "curl example.com" })),
+        )
+        .unwrap();
+        assert_eq!(result.priority, Some(1));
+        assert_eq!(result.reason.as_deref(), Some("All conditions satisfied"));
+    }
+
+    #[test]
+    fn session_and_provider_conditions_scope_rules() {
+        let policy = policy(json!({
+            "rules": [{
+                "tool": "*",
+                "action": "allow",
+                "priority": 1,
+                "conditions": { "sessionTypes": ["main"], "providers": ["github"] }
+            }]
+        }));
+        assert_eq!(
+            decide(&policy, &call("list_issues", Some("github"), json!({}))),
+            decision("allow", "rule_allow")
+        );
+        assert_eq!(
+            decide(&policy, &call("list_issues", Some("slack"), json!({}))),
+            decision("ask", "default_ask")
+        );
+        let mut isolated = call("list_issues", Some("github"), json!({}));
+        isolated.session_type = "isolated".to_string();
+        assert_eq!(decide(&policy, &isolated), decision("ask", "default_ask"));
+    }
+
+    #[test]
+    fn max_risk_level_scopes_rules() {
+        let low = policy(json!({
+            "rules": [{
+                "tool": "group:fs",
+                "action": "allow",
+                "priority": 1,
+                "conditions": { "maxRiskLevel": "low" }
+            }]
+        }));
+        assert_eq!(
+            decide(&low, &call("read_file", None, json!({ "path": "/work/a" }))),
+            decision("allow", "rule_allow")
+        );
+        assert_eq!(
+            decide(
+                &low,
+                &call("write_file", None, json!({ "path": "/work/a" }))
+            ),
+            decision("ask", "default_ask")
+        );
+
+        let medium = policy(json!({
+            "rules": [{
+                "tool": "group:fs",
+                "action": "allow",
+                "priority": 1,
+                "conditions": { "maxRiskLevel": "medium" }
+            }]
+        }));
+        assert_eq!(
+            decide(
+                &medium,
+                &call("write_file", None, json!({ "path": "/work/a" }))
+            ),
+            decision("allow", "rule_allow")
+        );
+        assert_eq!(
+            decide(
+                &medium,
+                &call("write_file", None, json!({ "path": "/etc/hosts" }))
+            ),
+            decision("ask", "default_ask")
+        );
+
+        let unknown = policy(json!({
+            "rules": [{
+                "tool": "read_file",
+                "action": "allow",
+                "priority": 1,
+                "conditions": { "maxRiskLevel": "extreme" }
+            }]
+        }));
+        assert_eq!(
+            decide(&unknown, &call("read_file", None, json!({}))),
+            decision("ask", "default_ask")
+        );
+    }
+
+    #[test]
+    fn template_materializes_profile_defaults_as_lowest_rules() {
+        let template = policy_template("readonly").unwrap();
+        assert_eq!(template.profile.as_deref(), Some("readonly"));
+        assert_eq!(template.global_allow, Some(Vec::new()));
+        assert_eq!(template.global_deny, Some(Vec::new()));
+        let rules = template.rules.unwrap();
+        let (_, allow, deny) = PROFILE_DEFAULTS
+            .iter()
+            .find(|(name, _, _)| *name == "readonly")
+            .unwrap();
+        assert_eq!(rules.len(), allow.len() + deny.len());
+        let lowest_deny = rules
+            .iter()
+            .filter(|rule| rule.action == "deny")
+            .map(|rule| rule.priority)
+            .min()
+            .unwrap();
+        assert!(rules
+            .iter()
+            .filter(|rule| rule.action == "allow")
+            .all(|rule| rule.priority < lowest_deny));
+
+        // The concrete policy decides like the profile, and a rule added on
+        // top (the sidecar numbers it after the existing ones) still wins.
+        let mut applied = policy(json!({ "profile": "custom" }));
+        applied.rules = rules;
+        assert_eq!(
+            decide(&applied, &call("Write", None, json!({}))),
+            decision("deny", "rule_deny")
+        );
+        assert_eq!(
+            decide(&applied, &call("Read", None, json!({}))),
+            decision("allow", "rule_allow")
+        );
+        let next_priority = applied.rules.len() as i32 + 1;
+        applied.rules.push(ToolRule {
+            tool: "Bash".to_string(),
+            action: "allow".to_string(),
+            conditions: None,
+            priority: next_priority,
+        });
+        assert_eq!(
+            decide(&applied, &call("Bash", None, json!({}))),
+            decision("allow", "rule_allow")
+        );
+    }
+
+    #[test]
+    fn import_keeps_rule_priorities_in_one_update() {
+        let current = policy(json!({
+            "rules": [
+                { "tool": "Bash", "action": "ask", "priority": 3 },
+                { "tool": "Write", "action": "deny", "priority": 7 }
+            ]
+        }));
+        let imported = policy(json!({
+            "rules": [
+                { "tool": "Bash", "action": "allow", "priority": 40 },
+                { "tool": "Read", "action": "allow", "priority": 12 }
+            ]
+        }));
+        let priorities = |plan: &PolicyImportPlan| -> Vec<(String, String, i32)> {
+            plan.update
+                .rules
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|rule| (rule.tool.clone(), rule.action.clone(), rule.priority))
+                .collect()
+        };
+        let entry = |tool: &str, action: &str, priority: i32| {
+            (tool.to_string(), action.to_string(), priority)
+        };
+
+        let plan = plan_policy_import(&current, &imported, PolicyConflictStrategy::Overwrite);
+        assert_eq!(
+            priorities(&plan),
+            vec![entry("Read", "allow", 12), entry("Bash", "allow", 40)]
+        );
+        assert_eq!(plan.imported, 2);
+
+        let plan = plan_policy_import(&current, &imported, PolicyConflictStrategy::Merge);
+        assert_eq!(
+            priorities(&plan),
+            vec![
+                entry("Write", "deny", 7),
+                entry("Read", "allow", 12),
+                entry("Bash", "allow", 40)
+            ]
+        );
+
+        let plan = plan_policy_import(&current, &imported, PolicyConflictStrategy::KeepExisting);
+        assert_eq!(
+            priorities(&plan),
+            vec![
+                entry("Read", "allow", 12),
+                entry("Bash", "ask", 3),
+                entry("Write", "deny", 7)
+            ]
+        );
+        assert_eq!(plan.imported, 1);
+    }
+
+    #[test]
+    fn template_rejects_unknown_profiles() {
+        let error = policy_template("paranoid").err().unwrap();
+        assert!(error.starts_with("Unknown policy profile \"paranoid\""));
+        assert!(error.contains("minimal, readonly, coding"));
+    }
+}
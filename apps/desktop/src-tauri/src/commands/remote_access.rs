@@ -2,7 +2,17 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
+use crate::paths;
+use crate::rate_limit::TokenBucket;
+use crate::redact;
+use crate::sidecar::SidecarManager;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use qrcode::{Color, EcLevel, QrCode};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex as StdMutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +67,12 @@ pub struct RemoteAccessStatus {
     pub tunnel_binary_installed: bool,
     #[serde(default)]
     pub tunnel_binary_path: Option<String>,
+    /// Version reported by the tunnel binary, detected locally.
+    #[serde(default)]
+    pub tunnel_binary_version: Option<String>,
+    /// Whether the detected version is below the provider's known-good minimum.
+    #[serde(default)]
+    pub tunnel_binary_outdated: bool,
     pub tunnel_auth_status: String,
     #[serde(default)]
     pub tunnel_started_at: Option<i64>,
@@ -72,6 +88,9 @@ pub struct RemoteAccessStatus {
     #[serde(default)]
     pub diagnostics: Vec<RemoteDiagnosticEntry>,
     pub device_count: usize,
+    /// Cap on active devices, filled in from the local pairing limits.
+    #[serde(default)]
+    pub max_devices: usize,
     #[serde(default)]
     pub devices: Vec<RemoteAccessDeviceSummary>,
 }
@@ -96,6 +115,696 @@ struct RevokeResult {
     revoked: bool,
 }
 
+/// Limits applied to pairing QR generation and device registration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePairingLimits {
+    /// Maximum number of active (non-revoked, unexpired) devices.
+    pub max_devices: usize,
+    /// Pairing codes that can be generated back to back.
+    pub pairing_burst: u32,
+    /// Seconds to regain one pairing code after the burst is spent.
+    pub pairing_refill_secs: u64,
+}
+
+impl Default for RemotePairingLimits {
+    fn default() -> Self {
+        Self {
+            max_devices: 10,
+            pairing_burst: 5,
+            pairing_refill_secs: 60,
+        }
+    }
+}
+
+const MAX_DEVICES_LIMIT: usize = 100;
+const MAX_PAIRING_BURST: u32 = 50;
+const MAX_PAIRING_REFILL_SECS: u64 = 3600;
+const PAIRING_LIMITS_DIR: &str = "remote-access";
+const PAIRING_LIMITS_FILE: &str = "pairing-limits.json";
+
+static PAIRING_LIMITS: OnceLock<StdMutex<RemotePairingLimits>> = OnceLock::new();
+/// Token bucket guarding pairing QR generation.
+static PAIRING_RATE_LIMITER: OnceLock<StdMutex<TokenBucket>> = OnceLock::new();
+
+fn pairing_limits() -> &'static StdMutex<RemotePairingLimits> {
+    PAIRING_LIMITS.get_or_init(|| {
+        let limits = pairing_limits_path()
+            .map(|path| load_pairing_limits_file(&path))
+            .unwrap_or_default();
+        StdMutex::new(limits)
+    })
+}
+
+fn pairing_limits_path() -> Result<std::path::PathBuf, String> {
+    Ok(paths::app_data_dir()?
+        .join(PAIRING_LIMITS_DIR)
+        .join(PAIRING_LIMITS_FILE))
+}
+
+fn validate_pairing_limits(
+    max_devices: Option<usize>,
+    pairing_burst: Option<u32>,
+    pairing_refill_secs: Option<u64>,
+) -> Result<(), String> {
+    if let Some(max_devices) = max_devices {
+        if max_devices == 0 || max_devices > MAX_DEVICES_LIMIT {
+            return Err(format!("maxDevices must be between 1 and {}", MAX_DEVICES_LIMIT));
+        }
+    }
+    if let Some(burst) = pairing_burst {
+        if burst == 0 || burst > MAX_PAIRING_BURST {
+            return Err(format!("pairingBurst must be between 1 and {}", MAX_PAIRING_BURST));
+        }
+    }
+    if let Some(refill) = pairing_refill_secs {
+        if refill == 0 || refill > MAX_PAIRING_REFILL_SECS {
+            return Err(format!(
+                "pairingRefillSecs must be between 1 and {}",
+                MAX_PAIRING_REFILL_SECS
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Saved limits, or the defaults when the file is missing or invalid.
+fn load_pairing_limits_file(path: &std::path::Path) -> RemotePairingLimits {
+    let Ok(content) = fs::read_to_string(path) else {
+        return RemotePairingLimits::default();
+    };
+    match serde_json::from_str::<RemotePairingLimits>(&content) {
+        Ok(limits)
+            if validate_pairing_limits(
+                Some(limits.max_devices),
+                Some(limits.pairing_burst),
+                Some(limits.pairing_refill_secs),
+            )
+            .is_ok() =>
+        {
+            limits
+        }
+        _ => {
+            log::warn!("Ignoring invalid pairing limits in {}", path.display());
+            RemotePairingLimits::default()
+        }
+    }
+}
+
+fn write_pairing_limits_file(
+    path: &std::path::Path,
+    limits: &RemotePairingLimits,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(limits)
+        .map_err(|e| format!("Failed to serialize pairing limits: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to save pairing limits: {}", e))
+}
+
+fn current_pairing_limits() -> RemotePairingLimits {
+    pairing_limits()
+        .lock()
+        .map(|limits| limits.clone())
+        .unwrap_or_default()
+}
+
+fn pairing_rate_limiter() -> &'static StdMutex<TokenBucket> {
+    PAIRING_RATE_LIMITER.get_or_init(|| {
+        StdMutex::new(TokenBucket::full(
+            RemotePairingLimits::default().pairing_burst,
+        ))
+    })
+}
+
+/// Take one pairing token, or return how long until the next one is available.
+fn acquire_pairing_token(limits: &RemotePairingLimits) -> Result<(), Duration> {
+    let mut limiter = match pairing_rate_limiter().lock() {
+        Ok(limiter) => limiter,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    limiter.try_acquire(
+        limits.pairing_burst,
+        Duration::from_secs(limits.pairing_refill_secs),
+    )
+}
+
+const TUNNEL_STATES: [&str; 4] = ["stopped", "starting", "running", "error"];
+const TUNNEL_AUTH_STATUSES: [&str; 3] = ["authenticated", "unauthenticated", "unknown"];
+
+/// A tunnel backend driven by the sidecar. Each provider describes what its
+/// mode needs and normalizes the sidecar's status into consistent fields.
+trait TunnelProvider: Sync {
+    /// Mode identifier understood by the sidecar.
+    fn mode(&self) -> &'static str;
+    fn label(&self) -> &'static str;
+    /// Executable the sidecar installs and runs, if any.
+    fn binary_name(&self) -> Option<&'static str>;
+    /// Whether the provider needs an authentication step for this status.
+    fn requires_auth(&self, status: &RemoteAccessStatus) -> bool;
+    /// Arguments that make the binary print its version.
+    fn version_args(&self) -> &'static [&'static str] {
+        &["--version"]
+    }
+    /// Oldest binary version known to work with Cowork.
+    fn minimum_version(&self) -> Option<[u64; 3]> {
+        None
+    }
+    /// Command that upgrades the binary in place.
+    fn upgrade_hint(&self) -> Option<&'static str> {
+        None
+    }
+    /// Characters allowed in a tunnel name besides ASCII letters and digits.
+    fn tunnel_name_extra_chars(&self) -> &'static [char] {
+        &['-', '_', '.']
+    }
+
+    fn normalize_status(&self, status: &mut RemoteAccessStatus) {
+        if !TUNNEL_STATES.contains(&status.tunnel_state.as_str()) {
+            status.tunnel_state = "error".to_string();
+        }
+        if !TUNNEL_AUTH_STATUSES.contains(&status.tunnel_auth_status.as_str()) {
+            status.tunnel_auth_status = "unknown".to_string();
+        }
+        if self.binary_name().is_none() {
+            status.tunnel_binary_installed = true;
+            status.tunnel_binary_path = None;
+        } else if !status.tunnel_binary_installed {
+            status.tunnel_binary_path = None;
+        }
+        if !self.requires_auth(status) {
+            status.tunnel_auth_status = "authenticated".to_string();
+        }
+        if !matches!(status.tunnel_state.as_str(), "running" | "starting") {
+            status.tunnel_pid = None;
+            status.tunnel_started_at = None;
+        }
+    }
+}
+
+struct TailscaleTunnel;
+struct CloudflareTunnel;
+struct CustomTunnel;
+
+impl TunnelProvider for TailscaleTunnel {
+    fn mode(&self) -> &'static str {
+        "tailscale"
+    }
+    fn label(&self) -> &'static str {
+        "Tailscale Funnel"
+    }
+    fn binary_name(&self) -> Option<&'static str> {
+        Some("tailscale")
+    }
+    fn requires_auth(&self, _status: &RemoteAccessStatus) -> bool {
+        true
+    }
+    fn version_args(&self) -> &'static [&'static str] {
+        &["version"]
+    }
+    /// Tailscale names become DNS labels.
+    fn tunnel_name_extra_chars(&self) -> &'static [char] {
+        &['-']
+    }
+    /// Funnel with `--bg` needs 1.52.
+    fn minimum_version(&self) -> Option<[u64; 3]> {
+        Some([1, 52, 0])
+    }
+    fn upgrade_hint(&self) -> Option<&'static str> {
+        Some("tailscale update")
+    }
+}
+
+impl TunnelProvider for CloudflareTunnel {
+    fn mode(&self) -> &'static str {
+        "cloudflare"
+    }
+    fn label(&self) -> &'static str {
+        "Cloudflare Tunnel"
+    }
+    fn binary_name(&self) -> Option<&'static str> {
+        Some("cloudflared")
+    }
+    /// Quick tunnels work anonymously; only named domains need a login.
+    fn requires_auth(&self, status: &RemoteAccessStatus) -> bool {
+        status
+            .tunnel_domain
+            .as_deref()
+            .is_some_and(|domain| !domain.trim().is_empty())
+    }
+    fn minimum_version(&self) -> Option<[u64; 3]> {
+        Some([2023, 2, 2])
+    }
+    fn upgrade_hint(&self) -> Option<&'static str> {
+        Some("cloudflared update")
+    }
+}
+
+impl TunnelProvider for CustomTunnel {
+    fn mode(&self) -> &'static str {
+        "custom"
+    }
+    fn label(&self) -> &'static str {
+        "Custom endpoint"
+    }
+    fn binary_name(&self) -> Option<&'static str> {
+        None
+    }
+    fn requires_auth(&self, _status: &RemoteAccessStatus) -> bool {
+        false
+    }
+
+    fn normalize_status(&self, status: &mut RemoteAccessStatus) {
+        status.tunnel_binary_installed = true;
+        status.tunnel_binary_path = None;
+        status.tunnel_auth_status = "authenticated".to_string();
+        status.tunnel_pid = None;
+        if !TUNNEL_STATES.contains(&status.tunnel_state.as_str()) {
+            status.tunnel_state = "error".to_string();
+        }
+        if status.tunnel_public_url.is_none() {
+            status.tunnel_public_url = status.public_base_url.clone();
+        }
+    }
+}
+
+static TUNNEL_PROVIDERS: [&dyn TunnelProvider; 3] = [&TailscaleTunnel, &CloudflareTunnel, &CustomTunnel];
+
+fn tunnel_provider(mode: &str) -> Option<&'static dyn TunnelProvider> {
+    TUNNEL_PROVIDERS
+        .iter()
+        .copied()
+        .find(|provider| provider.mode() == mode)
+}
+
+/// Validate a requested tunnel mode against the registered providers.
+fn validate_tunnel_mode(mode: &str) -> Result<String, String> {
+    let normalized = mode.trim().to_ascii_lowercase();
+    if tunnel_provider(&normalized).is_some() {
+        return Ok(normalized);
+    }
+    let supported: Vec<&str> = TUNNEL_PROVIDERS.iter().map(|provider| provider.mode()).collect();
+    Err(format!(
+        "Unsupported tunnel mode \"{}\" (expected one of: {})",
+        mode,
+        supported.join(", ")
+    ))
+}
+
+const TUNNEL_VISIBILITIES: [&str; 2] = ["public", "private"];
+const TUNNEL_NAME_MAX_LEN: usize = 64;
+const HOSTNAME_MAX_LEN: usize = 253;
+const HOSTNAME_LABEL_MAX_LEN: usize = 63;
+
+/// Tunnel options after validation. Empty inputs become `None`, which the
+/// sidecar treats as "clear".
+struct ValidatedTunnelOptions {
+    name: Option<String>,
+    domain: Option<String>,
+    visibility: Option<String>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn validate_tunnel_name(provider: &dyn TunnelProvider, name: &str) -> Result<String, String> {
+    let extra = provider.tunnel_name_extra_chars();
+    if name.len() > TUNNEL_NAME_MAX_LEN {
+        return Err(format!(
+            "tunnelName must be at most {} characters",
+            TUNNEL_NAME_MAX_LEN
+        ));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("tunnelName must start with a letter or digit".to_string());
+    }
+    if let Some(invalid) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !extra.contains(c))
+    {
+        let allowed: String = extra.iter().map(|c| format!(" '{}'", c)).collect();
+        return Err(format!(
+            "tunnelName contains '{}'; {} tunnel names may only use letters, digits, and{}",
+            invalid,
+            provider.label(),
+            allowed
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Accepts `host.example.com`, optionally with an `http(s)://` prefix and a
+/// trailing dot or slash, and returns the lowercase hostname.
+fn validate_tunnel_domain(domain: &str) -> Result<String, String> {
+    let host = domain
+        .strip_prefix("https://")
+        .or_else(|| domain.strip_prefix("http://"))
+        .unwrap_or(domain);
+    let host = host.strip_suffix('/').unwrap_or(host);
+    let host = host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase();
+
+    if host.contains(['/', ':', '?', '#', '@']) {
+        return Err(format!(
+            "tunnelDomain must be a bare hostname without a port or path: {}",
+            domain
+        ));
+    }
+    if host.len() > HOSTNAME_MAX_LEN {
+        return Err(format!(
+            "tunnelDomain must be at most {} characters",
+            HOSTNAME_MAX_LEN
+        ));
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() < 2 {
+        return Err(format!(
+            "tunnelDomain must be a fully qualified hostname (e.g. remote.example.com): {}",
+            domain
+        ));
+    }
+    for label in &labels {
+        let valid = !label.is_empty()
+            && label.len() <= HOSTNAME_LABEL_MAX_LEN
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !label.starts_with('-')
+            && !label.ends_with('-');
+        if !valid {
+            return Err(format!(
+                "tunnelDomain has an invalid label \"{}\": labels must be 1-{} letters, digits, or hyphens and cannot start or end with a hyphen",
+                label, HOSTNAME_LABEL_MAX_LEN
+            ));
+        }
+    }
+    if labels
+        .last()
+        .is_some_and(|tld| tld.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(format!(
+            "tunnelDomain must be a hostname, not an IP address: {}",
+            domain
+        ));
+    }
+    Ok(host)
+}
+
+fn validate_tunnel_visibility(visibility: &str) -> Result<String, String> {
+    let normalized = visibility.to_ascii_lowercase();
+    if TUNNEL_VISIBILITIES.contains(&normalized.as_str()) {
+        Ok(normalized)
+    } else {
+        Err(format!(
+            "tunnelVisibility must be one of: {} (got \"{}\")",
+            TUNNEL_VISIBILITIES.join(", "),
+            visibility
+        ))
+    }
+}
+
+fn validate_tunnel_options(
+    provider: &dyn TunnelProvider,
+    name: Option<&str>,
+    domain: Option<&str>,
+    visibility: Option<&str>,
+) -> Result<ValidatedTunnelOptions, String> {
+    Ok(ValidatedTunnelOptions {
+        name: non_empty(name)
+            .map(|name| validate_tunnel_name(provider, name))
+            .transpose()?,
+        domain: non_empty(domain).map(validate_tunnel_domain).transpose()?,
+        visibility: non_empty(visibility)
+            .map(validate_tunnel_visibility)
+            .transpose()?,
+    })
+}
+
+/// Provider for `mode`, or for the currently configured mode when `None`.
+async fn resolve_tunnel_provider(
+    manager: &SidecarManager,
+    mode: Option<&str>,
+) -> Result<&'static dyn TunnelProvider, String> {
+    let mode = match mode {
+        Some(mode) => mode.to_string(),
+        None => get_remote_status(manager).await?.tunnel_mode,
+    };
+    tunnel_provider(&mode).ok_or_else(|| format!("Unsupported tunnel mode \"{}\"", mode))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelProviderInfo {
+    pub mode: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary_name: Option<String>,
+    pub supports_install: bool,
+}
+
+const TUNNEL_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Version detected for a tunnel binary path, with the detection time.
+#[derive(Debug, Clone)]
+struct DetectedTunnelVersion {
+    version: String,
+    detected_at: i64,
+}
+
+static TUNNEL_BINARY_VERSIONS: OnceLock<StdMutex<HashMap<String, DetectedTunnelVersion>>> =
+    OnceLock::new();
+
+fn tunnel_binary_versions() -> &'static StdMutex<HashMap<String, DetectedTunnelVersion>> {
+    TUNNEL_BINARY_VERSIONS.get_or_init(|| StdMutex::new(HashMap::new()))
+}
+
+/// First dotted version number in `output`, e.g. `1.66.4` from
+/// `tailscale 1.66.4` or `2024.6.1` from `cloudflared version 2024.6.1 (built ...)`.
+/// The numeric `major.minor[.patch]` part of a version, without any
+/// pre-release or build suffix (`1.66.4-t1a2b3c`, `2.0.0+build.5`).
+fn version_core(version: &str) -> &str {
+    version.split(['-', '+']).next().unwrap_or(version)
+}
+
+/// First version-looking token in a binary's `--version` output, keeping any
+/// suffix for display.
+fn parse_binary_version(output: &str) -> Option<String> {
+    output
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| {
+            let mut parts = version_core(token).split('.');
+            parts.clone().count() >= 2
+                && parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(str::to_string)
+}
+
+fn version_components(version: &str) -> [u64; 3] {
+    let mut components = [0; 3];
+    for (slot, part) in components.iter_mut().zip(version_core(version).split('.')) {
+        *slot = part.parse().unwrap_or(0);
+    }
+    components
+}
+
+/// Run the tunnel binary's version command and cache the parsed result.
+async fn detect_tunnel_binary_version(provider: &dyn TunnelProvider, binary_path: &str) {
+    let output = tokio::time::timeout(
+        TUNNEL_VERSION_TIMEOUT,
+        tokio::process::Command::new(binary_path)
+            .args(provider.version_args())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    let version = match output {
+        Ok(Ok(output)) => {
+            let text = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            parse_binary_version(&text)
+        }
+        Ok(Err(e)) => {
+            eprintln!("[remote-access] Failed to run {} for version: {}", binary_path, e);
+            None
+        }
+        Err(_) => {
+            eprintln!("[remote-access] Timed out reading version from {}", binary_path);
+            None
+        }
+    };
+
+    if let Ok(mut versions) = tunnel_binary_versions().lock() {
+        match version {
+            Some(version) => {
+                let detected_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as i64)
+                    .unwrap_or_default();
+                versions.insert(
+                    binary_path.to_string(),
+                    DetectedTunnelVersion {
+                        version,
+                        detected_at,
+                    },
+                );
+            }
+            None => {
+                versions.remove(binary_path);
+            }
+        }
+    }
+}
+
+/// Fill in the cached binary version, the outdated flag, and a diagnostics
+/// entry describing the detected version.
+fn apply_tunnel_binary_version(provider: &dyn TunnelProvider, status: &mut RemoteAccessStatus) {
+    let Some(path) = status.tunnel_binary_path.as_deref() else {
+        return;
+    };
+    let Some(detected) = tunnel_binary_versions()
+        .lock()
+        .ok()
+        .and_then(|versions| versions.get(path).cloned())
+    else {
+        return;
+    };
+
+    let binary = provider.binary_name().unwrap_or("tunnel binary");
+    let outdated = provider
+        .minimum_version()
+        .is_some_and(|minimum| version_components(&detected.version) < minimum);
+    let (level, message) = match provider.minimum_version() {
+        Some([major, minor, patch]) if outdated => (
+            "warn",
+            format!(
+                "{} {} is older than the minimum supported version {}.{}.{}; the tunnel may fail to start.",
+                binary, detected.version, major, minor, patch
+            ),
+        ),
+        _ => ("info", format!("Detected {} {}.", binary, detected.version)),
+    };
+
+    status.diagnostics.push(RemoteDiagnosticEntry {
+        id: format!("tunnel-binary-version-{}", detected.detected_at),
+        level: level.to_string(),
+        message,
+        step: "version".to_string(),
+        at: detected.detected_at,
+        command_hint: if outdated {
+            provider.upgrade_hint().map(str::to_string)
+        } else {
+            None
+        },
+    });
+    status.tunnel_binary_version = Some(detected.version);
+    status.tunnel_binary_outdated = outdated;
+}
+
+fn parse_remote_status(result: serde_json::Value) -> Result<RemoteAccessStatus, String> {
+    let mut status: RemoteAccessStatus = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse remote status: {}", e))?;
+    match tunnel_provider(&status.tunnel_mode) {
+        Some(provider) => {
+            provider.normalize_status(&mut status);
+            apply_tunnel_binary_version(provider, &mut status);
+        }
+        None => eprintln!(
+            "[remote-access] Unknown tunnel mode in status: {}",
+            status.tunnel_mode
+        ),
+    }
+    status.max_devices = current_pairing_limits().max_devices;
+    Ok(status)
+}
+
+/// Re-detect the tunnel binary's version from a fresh sidecar status.
+async fn refresh_tunnel_binary_version(
+    result: serde_json::Value,
+) -> Result<RemoteAccessStatus, String> {
+    let status = parse_remote_status(result)?;
+    let provider = match tunnel_provider(&status.tunnel_mode) {
+        Some(provider) if provider.binary_name().is_some() => provider,
+        _ => return Ok(status),
+    };
+    let Some(path) = status
+        .tunnel_binary_path
+        .clone()
+        .filter(|_| status.tunnel_binary_installed)
+    else {
+        return Ok(status);
+    };
+
+    detect_tunnel_binary_version(provider, &path).await;
+
+    let mut status = status;
+    status
+        .diagnostics
+        .retain(|entry| !entry.id.starts_with("tunnel-binary-version-"));
+    apply_tunnel_binary_version(provider, &mut status);
+    Ok(status)
+}
+
+/// List the tunnel providers remote access can use.
+#[tauri::command]
+pub async fn remote_access_list_tunnel_providers() -> Result<Vec<TunnelProviderInfo>, String> {
+    Ok(TUNNEL_PROVIDERS
+        .iter()
+        .map(|provider| TunnelProviderInfo {
+            mode: provider.mode().to_string(),
+            label: provider.label().to_string(),
+            binary_name: provider.binary_name().map(str::to_string),
+            supports_install: provider.binary_name().is_some(),
+        })
+        .collect())
+}
+
+/// Get the pairing rate limit and device cap.
+#[tauri::command]
+pub async fn remote_access_get_pairing_limits() -> Result<RemotePairingLimits, String> {
+    Ok(current_pairing_limits())
+}
+
+/// Update the pairing rate limit and device cap. Omitted fields keep their
+/// current values. The limits are saved and survive restarts; the device cap
+/// is passed on to the sidecar with each pairing code, which enforces it
+/// when a device completes pairing.
+#[tauri::command]
+pub async fn remote_access_set_pairing_limits(
+    max_devices: Option<usize>,
+    pairing_burst: Option<u32>,
+    pairing_refill_secs: Option<u64>,
+) -> Result<RemotePairingLimits, String> {
+    validate_pairing_limits(max_devices, pairing_burst, pairing_refill_secs)?;
+
+    let mut limits = pairing_limits()
+        .lock()
+        .map_err(|_| "Pairing limits lock poisoned".to_string())?;
+    let mut updated = limits.clone();
+    if let Some(max_devices) = max_devices {
+        updated.max_devices = max_devices;
+    }
+    if let Some(burst) = pairing_burst {
+        updated.pairing_burst = burst;
+    }
+    if let Some(refill) = pairing_refill_secs {
+        updated.pairing_refill_secs = refill;
+    }
+    write_pairing_limits_file(&pairing_limits_path()?, &updated)?;
+
+    if let Some(burst) = pairing_burst {
+        if let Ok(mut limiter) = pairing_rate_limiter().lock() {
+            limiter.cap(burst);
+        }
+    }
+    *limits = updated.clone();
+    Ok(updated)
+}
+
 /// Get remote access status.
 #[tauri::command]
 pub async fn remote_access_get_status(
@@ -103,13 +812,110 @@ pub async fn remote_access_get_status(
     state: State<'_, AgentState>,
 ) -> Result<RemoteAccessStatus, String> {
     ensure_sidecar_started_public(&app, &state).await?;
+    get_remote_status(&state.manager).await
+}
 
-    let manager = &state.manager;
+async fn get_remote_status(manager: &SidecarManager) -> Result<RemoteAccessStatus, String> {
     let result = manager
         .send_command("remote_access_get_status", serde_json::json!({}))
         .await?;
+    parse_remote_status(result)
+}
+
+const DIAGNOSTIC_LEVELS: [&str; 3] = ["info", "warn", "error"];
+const DIAGNOSTICS_EXPORT_DIR: &str = "diagnostics";
+fn diagnostic_level_rank(level: &str) -> Result<usize, String> {
+    DIAGNOSTIC_LEVELS
+        .iter()
+        .position(|candidate| *candidate == level)
+        .ok_or_else(|| {
+            format!(
+                "Invalid diagnostic level: {} (expected info, warn, or error)",
+                level
+            )
+        })
+}
+
+/// Diagnostics at or above `min_level`, recorded at or after `since`,
+/// newest first, capped at `limit`.
+fn filter_diagnostics(
+    diagnostics: Vec<RemoteDiagnosticEntry>,
+    min_level: Option<&str>,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<RemoteDiagnosticEntry>, String> {
+    let min_rank = min_level.map(diagnostic_level_rank).transpose()?.unwrap_or(0);
+    let mut entries: Vec<RemoteDiagnosticEntry> = diagnostics
+        .into_iter()
+        .filter(|entry| diagnostic_level_rank(&entry.level).unwrap_or(0) >= min_rank)
+        .filter(|entry| since.is_none_or(|since| entry.at >= since))
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.at));
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
+
+/// Get remote-access diagnostics, optionally limited to entries at or above
+/// `level`, recorded at or after `since` (ms), newest first.
+#[tauri::command]
+pub async fn remote_access_get_diagnostics(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    level: Option<String>,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<RemoteDiagnosticEntry>, String> {
+    if let Some(level) = level.as_deref() {
+        diagnostic_level_rank(level)?;
+    }
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    let status = get_remote_status(&state.manager).await?;
+    filter_diagnostics(status.diagnostics, level.as_deref(), since, limit)
+}
+
+/// Write diagnostics and a redacted status snapshot to
+/// `~/.cowork/diagnostics/remote-access-<ms>.json` and return the path.
+#[tauri::command]
+pub async fn remote_access_export_diagnostics(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+) -> Result<String, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    let mut status = get_remote_status(&state.manager).await?;
+    let diagnostics = filter_diagnostics(std::mem::take(&mut status.diagnostics), None, None, None)?;
+    let exported_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default();
+
+    let mut report = serde_json::json!({
+        "exportedAt": exported_at,
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "platform": std::env::consts::OS,
+        "status": status,
+        "diagnostics": diagnostics,
+    });
+    redact::redact_json(&mut report);
+
+    let export_dir = paths::app_data_dir()?.join(DIAGNOSTICS_EXPORT_DIR);
+    fs::create_dir_all(&export_dir)
+        .map_err(|e| format!("Failed to create diagnostics directory: {}", e))?;
+    let path = export_dir.join(format!("remote-access-{}.json", exported_at));
+    let contents = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("Failed to serialize diagnostics: {}", e))?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o600));
+    }
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    Ok(path.to_string_lossy().to_string())
 }
 
 /// Enable remote access.
@@ -124,19 +930,27 @@ pub async fn remote_access_enable(
     tunnel_visibility: Option<String>,
     bind_port: Option<u16>,
 ) -> Result<RemoteAccessStatus, String> {
+    let tunnel_mode = tunnel_mode.as_deref().map(validate_tunnel_mode).transpose()?;
     ensure_sidecar_started_public(&app, &state).await?;
 
     let manager = &state.manager;
+    let provider = resolve_tunnel_provider(manager, tunnel_mode.as_deref()).await?;
+    let options = validate_tunnel_options(
+        provider,
+        tunnel_name.as_deref(),
+        tunnel_domain.as_deref(),
+        tunnel_visibility.as_deref(),
+    )?;
     let params = serde_json::json!({
         "publicBaseUrl": public_base_url,
         "tunnelMode": tunnel_mode,
-        "tunnelName": tunnel_name,
-        "tunnelDomain": tunnel_domain,
-        "tunnelVisibility": tunnel_visibility,
+        "tunnelName": options.name,
+        "tunnelDomain": options.domain,
+        "tunnelVisibility": options.visibility,
         "bindPort": bind_port,
     });
     let result = manager.send_command("remote_access_enable", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Disable remote access.
@@ -152,23 +966,139 @@ pub async fn remote_access_disable(
         .send_command("remote_access_disable", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
+}
+
+const QR_DEFAULT_SIZE_PX: u32 = 512;
+const QR_MIN_SIZE_PX: u32 = 128;
+const QR_MAX_SIZE_PX: u32 = 2048;
+/// Quiet zone in modules; the QR spec asks for at least 4.
+const QR_DEFAULT_QUIET_ZONE: u32 = 4;
+const QR_MAX_QUIET_ZONE: u32 = 16;
+
+/// Pairing payload from the sidecar; the image itself is rendered locally.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairingQrPayload {
+    pairing_uri: String,
+    expires_at: i64,
+}
+
+fn parse_qr_error_correction(level: Option<&str>) -> Result<EcLevel, String> {
+    match level.map(|l| l.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("") | Some("medium") | Some("m") => Ok(EcLevel::M),
+        Some("low") | Some("l") => Ok(EcLevel::L),
+        Some("quartile") | Some("q") => Ok(EcLevel::Q),
+        Some("high") | Some("h") => Ok(EcLevel::H),
+        Some(other) => Err(format!(
+            "Invalid QR error correction level: {} (expected low, medium, quartile, or high)",
+            other
+        )),
+    }
+}
+
+/// Render `data` as a grayscale PNG data URL. The image is at most `size`
+/// pixels wide, rounded down to a whole number of pixels per module.
+pub(crate) fn render_qr_png_data_url(
+    data: &str,
+    size: u32,
+    quiet_zone: u32,
+    level: EcLevel,
+) -> Result<String, String> {
+    let code = QrCode::with_error_correction_level(data.as_bytes(), level)
+        .map_err(|e| format!("Failed to encode pairing QR code: {}", e))?;
+    let modules = code.width() as u32;
+    let total_modules = modules + quiet_zone * 2;
+    let scale = (size / total_modules).max(1);
+    let dimension = total_modules * scale;
+
+    let colors = code.to_colors();
+    let mut pixels = vec![0xFFu8; (dimension * dimension) as usize];
+    for y in 0..modules {
+        for x in 0..modules {
+            if colors[(y * modules + x) as usize] != Color::Dark {
+                continue;
+            }
+            let left = (x + quiet_zone) * scale;
+            let top = (y + quiet_zone) * scale;
+            for row in top..top + scale {
+                let start = (row * dimension + left) as usize;
+                pixels[start..start + scale as usize].fill(0);
+            }
+        }
+    }
+
+    let mut png_bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_bytes, dimension, dimension);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Failed to write QR image header: {}", e))?;
+        writer
+            .write_image_data(&pixels)
+            .map_err(|e| format!("Failed to write QR image data: {}", e))?;
+    }
+
+    Ok(format!("data:image/png;base64,{}", BASE64.encode(png_bytes)))
 }
 
 /// Generate a short-lived pairing QR code for the mobile app.
+///
+/// The sidecar issues the pairing URI; the PNG is rendered here so the app
+/// controls its size (`size` in pixels), quiet zone (in modules), and error
+/// correction level (`low`, `medium`, `quartile`, `high`).
 #[tauri::command]
 pub async fn remote_access_generate_qr(
     app: AppHandle,
     state: State<'_, AgentState>,
+    size: Option<u32>,
+    quiet_zone: Option<u32>,
+    error_correction: Option<String>,
 ) -> Result<RemotePairingQr, String> {
+    let level = parse_qr_error_correction(error_correction.as_deref())?;
+    let size = size
+        .unwrap_or(QR_DEFAULT_SIZE_PX)
+        .clamp(QR_MIN_SIZE_PX, QR_MAX_SIZE_PX);
+    let quiet_zone = quiet_zone
+        .unwrap_or(QR_DEFAULT_QUIET_ZONE)
+        .min(QR_MAX_QUIET_ZONE);
+
     ensure_sidecar_started_public(&app, &state).await?;
 
     let manager = &state.manager;
+    let limits = current_pairing_limits();
+    let status = get_remote_status(manager).await?;
+    if status.device_count >= limits.max_devices {
+        return Err(format!(
+            "Device limit reached ({} of {} active devices). Revoke a device before pairing another.",
+            status.device_count, limits.max_devices
+        ));
+    }
+    if let Err(wait) = acquire_pairing_token(&limits) {
+        return Err(format!(
+            "Too many pairing requests. Try again in {} seconds.",
+            wait.as_secs().max(1)
+        ));
+    }
+
     let result = manager
-        .send_command("remote_access_generate_qr", serde_json::json!({}))
+        .send_command(
+            "remote_access_generate_qr",
+            serde_json::json!({ "maxDevices": limits.max_devices }),
+        )
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse pairing QR result: {}", e))
+    let payload: PairingQrPayload = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse pairing QR result: {}", e))?;
+    let qr_data_url = render_qr_png_data_url(&payload.pairing_uri, size, quiet_zone, level)?;
+
+    Ok(RemotePairingQr {
+        qr_data_url,
+        pairing_uri: payload.pairing_uri,
+        expires_at: payload.expires_at,
+    })
 }
 
 /// List paired mobile devices.
@@ -228,7 +1158,7 @@ pub async fn remote_access_set_public_base_url(
         )
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Update tunnel mode.
@@ -238,6 +1168,7 @@ pub async fn remote_access_set_tunnel_mode(
     state: State<'_, AgentState>,
     tunnel_mode: String,
 ) -> Result<RemoteAccessStatus, String> {
+    let tunnel_mode = validate_tunnel_mode(&tunnel_mode)?;
     ensure_sidecar_started_public(&app, &state).await?;
 
     let manager = &state.manager;
@@ -248,7 +1179,7 @@ pub async fn remote_access_set_tunnel_mode(
         )
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Update tunnel naming/domain/visibility options.
@@ -261,22 +1192,37 @@ pub async fn remote_access_set_tunnel_options(
     tunnel_visibility: Option<String>,
     public_base_url: Option<String>,
 ) -> Result<RemoteAccessStatus, String> {
+    // Domain and visibility don't depend on the provider; check them before
+    // starting the sidecar.
+    let domain = non_empty(tunnel_domain.as_deref())
+        .map(validate_tunnel_domain)
+        .transpose()?;
+    let visibility = non_empty(tunnel_visibility.as_deref())
+        .map(validate_tunnel_visibility)
+        .transpose()?;
     ensure_sidecar_started_public(&app, &state).await?;
 
     let manager = &state.manager;
+    let name = match non_empty(tunnel_name.as_deref()) {
+        Some(name) => Some(validate_tunnel_name(
+            resolve_tunnel_provider(manager, None).await?,
+            name,
+        )?),
+        None => None,
+    };
     let result = manager
         .send_command(
             "remote_access_set_tunnel_options",
             serde_json::json!({
-                "tunnelName": tunnel_name,
-                "tunnelDomain": tunnel_domain,
-                "tunnelVisibility": tunnel_visibility,
+                "tunnelName": name,
+                "tunnelDomain": domain,
+                "tunnelVisibility": visibility,
                 "publicBaseUrl": public_base_url,
             }),
         )
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Refresh tunnel dependency/auth/runtime health.
@@ -292,7 +1238,7 @@ pub async fn remote_access_refresh_tunnel(
         .send_command("remote_access_refresh_tunnel", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    refresh_tunnel_binary_version(result).await
 }
 
 /// Install tunnel dependency for selected tunnel mode.
@@ -308,7 +1254,7 @@ pub async fn remote_access_install_tunnel_binary(
         .send_command("remote_access_install_tunnel_binary", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    refresh_tunnel_binary_version(result).await
 }
 
 /// Authenticate tunnel provider (if required).
@@ -324,7 +1270,7 @@ pub async fn remote_access_authenticate_tunnel(
         .send_command("remote_access_authenticate_tunnel", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Start managed tunnel process.
@@ -340,7 +1286,7 @@ pub async fn remote_access_start_tunnel(
         .send_command("remote_access_start_tunnel", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Stop managed tunnel process.
@@ -356,7 +1302,7 @@ pub async fn remote_access_stop_tunnel(
         .send_command("remote_access_stop_tunnel", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
 }
 
 /// Delete all remote setup and paired devices.
@@ -372,5 +1318,206 @@ pub async fn remote_access_delete_all(
         .send_command("remote_access_delete_all", serde_json::json!({}))
         .await?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse remote status: {}", e))
+    parse_remote_status(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(mode: &str, overrides: serde_json::Value) -> RemoteAccessStatus {
+        let mut value = serde_json::json!({
+            "enabled": true,
+            "running": true,
+            "bindHost": "127.0.0.1",
+            "bindPort": 4317,
+            "publicBaseUrl": "https://remote.example.com",
+            "tunnelMode": mode,
+            "tunnelVisibility": "public",
+            "tunnelState": "running",
+            "tunnelBinaryInstalled": false,
+            "tunnelBinaryPath": "/usr/local/bin/tunnel",
+            "tunnelAuthStatus": "unauthenticated",
+            "tunnelStartedAt": 1_700_000_000_000i64,
+            "tunnelPid": 4242,
+            "configHealth": "valid",
+            "deviceCount": 0
+        });
+        for (key, field) in overrides.as_object().unwrap() {
+            value[key] = field.clone();
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn normalized(mode: &str, overrides: serde_json::Value) -> RemoteAccessStatus {
+        let mut status = status(mode, overrides);
+        tunnel_provider(mode).unwrap().normalize_status(&mut status);
+        status
+    }
+
+    #[test]
+    fn unknown_state_and_auth_values_are_coerced() {
+        let status = normalized(
+            "tailscale",
+            serde_json::json!({ "tunnelState": "booting", "tunnelAuthStatus": "maybe" }),
+        );
+        assert_eq!(status.tunnel_state, "error");
+        assert_eq!(status.tunnel_auth_status, "unknown");
+    }
+
+    #[test]
+    fn missing_binary_clears_its_path() {
+        let status = normalized("tailscale", serde_json::json!({}));
+        assert!(!status.tunnel_binary_installed);
+        assert_eq!(status.tunnel_binary_path, None);
+
+        let status = normalized(
+            "tailscale",
+            serde_json::json!({ "tunnelBinaryInstalled": true }),
+        );
+        assert_eq!(
+            status.tunnel_binary_path.as_deref(),
+            Some("/usr/local/bin/tunnel")
+        );
+    }
+
+    #[test]
+    fn stopped_tunnel_drops_process_fields() {
+        let status = normalized("tailscale", serde_json::json!({ "tunnelState": "stopped" }));
+        assert_eq!(status.tunnel_pid, None);
+        assert_eq!(status.tunnel_started_at, None);
+
+        let status = normalized("tailscale", serde_json::json!({}));
+        assert_eq!(status.tunnel_pid, Some(4242));
+        assert_eq!(status.tunnel_started_at, Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn cloudflare_quick_tunnel_needs_no_login() {
+        let quick = normalized("cloudflare", serde_json::json!({}));
+        assert_eq!(quick.tunnel_auth_status, "authenticated");
+
+        let named = normalized(
+            "cloudflare",
+            serde_json::json!({ "tunnelDomain": "remote.example.com" }),
+        );
+        assert_eq!(named.tunnel_auth_status, "unauthenticated");
+    }
+
+    #[test]
+    fn custom_tunnel_has_no_binary_or_auth() {
+        let status = normalized("custom", serde_json::json!({}));
+        assert!(status.tunnel_binary_installed);
+        assert_eq!(status.tunnel_binary_path, None);
+        assert_eq!(status.tunnel_auth_status, "authenticated");
+        assert_eq!(status.tunnel_pid, None);
+        assert_eq!(
+            status.tunnel_public_url.as_deref(),
+            Some("https://remote.example.com")
+        );
+
+        let status = normalized(
+            "custom",
+            serde_json::json!({ "tunnelPublicUrl": "https://proxy.example.com" }),
+        );
+        assert_eq!(
+            status.tunnel_public_url.as_deref(),
+            Some("https://proxy.example.com")
+        );
+    }
+
+    #[test]
+    fn parse_remote_status_applies_pairing_limit() {
+        let value = serde_json::to_value(status("custom", serde_json::json!({}))).unwrap();
+        let status = parse_remote_status(value).unwrap();
+        assert_eq!(status.max_devices, current_pairing_limits().max_devices);
+        assert_eq!(status.tunnel_auth_status, "authenticated");
+    }
+
+    #[test]
+    fn tunnel_mode_is_checked_against_registered_providers() {
+        assert_eq!(validate_tunnel_mode(" Cloudflare ").unwrap(), "cloudflare");
+        assert_eq!(validate_tunnel_mode("tailscale").unwrap(), "tailscale");
+
+        let error = validate_tunnel_mode("ngrok").unwrap_err();
+        assert!(error.contains("\"ngrok\""));
+        assert!(error.contains("tailscale, cloudflare, custom"));
+    }
+
+    #[test]
+    fn pairing_limits_persist_and_reject_invalid_files() {
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-pairing-limits-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos())
+                .unwrap_or(0)
+        ));
+        let path = dir.join(PAIRING_LIMITS_DIR).join(PAIRING_LIMITS_FILE);
+
+        let missing = load_pairing_limits_file(&path);
+        assert_eq!(
+            missing.max_devices,
+            RemotePairingLimits::default().max_devices
+        );
+
+        let limits = RemotePairingLimits {
+            max_devices: 3,
+            pairing_burst: 2,
+            pairing_refill_secs: 120,
+        };
+        write_pairing_limits_file(&path, &limits).unwrap();
+        let loaded = load_pairing_limits_file(&path);
+        assert_eq!(loaded.max_devices, 3);
+        assert_eq!(loaded.pairing_burst, 2);
+        assert_eq!(loaded.pairing_refill_secs, 120);
+
+        fs::write(
+            &path,
+            r#"{ "maxDevices": 0, "pairingBurst": 2, "pairingRefillSecs": 120 }"#,
+        )
+        .unwrap();
+        assert_eq!(load_pairing_limits_file(&path).max_devices, 10);
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(load_pairing_limits_file(&path).pairing_burst, 5);
+
+        assert!(validate_pairing_limits(Some(MAX_DEVICES_LIMIT + 1), None, None).is_err());
+        assert!(validate_pairing_limits(None, Some(0), None).is_err());
+        assert!(validate_pairing_limits(None, None, Some(MAX_PAIRING_REFILL_SECS + 1)).is_err());
+        assert!(validate_pairing_limits(Some(1), Some(1), Some(1)).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn binary_versions_are_found_in_version_output() {
+        assert_eq!(
+            parse_binary_version("1.66.4\n  tailscale commit: abc\n").as_deref(),
+            Some("1.66.4")
+        );
+        assert_eq!(
+            parse_binary_version("1.66.4-t3b1d6c9a0-g1c0c8c0f\n").as_deref(),
+            Some("1.66.4-t3b1d6c9a0-g1c0c8c0f")
+        );
+        assert_eq!(
+            parse_binary_version("cloudflared version 2024.6.1 (built 2024-06-12-1234 UTC)")
+                .as_deref(),
+            Some("2024.6.1")
+        );
+        assert_eq!(
+            parse_binary_version("ngrok version v3.10.0+build.5").as_deref(),
+            Some("3.10.0+build.5")
+        );
+        assert_eq!(parse_binary_version("ngrok version unknown"), None);
+        assert_eq!(parse_binary_version("1.-beta"), None);
+    }
+
+    #[test]
+    fn version_components_ignore_suffixes() {
+        assert_eq!(version_components("1.66.4-t3b1d6c9a0"), [1, 66, 4]);
+        assert_eq!(version_components("3.10.0+build.5"), [3, 10, 0]);
+        assert_eq!(version_components("2024.6"), [2024, 6, 0]);
+        assert!(version_components("1.66.4-t3b1d6c9a0") >= [1, 58, 0]);
+    }
 }
@@ -1,11 +1,16 @@
 // Copyright (c) 2026 Naresh. All rights reserved.
 // Licensed under the MIT License. See LICENSE file for details.
 
+use crate::argv;
+use crate::commands::agent::{ensure_agent_idle, AgentState};
+use crate::paths;
 use crate::sidecar::resolve_sidecar_dir;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
+use std::sync::{Mutex, OnceLock};
+use tauri::State;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -27,6 +32,9 @@ const SERVICE_DISPLAY_NAME: &str = "Cowork Agent Daemon";
 const WINDOWS_USER_TASK_NAME: &str = "\\Cowork\\AgentDUser";
 #[cfg(target_os = "windows")]
 const WINDOWS_SYSTEM_SERVICE_NAME: &str = "CoworkAgentD";
+/// `schtasks /TR` rejects task commands longer than this.
+#[cfg(target_os = "windows")]
+const WINDOWS_TASK_RUN_MAX_LEN: usize = 261;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -78,6 +86,20 @@ pub struct ServiceStatus {
     pub details: Option<String>,
 }
 
+/// Resolved daemon launch settings, for debugging without installing a service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonConfig {
+    pub program: String,
+    pub args: Vec<String>,
+    pub app_data_dir: String,
+    pub endpoint: String,
+    pub token_file: String,
+    pub token_file_exists: bool,
+    pub lock_file: String,
+    pub saved_mode: String,
+}
+
 #[derive(Debug, Clone)]
 struct DaemonExecSpec {
     program: String,
@@ -101,7 +123,7 @@ fn resolve_home_dir() -> Result<PathBuf, String> {
 }
 
 fn resolve_user_app_data_dir() -> Result<PathBuf, String> {
-    let dir = resolve_home_dir()?.join(".cowork");
+    let dir = paths::app_data_dir()?;
     fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory {:?}: {}", dir, e))?;
     Ok(dir)
 }
@@ -213,6 +235,38 @@ fn resolve_node_binary() -> Result<String, String> {
 }
 
 fn resolve_daemon_exec_spec() -> Result<DaemonExecSpec, String> {
+    let spec = build_daemon_exec_spec()?;
+    argv::validate_argv(&spec.program, &spec.args)
+        .map_err(|e| format!("Invalid daemon command line: {}", e))?;
+    Ok(spec)
+}
+
+/// Flags whose value is a secret rather than a path to one.
+const SECRET_ARG_FLAGS: &[&str] = &["--token", "--auth-token", "--api-key"];
+const REDACTED: &str = "<redacted>";
+
+fn redact_daemon_args(args: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        if redact_next {
+            redacted.push(REDACTED.to_string());
+            redact_next = false;
+            continue;
+        }
+        if let Some((flag, _)) = arg.split_once('=') {
+            if SECRET_ARG_FLAGS.contains(&flag) {
+                redacted.push(format!("{}={}", flag, REDACTED));
+                continue;
+            }
+        }
+        redact_next = SECRET_ARG_FLAGS.contains(&arg.as_str());
+        redacted.push(arg.clone());
+    }
+    redacted
+}
+
+fn build_daemon_exec_spec() -> Result<DaemonExecSpec, String> {
     let app_data_dir = resolve_user_app_data_dir()?;
     let endpoint = resolve_daemon_endpoint(&app_data_dir);
     let token_file = resolve_daemon_token_path(&app_data_dir);
@@ -282,29 +336,80 @@ fn mode_state_path() -> Result<PathBuf, String> {
     Ok(resolve_user_app_data_dir()?.join(MODE_STATE_DIR).join(MODE_STATE_FILE))
 }
 
-fn load_saved_mode() -> ServiceMode {
-    let path = match mode_state_path() {
-        Ok(path) => path,
-        Err(_) => return ServiceMode::User,
+/// Set when a corrupted mode file was reset during this run, so
+/// `service_status` can tell the user their saved mode was lost.
+static MODE_REPAIR_NOTICE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+
+fn mode_repair_notice() -> std::sync::MutexGuard<'static, Option<String>> {
+    let lock = MODE_REPAIR_NOTICE.get_or_init(|| Mutex::new(None));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Move an unparseable mode file aside and write a clean default in its place.
+fn reset_corrupted_mode_file(path: &Path, reason: &str) -> ServiceMode {
+    log::warn!("Service mode file {:?} is corrupted: {}", path, reason);
+
+    let backup = path.with_extension(format!("json.corrupt-{}", now_ms()));
+    let mut notice = match fs::rename(path, &backup) {
+        Ok(()) => format!(
+            "Saved service mode was unreadable ({}); reset to user mode. The old file was backed up to {}.",
+            reason,
+            backup.to_string_lossy()
+        ),
+        Err(error) => {
+            log::warn!("Failed to back up corrupted service mode file {:?}: {}", path, error);
+            format!(
+                "Saved service mode was unreadable ({}); reset to user mode.",
+                reason
+            )
+        }
     };
 
-    if !path.exists() {
-        return ServiceMode::User;
+    if let Err(error) = write_mode_file(path, ServiceMode::User) {
+        log::warn!("Failed to rewrite service mode file: {}", error);
+        notice.push_str(&format!(" Rewriting the mode file failed: {}", error));
     }
+    *mode_repair_notice() = Some(notice);
+    ServiceMode::User
+}
 
-    let raw = match fs::read_to_string(&path) {
+fn load_saved_mode() -> ServiceMode {
+    match mode_state_path() {
+        Ok(path) => load_mode_file(&path),
+        Err(_) => ServiceMode::User,
+    }
+}
+
+fn load_mode_file(path: &Path) -> ServiceMode {
+    // A missing file just means the user never picked a mode.
+    let raw = match fs::read_to_string(path) {
         Ok(raw) => raw,
-        Err(_) => return ServiceMode::User,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return ServiceMode::User,
+        Err(error) => {
+            log::warn!("Failed to read service mode file {:?}: {}", path, error);
+            return ServiceMode::User;
+        }
     };
     let parsed: ServiceModeState = match serde_json::from_str(&raw) {
         Ok(parsed) => parsed,
-        Err(_) => return ServiceMode::User,
+        Err(error) => return reset_corrupted_mode_file(path, &error.to_string()),
     };
-    ServiceMode::parse(&parsed.mode).unwrap_or(ServiceMode::User)
+    match ServiceMode::parse(&parsed.mode) {
+        Ok(mode) => mode,
+        Err(error) => reset_corrupted_mode_file(path, &error),
+    }
 }
 
 fn save_mode(mode: ServiceMode) -> Result<ServiceModeState, String> {
-    let path = mode_state_path()?;
+    let state = write_mode_file(&mode_state_path()?, mode)?;
+    *mode_repair_notice() = None;
+    Ok(state)
+}
+
+fn write_mode_file(path: &Path, mode: ServiceMode) -> Result<ServiceModeState, String> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create service mode directory {:?}: {}", parent, e))?;
@@ -317,7 +422,7 @@ fn save_mode(mode: ServiceMode) -> Result<ServiceModeState, String> {
 
     let serialized = serde_json::to_string_pretty(&state)
         .map_err(|e| format!("Failed to serialize service mode state: {}", e))?;
-    fs::write(&path, serialized)
+    fs::write(path, serialized)
         .map_err(|e| format!("Failed to write service mode state {:?}: {}", path, e))?;
 
     Ok(state)
@@ -351,10 +456,22 @@ fn command_preview(program: &str, args: &[String]) -> String {
 }
 
 fn run_command(program: &str, args: &[String]) -> Result<Output, String> {
-    Command::new(program)
-        .args(args)
-        .output()
-        .map_err(|e| format!("Failed to run command `{}`: {}", command_preview(program, args), e))
+    let preview = command_preview(program, args);
+    log::debug!("Running `{}`", preview);
+    let output = Command::new(program).args(args).output().map_err(|e| {
+        let message = format!("Failed to run command `{}`: {}", preview, e);
+        log::warn!("{}", message);
+        message
+    })?;
+    if !output.status.success() {
+        log::debug!(
+            "`{}` exited with code {:?}: {}",
+            preview,
+            output.status.code(),
+            output_text(&output)
+        );
+    }
+    Ok(output)
 }
 
 #[allow(dead_code)]
@@ -588,7 +705,9 @@ fn stop_service_impl(mode: ServiceMode, _spec: &DaemonExecSpec) -> Result<(), St
 
 #[cfg(target_os = "macos")]
 fn restart_service_impl(mode: ServiceMode, spec: &DaemonExecSpec) -> Result<(), String> {
-    let _ = stop_service_impl(mode, spec);
+    if let Err(error) = stop_service_impl(mode, spec) {
+        log::warn!("Failed to stop service before restart: {}", error);
+    }
     start_service_impl(mode, spec)
 }
 
@@ -664,17 +783,12 @@ fn linux_systemctl_base_args(mode: ServiceMode) -> Vec<String> {
     }
 }
 
-#[cfg(target_os = "linux")]
-fn systemd_quote(value: &str) -> String {
-    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
-}
-
 #[cfg(target_os = "linux")]
 fn build_linux_unit(spec: &DaemonExecSpec, mode: ServiceMode) -> String {
     let mut exec_parts = Vec::with_capacity(spec.args.len() + 1);
-    exec_parts.push(systemd_quote(&spec.program));
+    exec_parts.push(argv::systemd_quote(&spec.program));
     for arg in &spec.args {
-        exec_parts.push(systemd_quote(arg));
+        exec_parts.push(argv::systemd_quote(arg));
     }
     let exec_start = exec_parts.join(" ");
     let wanted_by = match mode {
@@ -688,8 +802,8 @@ fn build_linux_unit(spec: &DaemonExecSpec, mode: ServiceMode) -> String {
         "[Unit]\nDescription={display}\nAfter=network.target\n\n[Service]\nType=simple\nExecStart={exec}\nWorkingDirectory={wd}\nRestart=always\nRestartSec=2\nEnvironment=COWORK_APP_DATA_DIR={app_data}\n\n[Install]\nWantedBy={wanted}\n",
         display = SERVICE_DISPLAY_NAME,
         exec = exec_start,
-        wd = systemd_quote(&working_dir),
-        app_data = systemd_quote(&app_data_dir),
+        wd = argv::systemd_quote(&working_dir),
+        app_data = argv::systemd_quote(&app_data_dir),
         wanted = wanted_by
     )
 }
@@ -822,61 +936,16 @@ fn service_status_impl(mode: ServiceMode, spec: &DaemonExecSpec) -> Result<Servi
     })
 }
 
-#[cfg(target_os = "windows")]
-fn windows_quote_arg(value: &str) -> String {
-    if value.is_empty() {
-        return "\"\"".to_string();
-    }
-
-    let needs_quotes = value
-        .chars()
-        .any(|ch| ch.is_ascii_whitespace() || ch == '"');
-    if !needs_quotes {
-        return value.to_string();
-    }
-
-    let mut escaped = String::new();
-    let mut backslashes = 0;
-    for ch in value.chars() {
-        if ch == '\\' {
-            backslashes += 1;
-            continue;
-        }
-
-        if ch == '"' {
-            escaped.push_str(&"\\".repeat(backslashes * 2 + 1));
-            escaped.push('"');
-            backslashes = 0;
-            continue;
-        }
-
-        if backslashes > 0 {
-            escaped.push_str(&"\\".repeat(backslashes));
-            backslashes = 0;
-        }
-        escaped.push(ch);
-    }
-
-    if backslashes > 0 {
-        escaped.push_str(&"\\".repeat(backslashes * 2));
-    }
-
-    format!("\"{}\"", escaped)
-}
-
-#[cfg(target_os = "windows")]
-fn windows_command_line(program: &str, args: &[String]) -> String {
-    let mut parts = Vec::with_capacity(args.len() + 1);
-    parts.push(windows_quote_arg(program));
-    for arg in args {
-        parts.push(windows_quote_arg(arg));
-    }
-    parts.join(" ")
-}
-
 #[cfg(target_os = "windows")]
 fn windows_task_install(spec: &DaemonExecSpec) -> Result<(), String> {
-    let command_line = windows_command_line(&spec.program, &spec.args);
+    let command_line = argv::windows_command_line(&spec.program, &spec.args)?;
+    let length = command_line.encode_utf16().count();
+    if length > WINDOWS_TASK_RUN_MAX_LEN {
+        return Err(format!(
+            "Daemon command line is {} characters, but Scheduled Tasks allow at most {}. Move the Cowork data directory to a shorter path.",
+            length, WINDOWS_TASK_RUN_MAX_LEN
+        ));
+    }
     let args = vec![
         "/Create".to_string(),
         "/F".to_string(),
@@ -1005,7 +1074,7 @@ fn windows_task_status(spec: &DaemonExecSpec) -> Result<ServiceStatus, String> {
 
 #[cfg(target_os = "windows")]
 fn windows_service_install(spec: &DaemonExecSpec) -> Result<(), String> {
-    let command_line = windows_command_line(&spec.program, &spec.args);
+    let command_line = argv::windows_command_line(&spec.program, &spec.args)?;
     let create_args = vec![
         "create".to_string(),
         WINDOWS_SYSTEM_SERVICE_NAME.to_string(),
@@ -1174,7 +1243,9 @@ fn stop_service_impl(mode: ServiceMode, _spec: &DaemonExecSpec) -> Result<(), St
 
 #[cfg(target_os = "windows")]
 fn restart_service_impl(mode: ServiceMode, spec: &DaemonExecSpec) -> Result<(), String> {
-    let _ = stop_service_impl(mode, spec);
+    if let Err(error) = stop_service_impl(mode, spec) {
+        log::warn!("Failed to stop service before restart: {}", error);
+    }
     start_service_impl(mode, spec)
 }
 
@@ -1250,7 +1321,29 @@ pub async fn service_set_mode(mode: String) -> Result<ServiceModeState, String>
 pub async fn service_status(mode: Option<String>) -> Result<ServiceStatus, String> {
     let parsed_mode = resolve_mode(mode)?;
     let spec = resolve_daemon_exec_spec()?;
-    service_status_impl(parsed_mode, &spec)
+    let mut status = service_status_impl(parsed_mode, &spec)?;
+    if let Some(notice) = mode_repair_notice().clone() {
+        status.details = Some(match status.details.take() {
+            Some(details) => format!("{}\n{}", notice, details),
+            None => notice,
+        });
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn agent_get_daemon_config() -> Result<DaemonConfig, String> {
+    let spec = resolve_daemon_exec_spec()?;
+    Ok(DaemonConfig {
+        program: spec.program.clone(),
+        args: redact_daemon_args(&spec.args),
+        app_data_dir: spec.app_data_dir.to_string_lossy().to_string(),
+        endpoint: spec.endpoint.clone(),
+        token_file: spec.token_file.to_string_lossy().to_string(),
+        token_file_exists: spec.token_file.exists(),
+        lock_file: spec.lock_file.to_string_lossy().to_string(),
+        saved_mode: load_saved_mode().as_str().to_string(),
+    })
 }
 
 #[tauri::command]
@@ -1258,7 +1351,9 @@ pub async fn service_install(mode: Option<String>) -> Result<ServiceStatus, Stri
     let parsed_mode = resolve_mode(mode)?;
     let spec = resolve_daemon_exec_spec()?;
     install_service_impl(parsed_mode, &spec)?;
-    let _ = save_mode(parsed_mode);
+    if let Err(error) = save_mode(parsed_mode) {
+        log::warn!("Service installed but mode was not saved: {}", error);
+    }
     service_status_impl(parsed_mode, &spec)
 }
 
@@ -1286,10 +1381,85 @@ pub async fn service_stop(mode: Option<String>) -> Result<ServiceStatus, String>
     service_status_impl(parsed_mode, &spec)
 }
 
+/// Restart the daemon service. Any generation in flight would be cut off, so
+/// this is refused while the agent is busy unless `force` is set.
 #[tauri::command]
-pub async fn service_restart(mode: Option<String>) -> Result<ServiceStatus, String> {
+pub async fn service_restart(
+    state: State<'_, AgentState>,
+    mode: Option<String>,
+    force: Option<bool>,
+) -> Result<ServiceStatus, String> {
+    ensure_agent_idle(
+        &state.manager,
+        None,
+        "restart the background service",
+        force.unwrap_or(false),
+    )
+    .await?;
     let parsed_mode = resolve_mode(mode)?;
     let spec = resolve_daemon_exec_spec()?;
     restart_service_impl(parsed_mode, &spec)?;
     service_status_impl(parsed_mode, &spec)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-service-mode-{}-{}-{}",
+            name,
+            std::process::id(),
+            now_ms()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_mode_file_defaults_to_user() {
+        let dir = mode_dir("missing");
+        let path = dir.join(MODE_STATE_FILE);
+        assert_eq!(load_mode_file(&path), ServiceMode::User);
+        assert!(!path.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn saved_mode_round_trips() {
+        let dir = mode_dir("saved");
+        let path = dir.join(MODE_STATE_FILE);
+        write_mode_file(&path, ServiceMode::System).unwrap();
+        assert_eq!(load_mode_file(&path), ServiceMode::System);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupted_mode_file_is_backed_up_and_reset() {
+        let dir = mode_dir("corrupt");
+        let path = dir.join(MODE_STATE_FILE);
+        fs::write(&path, "{\"mode\": \"sys").unwrap();
+
+        assert_eq!(load_mode_file(&path), ServiceMode::User);
+
+        let reset: ServiceModeState =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(reset.mode, "user");
+
+        let backups: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|entry| entry != &path)
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].to_string_lossy().contains("mode.json.corrupt-"));
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), "{\"mode\": \"sys");
+
+        let notice = mode_repair_notice().clone().unwrap();
+        assert!(notice.starts_with("Saved service mode was unreadable"));
+        assert!(notice.contains(&backups[0].to_string_lossy().to_string()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -3,8 +3,17 @@
 
 use crate::commands::agent::AgentState;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, State};
 
+/// How long a skill eligibility result is reused before asking the sidecar again.
+const ELIGIBILITY_CACHE_TTL: Duration = Duration::from_secs(30);
+/// How long a discovery result is reused; discovery walks every skill source.
+const DISCOVERY_CACHE_TTL: Duration = Duration::from_secs(15);
+const SKILL_SORT_KEYS: [&str; 3] = ["name", "source", "category"];
+
 // ============================================================================
 // Skill Types
 // ============================================================================
@@ -104,10 +113,577 @@ pub struct InstalledSkill {
     pub eligibility: SkillEligibility,
 }
 
+/// Front matter fields the editor and the agent both rely on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillContentMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub triggers: Vec<String>,
+    pub allowed_tools: Vec<String>,
+}
+
+/// A SKILL.md split into its parsed front matter and markdown body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillContent {
+    pub metadata: SkillContentMetadata,
+    pub body: String,
+    pub has_front_matter: bool,
+}
+
+/// Optional filtering and ordering for skill discovery.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillListOptions {
+    /// Case-insensitive match against id, name, description, category and tags
+    pub query: Option<String>,
+    /// Source type, e.g. "bundled", "managed" or "workspace"
+    pub source: Option<String>,
+    /// Only skills enabled by the last `agent_set_skills` sync
+    pub enabled_only: Option<bool>,
+    /// One of `SKILL_SORT_KEYS`; defaults to "name"
+    pub sort_by: Option<String>,
+    /// "asc" (default) or "desc"
+    pub order: Option<String>,
+    /// Skip the discovery cache
+    pub refresh: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSourceGroup {
+    pub source: String,
+    pub priority: i32,
+    pub skills: Vec<SkillManifest>,
+}
+
+/// Result of `agent_create_skill`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedSkill {
+    pub skill_id: String,
+    pub skill_path: Option<String>,
+    pub metadata: SkillContentMetadata,
+}
+
+/// Starter body for a new skill. `{{name}}` and `{{description}}` are filled
+/// in from the create request.
+struct SkillTemplate {
+    id: &'static str,
+    category: &'static str,
+    body: &'static str,
+}
+
+const SKILL_TEMPLATES: &[SkillTemplate] = &[
+    SkillTemplate {
+        id: "coding",
+        category: "development",
+        body: "# {{name}}\n\n{{description}}\n\n## When to use\n\n- Describe the coding tasks this skill applies to.\n\n## Steps\n\n1. Read the relevant files before changing anything.\n2. Make the smallest change that solves the task.\n3. Run the project's build and tests, and fix what fails.\n\n## Conventions\n\n- List naming, formatting, and review rules to follow.\n",
+    },
+    SkillTemplate {
+        id: "research",
+        category: "research",
+        body: "# {{name}}\n\n{{description}}\n\n## When to use\n\n- Describe the questions this skill helps answer.\n\n## Steps\n\n1. Restate the question and what a good answer looks like.\n2. Gather sources and note where each fact comes from.\n3. Summarize findings, flagging anything uncertain.\n\n## Output\n\n- Key findings with sources.\n- Open questions.\n",
+    },
+    SkillTemplate {
+        id: "messaging",
+        category: "productivity",
+        body: "# {{name}}\n\n{{description}}\n\n## When to use\n\n- Describe the messages or channels this skill handles.\n\n## Steps\n\n1. Identify the audience and the channel.\n2. Draft the message in the expected tone and length.\n3. Confirm with the user before sending anything.\n\n## Tone\n\n- Describe voice, formality, and formatting rules.\n",
+    },
+];
+
+fn find_skill_template(id: &str) -> Result<&'static SkillTemplate, String> {
+    SKILL_TEMPLATES
+        .iter()
+        .find(|template| template.id == id)
+        .ok_or_else(|| {
+            let known = SKILL_TEMPLATES
+                .iter()
+                .map(|template| template.id)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("Unknown skill template '{}'. Available: {}", id, known)
+        })
+}
+
+// ============================================================================
+// Front Matter Parsing
+// ============================================================================
+
+/// Value of a top-level front matter key. Nested maps are recognised so they
+/// can be skipped, but their contents are not parsed.
+#[derive(Debug, Clone, PartialEq)]
+enum FrontMatterValue {
+    Empty,
+    Scalar(String),
+    List(Vec<String>),
+    Nested,
+}
+
+/// Split `---` delimited front matter from the body. Content without an
+/// opening delimiter has no front matter.
+fn split_front_matter(content: &str) -> Result<Option<(String, String)>, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("---") {
+        return Ok(None);
+    }
+
+    let mut yaml = Vec::new();
+    for line in lines.by_ref() {
+        if line.trim() == "---" {
+            let body = lines.collect::<Vec<_>>().join("\n");
+            return Ok(Some((yaml.join("\n"), body.trim().to_string())));
+        }
+        yaml.push(line);
+    }
+    Err("Skill front matter is missing its closing '---'".to_string())
+}
+
+fn unquote_front_matter(value: &str, line_no: usize) -> Result<String, String> {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if value.starts_with(quote) {
+            if value.len() < 2 || !value.ends_with(quote) {
+                return Err(format!(
+                    "Unterminated quoted value on front matter line {}",
+                    line_no
+                ));
+            }
+            return Ok(value[1..value.len() - 1].to_string());
+        }
+    }
+    Ok(value.to_string())
+}
+
+fn brace_balance(line: &str) -> i64 {
+    line.chars().fold(0, |depth, ch| match ch {
+        '{' => depth + 1,
+        '}' => depth - 1,
+        _ => depth,
+    })
+}
+
+/// Parse the flat subset of YAML skills use: `key: value`, quoted scalars,
+/// inline `[a, b]` lists, `- item` block lists, and nested or JSON blocks
+/// (skipped). Line numbers in errors count from the opening `---`.
+fn parse_front_matter(yaml: &str) -> Result<Vec<(String, FrontMatterValue)>, String> {
+    let mut fields: Vec<(String, FrontMatterValue)> = Vec::new();
+    let mut open_key = false;
+    let mut json_depth = 0i64;
+
+    for (index, line) in yaml.lines().enumerate() {
+        let line_no = index + 2;
+        let trimmed = line.trim();
+
+        if json_depth > 0 {
+            json_depth += brace_balance(line);
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if indented || trimmed.starts_with('-') {
+            let Some((key, value)) = fields.last_mut().filter(|_| open_key) else {
+                return Err(format!(
+                    "Unexpected indented line {} in skill front matter",
+                    line_no
+                ));
+            };
+            match (trimmed.strip_prefix('-'), &mut *value) {
+                (Some(item), FrontMatterValue::Empty) => {
+                    *value = FrontMatterValue::List(vec![unquote_front_matter(item, line_no)?]);
+                }
+                (Some(item), FrontMatterValue::List(items)) => {
+                    items.push(unquote_front_matter(item, line_no)?);
+                }
+                (None, FrontMatterValue::Empty | FrontMatterValue::Nested) => {
+                    *value = FrontMatterValue::Nested;
+                }
+                _ => {
+                    return Err(format!(
+                        "Front matter key '{}' mixes list items and fields (line {})",
+                        key, line_no
+                    ));
+                }
+            }
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else {
+            return Err(format!(
+                "Expected 'key: value' on front matter line {}",
+                line_no
+            ));
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid front matter key '{}' on line {}",
+                key, line_no
+            ));
+        }
+        if fields.iter().any(|(existing, _)| existing == key) {
+            return Err(format!(
+                "Duplicate front matter key '{}' on line {}",
+                key, line_no
+            ));
+        }
+
+        let value = value.trim();
+        open_key = value.is_empty();
+        let parsed = if value.is_empty() {
+            FrontMatterValue::Empty
+        } else if value.starts_with('{') {
+            json_depth = brace_balance(value);
+            FrontMatterValue::Nested
+        } else if let Some(inner) = value.strip_prefix('[') {
+            let Some(inner) = inner.strip_suffix(']') else {
+                return Err(format!(
+                    "Unterminated list for '{}' on front matter line {}",
+                    key, line_no
+                ));
+            };
+            let items = inner
+                .split(',')
+                .filter(|item| !item.trim().is_empty())
+                .map(|item| unquote_front_matter(item, line_no))
+                .collect::<Result<Vec<_>, _>>()?;
+            FrontMatterValue::List(items)
+        } else {
+            FrontMatterValue::Scalar(unquote_front_matter(value, line_no)?)
+        };
+        fields.push((key.to_string(), parsed));
+    }
+
+    if json_depth > 0 {
+        return Err("Unterminated '{' block in skill front matter".to_string());
+    }
+    Ok(fields)
+}
+
+/// Read a list field, accepting a YAML list or a delimited string.
+fn front_matter_list(value: &FrontMatterValue, separators: &[char]) -> Vec<String> {
+    let items = match value {
+        FrontMatterValue::List(items) => items.clone(),
+        FrontMatterValue::Scalar(text) => text
+            .split(|c: char| separators.contains(&c))
+            .map(str::to_string)
+            .collect(),
+        FrontMatterValue::Empty | FrontMatterValue::Nested => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn parse_skill_content(content: &str) -> Result<SkillContent, String> {
+    let Some((yaml, body)) = split_front_matter(content)? else {
+        return Ok(SkillContent {
+            metadata: SkillContentMetadata::default(),
+            body: content.trim().to_string(),
+            has_front_matter: false,
+        });
+    };
+
+    let mut metadata = SkillContentMetadata::default();
+    for (key, value) in parse_front_matter(&yaml)? {
+        match (key.as_str(), value) {
+            ("name", FrontMatterValue::Scalar(name)) => metadata.name = Some(name),
+            ("description", FrontMatterValue::Scalar(description)) => {
+                metadata.description = Some(description)
+            }
+            ("name" | "description", _) => {
+                return Err(format!("Front matter '{}' must be a string", key));
+            }
+            ("triggers", value) => metadata.triggers = front_matter_list(&value, &[',']),
+            ("allowed-tools" | "allowed_tools" | "allowedTools", value) => {
+                metadata.allowed_tools = front_matter_list(&value, &[',', ' '])
+            }
+            _ => {}
+        }
+    }
+
+    Ok(SkillContent {
+        metadata,
+        body,
+        has_front_matter: true,
+    })
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Eligibility results keyed by skill id. Installing, uninstalling or
+/// re-syncing skills clears it, since any of those can change the answer.
+static ELIGIBILITY_CACHE: OnceLock<Mutex<HashMap<String, (Instant, SkillEligibility)>>> =
+    OnceLock::new();
+
+fn eligibility_cache() -> MutexGuard<'static, HashMap<String, (Instant, SkillEligibility)>> {
+    let lock = ELIGIBILITY_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn cached_eligibility(skill_id: &str) -> Option<SkillEligibility> {
+    let mut cache = eligibility_cache();
+    match cache.get(skill_id) {
+        Some((checked_at, eligibility)) if checked_at.elapsed() < ELIGIBILITY_CACHE_TTL => {
+            Some(eligibility.clone())
+        }
+        Some(_) => {
+            cache.remove(skill_id);
+            None
+        }
+        None => None,
+    }
+}
+
+fn cache_eligibility(skill_id: &str, eligibility: &SkillEligibility) {
+    eligibility_cache().insert(skill_id.to_string(), (Instant::now(), eligibility.clone()));
+}
+
+/// Split `skill_ids` into cached results and the distinct ids still to check.
+fn split_cached_eligibility(
+    skill_ids: Vec<String>,
+) -> (HashMap<String, SkillEligibility>, Vec<String>) {
+    let mut cached = HashMap::new();
+    let mut missing = Vec::new();
+    for skill_id in skill_ids {
+        if cached.contains_key(&skill_id) || missing.contains(&skill_id) {
+            continue;
+        }
+        match cached_eligibility(&skill_id) {
+            Some(eligibility) => {
+                cached.insert(skill_id, eligibility);
+            }
+            None => missing.push(skill_id),
+        }
+    }
+    (cached, missing)
+}
+
+pub(crate) fn invalidate_skill_eligibility_cache() {
+    eligibility_cache().clear();
+}
+
+type DiscoveryCache = HashMap<Option<String>, (Instant, Vec<SkillManifest>)>;
+
+/// Discovery results keyed by working directory, cleared whenever skills are
+/// installed, uninstalled or created.
+static DISCOVERY_CACHE: OnceLock<Mutex<DiscoveryCache>> = OnceLock::new();
+
+fn discovery_cache() -> MutexGuard<'static, DiscoveryCache> {
+    let lock = DISCOVERY_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn invalidate_discovery_cache() {
+    discovery_cache().clear();
+}
+
+/// Ids enabled by the last `agent_set_skills` sync; `None` until the first sync.
+static ENABLED_SKILLS: OnceLock<Mutex<Option<HashSet<String>>>> = OnceLock::new();
+
+fn enabled_skills() -> MutexGuard<'static, Option<HashSet<String>>> {
+    let lock = ENABLED_SKILLS.get_or_init(|| Mutex::new(None));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+pub(crate) fn record_enabled_skills<'a>(enabled: impl Iterator<Item = &'a str>) {
+    *enabled_skills() = Some(enabled.map(str::to_string).collect());
+}
+
+fn skill_matches_query(skill: &SkillManifest, query: &str) -> bool {
+    let frontmatter = &skill.frontmatter;
+    let metadata = frontmatter.metadata.as_ref();
+    let mut fields = vec![
+        skill.id.as_str(),
+        frontmatter.name.as_str(),
+        frontmatter.description.as_str(),
+    ];
+    if let Some(category) = metadata.and_then(|m| m.category.as_deref()) {
+        fields.push(category);
+    }
+    if let Some(tags) = metadata.and_then(|m| m.tags.as_ref()) {
+        fields.extend(tags.iter().map(String::as_str));
+    }
+    fields
+        .iter()
+        .any(|field| field.to_lowercase().contains(query))
+}
+
+/// Apply query, source and enabled filters, then sort. `enabled_only` is
+/// ignored until skills have been synced once, since nothing is known to be
+/// enabled before that.
+fn filter_and_sort_skills(
+    mut skills: Vec<SkillManifest>,
+    options: &SkillListOptions,
+) -> Result<Vec<SkillManifest>, String> {
+    let sort_by = options.sort_by.as_deref().unwrap_or("name");
+    if !SKILL_SORT_KEYS.contains(&sort_by) {
+        return Err(format!(
+            "Invalid sort key: {}. Expected one of: {}",
+            sort_by,
+            SKILL_SORT_KEYS.join(", ")
+        ));
+    }
+    let descending = match options.order.as_deref().unwrap_or("asc") {
+        "asc" => false,
+        "desc" => true,
+        other => {
+            return Err(format!(
+                "Invalid sort order: {}. Expected one of: asc, desc",
+                other
+            ))
+        }
+    };
+
+    let query = options
+        .query
+        .as_deref()
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+    if let Some(query) = &query {
+        skills.retain(|skill| skill_matches_query(skill, query));
+    }
+    if let Some(source) = options.source.as_deref().filter(|s| !s.is_empty()) {
+        skills.retain(|skill| skill.source.source_type.eq_ignore_ascii_case(source));
+    }
+    if options.enabled_only.unwrap_or(false) {
+        match enabled_skills().as_ref() {
+            Some(enabled) => skills.retain(|skill| enabled.contains(&skill.id)),
+            None => log::debug!("enabledOnly ignored: skills have not been synced yet"),
+        }
+    }
+
+    let name = |skill: &SkillManifest| skill.frontmatter.name.to_lowercase();
+    let category = |skill: &SkillManifest| {
+        skill
+            .frontmatter
+            .metadata
+            .as_ref()
+            .and_then(|m| m.category.as_deref())
+            .unwrap_or("")
+            .to_lowercase()
+    };
+    skills.sort_by(|a, b| {
+        let primary = match sort_by {
+            "source" => a
+                .source
+                .priority
+                .cmp(&b.source.priority)
+                .then_with(|| name(a).cmp(&name(b))),
+            "category" => category(a)
+                .cmp(&category(b))
+                .then_with(|| name(a).cmp(&name(b))),
+            _ => name(a).cmp(&name(b)),
+        };
+        let primary = if descending {
+            primary.reverse()
+        } else {
+            primary
+        };
+        primary.then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(skills)
+}
+
+/// Group skills by source type, keeping each group's order and ordering
+/// groups by source priority.
+fn group_skills_by_source(skills: Vec<SkillManifest>) -> Vec<SkillSourceGroup> {
+    let mut groups: Vec<SkillSourceGroup> = Vec::new();
+    for skill in skills {
+        match groups
+            .iter_mut()
+            .find(|group| group.source == skill.source.source_type)
+        {
+            Some(group) => group.skills.push(skill),
+            None => groups.push(SkillSourceGroup {
+                source: skill.source.source_type.clone(),
+                priority: skill.source.priority,
+                skills: vec![skill],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    groups
+}
+
+async fn discover_skills(
+    state: &AgentState,
+    working_directory: &Option<String>,
+    refresh: bool,
+) -> Result<Vec<SkillManifest>, String> {
+    if !refresh {
+        if let Some((discovered_at, skills)) = discovery_cache().get(working_directory) {
+            if discovered_at.elapsed() < DISCOVERY_CACHE_TTL {
+                return Ok(skills.clone());
+            }
+        }
+    }
+
+    let params = serde_json::json!({
+        "workingDirectory": working_directory,
+    });
+
+    let result = state
+        .manager
+        .send_command("discover_skills", params)
+        .await?;
+
+    // Parse the skills array from the result
+    let skills = result
+        .get("skills")
+        .and_then(|s| s.as_array())
+        .ok_or("Invalid response format: missing skills array")?;
+
+    let skills: Vec<SkillManifest> =
+        serde_json::from_value(serde_json::Value::Array(skills.clone()))
+            .map_err(|e| format!("Failed to parse skills: {}", e))?;
+    discovery_cache().insert(working_directory.clone(), (Instant::now(), skills.clone()));
+    Ok(skills)
+}
+
+async fn fetch_skill_content(state: &AgentState, skill_id: &str) -> Result<String, String> {
+    let params = serde_json::json!({
+        "skillId": skill_id,
+    });
+
+    let result = state
+        .manager
+        .send_command("get_skill_content", params)
+        .await?;
+    let content = result
+        .get("content")
+        .and_then(|c| c.as_str())
+        .ok_or("Invalid response format: missing content")?;
+
+    Ok(content.to_string())
+}
+
 /// Ensure sidecar is started (reuse from agent module)
 async fn ensure_sidecar(
     app: &AppHandle,
@@ -126,25 +702,31 @@ pub async fn agent_discover_skills(
     app: AppHandle,
     state: State<'_, AgentState>,
     working_directory: Option<String>,
+    options: Option<SkillListOptions>,
 ) -> Result<Vec<SkillManifest>, String> {
+    let options = options.unwrap_or_default();
     ensure_sidecar(&app, &state).await?;
 
-    let manager = &state.manager;
-    let params = serde_json::json!({
-        "workingDirectory": working_directory,
-    });
-
-    let result = manager.send_command("discover_skills", params).await?;
+    let skills =
+        discover_skills(&state, &working_directory, options.refresh.unwrap_or(false)).await?;
+    filter_and_sort_skills(skills, &options)
+}
 
-    // Parse the skills array from the result
-    let response: serde_json::Value = result;
-    let skills = response
-        .get("skills")
-        .and_then(|s| s.as_array())
-        .ok_or("Invalid response format: missing skills array")?;
+/// Discover skills grouped by source, with the same filters as `agent_discover_skills`
+#[tauri::command]
+pub async fn agent_discover_skills_grouped(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    working_directory: Option<String>,
+    options: Option<SkillListOptions>,
+) -> Result<Vec<SkillSourceGroup>, String> {
+    let options = options.unwrap_or_default();
+    ensure_sidecar(&app, &state).await?;
 
-    serde_json::from_value(serde_json::Value::Array(skills.clone()))
-        .map_err(|e| format!("Failed to parse skills: {}", e))
+    let skills =
+        discover_skills(&state, &working_directory, options.refresh.unwrap_or(false)).await?;
+    let skills = filter_and_sort_skills(skills, &options)?;
+    Ok(group_skills_by_source(skills))
 }
 
 /// Install a skill from marketplace to managed directory
@@ -162,6 +744,8 @@ pub async fn agent_install_skill(
     });
 
     manager.send_command("install_skill", params).await?;
+    invalidate_skill_eligibility_cache();
+    invalidate_discovery_cache();
     Ok(())
 }
 
@@ -180,6 +764,8 @@ pub async fn agent_uninstall_skill(
     });
 
     manager.send_command("uninstall_skill", params).await?;
+    invalidate_skill_eligibility_cache();
+    invalidate_discovery_cache();
     Ok(())
 }
 
@@ -190,6 +776,9 @@ pub async fn agent_check_skill_eligibility(
     state: State<'_, AgentState>,
     skill_id: String,
 ) -> Result<SkillEligibility, String> {
+    if let Some(eligibility) = cached_eligibility(&skill_id) {
+        return Ok(eligibility);
+    }
     ensure_sidecar(&app, &state).await?;
 
     let manager = &state.manager;
@@ -198,33 +787,83 @@ pub async fn agent_check_skill_eligibility(
     });
 
     let result = manager.send_command("check_skill_eligibility", params).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse eligibility: {}", e))
+    let eligibility: SkillEligibility = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse eligibility: {}", e))?;
+    cache_eligibility(&skill_id, &eligibility);
+    Ok(eligibility)
 }
 
-/// Get skill content for display
+/// Check eligibility for several skills in one round-trip. Skills the sidecar
+/// cannot find are left out of the result.
 #[tauri::command]
-pub async fn agent_get_skill_content(
+pub async fn agent_check_skills_eligibility(
     app: AppHandle,
     state: State<'_, AgentState>,
-    skill_id: String,
-) -> Result<String, String> {
+    skill_ids: Vec<String>,
+) -> Result<HashMap<String, SkillEligibility>, String> {
+    let (mut results, missing) = split_cached_eligibility(skill_ids);
+    if missing.is_empty() {
+        return Ok(results);
+    }
+
     ensure_sidecar(&app, &state).await?;
 
     let manager = &state.manager;
     let params = serde_json::json!({
-        "skillId": skill_id,
+        "skillIds": missing,
     });
 
-    let result = manager.send_command("get_skill_content", params).await?;
-    let content = result
-        .get("content")
-        .and_then(|c| c.as_str())
-        .ok_or("Invalid response format: missing content")?;
+    let result = manager
+        .send_command("check_skills_eligibility", params)
+        .await?;
+    let checked: HashMap<String, SkillEligibility> = serde_json::from_value(
+        result
+            .get("results")
+            .cloned()
+            .unwrap_or(serde_json::json!({})),
+    )
+    .map_err(|e| format!("Failed to parse eligibility: {}", e))?;
+    if let Some(errors) = result.get("errors").and_then(|v| v.as_object()) {
+        for (skill_id, error) in errors {
+            log::warn!("Failed to check eligibility for {}: {}", skill_id, error);
+        }
+    }
 
-    Ok(content.to_string())
+    for (skill_id, eligibility) in checked {
+        cache_eligibility(&skill_id, &eligibility);
+        results.insert(skill_id, eligibility);
+    }
+    Ok(results)
+}
+
+/// Get skill content for display
+#[tauri::command]
+pub async fn agent_get_skill_content(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    skill_id: String,
+) -> Result<String, String> {
+    ensure_sidecar(&app, &state).await?;
+    fetch_skill_content(&state, &skill_id).await
+}
+
+/// Get skill content with its front matter parsed into typed metadata
+#[tauri::command]
+pub async fn agent_get_parsed_skill_content(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    skill_id: String,
+) -> Result<SkillContent, String> {
+    ensure_sidecar(&app, &state).await?;
+    let content = fetch_skill_content(&state, &skill_id).await?;
+    parse_skill_content(&content).map_err(|e| format!("Skill {}: {}", skill_id, e))
 }
 
 /// Create a new custom skill
+///
+/// With a `template`, an empty `content` is filled with that template's
+/// starter body and its category is used when none is given. The scaffolded
+/// file is parsed before anything is written.
 #[tauri::command]
 pub async fn agent_create_skill(
     app: AppHandle,
@@ -233,9 +872,39 @@ pub async fn agent_create_skill(
     description: String,
     emoji: Option<String>,
     category: Option<String>,
-    content: String,
+    content: Option<String>,
     requirements: Option<serde_json::Value>,
-) -> Result<String, String> {
+    template: Option<String>,
+) -> Result<CreatedSkill, String> {
+    let template = template
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(find_skill_template)
+        .transpose()?;
+    let content = content.unwrap_or_default();
+    let body = match template {
+        Some(template) if content.trim().is_empty() => template
+            .body
+            .replace("{{name}}", &name)
+            .replace("{{description}}", description.trim()),
+        _ => content,
+    };
+    let category = category.or_else(|| template.map(|t| t.category.to_string()));
+
+    // Same front matter the sidecar writes; make sure the result parses.
+    let scaffold = format!(
+        "---\nname: {}\ndescription: \"{}\"\n---\n\n{}",
+        name,
+        description.replace('"', "\\\""),
+        body
+    );
+    let parsed = parse_skill_content(&scaffold)
+        .map_err(|e| format!("Skill '{}' would not parse: {}", name, e))?;
+    if parsed.body.is_empty() {
+        return Err("Skill content is required (or choose a template)".to_string());
+    }
+
     ensure_sidecar(&app, &state).await?;
 
     let manager = &state.manager;
@@ -244,19 +913,40 @@ pub async fn agent_create_skill(
         "description": description,
         "emoji": emoji,
         "category": category,
-        "content": content,
+        "content": body,
         "requirements": requirements,
     });
 
     let result = manager.send_command("create_skill", params).await?;
+    invalidate_discovery_cache();
 
     let skill_id = result
         .get("skillId")
         .and_then(|s| s.as_str())
         .ok_or("Invalid response: missing skillId")?
         .to_string();
+    let skill_path = result
+        .get("skillPath")
+        .and_then(|s| s.as_str())
+        .map(|s| s.to_string());
+
+    let metadata = match fetch_skill_content(&state, &skill_id).await {
+        Ok(content) => {
+            parse_skill_content(&content)
+                .map_err(|e| format!("Created skill {} does not parse: {}", skill_id, e))?
+                .metadata
+        }
+        Err(err) => {
+            log::warn!("Could not read back created skill {}: {}", skill_id, err);
+            parsed.metadata
+        }
+    };
 
-    Ok(skill_id)
+    Ok(CreatedSkill {
+        skill_id,
+        skill_path,
+        metadata,
+    })
 }
 
 #[tauri::command]
@@ -304,7 +994,11 @@ pub async fn agent_create_skill_from_session(
         "maxSkills": max_skills,
     });
 
-    manager.send_command("create_skill_from_session", params).await
+    let result = manager
+        .send_command("create_skill_from_session", params)
+        .await?;
+    invalidate_discovery_cache();
+    Ok(result)
 }
 
 #[tauri::command]
@@ -315,7 +1009,304 @@ pub async fn agent_ensure_default_skill_creator_installed(
     ensure_sidecar(&app, &state).await?;
 
     let manager = &state.manager;
-    manager
+    let result = manager
         .send_command("ensure_default_skill_creator_installed", serde_json::json!({}))
-        .await
+        .await?;
+    invalidate_discovery_cache();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eligibility(eligible: bool) -> SkillEligibility {
+        SkillEligibility {
+            eligible,
+            missing_bins: if eligible {
+                Vec::new()
+            } else {
+                vec!["gh".to_string()]
+            },
+            missing_env_vars: Vec::new(),
+            platform_mismatch: false,
+            install_hints: Vec::new(),
+            found_bins: None,
+        }
+    }
+
+    // The cache is process-wide, so every eligibility case runs in this one test.
+    #[test]
+    fn eligibility_cache_hits_expires_and_invalidates() {
+        cache_eligibility("github", &eligibility(false));
+        cache_eligibility("notes", &eligibility(true));
+
+        let (cached, missing) = split_cached_eligibility(vec![
+            "github".to_string(),
+            "weather".to_string(),
+            "github".to_string(),
+            "weather".to_string(),
+        ]);
+        assert_eq!(cached.len(), 1);
+        assert!(!cached["github"].eligible);
+        assert_eq!(missing, vec!["weather"]);
+
+        if let Some(stale) = Instant::now().checked_sub(ELIGIBILITY_CACHE_TTL) {
+            eligibility_cache().insert("stale".to_string(), (stale, eligibility(true)));
+            assert!(cached_eligibility("stale").is_none());
+            assert!(!eligibility_cache().contains_key("stale"));
+        }
+
+        invalidate_skill_eligibility_cache();
+        assert!(cached_eligibility("github").is_none());
+        let (cached, missing) =
+            split_cached_eligibility(vec!["notes".to_string(), "github".to_string()]);
+        assert!(cached.is_empty());
+        assert_eq!(missing, vec!["notes", "github"]);
+    }
+
+    #[test]
+    fn skill_front_matter_is_parsed() {
+        let content = "\u{feff}---\nname: pdf-tools\ndescription: \"Fill: and merge PDFs\"\ntriggers:\n  - pdf\n  - 'merge files'\nallowed-tools: Read, Bash(pdftk:*) Write\nmetadata:\n  author: me\n  tags: [a, b]\nextra: {\"x\": {\n  \"y\": 1}}\n---\n\n# PDF tools\n\nUse pdftk.\n";
+        let parsed = parse_skill_content(content).unwrap();
+        assert!(parsed.has_front_matter);
+        assert_eq!(parsed.metadata.name.as_deref(), Some("pdf-tools"));
+        assert_eq!(
+            parsed.metadata.description.as_deref(),
+            Some("Fill: and merge PDFs")
+        );
+        assert_eq!(parsed.metadata.triggers, vec!["pdf", "merge files"]);
+        assert_eq!(
+            parsed.metadata.allowed_tools,
+            vec!["Read", "Bash(pdftk:*)", "Write"]
+        );
+        assert_eq!(parsed.body, "# PDF tools\n\nUse pdftk.");
+    }
+
+    #[test]
+    fn inline_lists_and_missing_front_matter() {
+        let parsed =
+            parse_skill_content("---\ntriggers: [deploy, \"ship it\", ]\n---\nbody").unwrap();
+        assert_eq!(parsed.metadata.triggers, vec!["deploy", "ship it"]);
+        assert_eq!(parsed.metadata.name, None);
+
+        let plain = parse_skill_content("\n# Just markdown\n").unwrap();
+        assert!(!plain.has_front_matter);
+        assert_eq!(plain.body, "# Just markdown");
+    }
+
+    #[test]
+    fn malformed_front_matter_is_rejected() {
+        let error = |content: &str| parse_skill_content(content).unwrap_err();
+
+        assert_eq!(
+            error("---\nname: x\n# body"),
+            "Skill front matter is missing its closing '---'"
+        );
+        assert_eq!(
+            error("---\nname: \"x\n---\n"),
+            "Unterminated quoted value on front matter line 2"
+        );
+        assert_eq!(
+            error("---\nname: a\nname: b\n---\n"),
+            "Duplicate front matter key 'name' on line 3"
+        );
+        assert_eq!(
+            error("---\nbad key: a\n---\n"),
+            "Invalid front matter key 'bad key' on line 2"
+        );
+        assert_eq!(
+            error("---\njust text\n---\n"),
+            "Expected 'key: value' on front matter line 2"
+        );
+        assert_eq!(
+            error("---\n  indented: a\n---\n"),
+            "Unexpected indented line 2 in skill front matter"
+        );
+        assert_eq!(
+            error("---\nname: a\n- item\n---\n"),
+            "Unexpected indented line 3 in skill front matter"
+        );
+        assert_eq!(
+            error("---\ntriggers:\n  - a\n  key: b\n---\n"),
+            "Front matter key 'triggers' mixes list items and fields (line 4)"
+        );
+        assert_eq!(
+            error("---\ntriggers: [a, b\n---\n"),
+            "Unterminated list for 'triggers' on front matter line 2"
+        );
+        assert_eq!(
+            error("---\nextra: {\"a\": 1\n---\n"),
+            "Unterminated '{' block in skill front matter"
+        );
+        assert_eq!(
+            error("---\nname: [a, b]\n---\n"),
+            "Front matter 'name' must be a string"
+        );
+    }
+
+    fn manifest(
+        id: &str,
+        name: &str,
+        source: (&str, i32),
+        category: Option<&str>,
+    ) -> SkillManifest {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "source": { "type": source.0, "path": format!("/skills/{}", source.0), "priority": source.1 },
+            "frontmatter": {
+                "name": name,
+                "description": format!("{} skill", name),
+                "metadata": { "category": category, "tags": ["docs"] }
+            },
+            "skillPath": format!("/skills/{}/{}", source.0, id),
+            "hasScripts": false,
+            "hasReferences": false,
+            "hasAssets": false
+        }))
+        .unwrap()
+    }
+
+    fn catalog() -> Vec<SkillManifest> {
+        vec![
+            manifest("w-pdf", "pdf", ("workspace", 1), Some("documents")),
+            manifest("b-git", "Git", ("bundled", 3), Some("dev")),
+            manifest("m-pdf", "PDF", ("managed", 2), Some("documents")),
+            manifest("b-web", "web", ("bundled", 3), None),
+        ]
+    }
+
+    fn listed(options: SkillListOptions) -> Vec<String> {
+        filter_and_sort_skills(catalog(), &options)
+            .unwrap()
+            .into_iter()
+            .map(|skill| skill.id)
+            .collect()
+    }
+
+    #[test]
+    fn skills_default_to_name_order_with_id_ties() {
+        assert_eq!(
+            listed(SkillListOptions::default()),
+            vec!["b-git", "m-pdf", "w-pdf", "b-web"]
+        );
+        assert_eq!(
+            listed(SkillListOptions {
+                order: Some("desc".into()),
+                ..Default::default()
+            }),
+            vec!["b-web", "m-pdf", "w-pdf", "b-git"]
+        );
+    }
+
+    #[test]
+    fn skills_sort_by_source_and_category() {
+        assert_eq!(
+            listed(SkillListOptions {
+                sort_by: Some("source".into()),
+                ..Default::default()
+            }),
+            vec!["w-pdf", "m-pdf", "b-git", "b-web"]
+        );
+        assert_eq!(
+            listed(SkillListOptions {
+                sort_by: Some("category".into()),
+                ..Default::default()
+            }),
+            vec!["b-web", "b-git", "m-pdf", "w-pdf"]
+        );
+    }
+
+    #[test]
+    fn skills_filter_by_query_and_source() {
+        assert_eq!(
+            listed(SkillListOptions {
+                query: Some("  PDF ".into()),
+                ..Default::default()
+            }),
+            vec!["m-pdf", "w-pdf"]
+        );
+        assert_eq!(
+            listed(SkillListOptions {
+                query: Some("dev".into()),
+                ..Default::default()
+            }),
+            vec!["b-git"]
+        );
+        assert_eq!(
+            listed(SkillListOptions {
+                query: Some("   ".into()),
+                source: Some("Bundled".into()),
+                ..Default::default()
+            }),
+            vec!["b-git", "b-web"]
+        );
+    }
+
+    // Enabled skills are process-wide, so both states are checked here.
+    #[test]
+    fn enabled_only_is_ignored_until_skills_sync() {
+        let options = SkillListOptions {
+            enabled_only: Some(true),
+            ..Default::default()
+        };
+        *enabled_skills() = None;
+        assert_eq!(listed(options.clone()).len(), 4);
+
+        record_enabled_skills(["w-pdf", "b-web"].into_iter());
+        assert_eq!(listed(options), vec!["w-pdf", "b-web"]);
+        *enabled_skills() = None;
+    }
+
+    #[test]
+    fn invalid_skill_sort_options_are_rejected() {
+        let error = filter_and_sort_skills(
+            catalog(),
+            &SkillListOptions {
+                sort_by: Some("rating".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "Invalid sort key: rating. Expected one of: name, source, category"
+        );
+        let error = filter_and_sort_skills(
+            catalog(),
+            &SkillListOptions {
+                order: Some("up".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error, "Invalid sort order: up. Expected one of: asc, desc");
+    }
+
+    #[test]
+    fn skills_group_by_source_priority() {
+        let groups = group_skills_by_source(
+            filter_and_sort_skills(catalog(), &SkillListOptions::default()).unwrap(),
+        );
+        let summary: Vec<(String, Vec<String>)> = groups
+            .into_iter()
+            .map(|group| {
+                (
+                    group.source,
+                    group.skills.into_iter().map(|skill| skill.id).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("workspace".to_string(), vec!["w-pdf".to_string()]),
+                ("managed".to_string(), vec!["m-pdf".to_string()]),
+                (
+                    "bundled".to_string(),
+                    vec!["b-git".to_string(), "b-web".to_string()]
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,811 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
+use crate::commands::credentials::credential_storage_paths;
+use crate::paths;
+use crate::sidecar;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+#[cfg(unix)]
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+// ============================================================================
+// Storage Diagnostics Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageCheckStatus {
+    Ok,
+    /// A problem was found and fixed, e.g. a missing directory was created.
+    Repaired,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCheck {
+    pub id: String,
+    pub label: String,
+    pub path: String,
+    pub kind: String, // "directory" | "file"
+    pub status: StorageCheckStatus,
+    pub exists: bool,
+    pub writable: bool,
+    /// Unix only: whether the path belongs to the user running the app.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owned_by_current_user: Option<bool>,
+    /// Unix only: permission bits in octal, e.g. "0700".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    pub repairs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub healthy: bool,
+    pub checks: Vec<StorageCheck>,
+}
+
+// ============================================================================
+// Data Directory Migration Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigratedItem {
+    pub name: String,
+    pub kind: String, // "directory" | "file"
+    pub method: String, // "renamed" | "copied"
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirMigration {
+    pub from: String,
+    pub to: String,
+    pub items: Vec<MigratedItem>,
+    /// Daemon sockets and locks left behind; the daemon recreates them.
+    pub skipped: Vec<String>,
+    pub bytes_moved: u64,
+    pub transport_restarted: bool,
+}
+
+impl StorageCheck {
+    fn new(id: &str, label: &str, path: &Path, kind: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            path: path.to_string_lossy().to_string(),
+            kind: kind.to_string(),
+            status: StorageCheckStatus::Ok,
+            exists: false,
+            writable: false,
+            owned_by_current_user: None,
+            mode: None,
+            repairs: Vec::new(),
+            message: None,
+        }
+    }
+
+    fn fail(&mut self, message: String) {
+        self.status = StorageCheckStatus::Error;
+        self.message = Some(message);
+    }
+
+    fn repaired(&mut self, repair: String) {
+        if self.status == StorageCheckStatus::Ok {
+            self.status = StorageCheckStatus::Repaired;
+        }
+        self.repairs.push(repair);
+    }
+}
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+#[cfg(unix)]
+fn current_uid() -> u32 {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(unix)]
+fn inspect_ownership(check: &mut StorageCheck, path: &Path) {
+    if let Ok(metadata) = fs::metadata(path) {
+        check.mode = Some(format!("{:04o}", metadata.permissions().mode() & 0o7777));
+        check.owned_by_current_user = Some(metadata.uid() == current_uid());
+    }
+}
+
+#[cfg(not(unix))]
+fn inspect_ownership(_check: &mut StorageCheck, _path: &Path) {}
+
+fn ownership_hint(check: &StorageCheck) -> String {
+    if check.owned_by_current_user == Some(false) {
+        format!(
+            "{} is owned by another user. Fix it with: sudo chown -R \"$(whoami)\" \"{}\"",
+            check.label, check.path
+        )
+    } else {
+        format!(
+            "{} is not writable. Check its permissions and free disk space.",
+            check.label
+        )
+    }
+}
+
+fn directory_is_writable(path: &Path) -> bool {
+    paths::probe_writable(path).is_ok()
+}
+
+fn check_directory(id: &str, label: &str, path: PathBuf) -> StorageCheck {
+    let mut check = StorageCheck::new(id, label, &path, "directory");
+
+    if !path.exists() {
+        if let Err(e) = fs::create_dir_all(&path) {
+            check.fail(format!(
+                "{} does not exist and could not be created: {}. Make sure the parent directory is writable.",
+                label, e
+            ));
+            return check;
+        }
+        check.repaired("Created missing directory".to_string());
+    } else if !path.is_dir() {
+        check.exists = true;
+        check.fail(format!(
+            "{} exists but is not a directory. Move it aside and restart the app.",
+            label
+        ));
+        return check;
+    }
+    check.exists = true;
+
+    inspect_ownership(&mut check, &path);
+    check.writable = directory_is_writable(&path);
+
+    #[cfg(unix)]
+    if !check.writable
+        && check.owned_by_current_user == Some(true)
+        && fs::set_permissions(&path, fs::Permissions::from_mode(0o700)).is_ok()
+    {
+        check.writable = directory_is_writable(&path);
+        if check.writable {
+            check.repaired("Restored owner permissions (0700)".to_string());
+            inspect_ownership(&mut check, &path);
+        }
+    }
+
+    if !check.writable {
+        let hint = ownership_hint(&check);
+        check.fail(hint);
+    }
+    check
+}
+
+fn check_vault_file(path: PathBuf) -> StorageCheck {
+    let label = "Credential vault";
+    let mut check = StorageCheck::new("vault", label, &path, "file");
+
+    if !path.exists() {
+        check.writable = true;
+        check.message =
+            Some("Not created yet; it is written when a credential is saved".to_string());
+        return check;
+    }
+    check.exists = true;
+
+    inspect_ownership(&mut check, &path);
+
+    #[cfg(unix)]
+    if check.owned_by_current_user != Some(false) {
+        let too_open = fs::metadata(&path)
+            .map(|metadata| metadata.permissions().mode() & 0o077 != 0)
+            .unwrap_or(false);
+        if too_open {
+            match fs::set_permissions(&path, fs::Permissions::from_mode(0o600)) {
+                Ok(()) => {
+                    check.repaired("Restricted permissions to 0600".to_string());
+                    inspect_ownership(&mut check, &path);
+                }
+                Err(e) => check.fail(format!(
+                    "{} is readable by other users and its permissions could not be fixed: {}",
+                    label, e
+                )),
+            }
+        }
+    }
+
+    // Opening for append checks writability without touching the contents.
+    check.writable = OpenOptions::new().append(true).open(&path).is_ok();
+    if !check.writable {
+        let hint = ownership_hint(&check);
+        check.fail(hint);
+    }
+    check
+}
+
+// ============================================================================
+// Data Directory Migration
+// ============================================================================
+
+/// Runtime files under `daemon/` that belong to a live daemon and must not
+/// be carried over to the new location.
+fn is_transient_daemon_file(name: &str) -> bool {
+    name.ends_with(".sock") || name == "agentd.lock"
+}
+
+fn entry_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn copy_entry(source: &Path, target: &Path) -> Result<(), String> {
+    let metadata = fs::symlink_metadata(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+
+    if metadata.file_type().is_symlink() {
+        let link = fs::read_link(source)
+            .map_err(|e| format!("Failed to read link {}: {}", source.display(), e))?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&link, target)
+            .map_err(|e| format!("Failed to create link {}: {}", target.display(), e))?;
+        #[cfg(not(unix))]
+        return Err(format!(
+            "Cannot copy symbolic link {} -> {}",
+            source.display(),
+            link.display()
+        ));
+    } else if metadata.is_dir() {
+        fs::create_dir(target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        let entries = fs::read_dir(source)
+            .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+            copy_entry(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        fs::set_permissions(target, metadata.permissions())
+            .map_err(|e| format!("Failed to set permissions on {}: {}", target.display(), e))?;
+    } else {
+        let copied = fs::copy(source, target).map_err(|e| {
+            format!(
+                "Failed to copy {} to {}: {}",
+                source.display(),
+                target.display(),
+                e
+            )
+        })?;
+        if copied != metadata.len() {
+            return Err(format!(
+                "Copy of {} is incomplete ({} of {} bytes)",
+                source.display(),
+                copied,
+                metadata.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Rename when possible; across filesystems copy, verify the copied size and
+/// only then remove the original.
+fn move_entry(source: &Path, target: &Path) -> Result<&'static str, String> {
+    move_entry_with(source, target, |from, to| fs::rename(from, to))
+}
+
+/// `move_entry` with the rename step supplied, so the copy fallback can be
+/// exercised without a second filesystem.
+fn move_entry_with(
+    source: &Path,
+    target: &Path,
+    rename: impl Fn(&Path, &Path) -> std::io::Result<()>,
+) -> Result<&'static str, String> {
+    if rename(source, target).is_ok() {
+        return Ok("renamed");
+    }
+
+    let expected = entry_size(source);
+    if let Err(e) = copy_entry(source, target) {
+        let _ = remove_entry(target);
+        return Err(e);
+    }
+    let copied = entry_size(target);
+    if copied != expected {
+        let _ = remove_entry(target);
+        return Err(format!(
+            "Copy of {} could not be verified ({} of {} bytes)",
+            source.display(),
+            copied,
+            expected
+        ));
+    }
+    remove_entry(source).map_err(|e| {
+        format!(
+            "Copied {} but could not remove the original: {}",
+            source.display(),
+            e
+        )
+    })?;
+    Ok("copied")
+}
+
+/// Drop stale sockets and locks from `daemon/` so they are not moved.
+fn clear_transient_daemon_files(source: &Path) -> Result<Vec<String>, String> {
+    let daemon_dir = source.join("daemon");
+    let Ok(entries) = fs::read_dir(&daemon_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut skipped = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_transient_daemon_file(&name) {
+            continue;
+        }
+        fs::remove_file(entry.path())
+            .map_err(|e| format!("Failed to remove daemon file {}: {}", name, e))?;
+        skipped.push(format!("daemon/{}", name));
+    }
+    Ok(skipped)
+}
+
+/// Canonicalize the deepest existing ancestor of `path` and append the rest,
+/// so a target that does not exist yet compares like the source does.
+fn canonicalize_existing_ancestor(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut tail = Vec::new();
+    loop {
+        if let Ok(canonical) = fs::canonicalize(existing) {
+            return Ok(tail
+                .iter()
+                .rev()
+                .fold(canonical, |resolved, name| resolved.join(name)));
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(std::path::Component::Normal(name))) => {
+                tail.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => {
+                return Err(format!(
+                    "New data directory {} could not be resolved",
+                    path.display()
+                ))
+            }
+        }
+    }
+}
+
+fn check_migration_target(source: &Path, target: &Path, force: bool) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err(format!(
+            "New data directory must be an absolute path, got {}",
+            target.display()
+        ));
+    }
+    let source = fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf());
+    let resolved_target = canonicalize_existing_ancestor(target)?;
+    if resolved_target == source {
+        return Err("New data directory is the current data directory".to_string());
+    }
+    if resolved_target.starts_with(&source) || source.starts_with(&resolved_target) {
+        return Err(format!(
+            "New data directory {} must not be inside the current one or contain it",
+            target.display()
+        ));
+    }
+
+    let Ok(mut entries) = fs::read_dir(target) else {
+        return Ok(());
+    };
+    if entries.next().is_none() {
+        return Ok(());
+    }
+    if !force {
+        return Err(format!(
+            "{} is not empty. Choose an empty directory or pass force to merge into it.",
+            target.display()
+        ));
+    }
+    // Even with force nothing already in the target is overwritten.
+    let conflicts: Vec<String> = fs::read_dir(&source)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name())
+                .filter(|name| fs::symlink_metadata(target.join(name)).is_ok())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "{} already contains {}; move them aside first",
+            target.display(),
+            conflicts.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn move_data_dir_contents(source: &Path, target: &Path) -> Result<Vec<MigratedItem>, String> {
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let mut names: Vec<_> = entries.flatten().map(|entry| entry.file_name()).collect();
+    names.sort();
+
+    let mut items: Vec<MigratedItem> = Vec::new();
+    for name in names {
+        let from = source.join(&name);
+        let to = target.join(&name);
+        let kind = if from.is_dir() { "directory" } else { "file" };
+        let bytes = entry_size(&from);
+
+        match move_entry(&from, &to) {
+            Ok(method) => items.push(MigratedItem {
+                name: name.to_string_lossy().to_string(),
+                kind: kind.to_string(),
+                method: method.to_string(),
+                bytes,
+            }),
+            Err(error) => {
+                restore_moved_items(source, target, &items);
+                return Err(error);
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Put back what already moved so the old location stays whole.
+fn restore_moved_items(source: &Path, target: &Path, items: &[MigratedItem]) {
+    for item in items.iter().rev() {
+        if let Err(e) = move_entry(&target.join(&item.name), &source.join(&item.name)) {
+            log::error!(
+                "Failed to restore {} after migration error: {}",
+                item.name,
+                e
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check that app storage locations exist and are writable, repairing what
+/// can be fixed safely
+#[tauri::command]
+pub async fn diagnose_storage() -> Result<StorageReport, String> {
+    let app_data_dir = paths::app_data_dir()?;
+    let (config_dir, vault_path) = credential_storage_paths()?;
+
+    let mut checks = vec![check_directory(
+        "app_data",
+        "App data directory",
+        app_data_dir.clone(),
+    )];
+    checks.push(check_directory(
+        "daemon",
+        "Daemon directory",
+        app_data_dir.join("daemon"),
+    ));
+    let config_check = check_directory("config", "Config directory", config_dir);
+    let config_ok = config_check.status != StorageCheckStatus::Error;
+    checks.push(config_check);
+    if config_ok {
+        checks.push(check_vault_file(vault_path));
+    }
+
+    for check in checks
+        .iter()
+        .filter(|check| check.status != StorageCheckStatus::Ok)
+    {
+        log::warn!(
+            "Storage check {} ({}): {:?} {}",
+            check.id,
+            check.path,
+            check.status,
+            check
+                .message
+                .as_deref()
+                .unwrap_or(&check.repairs.join("; "))
+        );
+    }
+
+    Ok(StorageReport {
+        healthy: checks
+            .iter()
+            .all(|check| check.status != StorageCheckStatus::Error),
+        checks,
+    })
+}
+
+/// Move the app data directory to `new_path` and restart the agent transport
+/// against it. Refuses a non-empty target unless `force` is set.
+#[tauri::command]
+pub async fn migrate_data_dir(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    new_path: String,
+    force: Option<bool>,
+) -> Result<DataDirMigration, String> {
+    if let Some(path) = paths::data_dir_env_override()? {
+        return Err(format!(
+            "The data directory is set by {} ({}); change the variable instead",
+            paths::DATA_DIR_ENV_VAR,
+            path.display()
+        ));
+    }
+
+    let source = paths::app_data_dir()?;
+    let target = PathBuf::from(new_path.trim());
+    check_migration_target(&source, &target, force.unwrap_or(false))?;
+
+    let source_str = source.to_string_lossy().to_string();
+    if state.manager.is_daemon_transport().await || sidecar::daemon_reachable(&source_str) {
+        return Err(
+            "The background daemon is using the data directory. Stop the daemon service and try again."
+                .to_string(),
+        );
+    }
+
+    paths::validate_data_dir(&target)?;
+    let was_running = state.manager.is_running().await;
+    state.manager.stop().await?;
+
+    let result = if source.is_dir() {
+        clear_transient_daemon_files(&source)
+            .and_then(|skipped| Ok((skipped, move_data_dir_contents(&source, &target)?)))
+    } else {
+        Ok((Vec::new(), Vec::new()))
+    };
+    let (skipped, items) = match result {
+        Ok(moved) => moved,
+        Err(error) => {
+            if was_running {
+                let _ = ensure_sidecar_started_public(&app, &state).await;
+            }
+            return Err(format!("Data directory migration failed: {}", error));
+        }
+    };
+
+    // Without the saved pointer the next launch would start from an empty
+    // default directory, so undo the move rather than leave it half done.
+    if let Err(error) = paths::save_data_dir(&target) {
+        restore_moved_items(&source, &target, &items);
+        if was_running {
+            let _ = ensure_sidecar_started_public(&app, &state).await;
+        }
+        return Err(format!("Data directory migration failed: {}", error));
+    }
+    if source.is_dir() {
+        if let Err(e) = fs::remove_dir(&source) {
+            log::warn!(
+                "Left old data directory {} in place: {}",
+                source.display(),
+                e
+            );
+        }
+    }
+
+    let bytes_moved = items.iter().map(|item| item.bytes).sum();
+    log::info!(
+        "Moved data directory {} -> {} ({} items, {} bytes)",
+        source.display(),
+        target.display(),
+        items.len(),
+        bytes_moved
+    );
+
+    // A transport that was not running starts on first use, as usual.
+    let transport_restarted = was_running
+        && match ensure_sidecar_started_public(&app, &state).await {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Agent transport did not restart after migration: {}", e);
+                false
+            }
+        };
+
+    Ok(DataDirMigration {
+        from: source_str,
+        to: target.to_string_lossy().to_string(),
+        items,
+        skipped,
+        bytes_moved,
+        transport_restarted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!(
+            "cowork-storage-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::canonicalize(dir).unwrap()
+    }
+
+    #[test]
+    fn migration_target_must_not_nest_with_the_source() {
+        let base = scratch_dir("nested");
+        let source = base.join("data");
+        fs::create_dir_all(&source).unwrap();
+
+        let error = check_migration_target(&source, &source, false).unwrap_err();
+        assert!(error.contains("is the current data directory"), "{}", error);
+        let error =
+            check_migration_target(&source, &source.join("new").join("deeper"), false).unwrap_err();
+        assert!(error.contains("must not be inside"), "{}", error);
+        let error = check_migration_target(&source, &base, false).unwrap_err();
+        assert!(error.contains("must not be inside"), "{}", error);
+        assert!(
+            check_migration_target(&source, Path::new("relative/dir"), false)
+                .unwrap_err()
+                .contains("absolute path")
+        );
+        check_migration_target(&source, &base.join("elsewhere"), false).unwrap();
+
+        // A missing target reached through a symlink is resolved before the
+        // containment check.
+        #[cfg(unix)]
+        {
+            let link = base.join("link");
+            std::os::unix::fs::symlink(&source, &link).unwrap();
+            let error = check_migration_target(&source, &link.join("new"), false).unwrap_err();
+            assert!(error.contains("must not be inside"), "{}", error);
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn non_empty_target_needs_force_and_no_conflicts() {
+        let base = scratch_dir("non-empty");
+        let source = base.join("data");
+        let target = base.join("target");
+        fs::create_dir_all(source.join("sessions")).unwrap();
+        fs::create_dir_all(&target).unwrap();
+
+        check_migration_target(&source, &target, false).unwrap();
+
+        fs::write(target.join("notes.txt"), "mine").unwrap();
+        let error = check_migration_target(&source, &target, false).unwrap_err();
+        assert!(error.contains("is not empty"), "{}", error);
+        check_migration_target(&source, &target, true).unwrap();
+
+        fs::create_dir_all(target.join("sessions")).unwrap();
+        let error = check_migration_target(&source, &target, true).unwrap_err();
+        assert!(error.contains("already contains sessions"), "{}", error);
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn move_entry_renames_or_falls_back_to_a_verified_copy() {
+        let base = scratch_dir("move");
+        let source = base.join("source");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::write(source.join("nested").join("a.txt"), "alpha").unwrap();
+        fs::write(source.join("b.txt"), "bravo!").unwrap();
+
+        let renamed = base.join("renamed");
+        assert_eq!(move_entry(&source, &renamed).unwrap(), "renamed");
+        assert!(!source.exists());
+
+        // Simulate a cross-device move: rename fails, so the tree is copied,
+        // checked and only then removed.
+        let copied = base.join("copied");
+        let cross_device = |_: &Path, _: &Path| -> std::io::Result<()> {
+            Err(std::io::Error::other("Invalid cross-device link"))
+        };
+        assert_eq!(
+            move_entry_with(&renamed, &copied, cross_device).unwrap(),
+            "copied"
+        );
+        assert!(!renamed.exists());
+        assert_eq!(
+            fs::read_to_string(copied.join("nested").join("a.txt")).unwrap(),
+            "alpha"
+        );
+        assert_eq!(entry_size(&copied), 11);
+
+        // A failed copy leaves the original alone and cleans up the target.
+        fs::create_dir_all(base.join("blocked")).unwrap();
+        let error = move_entry_with(&copied, &base.join("blocked"), cross_device).unwrap_err();
+        assert!(error.contains("Failed to create"), "{}", error);
+        assert!(copied.join("b.txt").exists());
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn moved_items_can_be_restored() {
+        let base = scratch_dir("restore");
+        let source = base.join("source");
+        let target = base.join("target");
+        fs::create_dir_all(source.join("sessions")).unwrap();
+        fs::write(source.join("data.db"), "db").unwrap();
+        fs::create_dir_all(&target).unwrap();
+
+        let items = move_data_dir_contents(&source, &target).unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(target.join("data.db").exists());
+
+        restore_moved_items(&source, &target, &items);
+        assert!(source.join("data.db").exists());
+        assert!(source.join("sessions").is_dir());
+        assert!(!target.join("data.db").exists());
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn check_status_moves_from_ok_to_repaired_to_error() {
+        let mut check = StorageCheck::new("x", "X", Path::new("/x"), "directory");
+        assert_eq!(check.status, StorageCheckStatus::Ok);
+        check.repaired("Created missing directory".to_string());
+        assert_eq!(check.status, StorageCheckStatus::Repaired);
+        check.fail("still broken".to_string());
+        check.repaired("late repair".to_string());
+        assert_eq!(check.status, StorageCheckStatus::Error);
+        assert_eq!(
+            serde_json::to_value(&check).unwrap()["status"],
+            serde_json::json!("error")
+        );
+    }
+
+    #[test]
+    fn missing_directories_are_created_and_owned_by_us() {
+        let base = scratch_dir("check");
+        let check = check_directory("logs", "Logs", base.join("logs"));
+        assert_eq!(check.status, StorageCheckStatus::Repaired);
+        assert!(check.exists && check.writable);
+        #[cfg(unix)]
+        assert_eq!(check.owned_by_current_user, Some(true));
+        let _ = fs::remove_dir_all(base);
+    }
+}
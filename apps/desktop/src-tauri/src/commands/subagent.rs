@@ -44,6 +44,136 @@ pub struct CreateSubagentInput {
     pub model: Option<String>,
 }
 
+/// Another subagent that an install would clash with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentConflict {
+    pub name: String,
+    pub display_name: String,
+    pub source: Option<String>,
+    pub installed: bool,
+    /// "alreadyInstalled", "name" or "displayName"
+    pub reason: String,
+}
+
+/// Serialized as the error string of `deep_subagent_install` when the install
+/// is refused, so the UI can show the conflicts and offer `force`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentInstallError {
+    pub code: String,
+    pub message: String,
+    pub subagent_name: String,
+    pub conflicts: Vec<SubagentConflict>,
+}
+
+impl SubagentInstallError {
+    fn into_error_string(self) -> String {
+        serde_json::to_string(&self).unwrap_or(self.message)
+    }
+}
+
+impl SubagentConflict {
+    fn new(subagent: &SubagentInfo, reason: &str) -> Self {
+        Self {
+            name: subagent.name.clone(),
+            display_name: subagent.display_name.clone(),
+            source: subagent.source.clone(),
+            installed: subagent.installed,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Subagent names the sidecar accepts: lowercase words joined by hyphens.
+fn is_valid_subagent_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+}
+
+fn validate_subagent_manifest(subagent: &SubagentInfo) -> Result<(), String> {
+    if !is_valid_subagent_name(&subagent.name) {
+        return Err(format!(
+            "Subagent name '{}' must be lowercase letters and digits separated by hyphens",
+            subagent.name
+        ));
+    }
+    let required = [
+        ("displayName", &subagent.display_name),
+        ("description", &subagent.description),
+        ("version", &subagent.version),
+        ("systemPrompt", &subagent.system_prompt),
+    ];
+    for (field, value) in required {
+        if value.trim().is_empty() {
+            return Err(format!(
+                "Subagent '{}' manifest is missing {}",
+                subagent.name, field
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Installed subagents other than `target` that share its name or display
+/// name, ignoring case.
+fn find_subagent_conflicts(
+    target: &SubagentInfo,
+    subagents: &[SubagentInfo],
+) -> Vec<SubagentConflict> {
+    let display_name = target.display_name.trim().to_lowercase();
+    subagents
+        .iter()
+        .filter(|other| other.installed && other.name != target.name)
+        .filter_map(|other| {
+            if other.name.eq_ignore_ascii_case(&target.name) {
+                Some(SubagentConflict::new(other, "name"))
+            } else if other.display_name.trim().to_lowercase() == display_name {
+                Some(SubagentConflict::new(other, "displayName"))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+async fn list_subagents(
+    state: &AgentState,
+    working_directory: &Option<String>,
+) -> Result<Vec<SubagentInfo>, String> {
+    let params = serde_json::json!({
+        "workingDirectory": working_directory,
+    });
+
+    let result = state.manager.send_command("subagent_list", params).await?;
+    let wrapper: serde_json::Value = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse result: {}", e))?;
+    let subagents = wrapper.get("subagents").cloned().unwrap_or(serde_json::json!([]));
+    serde_json::from_value(subagents).map_err(|e| format!("Failed to parse subagents: {}", e))
+}
+
+async fn is_subagent_installed(
+    state: &AgentState,
+    subagent_name: &str,
+    working_directory: &Option<String>,
+) -> Result<bool, String> {
+    let params = serde_json::json!({
+        "subagentName": subagent_name,
+        "workingDirectory": working_directory,
+    });
+
+    let result = state.manager.send_command("subagent_is_installed", params).await?;
+    let wrapper: serde_json::Value = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+    Ok(wrapper.get("installed").and_then(|v| v.as_bool()).unwrap_or(false))
+}
+
 // ============================================================================
 // Subagent Commands
 // ============================================================================
@@ -56,30 +186,77 @@ pub async fn deep_subagent_list(
     working_directory: Option<String>,
 ) -> Result<Vec<SubagentInfo>, String> {
     ensure_sidecar_started(&app, &state).await?;
-
-    let manager = &state.manager;
-    let params = serde_json::json!({
-        "workingDirectory": working_directory,
-    });
-
-    let result = manager.send_command("subagent_list", params).await?;
-    let wrapper: serde_json::Value = serde_json::from_value(result)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
-    let subagents = wrapper.get("subagents").cloned().unwrap_or(serde_json::json!([]));
-    serde_json::from_value(subagents).map_err(|e| format!("Failed to parse subagents: {}", e))
+    list_subagents(&state, &working_directory).await
 }
 
 /// Install a subagent (copy from bundled to managed)
+///
+/// Refuses to overwrite an installed subagent or to install one whose name or
+/// display name clashes with another installed subagent, unless `force` is set.
 #[tauri::command]
 pub async fn deep_subagent_install(
     app: AppHandle,
     state: State<'_, AgentState>,
     subagent_name: String,
     working_directory: Option<String>,
+    force: Option<bool>,
 ) -> Result<(), String> {
     ensure_sidecar_started(&app, &state).await?;
+    let force = force.unwrap_or(false);
+
+    let subagents = list_subagents(&state, &working_directory).await?;
+    let target = subagents
+        .iter()
+        .find(|subagent| subagent.name == subagent_name)
+        .ok_or_else(|| format!("Subagent not found: {}", subagent_name))?;
+    validate_subagent_manifest(target)?;
+
+    let already_installed =
+        is_subagent_installed(&state, &subagent_name, &working_directory).await?;
+    let mut conflicts = find_subagent_conflicts(target, &subagents);
+    if already_installed {
+        conflicts.insert(0, SubagentConflict::new(target, "alreadyInstalled"));
+    }
+
+    if !conflicts.is_empty() {
+        if !force {
+            let message = if already_installed {
+                format!("Subagent '{}' is already installed", subagent_name)
+            } else {
+                format!(
+                    "Subagent '{}' conflicts with installed subagent(s): {}",
+                    subagent_name,
+                    conflicts
+                        .iter()
+                        .map(|conflict| conflict.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            return Err(SubagentInstallError {
+                code: "subagent_conflict".to_string(),
+                message,
+                subagent_name,
+                conflicts,
+            }
+            .into_error_string());
+        }
+        log::warn!(
+            "Installing subagent '{}' despite {} conflict(s) (force)",
+            subagent_name,
+            conflicts.len()
+        );
+    }
 
     let manager = &state.manager;
+    if already_installed {
+        let params = serde_json::json!({
+            "subagentName": subagent_name,
+            "workingDirectory": working_directory,
+        });
+        manager.send_command("subagent_uninstall", params).await?;
+    }
+
     let params = serde_json::json!({
         "subagentName": subagent_name,
         "workingDirectory": working_directory,
@@ -132,18 +309,7 @@ pub async fn deep_subagent_is_installed(
     working_directory: Option<String>,
 ) -> Result<bool, String> {
     ensure_sidecar_started(&app, &state).await?;
-
-    let manager = &state.manager;
-    let params = serde_json::json!({
-        "subagentName": subagent_name,
-        "workingDirectory": working_directory,
-    });
-
-    let result = manager.send_command("subagent_is_installed", params).await?;
-    let wrapper: serde_json::Value = serde_json::from_value(result)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
-
-    Ok(wrapper.get("installed").and_then(|v| v.as_bool()).unwrap_or(false))
+    is_subagent_installed(&state, &subagent_name, &working_directory).await
 }
 
 /// Get a specific subagent
@@ -0,0 +1,621 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+use crate::commands::agent::AgentState;
+use crate::paths;
+use crate::sidecar::SidecarActivity;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex as StdMutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+const UPDATE_STATE_DIR: &str = "updater";
+const UPDATE_PREFERENCES_FILE: &str = "preferences.json";
+const UPDATE_STATUS_FILE: &str = "status.json";
+const DEFAULT_UPDATE_CHANNEL: &str = "stable";
+/// Known update channels and their manifest endpoints. `None` uses the
+/// endpoint configured in tauri.conf.json.
+const UPDATE_CHANNELS: [(&str, Option<&str>); 2] = [
+    ("stable", None),
+    (
+        "beta",
+        Some("https://github.com/Naresh084/cowork/releases/download/beta/latest.json"),
+    ),
+];
+const DEFAULT_CHECK_INTERVAL_MINUTES: u64 = 30;
+const MIN_CHECK_INTERVAL_MINUTES: u64 = 5;
+const MAX_CHECK_INTERVAL_MINUTES: u64 = 7 * 24 * 60;
+/// How often the background loop wakes to compare against the cadence.
+const UPDATE_LOOP_TICK: Duration = Duration::from_secs(60);
+/// How often a deferred install re-checks whether the agent is idle.
+const DEFER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// ============================================================================
+// Updater Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePreferences {
+    #[serde(default = "default_true")]
+    pub auto_check: bool,
+    #[serde(default = "default_check_interval_minutes")]
+    pub check_interval_minutes: u64,
+    /// When set, available updates are announced via `update:available`
+    /// and only installed once the user calls `apply_update`.
+    #[serde(default = "default_true")]
+    pub require_confirmation: bool,
+    #[serde(default = "default_update_channel")]
+    pub channel: String,
+}
+
+impl Default for UpdatePreferences {
+    fn default() -> Self {
+        Self {
+            auto_check: true,
+            check_interval_minutes: DEFAULT_CHECK_INTERVAL_MINUTES,
+            require_confirmation: true,
+            channel: DEFAULT_UPDATE_CHANNEL.to_string(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_check_interval_minutes() -> u64 {
+    DEFAULT_CHECK_INTERVAL_MINUTES
+}
+
+fn default_update_channel() -> String {
+    DEFAULT_UPDATE_CHANNEL.to_string()
+}
+
+fn validate_update_channel(channel: &str) -> Result<(), String> {
+    if UPDATE_CHANNELS.iter().any(|(name, _)| *name == channel) {
+        return Ok(());
+    }
+    let known: Vec<&str> = UPDATE_CHANNELS.iter().map(|(name, _)| *name).collect();
+    Err(format!(
+        "Unknown update channel \"{}\". Expected one of: {}",
+        channel,
+        known.join(", ")
+    ))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl From<&Update> for UpdateInfo {
+    fn from(update: &Update) -> Self {
+        Self {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            body: update.body.clone(),
+            date: update.date.map(|date| date.to_string()),
+        }
+    }
+}
+
+/// Result of the last successful check, persisted across restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateCheckRecord {
+    last_checked_at: Option<i64>,
+    latest_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStatus {
+    pub last_checked_at: Option<i64>,
+    pub current_version: String,
+    pub latest_version: Option<String>,
+    pub channel: String,
+    pub update_available: bool,
+}
+
+fn now_ms() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Preferences
+// ============================================================================
+
+fn update_state_path(file: &str) -> Result<PathBuf, String> {
+    Ok(paths::app_data_dir()?.join(UPDATE_STATE_DIR).join(file))
+}
+
+fn update_preferences_path() -> Result<PathBuf, String> {
+    update_state_path(UPDATE_PREFERENCES_FILE)
+}
+
+fn load_update_preferences() -> UpdatePreferences {
+    let Ok(path) = update_preferences_path() else {
+        return UpdatePreferences::default();
+    };
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return UpdatePreferences::default();
+    };
+    match serde_json::from_str::<UpdatePreferences>(&raw) {
+        Ok(preferences) => normalize_update_preferences(preferences),
+        Err(e) => {
+            eprintln!(
+                "[updater] Ignoring unreadable preferences {:?}: {}",
+                path, e
+            );
+            UpdatePreferences::default()
+        }
+    }
+}
+
+/// Clamp a hand-edited or outdated preferences file back into range.
+fn normalize_update_preferences(mut preferences: UpdatePreferences) -> UpdatePreferences {
+    preferences.check_interval_minutes = preferences
+        .check_interval_minutes
+        .clamp(MIN_CHECK_INTERVAL_MINUTES, MAX_CHECK_INTERVAL_MINUTES);
+    if validate_update_channel(&preferences.channel).is_err() {
+        preferences.channel = default_update_channel();
+    }
+    preferences
+}
+
+fn save_update_preferences(preferences: &UpdatePreferences) -> Result<(), String> {
+    let path = update_preferences_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create updater directory {:?}: {}", parent, e))?;
+    }
+    let serialized = serde_json::to_string_pretty(preferences)
+        .map_err(|e| format!("Failed to serialize update preferences: {}", e))?;
+    fs::write(&path, serialized)
+        .map_err(|e| format!("Failed to write update preferences {:?}: {}", path, e))
+}
+
+fn load_update_check_record() -> UpdateCheckRecord {
+    update_state_path(UPDATE_STATUS_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_update_check_record(record: &UpdateCheckRecord) -> Result<(), String> {
+    let path = update_state_path(UPDATE_STATUS_FILE)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create updater directory {:?}: {}", parent, e))?;
+    }
+    let serialized = serde_json::to_string_pretty(record)
+        .map_err(|e| format!("Failed to serialize update status: {}", e))?;
+    fs::write(&path, serialized)
+        .map_err(|e| format!("Failed to write update status {:?}: {}", path, e))
+}
+
+// ============================================================================
+// Update Checks
+// ============================================================================
+
+/// Update found by the last check, held until the user applies it.
+static PENDING_UPDATE: OnceLock<StdMutex<Option<Update>>> = OnceLock::new();
+
+fn pending_update() -> std::sync::MutexGuard<'static, Option<Update>> {
+    let pending = PENDING_UPDATE.get_or_init(|| StdMutex::new(None));
+    match pending.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Put back an update that could not be installed so it can be retried.
+fn restore_pending_update(update: Update) {
+    let mut pending = pending_update();
+    if pending.is_none() {
+        *pending = Some(update);
+    }
+}
+
+/// Whether `latest` is a newer release than `current`. Versions that are not
+/// semver fall back to a plain comparison.
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| semver::Version::parse(version.trim().trim_start_matches('v'));
+    match (parse(latest), parse(current)) {
+        (Ok(latest), Ok(current)) => latest > current,
+        _ => latest.trim() != current.trim(),
+    }
+}
+
+fn channel_updater(app: &AppHandle, channel: &str) -> Result<Updater, String> {
+    let endpoint = UPDATE_CHANNELS
+        .iter()
+        .find(|(name, _)| *name == channel)
+        .and_then(|(_, endpoint)| *endpoint);
+    let builder = match endpoint {
+        Some(endpoint) => {
+            let url = Url::parse(endpoint)
+                .map_err(|e| format!("Invalid {} update endpoint: {}", channel, e))?;
+            app.updater_builder()
+                .endpoints(vec![url])
+                .map_err(|e| format!("Invalid {} update endpoint: {}", channel, e))?
+        }
+        None => app.updater_builder(),
+    };
+    builder
+        .build()
+        .map_err(|e| format!("Updater is not available: {}", e))
+}
+
+async fn check_update(app: &AppHandle) -> Result<Option<Update>, String> {
+    let updater = channel_updater(app, &load_update_preferences().channel)?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    let record = UpdateCheckRecord {
+        last_checked_at: Some(now_ms()),
+        latest_version: Some(match &update {
+            Some(update) => update.version.clone(),
+            None => app.package_info().version.to_string(),
+        }),
+    };
+    if let Err(e) = save_update_check_record(&record) {
+        eprintln!("[updater] {}", e);
+    }
+
+    *pending_update() = update.clone();
+    Ok(update)
+}
+
+/// Set while an install is downloading or waiting for the agent to go idle.
+static INSTALL_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+/// Set by `apply_update(force)` to stop waiting for the agent to go idle.
+static FORCE_INSTALL: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateDeferredPayload {
+    version: String,
+    reason: String,
+    #[serde(flatten)]
+    activity: SidecarActivity,
+}
+
+fn deferral_reason(activity: &SidecarActivity) -> String {
+    if activity.active_sessions.is_empty() {
+        format!(
+            "Waiting for {} pending agent request(s) to finish before restarting",
+            activity.pending_requests
+        )
+    } else {
+        format!(
+            "Waiting for {} active generation(s) to finish before restarting",
+            activity.active_sessions.len()
+        )
+    }
+}
+
+/// Block until no generation or request is in flight, emitting
+/// `update:deferred` whenever the reason for waiting changes.
+async fn wait_until_agent_idle(app: &AppHandle, version: &str) {
+    let mut last_reason: Option<String> = None;
+    loop {
+        if FORCE_INSTALL.load(Ordering::SeqCst) {
+            eprintln!(
+                "[updater] Installing {} without waiting for the agent",
+                version
+            );
+            return;
+        }
+
+        let activity = app.state::<AgentState>().manager.activity().await;
+        if activity.is_idle() {
+            return;
+        }
+
+        let reason = deferral_reason(&activity);
+        if last_reason.as_deref() != Some(reason.as_str()) {
+            eprintln!("[updater] Deferring update {}: {}", version, reason);
+            let _ = app.emit(
+                "update:deferred",
+                UpdateDeferredPayload {
+                    version: version.to_string(),
+                    reason: reason.clone(),
+                    activity,
+                },
+            );
+            last_reason = Some(reason);
+        }
+        tokio::time::sleep(DEFER_POLL_INTERVAL).await;
+    }
+}
+
+async fn install_update(app: &AppHandle, update: Update, force: bool) -> Result<(), String> {
+    if INSTALL_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        restore_pending_update(update);
+        return Err("An update is already being installed".to_string());
+    }
+    FORCE_INSTALL.store(force, Ordering::SeqCst);
+
+    let result = download_and_install(app, &update).await;
+    INSTALL_IN_PROGRESS.store(false, Ordering::SeqCst);
+    if let Err(e) = result {
+        restore_pending_update(update);
+        return Err(e);
+    }
+    app.restart()
+}
+
+async fn download_and_install(app: &AppHandle, update: &Update) -> Result<(), String> {
+    eprintln!("[updater] Downloading update {}", update.version);
+    let bytes = update
+        .download(|_, _| {}, || {})
+        .await
+        .map_err(|e| format!("Failed to download update {}: {}", update.version, e))?;
+
+    // Installing can exit the process on some platforms, so wait first.
+    wait_until_agent_idle(app, &update.version).await;
+
+    eprintln!("[updater] Installing update {}", update.version);
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update {}: {}", update.version, e))
+}
+
+async fn handle_available_update(app: &AppHandle, update: Update) {
+    if load_update_preferences().require_confirmation {
+        let _ = app.emit("update:available", UpdateInfo::from(&update));
+        return;
+    }
+
+    pending_update().take();
+    if let Err(e) = install_update(app, update, false).await {
+        eprintln!("[updater] {}", e);
+    }
+}
+
+/// Check for updates in the background at the user's configured cadence.
+/// Development builds never check automatically.
+pub fn start_update_loop(app: AppHandle) {
+    if cfg!(debug_assertions) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_check: Option<Instant> = None;
+        loop {
+            let preferences = load_update_preferences();
+            let interval = Duration::from_secs(preferences.check_interval_minutes * 60);
+            let due = match last_check {
+                Some(checked) => checked.elapsed() >= interval,
+                // After a restart, honour the cadence from the persisted check.
+                None => load_update_check_record()
+                    .last_checked_at
+                    .is_none_or(|checked_at| {
+                        now_ms().saturating_sub(checked_at) >= interval.as_millis() as i64
+                    }),
+            };
+
+            if preferences.auto_check && due {
+                last_check = Some(Instant::now());
+                match check_update(&app).await {
+                    Ok(Some(update)) => handle_available_update(&app, update).await,
+                    Ok(None) => {}
+                    Err(e) => eprintln!("[updater] {}", e),
+                }
+            }
+
+            tokio::time::sleep(UPDATE_LOOP_TICK).await;
+        }
+    });
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check for an update once without installing it
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let update = check_update(&app).await?;
+    Ok(update.as_ref().map(UpdateInfo::from))
+}
+
+/// Get when updates were last checked and the latest known version
+#[tauri::command]
+pub async fn get_update_status(app: AppHandle) -> Result<UpdateStatus, String> {
+    let record = load_update_check_record();
+    let channel = load_update_preferences().channel;
+    let current_version = app.package_info().version.to_string();
+    let update_available = pending_update().is_some()
+        || record
+            .latest_version
+            .as_deref()
+            .is_some_and(|latest| is_newer_version(latest, &current_version));
+    Ok(UpdateStatus {
+        last_checked_at: record.last_checked_at,
+        current_version,
+        latest_version: record.latest_version,
+        channel,
+        update_available,
+    })
+}
+
+/// Install the update found by the last check and restart the app once the
+/// agent is idle. With `force`, install without waiting, including for an
+/// install that is already deferred.
+#[tauri::command]
+pub async fn apply_update(app: AppHandle, force: Option<bool>) -> Result<(), String> {
+    let force = force.unwrap_or(false);
+    if INSTALL_IN_PROGRESS.load(Ordering::SeqCst) {
+        if force {
+            FORCE_INSTALL.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+        return Err("An update is already being installed".to_string());
+    }
+
+    let update = pending_update()
+        .take()
+        .ok_or_else(|| "No update is pending; check for updates first".to_string())?;
+    install_update(&app, update, force).await
+}
+
+/// Get update check cadence and confirmation preferences
+#[tauri::command]
+pub async fn get_update_preferences() -> Result<UpdatePreferences, String> {
+    Ok(load_update_preferences())
+}
+
+/// Update check cadence and confirmation preferences
+#[tauri::command]
+pub async fn set_update_preferences(
+    auto_check: Option<bool>,
+    check_interval_minutes: Option<u64>,
+    require_confirmation: Option<bool>,
+) -> Result<UpdatePreferences, String> {
+    if let Some(minutes) = check_interval_minutes {
+        if !(MIN_CHECK_INTERVAL_MINUTES..=MAX_CHECK_INTERVAL_MINUTES).contains(&minutes) {
+            return Err(format!(
+                "checkIntervalMinutes must be between {} and {}",
+                MIN_CHECK_INTERVAL_MINUTES, MAX_CHECK_INTERVAL_MINUTES
+            ));
+        }
+    }
+
+    let mut preferences = load_update_preferences();
+    if let Some(auto_check) = auto_check {
+        preferences.auto_check = auto_check;
+    }
+    if let Some(minutes) = check_interval_minutes {
+        preferences.check_interval_minutes = minutes;
+    }
+    if let Some(require_confirmation) = require_confirmation {
+        preferences.require_confirmation = require_confirmation;
+    }
+    save_update_preferences(&preferences)?;
+    Ok(preferences)
+}
+
+/// Switch the update channel; the next check uses the channel's manifest
+#[tauri::command]
+pub async fn set_update_channel(channel: String) -> Result<UpdatePreferences, String> {
+    let channel = channel.trim().to_lowercase();
+    validate_update_channel(&channel)?;
+
+    let mut preferences = load_update_preferences();
+    if preferences.channel != channel {
+        preferences.channel = channel;
+        save_update_preferences(&preferences)?;
+        // Results from the previous channel no longer apply.
+        pending_update().take();
+        if let Err(e) = save_update_check_record(&UpdateCheckRecord::default()) {
+            eprintln!("[updater] {}", e);
+        }
+    }
+    Ok(preferences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_known_channels_are_accepted() {
+        assert!(validate_update_channel("stable").is_ok());
+        assert!(validate_update_channel("beta").is_ok());
+        let error = validate_update_channel("nightly").unwrap_err();
+        assert!(error.contains("stable, beta"));
+    }
+
+    #[test]
+    fn preferences_are_clamped_and_unknown_channels_fall_back() {
+        let too_often = normalize_update_preferences(UpdatePreferences {
+            check_interval_minutes: 1,
+            channel: "nightly".to_string(),
+            ..UpdatePreferences::default()
+        });
+        assert_eq!(too_often.check_interval_minutes, MIN_CHECK_INTERVAL_MINUTES);
+        assert_eq!(too_often.channel, DEFAULT_UPDATE_CHANNEL);
+
+        let too_rarely = normalize_update_preferences(UpdatePreferences {
+            check_interval_minutes: u64::MAX,
+            channel: "beta".to_string(),
+            ..UpdatePreferences::default()
+        });
+        assert_eq!(
+            too_rarely.check_interval_minutes,
+            MAX_CHECK_INTERVAL_MINUTES
+        );
+        assert_eq!(too_rarely.channel, "beta");
+    }
+
+    #[test]
+    fn missing_preference_fields_use_defaults() {
+        let preferences: UpdatePreferences =
+            serde_json::from_str(r#"{"autoCheck":false}"#).unwrap();
+        assert!(!preferences.auto_check);
+        assert!(preferences.require_confirmation);
+        assert_eq!(
+            preferences.check_interval_minutes,
+            DEFAULT_CHECK_INTERVAL_MINUTES
+        );
+        assert_eq!(preferences.channel, DEFAULT_UPDATE_CHANNEL);
+    }
+
+    #[test]
+    fn deferral_reason_prefers_active_generations() {
+        let generating = SidecarActivity {
+            active_sessions: vec!["a".to_string(), "b".to_string()],
+            pending_requests: 5,
+        };
+        assert_eq!(
+            deferral_reason(&generating),
+            "Waiting for 2 active generation(s) to finish before restarting"
+        );
+
+        let requests = SidecarActivity {
+            active_sessions: Vec::new(),
+            pending_requests: 3,
+        };
+        assert_eq!(
+            deferral_reason(&requests),
+            "Waiting for 3 pending agent request(s) to finish before restarting"
+        );
+    }
+
+    #[test]
+    fn only_newer_versions_count_as_updates() {
+        assert!(is_newer_version("1.2.0", "1.1.9"));
+        assert!(is_newer_version("1.10.0", "1.9.0"));
+        assert!(is_newer_version("v2.0.0", "1.9.9"));
+        assert!(is_newer_version("1.2.0", "1.2.0-beta.1"));
+        assert!(!is_newer_version("1.2.0", "1.2.0"));
+        assert!(!is_newer_version("1.1.0", "1.2.0"));
+        assert!(!is_newer_version("1.2.0-beta.1", "1.2.0"));
+    }
+
+    #[test]
+    fn unparseable_versions_compare_as_strings() {
+        assert!(is_newer_version("nightly-2", "nightly-1"));
+        assert!(!is_newer_version("nightly-1", "nightly-1"));
+    }
+}
@@ -2,7 +2,12 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 use crate::commands::agent::{ensure_sidecar_started_public, AgentState};
+use crate::sidecar::{SidecarEvent, SidecarManager};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::Duration;
 use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,15 +118,11 @@ pub struct WorkflowScheduledTaskSummary {
     pub last_run_status: Option<String>,
 }
 
-#[tauri::command]
-pub async fn workflow_list(
-    app: AppHandle,
-    state: State<'_, AgentState>,
+async fn list_workflows(
+    state: &AgentState,
     limit: Option<u32>,
     offset: Option<u32>,
 ) -> Result<Vec<WorkflowDefinition>, String> {
-    ensure_sidecar_started_public(&app, &state).await?;
-
     let result = state
         .manager
         .send_command(
@@ -136,15 +137,11 @@ pub async fn workflow_list(
     serde_json::from_value(result).map_err(|e| format!("Failed to parse workflows: {}", e))
 }
 
-#[tauri::command]
-pub async fn workflow_get(
-    app: AppHandle,
-    state: State<'_, AgentState>,
-    workflow_id: String,
+async fn get_workflow(
+    state: &AgentState,
+    workflow_id: &str,
     version: Option<u32>,
 ) -> Result<Option<WorkflowDefinition>, String> {
-    ensure_sidecar_started_public(&app, &state).await?;
-
     let result = state
         .manager
         .send_command(
@@ -159,6 +156,215 @@ pub async fn workflow_get(
     serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow: {}", e))
 }
 
+async fn create_workflow_draft(
+    state: &AgentState,
+    input: serde_json::Value,
+) -> Result<WorkflowDefinition, String> {
+    let result = state.manager.send_command("workflow_create_draft", input).await?;
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow draft: {}", e))
+}
+
+/// Structural checks on a workflow's node/edge graph: a start node must
+/// exist, edges must reference known nodes, every node should be reachable
+/// from a start node, and the graph must not contain cycles. Cycles running
+/// through a `loop` node are reported as warnings only.
+fn validate_workflow_graph(definition: &serde_json::Value) -> WorkflowValidationReport {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    let empty = Vec::new();
+    let nodes = definition
+        .get("nodes")
+        .and_then(|value| value.as_array())
+        .unwrap_or(&empty);
+    let edges = definition
+        .get("edges")
+        .and_then(|value| value.as_array())
+        .unwrap_or(&empty);
+
+    let mut node_ids: Vec<&str> = Vec::new();
+    let mut node_types: HashMap<&str, &str> = HashMap::new();
+    for node in nodes {
+        if let Some(id) = json_item_id(node).filter(|id| !id.trim().is_empty()) {
+            if node_types
+                .insert(id, json_str_field(node, "type").unwrap_or_default())
+                .is_none()
+            {
+                node_ids.push(id);
+            }
+        }
+    }
+
+    let start_nodes: Vec<&str> = node_ids
+        .iter()
+        .copied()
+        .filter(|id| node_types.get(id) == Some(&"start"))
+        .collect();
+    if start_nodes.is_empty() {
+        errors.push("Workflow requires a start node.".to_string());
+    }
+
+    let mut outgoing: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges {
+        let edge_id = json_item_id(edge).unwrap_or("<unnamed>");
+        let from = json_str_field(edge, "from").unwrap_or_default();
+        let to = json_str_field(edge, "to").unwrap_or_default();
+        let from_known = node_types.contains_key(from);
+        let to_known = node_types.contains_key(to);
+        if !from_known {
+            errors.push(format!(
+                "Edge {} references unknown source node: {}",
+                edge_id, from
+            ));
+        }
+        if !to_known {
+            errors.push(format!(
+                "Edge {} references unknown target node: {}",
+                edge_id, to
+            ));
+        }
+        if from_known && to_known {
+            outgoing.entry(from).or_default().push(to);
+        }
+    }
+
+    // Reachability from every start node.
+    let mut reachable: HashSet<&str> = HashSet::new();
+    let mut queue: Vec<&str> = start_nodes.clone();
+    while let Some(id) = queue.pop() {
+        if reachable.insert(id) {
+            queue.extend(outgoing.get(id).into_iter().flatten().copied());
+        }
+    }
+    if !start_nodes.is_empty() {
+        for id in node_ids.iter().filter(|id| !reachable.contains(*id)) {
+            warnings.push(format!("Node {} is unreachable from the start node.", id));
+        }
+    }
+
+    // Cycle detection: iterative DFS, reporting each back edge's cycle once.
+    let mut finished: HashSet<&str> = HashSet::new();
+    let mut reported: HashSet<Vec<&str>> = HashSet::new();
+    for root in &node_ids {
+        if finished.contains(root) {
+            continue;
+        }
+        let mut path: Vec<&str> = vec![root];
+        let mut cursors: Vec<usize> = vec![0];
+        while let Some(&id) = path.last() {
+            let depth = path.len() - 1;
+            let next = outgoing
+                .get(id)
+                .and_then(|targets| targets.get(cursors[depth]))
+                .copied();
+            cursors[depth] += 1;
+            match next {
+                Some(target) if finished.contains(target) => {}
+                Some(target) => {
+                    if let Some(start) = path.iter().position(|entry| *entry == target) {
+                        let cycle = &path[start..];
+                        let mut key = cycle.to_vec();
+                        key.sort_unstable();
+                        if reported.insert(key) {
+                            let description = format!("{} -> {}", cycle.join(" -> "), target);
+                            if cycle.iter().any(|entry| node_types.get(entry) == Some(&"loop")) {
+                                warnings.push(format!(
+                                    "Workflow contains a loop cycle: {}",
+                                    description
+                                ));
+                            } else {
+                                errors.push(format!("Workflow contains a cycle: {}", description));
+                            }
+                        }
+                    } else {
+                        path.push(target);
+                        cursors.push(0);
+                    }
+                }
+                None => {
+                    finished.insert(id);
+                    path.pop();
+                    cursors.pop();
+                }
+            }
+        }
+    }
+
+    WorkflowValidationReport {
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    }
+}
+
+/// Fold the local graph checks into the sidecar's report, dropping messages
+/// both sides produced.
+fn merge_validation_reports(
+    mut report: WorkflowValidationReport,
+    graph: WorkflowValidationReport,
+) -> WorkflowValidationReport {
+    for error in graph.errors {
+        if !report.errors.contains(&error) {
+            report.errors.push(error);
+        }
+    }
+    for warning in graph.warnings {
+        if !report.warnings.contains(&warning) {
+            report.warnings.push(warning);
+        }
+    }
+    report.valid = report.valid && report.errors.is_empty();
+    report
+}
+
+async fn validate_workflow(
+    state: &AgentState,
+    definition: serde_json::Value,
+) -> Result<WorkflowValidationReport, String> {
+    let graph = validate_workflow_graph(&definition);
+
+    let report = match state
+        .manager
+        .send_command("workflow_validate", definition)
+        .await
+    {
+        Ok(result) => serde_json::from_value(result)
+            .map_err(|e| format!("Failed to parse workflow validation report: {}", e))?,
+        Err(e) => {
+            eprintln!("[workflow] Sidecar validation failed, using graph checks only: {}", e);
+            WorkflowValidationReport {
+                valid: true,
+                errors: Vec::new(),
+                warnings: vec![format!("Semantic validation unavailable: {}", e)],
+            }
+        }
+    };
+
+    Ok(merge_validation_reports(report, graph))
+}
+
+#[tauri::command]
+pub async fn workflow_list(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<WorkflowDefinition>, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+    list_workflows(&state, limit, offset).await
+}
+
+#[tauri::command]
+pub async fn workflow_get(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    workflow_id: String,
+    version: Option<u32>,
+) -> Result<Option<WorkflowDefinition>, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+    get_workflow(&state, &workflow_id, version).await
+}
+
 #[tauri::command]
 pub async fn workflow_create_draft(
     app: AppHandle,
@@ -166,9 +372,7 @@ pub async fn workflow_create_draft(
     input: serde_json::Value,
 ) -> Result<WorkflowDefinition, String> {
     ensure_sidecar_started_public(&app, &state).await?;
-
-    let result = state.manager.send_command("workflow_create_draft", input).await?;
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow draft: {}", e))
+    create_workflow_draft(&state, input).await
 }
 
 #[tauri::command]
@@ -215,15 +419,473 @@ pub async fn workflow_validate(
     state: State<'_, AgentState>,
     definition: serde_json::Value,
 ) -> Result<WorkflowValidationReport, String> {
+    if let Err(e) = ensure_sidecar_started_public(&app, &state).await {
+        let mut report = validate_workflow_graph(&definition);
+        report
+            .warnings
+            .push(format!("Semantic validation unavailable: {}", e));
+        return Ok(report);
+    }
+    validate_workflow(&state, definition).await
+}
+
+/// Schema version stamped on exported workflow bundles.
+const WORKFLOW_BUNDLE_SCHEMA_VERSION: u32 = 1;
+/// Page size used when scanning existing workflow names during import.
+const WORKFLOW_IMPORT_NAME_SCAN_LIMIT: u32 = 1000;
+
+/// The portable part of a workflow definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowBundleDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub triggers: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub nodes: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub edges: Vec<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub defaults: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowBundle {
+    pub schema_version: u32,
+    pub exported_at: i64,
+    #[serde(default)]
+    pub source_workflow_id: Option<String>,
+    #[serde(default)]
+    pub source_version: Option<i64>,
+    pub workflow: WorkflowBundleDefinition,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowImportResult {
+    pub workflow: WorkflowDefinition,
+    /// True when an existing workflow with the same name was kept instead.
+    pub skipped: bool,
+    pub renamed_from: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn parse_workflow_bundle(bundle: serde_json::Value) -> Result<WorkflowBundle, String> {
+    let schema_version = bundle
+        .get("schemaVersion")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Workflow bundle is missing schemaVersion".to_string())?;
+    if schema_version != WORKFLOW_BUNDLE_SCHEMA_VERSION as u64 {
+        return Err(format!(
+            "Unsupported workflow bundle schema version {} (expected {})",
+            schema_version, WORKFLOW_BUNDLE_SCHEMA_VERSION
+        ));
+    }
+    let bundle: WorkflowBundle = serde_json::from_value(bundle)
+        .map_err(|e| format!("Invalid workflow bundle: {}", e))?;
+    if bundle.workflow.name.trim().is_empty() {
+        return Err("Workflow bundle has an empty name".to_string());
+    }
+    Ok(bundle)
+}
+
+/// First free name of the form `name (imported)`, `name (imported 2)`, ...
+fn unique_workflow_name(name: &str, taken: &HashSet<String>) -> String {
+    let mut candidate = format!("{} (imported)", name);
+    let mut index = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{} (imported {})", name, index);
+        index += 1;
+    }
+    candidate
+}
+
+/// Package a workflow definition as a portable, versioned bundle
+#[tauri::command]
+pub async fn workflow_export(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    workflow_id: String,
+    version: Option<u32>,
+) -> Result<WorkflowBundle, String> {
     ensure_sidecar_started_public(&app, &state).await?;
 
-    let result = state
-        .manager
-        .send_command("workflow_validate", definition)
-        .await?;
+    let definition = get_workflow(&state, &workflow_id, version)
+        .await?
+        .ok_or_else(|| format!("Workflow not found: {}", workflow_id))?;
 
-    serde_json::from_value(result)
-        .map_err(|e| format!("Failed to parse workflow validation report: {}", e))
+    Ok(WorkflowBundle {
+        schema_version: WORKFLOW_BUNDLE_SCHEMA_VERSION,
+        exported_at: now_millis(),
+        source_workflow_id: Some(definition.id),
+        source_version: Some(definition.version),
+        workflow: WorkflowBundleDefinition {
+            name: definition.name,
+            description: definition.description,
+            tags: definition.tags,
+            triggers: definition.triggers,
+            nodes: definition.nodes,
+            edges: definition.edges,
+            defaults: definition.defaults,
+            permissions_profile: definition.permissions_profile,
+        },
+    })
+}
+
+/// Validate a workflow bundle and create it as a new draft.
+///
+/// `conflict_strategy` decides what happens when a workflow with the same
+/// name exists: `rename` (default) appends a suffix, `skip` keeps the
+/// existing workflow, and `duplicate` imports under the same name.
+#[tauri::command]
+pub async fn workflow_import(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    bundle: serde_json::Value,
+    conflict_strategy: Option<String>,
+) -> Result<WorkflowImportResult, String> {
+    let strategy = conflict_strategy.unwrap_or_else(|| "rename".to_string());
+    if !matches!(strategy.as_str(), "rename" | "skip" | "duplicate") {
+        return Err(format!(
+            "Unknown conflict strategy `{}`. Expected rename, skip or duplicate",
+            strategy
+        ));
+    }
+    let bundle = parse_workflow_bundle(bundle)?;
+    let mut workflow = bundle.workflow;
+
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    // Validate under a throwaway id; the draft gets a fresh id on creation.
+    let now = now_millis();
+    let mut candidate = serde_json::to_value(&workflow)
+        .map_err(|e| format!("Failed to serialize workflow bundle: {}", e))?;
+    if let Some(fields) = candidate.as_object_mut() {
+        fields.insert("id".to_string(), serde_json::json!(format!("import-{}", now)));
+        fields.insert("version".to_string(), serde_json::json!(1));
+        fields.insert("status".to_string(), serde_json::json!("draft"));
+        fields.insert("createdAt".to_string(), serde_json::json!(now));
+        fields.insert("updatedAt".to_string(), serde_json::json!(now));
+    }
+    let report = validate_workflow(&state, candidate).await?;
+    if !report.valid {
+        return Err(format!(
+            "Workflow bundle failed validation: {}",
+            report.errors.join("; ")
+        ));
+    }
+
+    let existing = list_workflows(&state, Some(WORKFLOW_IMPORT_NAME_SCAN_LIMIT), None).await?;
+    let mut renamed_from = None;
+    if let Some(conflict) = existing.iter().find(|w| w.name == workflow.name) {
+        match strategy.as_str() {
+            "skip" => {
+                return Ok(WorkflowImportResult {
+                    workflow: conflict.clone(),
+                    skipped: true,
+                    renamed_from: None,
+                    warnings: report.warnings,
+                });
+            }
+            "rename" => {
+                let taken: HashSet<String> = existing.iter().map(|w| w.name.clone()).collect();
+                let renamed = unique_workflow_name(&workflow.name, &taken);
+                renamed_from = Some(std::mem::replace(&mut workflow.name, renamed));
+            }
+            _ => {}
+        }
+    }
+
+    let input = serde_json::to_value(&workflow)
+        .map_err(|e| format!("Failed to serialize workflow draft: {}", e))?;
+    let created = create_workflow_draft(&state, input).await?;
+
+    Ok(WorkflowImportResult {
+        workflow: created,
+        skipped: false,
+        renamed_from,
+        warnings: report.warnings,
+    })
+}
+
+/// One changed value, addressed by a dotted path.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowFieldChange {
+    pub path: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// An added, removed, or modified trigger, node, or edge.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowItemChange {
+    /// `added`, `removed`, or `modified`.
+    pub change: String,
+    /// Id in the newer version, or in the older one for removed items.
+    pub id: String,
+    /// Id in the older version when an item was matched despite a new id.
+    pub previous_id: Option<String>,
+    /// `id` or `heuristic` for modified items.
+    pub matched_by: Option<String>,
+    pub fields: Vec<WorkflowFieldChange>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowDiffReport {
+    pub workflow_id: String,
+    pub from_version: i64,
+    pub to_version: i64,
+    pub has_changes: bool,
+    /// Name, description, tags, and permissions profile changes.
+    pub metadata: Vec<WorkflowFieldChange>,
+    pub triggers: Vec<WorkflowItemChange>,
+    pub nodes: Vec<WorkflowItemChange>,
+    pub edges: Vec<WorkflowItemChange>,
+    pub defaults: Vec<WorkflowFieldChange>,
+}
+
+fn json_item_id(item: &serde_json::Value) -> Option<&str> {
+    item.get("id")?.as_str()
+}
+
+fn json_str_field<'a>(item: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    item.get(key)?.as_str()
+}
+
+fn join_diff_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Recursively compare two JSON values, descending into objects and treating
+/// arrays and scalars as atomic. Null and missing are considered equal.
+fn diff_json_values(
+    path: &str,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+    changes: &mut Vec<WorkflowFieldChange>,
+) {
+    let before = before.filter(|value| !value.is_null());
+    let after = after.filter(|value| !value.is_null());
+    match (before, after) {
+        (Some(serde_json::Value::Object(a)), Some(serde_json::Value::Object(b))) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                diff_json_values(&join_diff_path(path, key), a.get(key), b.get(key), changes);
+            }
+        }
+        (a, b) if a == b => {}
+        (a, b) => changes.push(WorkflowFieldChange {
+            path: path.to_string(),
+            before: a.cloned(),
+            after: b.cloned(),
+        }),
+    }
+}
+
+/// Field-level changes between two items, ignoring their ids.
+fn diff_item_fields(before: &serde_json::Value, after: &serde_json::Value) -> Vec<WorkflowFieldChange> {
+    let strip_id = |value: &serde_json::Value| {
+        let mut value = value.clone();
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("id");
+        }
+        value
+    };
+    let mut changes = Vec::new();
+    diff_json_values("", Some(&strip_id(before)), Some(&strip_id(after)), &mut changes);
+    changes
+}
+
+/// Diff two lists of id-bearing items. Items are paired by id first; items
+/// left over on both sides are then paired with `same_item`. Returns the
+/// changes and a map from old to new id for heuristically matched items.
+fn diff_workflow_items(
+    before: &[serde_json::Value],
+    after: &[serde_json::Value],
+    same_item: impl Fn(&serde_json::Value, &serde_json::Value) -> bool,
+) -> (Vec<WorkflowItemChange>, HashMap<String, String>) {
+    let after_by_id: HashMap<&str, &serde_json::Value> = after
+        .iter()
+        .filter_map(|item| Some((json_item_id(item)?, item)))
+        .collect();
+    let before_ids: HashSet<&str> = before.iter().filter_map(json_item_id).collect();
+
+    let mut changes = Vec::new();
+    let mut renamed = HashMap::new();
+    let mut unmatched_before = Vec::new();
+
+    for item in before {
+        match json_item_id(item).and_then(|id| after_by_id.get(id).map(|a| (id, *a))) {
+            Some((id, counterpart)) => {
+                let fields = diff_item_fields(item, counterpart);
+                if !fields.is_empty() {
+                    changes.push(WorkflowItemChange {
+                        change: "modified".to_string(),
+                        id: id.to_string(),
+                        previous_id: None,
+                        matched_by: Some("id".to_string()),
+                        fields,
+                        before: Some(item.clone()),
+                        after: Some(counterpart.clone()),
+                    });
+                }
+            }
+            None => unmatched_before.push(item),
+        }
+    }
+
+    let mut unmatched_after: Vec<&serde_json::Value> = after
+        .iter()
+        .filter(|item| json_item_id(item).is_none_or(|id| !before_ids.contains(id)))
+        .collect();
+
+    for item in unmatched_before {
+        let old_id = json_item_id(item).unwrap_or_default().to_string();
+        match unmatched_after.iter().position(|candidate| same_item(item, candidate)) {
+            Some(index) => {
+                let counterpart = unmatched_after.remove(index);
+                let new_id = json_item_id(counterpart).unwrap_or_default().to_string();
+                renamed.insert(old_id.clone(), new_id.clone());
+                changes.push(WorkflowItemChange {
+                    change: "modified".to_string(),
+                    id: new_id,
+                    previous_id: Some(old_id),
+                    matched_by: Some("heuristic".to_string()),
+                    fields: diff_item_fields(item, counterpart),
+                    before: Some(item.clone()),
+                    after: Some(counterpart.clone()),
+                });
+            }
+            None => changes.push(WorkflowItemChange {
+                change: "removed".to_string(),
+                id: old_id,
+                previous_id: None,
+                matched_by: None,
+                fields: Vec::new(),
+                before: Some(item.clone()),
+                after: None,
+            }),
+        }
+    }
+
+    for item in unmatched_after {
+        changes.push(WorkflowItemChange {
+            change: "added".to_string(),
+            id: json_item_id(item).unwrap_or_default().to_string(),
+            previous_id: None,
+            matched_by: None,
+            fields: Vec::new(),
+            before: None,
+            after: Some(item.clone()),
+        });
+    }
+
+    (changes, renamed)
+}
+
+/// Structural diff between two versions of a workflow.
+fn diff_workflow_definitions(from: &WorkflowDefinition, to: &WorkflowDefinition) -> WorkflowDiffReport {
+    let mut metadata = Vec::new();
+    let metadata_fields = [
+        ("name", serde_json::json!(from.name), serde_json::json!(to.name)),
+        ("description", serde_json::json!(from.description), serde_json::json!(to.description)),
+        ("tags", serde_json::json!(from.tags), serde_json::json!(to.tags)),
+        (
+            "permissionsProfile",
+            serde_json::json!(from.permissions_profile),
+            serde_json::json!(to.permissions_profile),
+        ),
+    ];
+    for (path, before, after) in &metadata_fields {
+        diff_json_values(path, Some(before), Some(after), &mut metadata);
+    }
+
+    // Triggers with new ids are the same trigger if their type matches.
+    let (triggers, _) = diff_workflow_items(&from.triggers, &to.triggers, |a, b| {
+        json_str_field(a, "type") == json_str_field(b, "type")
+    });
+
+    // Nodes with new ids match on type plus either name or config.
+    let (nodes, renamed_nodes) = diff_workflow_items(&from.nodes, &to.nodes, |a, b| {
+        json_str_field(a, "type") == json_str_field(b, "type")
+            && (json_str_field(a, "name") == json_str_field(b, "name")
+                || a.get("config") == b.get("config"))
+    });
+
+    // Edges with new ids match when they join the same nodes, following
+    // any node id changes found above.
+    let remap = |id: Option<&str>| -> Option<String> {
+        id.map(|id| renamed_nodes.get(id).cloned().unwrap_or_else(|| id.to_string()))
+    };
+    let (edges, _) = diff_workflow_items(&from.edges, &to.edges, |a, b| {
+        remap(json_str_field(a, "from")).as_deref() == json_str_field(b, "from")
+            && remap(json_str_field(a, "to")).as_deref() == json_str_field(b, "to")
+    });
+
+    let mut defaults = Vec::new();
+    diff_json_values("", Some(&from.defaults), Some(&to.defaults), &mut defaults);
+
+    let has_changes = !(metadata.is_empty()
+        && triggers.is_empty()
+        && nodes.is_empty()
+        && edges.is_empty()
+        && defaults.is_empty());
+
+    WorkflowDiffReport {
+        workflow_id: to.id.clone(),
+        from_version: from.version,
+        to_version: to.version,
+        has_changes,
+        metadata,
+        triggers,
+        nodes,
+        edges,
+        defaults,
+    }
+}
+
+/// Compare two versions of a workflow
+#[tauri::command]
+pub async fn workflow_diff(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    workflow_id: String,
+    from_version: u32,
+    to_version: u32,
+) -> Result<WorkflowDiffReport, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    let from = get_workflow(&state, &workflow_id, Some(from_version))
+        .await?
+        .ok_or_else(|| format!("Workflow {} version {} not found", workflow_id, from_version))?;
+    let to = get_workflow(&state, &workflow_id, Some(to_version))
+        .await?
+        .ok_or_else(|| format!("Workflow {} version {} not found", workflow_id, to_version))?;
+
+    Ok(diff_workflow_definitions(&from, &to))
 }
 
 #[tauri::command]
@@ -303,16 +965,11 @@ pub async fn workflow_list_runs(
     serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow runs: {}", e))
 }
 
-#[tauri::command]
-pub async fn workflow_get_run(
-    app: AppHandle,
-    state: State<'_, AgentState>,
-    run_id: String,
+async fn get_workflow_run(
+    manager: &SidecarManager,
+    run_id: &str,
 ) -> Result<WorkflowRunDetails, String> {
-    ensure_sidecar_started_public(&app, &state).await?;
-
-    let result = state
-        .manager
+    let result = manager
         .send_command(
             "workflow_get_run",
             serde_json::json!({ "runId": run_id }),
@@ -322,6 +979,34 @@ pub async fn workflow_get_run(
     serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow run details: {}", e))
 }
 
+async fn get_workflow_run_events(
+    manager: &SidecarManager,
+    run_id: &str,
+    since_ts: Option<i64>,
+) -> Result<Vec<WorkflowEvent>, String> {
+    let result = manager
+        .send_command(
+            "workflow_get_run_events",
+            serde_json::json!({
+                "runId": run_id,
+                "sinceTs": since_ts,
+            }),
+        )
+        .await?;
+
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow events: {}", e))
+}
+
+#[tauri::command]
+pub async fn workflow_get_run(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    run_id: String,
+) -> Result<WorkflowRunDetails, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+    get_workflow_run(&state.manager, &run_id).await
+}
+
 #[tauri::command]
 pub async fn workflow_get_run_events(
     app: AppHandle,
@@ -330,19 +1015,294 @@ pub async fn workflow_get_run_events(
     since_ts: Option<i64>,
 ) -> Result<Vec<WorkflowEvent>, String> {
     ensure_sidecar_started_public(&app, &state).await?;
+    get_workflow_run_events(&state.manager, &run_id, since_ts).await
+}
+
+/// Node a retry should start from: the run's current node when it failed,
+/// otherwise the most recent failed node run.
+fn failed_node_id(details: &WorkflowRunDetails) -> Option<String> {
+    if matches!(details.run.status.as_str(), "failed" | "failed_recoverable") {
+        if let Some(node_id) = &details.run.current_node_id {
+            return Some(node_id.clone());
+        }
+    }
+    details
+        .node_runs
+        .iter()
+        .filter(|node_run| node_run.get("status").and_then(|v| v.as_str()) == Some("failed"))
+        .max_by_key(|node_run| {
+            node_run
+                .get("completedAt")
+                .or_else(|| node_run.get("startedAt"))
+                .and_then(|v| v.as_i64())
+                .unwrap_or_default()
+        })
+        .and_then(|node_run| json_str_field(node_run, "nodeId"))
+        .map(str::to_string)
+}
+
+/// Start a new run of a failed run's workflow version, resuming at
+/// `from_node_id` (defaults to the node that failed). The sidecar copies the
+/// already-succeeded upstream node runs into the new run so they are not
+/// executed again.
+#[tauri::command]
+pub async fn workflow_retry_run(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    run_id: String,
+    from_node_id: Option<String>,
+) -> Result<WorkflowRun, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    let details = get_workflow_run(&state.manager, &run_id).await?;
+    let run = &details.run;
+    if !WORKFLOW_TERMINAL_RUN_STATUSES.contains(&run.status.as_str()) {
+        return Err(format!(
+            "Workflow run {} is still {}; cancel it before retrying",
+            run_id, run.status
+        ));
+    }
+
+    let node_id = match from_node_id.filter(|id| !id.trim().is_empty()) {
+        Some(node_id) => node_id,
+        None => failed_node_id(&details)
+            .ok_or_else(|| format!("Workflow run {} has no failed node to retry from", run_id))?,
+    };
+
+    let version = u32::try_from(run.workflow_version)
+        .map_err(|_| format!("Invalid workflow version: {}", run.workflow_version))?;
+    let definition = get_workflow(&state, &run.workflow_id, Some(version))
+        .await?
+        .ok_or_else(|| {
+            format!(
+                "Workflow {} version {} not found",
+                run.workflow_id, run.workflow_version
+            )
+        })?;
+    if !definition
+        .nodes
+        .iter()
+        .any(|node| json_item_id(node) == Some(node_id.as_str()))
+    {
+        return Err(format!(
+            "Node {} does not exist in workflow {} version {}",
+            node_id, run.workflow_id, run.workflow_version
+        ));
+    }
 
     let result = state
         .manager
         .send_command(
-            "workflow_get_run_events",
+            "workflow_retry_run",
             serde_json::json!({
                 "runId": run_id,
-                "sinceTs": since_ts,
+                "fromNodeId": node_id,
             }),
         )
-        .await?;
+        .await
+        .map_err(|e| {
+            if e.contains("Unknown command") {
+                "Retrying from a node is not supported by this sidecar version".to_string()
+            } else {
+                e
+            }
+        })?;
 
-    serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow events: {}", e))
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse workflow run: {}", e))
+}
+
+const WORKFLOW_TERMINAL_RUN_STATUSES: [&str; 4] =
+    ["completed", "failed", "cancelled", "failed_recoverable"];
+const WORKFLOW_TERMINAL_EVENT_TYPES: [&str; 3] = ["run_completed", "run_failed", "run_cancelled"];
+const WORKFLOW_SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Re-check the run status every this many polls in case a terminal event
+/// was never recorded.
+const WORKFLOW_SUBSCRIPTION_STATUS_CHECK_EVERY: u32 = 20;
+const WORKFLOW_SUBSCRIPTION_MAX_FAILURES: u32 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRunSubscription {
+    pub run_id: String,
+    pub subscription_id: u64,
+    /// True when the run had already finished and its events were replayed
+    /// once instead of starting a live subscription.
+    pub replayed: bool,
+}
+
+/// Live subscriptions keyed by run id, holding the owning subscription id.
+static WORKFLOW_RUN_SUBSCRIPTIONS: OnceLock<StdMutex<HashMap<String, u64>>> = OnceLock::new();
+static WORKFLOW_SUBSCRIPTION_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+fn workflow_run_subscriptions() -> &'static StdMutex<HashMap<String, u64>> {
+    WORKFLOW_RUN_SUBSCRIPTIONS.get_or_init(|| StdMutex::new(HashMap::new()))
+}
+
+fn workflow_subscription_active(run_id: &str, subscription_id: u64) -> bool {
+    workflow_run_subscriptions()
+        .lock()
+        .map(|subscriptions| subscriptions.get(run_id) == Some(&subscription_id))
+        .unwrap_or(false)
+}
+
+async fn dispatch_workflow_run_event(manager: &SidecarManager, event: &WorkflowEvent) {
+    manager
+        .dispatch_event(SidecarEvent {
+            event_type: "workflow:run_event".to_string(),
+            session_id: None,
+            data: serde_json::to_value(event).unwrap_or_default(),
+        })
+        .await;
+}
+
+async fn dispatch_workflow_subscription_ended(
+    manager: &SidecarManager,
+    run_id: &str,
+    reason: &str,
+    status: Option<&str>,
+) {
+    manager
+        .dispatch_event(SidecarEvent {
+            event_type: "workflow:run_subscription_ended".to_string(),
+            session_id: None,
+            data: serde_json::json!({
+                "runId": run_id,
+                "reason": reason,
+                "status": status,
+            }),
+        })
+        .await;
+}
+
+async fn run_workflow_subscription(
+    manager: Arc<SidecarManager>,
+    run_id: String,
+    subscription_id: u64,
+    mut seen: HashSet<String>,
+    mut cursor: Option<i64>,
+) {
+    let mut polls: u32 = 0;
+    let mut failures: u32 = 0;
+
+    let (reason, status) = loop {
+        tokio::time::sleep(WORKFLOW_SUBSCRIPTION_POLL_INTERVAL).await;
+        if !workflow_subscription_active(&run_id, subscription_id) {
+            break ("unsubscribed", None);
+        }
+        polls = polls.wrapping_add(1);
+
+        let events = match get_workflow_run_events(&manager, &run_id, cursor).await {
+            Ok(events) => {
+                failures = 0;
+                events
+            }
+            Err(e) => {
+                failures += 1;
+                eprintln!("[workflow] Run event poll for {} failed: {}", run_id, e);
+                if failures >= WORKFLOW_SUBSCRIPTION_MAX_FAILURES {
+                    break ("error", None);
+                }
+                continue;
+            }
+        };
+
+        let mut terminal_event: Option<String> = None;
+        for event in events {
+            // The cursor is inclusive, so events at the cursor repeat.
+            if !seen.insert(event.id.clone()) {
+                continue;
+            }
+            cursor = Some(cursor.map_or(event.ts, |ts| ts.max(event.ts)));
+            if WORKFLOW_TERMINAL_EVENT_TYPES.contains(&event.event_type.as_str()) {
+                terminal_event = Some(event.event_type.clone());
+            }
+            dispatch_workflow_run_event(&manager, &event).await;
+        }
+        if let Some(event_type) = terminal_event {
+            break ("terminal", Some(event_type.trim_start_matches("run_").to_string()));
+        }
+
+        if polls.is_multiple_of(WORKFLOW_SUBSCRIPTION_STATUS_CHECK_EVERY) {
+            if let Ok(details) = get_workflow_run(&manager, &run_id).await {
+                if WORKFLOW_TERMINAL_RUN_STATUSES.contains(&details.run.status.as_str()) {
+                    break ("terminal", Some(details.run.status));
+                }
+            }
+        }
+    };
+
+    if let Ok(mut subscriptions) = workflow_run_subscriptions().lock() {
+        if subscriptions.get(&run_id) == Some(&subscription_id) {
+            subscriptions.remove(&run_id);
+        }
+    }
+    dispatch_workflow_subscription_ended(&manager, &run_id, reason, status.as_deref()).await;
+}
+
+/// Stream a run's events as `workflow:run_event` events until it finishes or
+/// the caller unsubscribes. Stored events are sent first, so subscribing to a
+/// finished run replays its history once.
+#[tauri::command]
+pub async fn workflow_subscribe_run_events(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    run_id: String,
+) -> Result<WorkflowRunSubscription, String> {
+    ensure_sidecar_started_public(&app, &state).await?;
+
+    let details = get_workflow_run(&state.manager, &run_id).await?;
+    let subscription_id = WORKFLOW_SUBSCRIPTION_COUNTER.fetch_add(1, Ordering::SeqCst);
+
+    let mut seen = HashSet::new();
+    let mut cursor: Option<i64> = None;
+    for event in &details.events {
+        seen.insert(event.id.clone());
+        cursor = Some(cursor.map_or(event.ts, |ts| ts.max(event.ts)));
+        dispatch_workflow_run_event(&state.manager, event).await;
+    }
+
+    if WORKFLOW_TERMINAL_RUN_STATUSES.contains(&details.run.status.as_str()) {
+        dispatch_workflow_subscription_ended(
+            &state.manager,
+            &run_id,
+            "terminal",
+            Some(&details.run.status),
+        )
+        .await;
+        return Ok(WorkflowRunSubscription {
+            run_id,
+            subscription_id,
+            replayed: true,
+        });
+    }
+
+    workflow_run_subscriptions()
+        .lock()
+        .map_err(|_| "Workflow subscription registry is unavailable".to_string())?
+        .insert(run_id.clone(), subscription_id);
+    tauri::async_runtime::spawn(run_workflow_subscription(
+        state.manager.clone(),
+        run_id.clone(),
+        subscription_id,
+        seen,
+        cursor,
+    ));
+
+    Ok(WorkflowRunSubscription {
+        run_id,
+        subscription_id,
+        replayed: false,
+    })
+}
+
+/// Stop streaming events for a run. Returns false when no subscription existed.
+#[tauri::command]
+pub async fn workflow_unsubscribe_run_events(run_id: String) -> Result<bool, String> {
+    let removed = workflow_run_subscriptions()
+        .lock()
+        .map_err(|_| "Workflow subscription registry is unavailable".to_string())?
+        .remove(&run_id);
+    Ok(removed.is_some())
 }
 
 #[tauri::command]
@@ -521,3 +1481,285 @@ pub async fn workflow_evaluate_triggers(
         )
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(
+        version: i64,
+        nodes: serde_json::Value,
+        edges: serde_json::Value,
+    ) -> WorkflowDefinition {
+        serde_json::from_value(json!({
+            "id": "wf-1",
+            "version": version,
+            "status": "draft",
+            "name": "Daily digest",
+            "triggers": [{ "id": "t1", "type": "manual" }],
+            "nodes": nodes,
+            "edges": edges,
+            "defaults": { "maxRunTimeMs": 1000 },
+            "createdAt": 0,
+            "updatedAt": 0
+        }))
+        .unwrap()
+    }
+
+    fn base_nodes() -> serde_json::Value {
+        json!([
+            { "id": "start", "type": "start", "name": "Start" },
+            { "id": "fetch", "type": "tool", "name": "Fetch", "config": { "url": "a" } },
+            { "id": "end", "type": "end", "name": "End" }
+        ])
+    }
+
+    fn base_edges() -> serde_json::Value {
+        json!([
+            { "id": "e1", "from": "start", "to": "fetch" },
+            { "id": "e2", "from": "fetch", "to": "end" }
+        ])
+    }
+
+    #[test]
+    fn identical_versions_have_no_changes() {
+        let from = definition(1, base_nodes(), base_edges());
+        let to = definition(2, base_nodes(), base_edges());
+        let report = diff_workflow_definitions(&from, &to);
+        assert!(!report.has_changes);
+        assert_eq!((report.from_version, report.to_version), (1, 2));
+    }
+
+    #[test]
+    fn added_removed_and_modified_nodes_are_matched_by_id() {
+        let from = definition(1, base_nodes(), base_edges());
+        let to = definition(
+            2,
+            json!([
+                { "id": "start", "type": "start", "name": "Start" },
+                { "id": "fetch", "type": "tool", "name": "Fetch", "config": { "url": "b" } },
+                { "id": "notify", "type": "notification", "name": "Notify" }
+            ]),
+            json!([{ "id": "e1", "from": "start", "to": "fetch" }]),
+        );
+        let report = diff_workflow_definitions(&from, &to);
+
+        let change = |id: &str| {
+            report
+                .nodes
+                .iter()
+                .find(|change| change.id == id)
+                .map(|change| change.change.as_str())
+        };
+        assert_eq!(change("fetch"), Some("modified"));
+        assert_eq!(change("end"), Some("removed"));
+        assert_eq!(change("notify"), Some("added"));
+
+        let fetch = report
+            .nodes
+            .iter()
+            .find(|change| change.id == "fetch")
+            .unwrap();
+        assert_eq!(fetch.matched_by.as_deref(), Some("id"));
+        assert_eq!(fetch.fields.len(), 1);
+        assert_eq!(fetch.fields[0].path, "config.url");
+
+        assert_eq!(report.edges.len(), 1);
+        assert_eq!(report.edges[0].change, "removed");
+        assert_eq!(report.edges[0].id, "e2");
+    }
+
+    #[test]
+    fn renamed_node_ids_fall_back_to_the_heuristic_and_carry_edges() {
+        let from = definition(1, base_nodes(), base_edges());
+        let to = definition(
+            2,
+            json!([
+                { "id": "start", "type": "start", "name": "Start" },
+                { "id": "fetch-v2", "type": "tool", "name": "Fetch", "config": { "url": "a" } },
+                { "id": "end", "type": "end", "name": "End" }
+            ]),
+            json!([
+                { "id": "e1b", "from": "start", "to": "fetch-v2" },
+                { "id": "e2b", "from": "fetch-v2", "to": "end" }
+            ]),
+        );
+        let report = diff_workflow_definitions(&from, &to);
+
+        assert_eq!(report.nodes.len(), 1);
+        let node = &report.nodes[0];
+        assert_eq!(node.change, "modified");
+        assert_eq!(node.id, "fetch-v2");
+        assert_eq!(node.previous_id.as_deref(), Some("fetch"));
+        assert_eq!(node.matched_by.as_deref(), Some("heuristic"));
+        assert!(node.fields.is_empty());
+
+        assert_eq!(report.edges.len(), 2);
+        assert!(report.edges.iter().all(
+            |edge| edge.change == "modified" && edge.matched_by.as_deref() == Some("heuristic")
+        ));
+    }
+
+    #[test]
+    fn trigger_defaults_and_metadata_changes_are_reported() {
+        let from = definition(1, base_nodes(), base_edges());
+        let mut to = definition(2, base_nodes(), base_edges());
+        to.name = "Weekly digest".to_string();
+        to.triggers = vec![json!({ "id": "t1", "type": "schedule" })];
+        to.defaults = json!({ "maxRunTimeMs": 2000, "retries": 1 });
+
+        let report = diff_workflow_definitions(&from, &to);
+        assert!(report.has_changes);
+        assert_eq!(report.metadata.len(), 1);
+        assert_eq!(report.metadata[0].path, "name");
+        assert_eq!(report.triggers.len(), 1);
+        assert_eq!(report.triggers[0].fields[0].path, "type");
+
+        let paths: Vec<&str> = report
+            .defaults
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect();
+        assert_eq!(paths, ["maxRunTimeMs", "retries"]);
+    }
+
+    fn graph(nodes: serde_json::Value, edges: serde_json::Value) -> serde_json::Value {
+        json!({ "nodes": nodes, "edges": edges })
+    }
+
+    #[test]
+    fn well_formed_graph_is_valid() {
+        let report = validate_workflow_graph(&graph(base_nodes(), base_edges()));
+        assert!(report.valid, "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn cyclic_graph_is_rejected() {
+        let report = validate_workflow_graph(&graph(
+            base_nodes(),
+            json!([
+                { "id": "e1", "from": "start", "to": "fetch" },
+                { "id": "e2", "from": "fetch", "to": "end" },
+                { "id": "e3", "from": "end", "to": "fetch" }
+            ]),
+        ));
+        assert!(!report.valid);
+        assert_eq!(
+            report.errors,
+            ["Workflow contains a cycle: fetch -> end -> fetch"]
+        );
+    }
+
+    #[test]
+    fn cycle_through_a_loop_node_is_only_a_warning() {
+        let report = validate_workflow_graph(&graph(
+            json!([
+                { "id": "start", "type": "start" },
+                { "id": "repeat", "type": "loop" },
+                { "id": "work", "type": "tool" }
+            ]),
+            json!([
+                { "id": "e1", "from": "start", "to": "repeat" },
+                { "id": "e2", "from": "repeat", "to": "work" },
+                { "id": "e3", "from": "work", "to": "repeat" }
+            ]),
+        ));
+        assert!(report.valid, "{:?}", report.errors);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("Workflow contains a loop cycle"));
+    }
+
+    #[test]
+    fn orphaned_node_is_reported_as_unreachable() {
+        let mut nodes = base_nodes();
+        nodes
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "id": "orphan", "type": "tool" }));
+        let report = validate_workflow_graph(&graph(nodes, base_edges()));
+        assert!(report.valid);
+        assert_eq!(
+            report.warnings,
+            ["Node orphan is unreachable from the start node."]
+        );
+    }
+
+    #[test]
+    fn dangling_edges_and_missing_start_are_errors() {
+        let report = validate_workflow_graph(&graph(
+            json!([{ "id": "work", "type": "tool" }]),
+            json!([{ "id": "e1", "from": "work", "to": "ghost" }]),
+        ));
+        assert!(!report.valid);
+        assert!(report
+            .errors
+            .contains(&"Workflow requires a start node.".to_string()));
+        assert!(report
+            .errors
+            .contains(&"Edge e1 references unknown target node: ghost".to_string()));
+    }
+
+    #[test]
+    fn merged_report_keeps_unique_messages_and_fails_on_graph_errors() {
+        let sidecar = WorkflowValidationReport {
+            valid: true,
+            errors: Vec::new(),
+            warnings: vec!["shared".to_string()],
+        };
+        let local = WorkflowValidationReport {
+            valid: false,
+            errors: vec!["cycle".to_string()],
+            warnings: vec!["shared".to_string()],
+        };
+        let merged = merge_validation_reports(sidecar, local);
+        assert!(!merged.valid);
+        assert_eq!(merged.errors, ["cycle"]);
+        assert_eq!(merged.warnings, ["shared"]);
+    }
+
+    fn run_details(
+        status: &str,
+        current_node_id: Option<&str>,
+        node_runs: serde_json::Value,
+    ) -> WorkflowRunDetails {
+        serde_json::from_value(json!({
+            "run": {
+                "id": "run-1",
+                "workflowId": "wf-1",
+                "workflowVersion": 1,
+                "triggerType": "manual",
+                "status": status,
+                "currentNodeId": current_node_id,
+                "createdAt": 0,
+                "updatedAt": 0
+            },
+            "nodeRuns": node_runs
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn retry_defaults_to_the_node_where_the_run_failed() {
+        let details = run_details("failed", Some("fetch"), json!([]));
+        assert_eq!(failed_node_id(&details).as_deref(), Some("fetch"));
+    }
+
+    #[test]
+    fn retry_falls_back_to_the_latest_failed_node_run() {
+        let details = run_details(
+            "cancelled",
+            Some("end"),
+            json!([
+                { "nodeId": "start", "status": "succeeded", "completedAt": 1 },
+                { "nodeId": "fetch", "status": "failed", "completedAt": 2 },
+                { "nodeId": "notify", "status": "failed", "completedAt": 5 }
+            ]),
+        );
+        assert_eq!(failed_node_id(&details).as_deref(), Some("notify"));
+
+        let details = run_details("completed", None, json!([]));
+        assert_eq!(failed_node_id(&details), None);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+//! Typed command errors. A `CommandError` serializes to
+//! `{ code, message, details }` so the UI can branch on `code` instead of
+//! matching message text. Commands that still return `Result<_, String>` can
+//! use `?` on it and get the plain message, as before.
+//!
+//! | code         | meaning                                              |
+//! |--------------|------------------------------------------------------|
+//! | `auth`       | missing or rejected credentials, locked keychain     |
+//! | `network`    | a remote service could not be reached or failed      |
+//! | `transport`  | the sidecar/daemon connection is down or timed out   |
+//! | `validation` | the caller passed something unusable                 |
+//! | `not_found`  | the thing asked for does not exist or is unsupported |
+//! | `internal`   | anything else, including untyped `String` errors     |
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Auth,
+    Network,
+    Transport,
+    Validation,
+    NotFound,
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    code: ErrorCode,
+    message: String,
+    details: Option<serde_json::Value>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Auth, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Network, message)
+    }
+
+    pub fn transport(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Transport, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Validation, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.message
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::credentials::CredentialLookupError;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_to_code_message_details() {
+        let error = CommandError::not_found("No such session");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "code": "not_found", "message": "No such session", "details": null })
+        );
+
+        let error = CommandError::validation("Bad input").with_details(json!({ "field": "model" }));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "validation",
+                "message": "Bad input",
+                "details": { "field": "model" }
+            })
+        );
+    }
+
+    #[test]
+    fn converts_to_and_from_plain_strings() {
+        let message: String = CommandError::transport("Sidecar is not running").into();
+        assert_eq!(message, "Sidecar is not running");
+
+        let error = CommandError::from("boom".to_string());
+        assert_eq!(error.code(), ErrorCode::Internal);
+        assert_eq!(error.message(), "boom");
+    }
+
+    #[test]
+    fn credential_lookup_errors_map_to_codes_and_reasons() {
+        let cases = [
+            (
+                CredentialLookupError::DecryptFailed("bad tag".to_string()),
+                "auth",
+                "decrypt_failed",
+            ),
+            (
+                CredentialLookupError::KeychainLocked("locked".to_string()),
+                "auth",
+                "keychain_locked",
+            ),
+            (
+                CredentialLookupError::BackendUnavailable("no vault".to_string()),
+                "internal",
+                "backend_unavailable",
+            ),
+        ];
+        for (lookup, code, reason) in cases {
+            let message = lookup.to_string();
+            let value = serde_json::to_value(CommandError::from(lookup)).unwrap();
+            assert_eq!(value["code"], code, "{}", reason);
+            assert_eq!(value["message"], message.as_str(), "{}", reason);
+            assert_eq!(value["details"], json!({ "reason": reason }));
+        }
+    }
+}
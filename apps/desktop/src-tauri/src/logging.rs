@@ -0,0 +1,217 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+//! File-backed `log` implementation writing to `logs/app.log` in the app data
+//! directory (see `paths::app_data_dir`).
+
+use crate::paths;
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "app.log";
+/// Rotate once the active log file grows past this size.
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Number of rotated files kept alongside the active one (app.log.1 ..).
+const MAX_ROTATED_FILES: u32 = 3;
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+/// Overrides the startup level, e.g. `COWORK_LOG_LEVEL=debug`.
+const LOG_LEVEL_ENV_VAR: &str = "COWORK_LOG_LEVEL";
+
+struct LogFile {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf, max_bytes: u64) -> Self {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .ok();
+        let size = file
+            .as_ref()
+            .and_then(|file| file.metadata().ok())
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        Self {
+            path,
+            file,
+            size,
+            max_bytes,
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = self.path.with_extension(format!("log.{}", index));
+            if from.exists() {
+                let _ = fs::rename(
+                    &from,
+                    self.path.with_extension(format!("log.{}", index + 1)),
+                );
+            }
+        }
+        let _ = fs::rename(&self.path, self.path.with_extension("log.1"));
+        *self = Self::open(self.path.clone(), self.max_bytes);
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.size + line.len() as u64 > self.max_bytes {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(line.as_bytes()).is_ok() {
+                self.size += line.len() as u64;
+            }
+        }
+    }
+}
+
+struct FileLogger {
+    file: Option<Mutex<LogFile>>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Dependencies only contribute warnings and errors.
+        let is_own = metadata.target().starts_with(env!("CARGO_CRATE_NAME"));
+        metadata.level() <= log::max_level() && (is_own || metadata.level() <= Level::Warn)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            format_timestamp(SystemTime::now()),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        eprint!("{}", line);
+        if let Some(file) = &self.file {
+            let mut file = match file.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                if let Some(file) = file.file.as_mut() {
+                    let _ = file.flush();
+                }
+            }
+        }
+    }
+}
+
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+
+/// RFC 3339 UTC timestamp with millisecond precision.
+fn format_timestamp(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn log_file_path() -> Option<PathBuf> {
+    paths::app_data_dir()
+        .ok()
+        .map(|dir| dir.join(LOG_DIR).join(LOG_FILE))
+}
+
+pub fn parse_level(value: &str) -> Result<LevelFilter, String> {
+    value.trim().parse::<LevelFilter>().map_err(|_| {
+        format!(
+            "Invalid log level '{}'. Expected off, error, warn, info, debug, or trace.",
+            value
+        )
+    })
+}
+
+/// Install the logger. Falls back to stderr only if the log directory
+/// can't be created.
+pub fn init() {
+    let file = log_file_path().and_then(|path| {
+        let parent = path.parent()?;
+        fs::create_dir_all(parent).ok()?;
+        Some(Mutex::new(LogFile::open(path, MAX_LOG_FILE_BYTES)))
+    });
+    let logger = LOGGER.get_or_init(|| FileLogger { file });
+    if log::set_logger(logger).is_err() {
+        return;
+    }
+
+    let level = match std::env::var(LOG_LEVEL_ENV_VAR) {
+        Ok(value) => parse_level(&value).unwrap_or(DEFAULT_LEVEL),
+        Err(_) => DEFAULT_LEVEL,
+    };
+    log::set_max_level(level);
+    if logger.file.is_none() {
+        log::warn!("Log directory is unavailable; logging to stderr only");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn timestamps_are_rfc3339_utc_with_millis() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let leap_day = UNIX_EPOCH + Duration::from_millis(1_709_210_096_789);
+        assert_eq!(format_timestamp(leap_day), "2024-02-29T12:34:56.789Z");
+    }
+
+    #[test]
+    fn levels_parse_case_insensitively() {
+        assert_eq!(parse_level("debug"), Ok(LevelFilter::Debug));
+        assert_eq!(parse_level(" WARN "), Ok(LevelFilter::Warn));
+        assert_eq!(parse_level("off"), Ok(LevelFilter::Off));
+        assert!(parse_level("verbose")
+            .unwrap_err()
+            .contains("Invalid log level 'verbose'"));
+    }
+
+    #[test]
+    fn full_log_files_rotate_and_old_ones_are_dropped() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir =
+            std::env::temp_dir().join(format!("cowork-logging-{}-{}", std::process::id(), nanos));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LOG_FILE);
+
+        // Two 7-byte lines fit per file.
+        let mut log = LogFile::open(path.clone(), 14);
+        for index in 0..10 {
+            log.write_line(&format!("line-{}\n", index));
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("app.log"), "line-8\nline-9\n");
+        assert_eq!(read("app.log.1"), "line-6\nline-7\n");
+        assert_eq!(read("app.log.2"), "line-4\nline-5\n");
+        assert_eq!(read("app.log.3"), "line-2\nline-3\n");
+        assert!(!dir.join("app.log.4").exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
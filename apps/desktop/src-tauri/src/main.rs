@@ -4,12 +4,20 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod argv;
 mod commands;
+mod error;
+mod logging;
+mod paths;
+mod rate_limit;
+mod redact;
 mod sidecar;
 
 use commands::agent::AgentState;
 
 fn main() {
+    logging::init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
@@ -17,6 +25,7 @@ fn main() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(AgentState::new())
         .invoke_handler(tauri::generate_handler![
             // Auth commands
@@ -39,6 +48,8 @@ fn main() {
             commands::auth::set_tavily_api_key,
             commands::auth::delete_tavily_api_key,
             commands::auth::validate_provider_connection,
+            commands::auth::diagnose_provider,
+            commands::auth::get_curated_models,
             commands::auth::fetch_provider_models,
             commands::auth::get_api_key,
             commands::auth::set_api_key,
@@ -46,13 +57,25 @@ fn main() {
             commands::auth::get_stitch_api_key,
             commands::auth::set_stitch_api_key,
             commands::auth::delete_stitch_api_key,
+            commands::auth::auth_logout_preview,
             commands::auth::auth_logout_and_cleanup,
+            commands::auth::auth_clear_provider_keys,
             commands::auth::auth_get_security_posture,
+            commands::auth::auth_repair_security_posture,
+            commands::auth::auth_rekey_credentials,
+            commands::auth::auth_migrate_credentials_to_keychain,
+            commands::credentials::credentials_read_audit,
+            commands::credentials::credentials_rotate_connector_seed,
+            commands::credentials::keychain_probe,
             commands::auth::validate_api_key,
             commands::auth::fetch_models,
             // File commands
             commands::files::read_file,
             commands::files::write_file,
+            commands::files::file_write_begin,
+            commands::files::file_write_chunk,
+            commands::files::file_write_commit,
+            commands::files::file_write_abort,
             commands::files::list_directory,
             commands::files::open_file_preview,
             // Agent commands
@@ -77,7 +100,11 @@ fn main() {
             commands::agent::agent_set_execution_mode,
             commands::agent::agent_set_models,
             commands::agent::agent_respond_question,
+            commands::agent::agent_list_pending_permissions,
+            commands::agent::agent_list_pending_questions,
             commands::agent::agent_stop_generation,
+            commands::agent::agent_is_busy,
+            commands::agent::diagnose_sidecar_install,
             commands::agent::agent_get_queue,
             commands::agent::agent_remove_from_queue,
             commands::agent::agent_reorder_queue,
@@ -85,8 +112,14 @@ fn main() {
             commands::agent::agent_edit_queued_message,
             commands::agent::agent_list_sessions,
             commands::agent::agent_list_sessions_page,
+            commands::agent::agent_search_sessions,
+            commands::agent::agent_get_sessions_bulk,
             commands::agent::agent_get_session,
             commands::agent::agent_get_session_chunk,
+            commands::agent::agent_export_session,
+            commands::agent::agent_import_session,
+            commands::agent::agent_duplicate_session,
+            commands::agent::agent_cancel_session_duplicate,
             commands::agent::agent_get_bootstrap_state,
             commands::agent::agent_get_events_since,
             commands::agent::agent_subscribe_events,
@@ -96,7 +129,9 @@ fn main() {
             commands::agent::agent_update_session_last_accessed,
             commands::agent::agent_load_memory,
             commands::agent::agent_save_memory,
+            commands::agent::agent_stop_memory_watch,
             commands::agent::agent_get_context_usage,
+            commands::agent::agent_compact_session,
             commands::agent::agent_set_mcp_servers,
             commands::agent::agent_set_skills,
             commands::agent::agent_set_specialized_models,
@@ -104,13 +139,17 @@ fn main() {
             commands::agent::agent_load_gemini_extensions,
             commands::agent::agent_get_initialization_status,
             commands::agent::agent_command,
+            commands::agent::agent_command_raw,
             commands::agent::agent_log_client_diagnostic,
             // Skill commands
             commands::skills::agent_discover_skills,
+            commands::skills::agent_discover_skills_grouped,
             commands::skills::agent_install_skill,
             commands::skills::agent_uninstall_skill,
             commands::skills::agent_check_skill_eligibility,
+            commands::skills::agent_check_skills_eligibility,
             commands::skills::agent_get_skill_content,
+            commands::skills::agent_get_parsed_skill_content,
             commands::skills::agent_create_skill,
             commands::skills::agent_draft_skill_from_session,
             commands::skills::agent_create_skill_from_session,
@@ -133,12 +172,18 @@ fn main() {
             commands::workflow::workflow_create_from_prompt,
             commands::workflow::workflow_update_draft,
             commands::workflow::workflow_validate,
+            commands::workflow::workflow_export,
+            commands::workflow::workflow_import,
+            commands::workflow::workflow_diff,
             commands::workflow::workflow_publish,
             commands::workflow::workflow_archive,
             commands::workflow::workflow_run,
             commands::workflow::workflow_list_runs,
             commands::workflow::workflow_get_run,
             commands::workflow::workflow_get_run_events,
+            commands::workflow::workflow_retry_run,
+            commands::workflow::workflow_subscribe_run_events,
+            commands::workflow::workflow_unsubscribe_run_events,
             commands::workflow::workflow_cancel_run,
             commands::workflow::workflow_pause_run,
             commands::workflow::workflow_resume_run,
@@ -164,7 +209,13 @@ fn main() {
             commands::policy::policy_add_rule,
             commands::policy::policy_remove_rule,
             commands::policy::policy_evaluate,
+            commands::policy::policy_evaluate_batch,
+            commands::policy::policy_get_cache_stats,
+            commands::policy::policy_set_cache_config,
             commands::policy::policy_reset,
+            commands::policy::policy_export,
+            commands::policy::policy_import,
+            commands::policy::policy_apply_template,
             commands::policy::policy_get_profiles,
             commands::policy::policy_get_groups,
             // Deep Agents memory commands
@@ -180,6 +231,8 @@ fn main() {
             commands::deep::deep_memory_export_bundle,
             commands::deep::deep_memory_import_bundle,
             commands::deep::deep_memory_get_migration_report,
+            commands::deep::deep_memory_export,
+            commands::deep::deep_memory_import,
             commands::deep::deep_memory_list_groups,
             commands::deep::deep_memory_create_group,
             commands::deep::deep_memory_delete_group,
@@ -212,8 +265,13 @@ fn main() {
             commands::connectors::get_all_connector_states,
             commands::connectors::connect_all_connectors,
             commands::connectors::disconnect_all_connectors,
+            commands::connectors::set_connector_health_polling,
+            commands::connectors::get_connector_health_polling,
+            commands::connectors::get_connector_reconnect_policy,
+            commands::connectors::set_connector_reconnect_policy,
             // OAuth commands
             commands::connectors::start_connector_oauth_flow,
+            commands::connectors::cancel_connector_oauth_flow,
             commands::connectors::poll_oauth_device_code,
             commands::connectors::get_oauth_status,
             commands::connectors::refresh_oauth_tokens,
@@ -233,8 +291,14 @@ fn main() {
             commands::integrations::agent_integration_get_settings,
             commands::integrations::agent_integration_update_settings,
             commands::integrations::agent_integration_send_test,
+            commands::integrations::agent_integration_get_test_throttle,
+            commands::integrations::agent_integration_set_test_throttle,
             // Remote access commands
             commands::remote_access::remote_access_get_status,
+            commands::remote_access::remote_access_get_diagnostics,
+            commands::remote_access::remote_access_export_diagnostics,
+            commands::remote_access::remote_access_get_pairing_limits,
+            commands::remote_access::remote_access_set_pairing_limits,
             commands::remote_access::remote_access_enable,
             commands::remote_access::remote_access_disable,
             commands::remote_access::remote_access_generate_qr,
@@ -242,6 +306,7 @@ fn main() {
             commands::remote_access::remote_access_revoke_device,
             commands::remote_access::remote_access_set_public_base_url,
             commands::remote_access::remote_access_set_tunnel_mode,
+            commands::remote_access::remote_access_list_tunnel_providers,
             commands::remote_access::remote_access_set_tunnel_options,
             commands::remote_access::remote_access_refresh_tunnel,
             commands::remote_access::remote_access_install_tunnel_binary,
@@ -253,15 +318,28 @@ fn main() {
             commands::service::service_get_mode,
             commands::service::service_set_mode,
             commands::service::service_status,
+            commands::service::agent_get_daemon_config,
             commands::service::service_install,
             commands::service::service_uninstall,
             commands::service::service_start,
             commands::service::service_stop,
             commands::service::service_restart,
+            // Logging commands
+            commands::logging::get_log_settings,
+            commands::logging::set_log_level,
+            // Storage commands
+            commands::storage::diagnose_storage,
+            commands::storage::migrate_data_dir,
+            // Updater commands
+            commands::updater::check_for_updates,
+            commands::updater::apply_update,
+            commands::updater::get_update_status,
+            commands::updater::get_update_preferences,
+            commands::updater::set_update_preferences,
+            commands::updater::set_update_channel,
         ])
         .setup(|app| {
-            // Auto-update disabled until a proper signing key pair is configured
-            let _ = app;
+            commands::updater::start_update_loop(app.handle().clone());
             commands::credentials::credentials_migrate_on_startup()
                 .map_err(|error| format!("Credential migration failed during startup: {}", error))?;
 
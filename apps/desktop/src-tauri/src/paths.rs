@@ -0,0 +1,152 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+//! Where Cowork keeps its data. By default that is `~/.cowork` plus the OS
+//! config directory. `COWORK_DATA_DIR` relocates both under one directory;
+//! a location saved by `migrate_data_dir` relocates the app data only.
+
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex as StdMutex, OnceLock};
+
+pub const DATA_DIR_ENV_VAR: &str = "COWORK_DATA_DIR";
+const DEFAULT_DATA_DIR_NAME: &str = ".cowork";
+/// Under an overridden data dir, what normally lives in the OS config
+/// directory goes here.
+const CONFIG_SUBDIR: &str = "config";
+/// Saved data dir location, kept in the OS config dir so it is found before
+/// the data dir itself is known.
+const DATA_DIR_POINTER_FILE: &str = "data-dir.json";
+const POINTER_APP_DIR_NAME: &str = "cowork";
+
+#[derive(Clone)]
+enum DataDirSource {
+    Default,
+    Env(PathBuf),
+    Saved(PathBuf),
+}
+
+static DATA_DIR_SOURCE: OnceLock<StdMutex<Option<Result<DataDirSource, String>>>> = OnceLock::new();
+
+fn data_dir_source_cache() -> std::sync::MutexGuard<'static, Option<Result<DataDirSource, String>>>
+{
+    let lock = DATA_DIR_SOURCE.get_or_init(|| StdMutex::new(None));
+    match lock.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Absolute, exists (created if needed) and writable.
+pub fn validate_data_dir(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!(
+            "Data directory must be an absolute path, got {}",
+            path.display()
+        ));
+    }
+    fs::create_dir_all(path).map_err(|e| {
+        format!(
+            "Data directory {} could not be created: {}",
+            path.display(),
+            e
+        )
+    })?;
+
+    probe_writable(path)
+        .map_err(|e| format!("Data directory {} is not writable: {}", path.display(), e))
+}
+
+/// Check that files can be created in `path` by writing and removing a probe.
+pub fn probe_writable(path: &Path) -> std::io::Result<()> {
+    let probe = path.join(format!(".cowork-write-probe-{}", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&probe)?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+fn data_dir_pointer_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(POINTER_APP_DIR_NAME).join(DATA_DIR_POINTER_FILE))
+}
+
+fn read_saved_data_dir() -> Option<PathBuf> {
+    let content = fs::read_to_string(data_dir_pointer_path()?).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let path = PathBuf::from(value["dataDir"].as_str()?);
+    path.is_absolute().then_some(path)
+}
+
+fn resolve_data_dir_source() -> Result<DataDirSource, String> {
+    if let Ok(value) = std::env::var(DATA_DIR_ENV_VAR) {
+        if !value.trim().is_empty() {
+            let path = PathBuf::from(value.trim());
+            validate_data_dir(&path).map_err(|e| format!("{}: {}", DATA_DIR_ENV_VAR, e))?;
+            return Ok(DataDirSource::Env(path));
+        }
+    }
+    match read_saved_data_dir() {
+        Some(path) => {
+            validate_data_dir(&path)?;
+            Ok(DataDirSource::Saved(path))
+        }
+        None => Ok(DataDirSource::Default),
+    }
+}
+
+/// Resolved once per run, or again after `save_data_dir`.
+fn data_dir_source() -> Result<DataDirSource, String> {
+    data_dir_source_cache()
+        .get_or_insert_with(resolve_data_dir_source)
+        .clone()
+}
+
+/// Whether `COWORK_DATA_DIR` is in effect; it takes precedence over a saved
+/// location.
+pub fn data_dir_env_override() -> Result<Option<PathBuf>, String> {
+    match data_dir_source()? {
+        DataDirSource::Env(path) => Ok(Some(path)),
+        _ => Ok(None),
+    }
+}
+
+/// App data directory (`~/.cowork` unless relocated). Not created here.
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    match data_dir_source()? {
+        DataDirSource::Env(path) | DataDirSource::Saved(path) => Ok(path),
+        DataDirSource::Default => dirs::home_dir()
+            .map(|home| home.join(DEFAULT_DATA_DIR_NAME))
+            .ok_or_else(|| "Could not determine home directory".to_string()),
+    }
+}
+
+/// Root that the `cowork` config directory is created in: the OS config
+/// directory, or `<data dir>/config` under `COWORK_DATA_DIR`.
+pub fn config_root() -> Result<PathBuf, String> {
+    if let DataDirSource::Env(path) = data_dir_source()? {
+        return Ok(path.join(CONFIG_SUBDIR));
+    }
+    dirs::config_dir().ok_or_else(|| "Could not determine config directory".to_string())
+}
+
+/// Persist a new app data directory and use it for the rest of this run.
+pub fn save_data_dir(path: &Path) -> Result<(), String> {
+    let pointer = data_dir_pointer_path()
+        .ok_or_else(|| "Could not determine config directory".to_string())?;
+    if let Some(parent) = pointer.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "dataDir": path.to_string_lossy(),
+    }))
+    .map_err(|e| format!("Failed to serialize data directory setting: {}", e))?;
+    fs::write(&pointer, content)
+        .map_err(|e| format!("Failed to save data directory setting: {}", e))?;
+
+    *data_dir_source_cache() = Some(Ok(DataDirSource::Saved(path.to_path_buf())));
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+//! Token bucket shared by the throttled commands (pairing QR generation,
+//! integration test messages).
+
+use std::time::{Duration, Instant};
+
+/// Allows `burst` actions back to back, then one more every refill interval.
+/// Limits are passed on each call so changes to them apply immediately.
+pub struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn full(burst: u32) -> Self {
+        Self {
+            tokens: burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take one token, or return how long until the next one is available.
+    pub fn try_acquire(&mut self, burst: u32, refill: Duration) -> Result<(), Duration> {
+        self.try_acquire_at(burst, refill, Instant::now())
+    }
+
+    fn try_acquire_at(
+        &mut self,
+        burst: u32,
+        refill: Duration,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let refill_secs = refill.as_secs_f64();
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed / refill_secs).min(burst as f64);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) * refill_secs))
+        }
+    }
+
+    /// Drop saved-up tokens above a lowered burst.
+    pub fn cap(&mut self, burst: u32) {
+        self.tokens = self.tokens.min(burst as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_then_refill() {
+        let refill = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut bucket = TokenBucket::full(2);
+        bucket.last_refill = start;
+
+        assert!(bucket.try_acquire_at(2, refill, start).is_ok());
+        assert!(bucket.try_acquire_at(2, refill, start).is_ok());
+        assert_eq!(
+            bucket.try_acquire_at(2, refill, start),
+            Err(Duration::from_secs(10))
+        );
+
+        let later = start + Duration::from_secs(4);
+        let wait = bucket.try_acquire_at(2, refill, later).unwrap_err();
+        assert!((wait.as_secs_f64() - 6.0).abs() < 1e-6);
+
+        let refilled = start + Duration::from_secs(10);
+        assert!(bucket.try_acquire_at(2, refill, refilled).is_ok());
+        assert!(bucket.try_acquire_at(2, refill, refilled).is_err());
+    }
+
+    #[test]
+    fn lowering_the_burst_caps_saved_tokens() {
+        let refill = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut bucket = TokenBucket::full(5);
+        bucket.last_refill = start;
+
+        bucket.cap(1);
+        assert!(bucket.try_acquire_at(1, refill, start).is_ok());
+        assert!(bucket.try_acquire_at(1, refill, start).is_err());
+    }
+}
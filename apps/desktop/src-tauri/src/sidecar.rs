@@ -1,16 +1,19 @@
 // Copyright (c) 2026 Naresh. All rights reserved.
 // Licensed under the MIT License. See LICENSE file for details.
 
+use crate::argv;
+use crate::error::{CommandError, ErrorCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read, Write};
-use std::net::TcpStream;
+use std::net::{TcpStream, ToSocketAddrs};
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tauri::async_runtime::Mutex;
 use tokio::sync::{mpsc, oneshot};
@@ -21,6 +24,55 @@ const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 300;
 const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
 const DEFAULT_RETRY_BACKOFF_MS: u64 = 250;
 const CONNECTOR_SECRET_ENV_VAR: &str = "COWORK_CONNECTOR_SECRET_KEY";
+const SIDECAR_CMD_ENV_VAR: &str = "COWORK_SIDECAR_CMD";
+const SIDECAR_EXTRA_ENV_VAR: &str = "COWORK_SIDECAR_EXTRA_ENV";
+/// Passed to every embedded sidecar we spawn so orphan cleanup can tell our
+/// processes apart from unrelated ones. The sidecar ignores unknown arguments.
+const EMBEDDED_SIDECAR_MARKER_ARG: &str = "--cowork-embedded-sidecar";
+/// A generation with no events for this long is treated as abandoned.
+const GENERATION_STALE_AFTER: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+/// How long a daemon liveness probe result is trusted by `is_running`.
+const DAEMON_LIVENESS_TTL: std::time::Duration = std::time::Duration::from_secs(2);
+const DAEMON_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
+/// Default bound on one connect attempt; `COWORK_DAEMON_CONNECT_TIMEOUT_MS`
+/// overrides it.
+const DEFAULT_DAEMON_CONNECT_TIMEOUT_MS: u64 = 1_000;
+/// How often the supervisor checks that the embedded sidecar is still alive.
+const EMBEDDED_SUPERVISE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+/// Consecutive failed respawns before the supervisor gives up.
+const EMBEDDED_RESTART_MAX_ATTEMPTS: u32 = 5;
+const EMBEDDED_RESTART_BASE_DELAY_MS: u64 = 500;
+const EMBEDDED_RESTART_MAX_DELAY_MS: u64 = 30_000;
+/// A sidecar that stays up this long resets the restart budget.
+const EMBEDDED_STABLE_AFTER: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// What the supervisor does when it finds the embedded sidecar gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestartDecision {
+    Retry { attempt: u32, delay_ms: u64 },
+    GiveUp { attempts: u32 },
+}
+
+/// `failed_attempts` counts consecutive respawns that either failed or
+/// exited before `EMBEDDED_STABLE_AFTER`; `uptime` is how long the process
+/// that just exited ran. A stable run clears the budget.
+fn embedded_restart_decision(failed_attempts: u32, uptime: std::time::Duration) -> RestartDecision {
+    let failed_attempts = if uptime >= EMBEDDED_STABLE_AFTER {
+        0
+    } else {
+        failed_attempts
+    };
+    if failed_attempts >= EMBEDDED_RESTART_MAX_ATTEMPTS {
+        return RestartDecision::GiveUp {
+            attempts: failed_attempts,
+        };
+    }
+    RestartDecision::Retry {
+        attempt: failed_attempts + 1,
+        delay_ms: (EMBEDDED_RESTART_BASE_DELAY_MS << failed_attempts.min(16))
+            .min(EMBEDDED_RESTART_MAX_DELAY_MS),
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TransportMode {
@@ -29,6 +81,23 @@ enum TransportMode {
     Daemon,
 }
 
+/// Mode and daemon token live under one lock so a request never pairs one
+/// transport's mode with another's token during a switch.
+#[derive(Debug, Clone)]
+struct TransportState {
+    mode: TransportMode,
+    daemon_auth_token: Option<String>,
+}
+
+impl TransportState {
+    fn disconnected() -> Self {
+        Self {
+            mode: TransportMode::Disconnected,
+            daemon_auth_token: None,
+        }
+    }
+}
+
 /// IPC Message sent to sidecar/daemon
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,6 +117,9 @@ pub struct IpcResponse {
     pub success: bool,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// Set by handlers that know why they failed; older sidecars omit it.
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
 }
 
 /// Event from sidecar/daemon
@@ -69,36 +141,172 @@ pub enum SidecarMessage {
 }
 
 type PendingRequests = Arc<Mutex<HashMap<String, oneshot::Sender<IpcResponse>>>>;
+/// Session id -> time of the last event seen for its in-flight generation.
+type ActiveGenerations = Arc<Mutex<HashMap<String, std::time::Instant>>>;
+/// Session id -> permission and question requests seen but not yet resolved.
+type OutstandingPrompts = Arc<Mutex<HashMap<String, SessionPrompts>>>;
+
+/// Unresolved prompts for one session, in the order they arrived.
+#[derive(Debug, Clone, Default)]
+pub struct SessionPrompts {
+    pub permissions: Vec<serde_json::Value>,
+    pub questions: Vec<serde_json::Value>,
+}
+
+/// Snapshot of work in flight on the transport.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarActivity {
+    pub active_sessions: Vec<String>,
+    pub pending_requests: usize,
+}
+
+impl SidecarActivity {
+    pub fn is_idle(&self) -> bool {
+        self.active_sessions.is_empty() && self.pending_requests == 0
+    }
+}
+
+/// What the connected sidecar/daemon says it can handle, from the
+/// `get_daemon_capabilities` handshake.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonCapabilities {
+    /// False for daemons that predate the handshake; every command is then
+    /// assumed to be supported.
+    #[serde(default)]
+    pub advertised: bool,
+    #[serde(default)]
+    pub protocol_version: u32,
+    #[serde(default)]
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl DaemonCapabilities {
+    pub fn supports_command(&self, command: &str) -> bool {
+        !self.advertised || self.commands.iter().any(|name| name == command)
+    }
+}
+
+fn remove_prompt(prompts: &mut Vec<serde_json::Value>, id: Option<&str>) {
+    if let Some(id) = id {
+        prompts.retain(|request| request["id"].as_str() != Some(id));
+    }
+}
+
+fn track_prompt_event(prompts: &mut HashMap<String, SessionPrompts>, event: &SidecarEvent) {
+    let Some(session_id) = event.session_id.as_deref() else {
+        return;
+    };
+    match event.event_type.as_str() {
+        "permission:request" | "question:ask" => {
+            let request = &event.data["request"];
+            let Some(id) = request["id"].as_str() else {
+                return;
+            };
+            let session = prompts.entry(session_id.to_string()).or_default();
+            let list = if event.event_type == "permission:request" {
+                &mut session.permissions
+            } else {
+                &mut session.questions
+            };
+            remove_prompt(list, Some(id));
+            list.push(request.clone());
+        }
+        "permission:resolved" => {
+            if let Some(session) = prompts.get_mut(session_id) {
+                remove_prompt(
+                    &mut session.permissions,
+                    event.data["permissionId"].as_str(),
+                );
+            }
+        }
+        "question:answered" => {
+            if let Some(session) = prompts.get_mut(session_id) {
+                remove_prompt(&mut session.questions, event.data["questionId"].as_str());
+            }
+        }
+        _ => {}
+    }
+}
+
+fn track_generation_event(
+    generations: &mut HashMap<String, std::time::Instant>,
+    event: &SidecarEvent,
+) {
+    let session_id = event.session_id.clone().unwrap_or_default();
+    match event.event_type.as_str() {
+        "stream:start" => {
+            generations.insert(session_id, std::time::Instant::now());
+        }
+        "stream:done" | "error" => {
+            generations.remove(&session_id);
+        }
+        _ => {
+            if let Some(last_seen) = generations.get_mut(&session_id) {
+                *last_seen = std::time::Instant::now();
+            }
+        }
+    }
+}
 
 pub struct SidecarManager {
     /// Embedded sidecar process handle (only used in legacy fallback mode).
-    process: Arc<Mutex<Option<Child>>>,
+    /// A std mutex that is never held across an await, so `Drop` can always
+    /// take the child instead of skipping cleanup under contention.
+    process: Arc<std::sync::Mutex<Option<Child>>>,
     /// Daemon process handle if spawned by this app for bootstrap.
     daemon_process: Arc<Mutex<Option<Child>>>,
     tx: Arc<Mutex<Option<mpsc::Sender<String>>>>,
     pending_requests: PendingRequests,
+    active_generations: ActiveGenerations,
+    outstanding_prompts: OutstandingPrompts,
     event_handler: Arc<Mutex<Option<Box<dyn Fn(SidecarEvent) + Send + 'static>>>>,
     request_counter: Arc<Mutex<u64>>,
     /// Track if writer is healthy (false if write failed)
     stdin_healthy: Arc<Mutex<bool>>,
-    mode: Arc<Mutex<TransportMode>>,
-    daemon_auth_token: Arc<Mutex<Option<String>>>,
+    transport: Arc<Mutex<TransportState>>,
+    /// Endpoint of the attached daemon, probed to confirm it is still alive.
+    daemon_endpoint: Arc<Mutex<Option<String>>>,
+    /// Last liveness probe: when it ran and whether the daemon answered.
+    daemon_liveness: Arc<Mutex<Option<(std::time::Instant, bool)>>>,
+    /// Set once processes left behind by a crashed run have been cleaned up.
+    orphans_cleaned: Arc<Mutex<bool>>,
+    /// Bumped whenever a new transport is attached, so callers can tell a
+    /// respawned sidecar from the one they initialized.
+    transport_generation: Arc<AtomicU64>,
+    /// Whether the embedded sidecar should be respawned if it exits; cleared
+    /// by `stop`.
+    embedded_supervised: Arc<AtomicBool>,
+    supervisor_running: Arc<AtomicBool>,
     start_lock: Arc<Mutex<()>>,
+    /// Handshake result and the transport generation it was taken on.
+    capabilities: Arc<Mutex<Option<(u64, DaemonCapabilities)>>>,
 }
 
 impl SidecarManager {
     pub fn new() -> Self {
         Self {
-            process: Arc::new(Mutex::new(None)),
+            process: Arc::new(std::sync::Mutex::new(None)),
             daemon_process: Arc::new(Mutex::new(None)),
             tx: Arc::new(Mutex::new(None)),
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            active_generations: Arc::new(Mutex::new(HashMap::new())),
+            outstanding_prompts: Arc::new(Mutex::new(HashMap::new())),
             event_handler: Arc::new(Mutex::new(None)),
             request_counter: Arc::new(Mutex::new(0)),
             stdin_healthy: Arc::new(Mutex::new(true)),
-            mode: Arc::new(Mutex::new(TransportMode::Disconnected)),
-            daemon_auth_token: Arc::new(Mutex::new(None)),
+            transport: Arc::new(Mutex::new(TransportState::disconnected())),
+            daemon_endpoint: Arc::new(Mutex::new(None)),
+            daemon_liveness: Arc::new(Mutex::new(None)),
+            orphans_cleaned: Arc::new(Mutex::new(false)),
+            transport_generation: Arc::new(AtomicU64::new(0)),
+            embedded_supervised: Arc::new(AtomicBool::new(false)),
+            supervisor_running: Arc::new(AtomicBool::new(false)),
             start_lock: Arc::new(Mutex::new(())),
+            capabilities: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -110,7 +318,22 @@ impl SidecarManager {
         *event_handler = Some(Box::new(handler));
     }
 
-    pub async fn start(&self, app_data_dir: &str) -> Result<(), String> {
+    /// Deliver a locally generated event through the registered handler so it
+    /// reaches the frontend the same way as events from the sidecar.
+    pub async fn dispatch_event(&self, event: SidecarEvent) {
+        let handler = self.event_handler.lock().await;
+        if let Some(ref handler) = *handler {
+            handler(event);
+        }
+    }
+
+    fn lock_process(&self) -> std::sync::MutexGuard<'_, Option<Child>> {
+        self.process
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub async fn start(self: &Arc<Self>, app_data_dir: &str) -> Result<(), String> {
         let _start_guard = self.start_lock.lock().await;
         if self.is_running().await {
             return Ok(());
@@ -118,6 +341,18 @@ impl SidecarManager {
 
         *self.stdin_healthy.lock().await = true;
 
+        {
+            let mut orphans_cleaned = self.orphans_cleaned.lock().await;
+            if !*orphans_cleaned {
+                *orphans_cleaned = true;
+                let app_data_dir = app_data_dir.to_string();
+                let _ = tauri::async_runtime::spawn_blocking(move || {
+                    cleanup_orphaned_processes(&app_data_dir)
+                })
+                .await;
+            }
+        }
+
         if daemon_transport_enabled() {
             match self.start_daemon_transport(app_data_dir).await {
                 Ok(()) => return Ok(()),
@@ -125,20 +360,144 @@ impl SidecarManager {
                     if !daemon_fallback_enabled() {
                         return Err(err);
                     }
-                    eprintln!(
-                        "[transport] Daemon transport unavailable, falling back to embedded sidecar: {}",
+                    log::warn!(
+                        "Daemon transport unavailable, falling back to embedded sidecar: {}",
                         err
                     );
                 }
             }
         }
 
-        self.start_embedded_sidecar(app_data_dir).await
+        self.start_embedded_sidecar(app_data_dir).await?;
+        self.supervise_embedded_sidecar(app_data_dir);
+        Ok(())
+    }
+
+    /// Incremented each time a transport is attached.
+    pub fn transport_generation(&self) -> u64 {
+        self.transport_generation.load(Ordering::SeqCst)
+    }
+
+    /// Respawn the embedded sidecar in the background when it exits, with
+    /// exponential backoff, until `stop` is called or the attempts run out.
+    fn supervise_embedded_sidecar(self: &Arc<Self>, app_data_dir: &str) {
+        self.embedded_supervised.store(true, Ordering::SeqCst);
+        if self.supervisor_running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let manager = Arc::clone(self);
+        let app_data_dir = app_data_dir.to_string();
+        tauri::async_runtime::spawn(async move {
+            manager.run_embedded_supervisor(&app_data_dir).await;
+            manager.supervisor_running.store(false, Ordering::SeqCst);
+        });
+    }
+
+    async fn run_embedded_supervisor(&self, app_data_dir: &str) {
+        let mut failed_attempts = 0u32;
+        // `None` while the sidecar is down or a respawn failed.
+        let mut up_since = Some(std::time::Instant::now());
+        let mut last_error: Option<String> = None;
+
+        loop {
+            tokio::time::sleep(EMBEDDED_SUPERVISE_INTERVAL).await;
+            if !self.embedded_supervised.load(Ordering::SeqCst) {
+                return;
+            }
+            if self.transport.lock().await.mode == TransportMode::Daemon {
+                return;
+            }
+            if self.is_running().await {
+                continue;
+            }
+
+            let uptime = up_since
+                .take()
+                .map(|since| since.elapsed())
+                .unwrap_or_default();
+            let (attempt, delay_ms) = match embedded_restart_decision(failed_attempts, uptime) {
+                RestartDecision::Retry { attempt, delay_ms } => (attempt, delay_ms),
+                RestartDecision::GiveUp { attempts } => {
+                    log::error!(
+                        "Embedded sidecar failed {} times in a row; giving up",
+                        attempts
+                    );
+                    self.embedded_supervised.store(false, Ordering::SeqCst);
+                    self.fail_pending_requests("Sidecar exited unexpectedly")
+                        .await;
+                    let error = last_error
+                        .unwrap_or_else(|| "Sidecar kept exiting shortly after start".to_string());
+                    self.dispatch_event(SidecarEvent {
+                        event_type: "sidecar:failed".to_string(),
+                        session_id: None,
+                        data: serde_json::json!({
+                            "attempts": attempts,
+                            "error": error,
+                        }),
+                    })
+                    .await;
+                    return;
+                }
+            };
+            // Counts as failed until the new process stays up long enough.
+            failed_attempts = attempt;
+            log::warn!(
+                "Embedded sidecar exited; restarting in {}ms (attempt {}/{})",
+                delay_ms,
+                attempt,
+                EMBEDDED_RESTART_MAX_ATTEMPTS
+            );
+            self.fail_pending_requests("Sidecar exited unexpectedly")
+                .await;
+            self.dispatch_event(SidecarEvent {
+                event_type: "sidecar:restarting".to_string(),
+                session_id: None,
+                data: serde_json::json!({
+                    "attempt": attempt,
+                    "maxAttempts": EMBEDDED_RESTART_MAX_ATTEMPTS,
+                    "delayMs": delay_ms,
+                }),
+            })
+            .await;
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+
+            let result = {
+                let _start_guard = self.start_lock.lock().await;
+                if !self.embedded_supervised.load(Ordering::SeqCst) {
+                    return;
+                }
+                if self.is_running().await {
+                    // A command restarted it while we were waiting.
+                    Ok(())
+                } else {
+                    *self.stdin_healthy.lock().await = true;
+                    self.start_embedded_sidecar(app_data_dir).await
+                }
+            };
+
+            match result {
+                Ok(()) => {
+                    log::info!("Embedded sidecar restarted (attempt {})", attempt);
+                    up_since = Some(std::time::Instant::now());
+                    self.dispatch_event(SidecarEvent {
+                        event_type: "sidecar:restarted".to_string(),
+                        session_id: None,
+                        data: serde_json::json!({ "attempt": attempt }),
+                    })
+                    .await;
+                }
+                Err(error) => {
+                    log::error!("Embedded sidecar restart failed: {}", error);
+                    last_error = Some(error);
+                }
+            }
+        }
     }
 
     async fn start_embedded_sidecar(&self, app_data_dir: &str) -> Result<(), String> {
-        let mut process_guard = self.process.lock().await;
-        if process_guard.is_some() {
+        // Callers hold `start_lock`, so nothing else spawns in between.
+        if self.lock_process().is_some() {
             return Ok(());
         }
 
@@ -151,41 +510,31 @@ impl SidecarManager {
             ));
         }
 
-        let mut child = if cfg!(debug_assertions) {
-            let pnpm_cmd = if cfg!(windows) { "pnpm.cmd" } else { "pnpm" };
-            let mut command = Command::new(pnpm_cmd);
-            command
-                .args(["exec", "tsx", "src/index.ts"])
-                .current_dir(&sidecar_path)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::inherit());
-            apply_connector_secret_seed_env(&mut command);
-            command
-                .spawn()
-                .map_err(|e| format!("Failed to spawn sidecar (dev mode): {}", e))?
-        } else {
-            let binary_name = if cfg!(windows) { "sidecar.exe" } else { "sidecar" };
-            let binary_path = sidecar_path.join(binary_name);
-
-            if !binary_path.exists() {
-                return Err(format!(
-                    "Sidecar binary not found at: {:?}. Please reinstall the application.",
-                    binary_path
-                ));
-            }
+        let (program, args, label) = resolve_embedded_sidecar_command(&sidecar_path)?;
+        argv::validate_argv(&program, &args)
+            .map_err(|e| format!("Invalid sidecar command: {}", e))?;
+        let extra_env = sidecar_extra_env()?;
+        log::info!(
+            "Spawning embedded sidecar ({}): {} {:?} in {:?}; extra env: {:?}",
+            label,
+            program,
+            args,
+            sidecar_path,
+            extra_env.keys().collect::<Vec<_>>()
+        );
 
-            let mut command = Command::new(&binary_path);
-            command
-                .current_dir(&sidecar_path)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::inherit());
-            apply_connector_secret_seed_env(&mut command);
-            command
-                .spawn()
-                .map_err(|e| format!("Failed to spawn sidecar binary: {}", e))?
-        };
+        let mut command = Command::new(&program);
+        command
+            .args(&args)
+            .current_dir(&sidecar_path)
+            .envs(&extra_env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        apply_connector_secret_seed_env(&mut command);
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to spawn sidecar ({}): {}", label, e))?;
 
         let stdin = child.stdin.take().ok_or("Failed to get sidecar stdin")?;
         let stdout = child.stdout.take().ok_or("Failed to get sidecar stdout")?;
@@ -198,8 +547,8 @@ impl SidecarManager {
         )
         .await;
 
-        *process_guard = Some(child);
-        *self.mode.lock().await = TransportMode::EmbeddedSidecar;
+        record_embedded_sidecar_pid(app_data_dir, child.id());
+        *self.lock_process() = Some(child);
         Ok(())
     }
 
@@ -213,6 +562,7 @@ impl SidecarManager {
             let token = read_daemon_token(&token_path)?;
             self.attach_io(writer, reader, TransportMode::Daemon, Some(token))
                 .await;
+            *self.daemon_endpoint.lock().await = Some(endpoint);
             return Ok(());
         }
 
@@ -236,6 +586,7 @@ impl SidecarManager {
                         Ok(token) => {
                             self.attach_io(writer, reader, TransportMode::Daemon, Some(token))
                                 .await;
+                            *self.daemon_endpoint.lock().await = Some(endpoint);
                             return Ok(());
                         }
                         Err(err) => {
@@ -268,20 +619,17 @@ impl SidecarManager {
     ) {
         let (tx, mut rx) = mpsc::channel::<String>(100);
 
-        {
-            let mut tx_guard = self.tx.lock().await;
-            *tx_guard = Some(tx);
-        }
-
-        {
-            let mut mode_guard = self.mode.lock().await;
-            *mode_guard = mode;
-        }
+        self.replace_transport(
+            Some(tx),
+            TransportState {
+                mode,
+                daemon_auth_token,
+            },
+        )
+        .await;
 
-        {
-            let mut token_guard = self.daemon_auth_token.lock().await;
-            *token_guard = daemon_auth_token;
-        }
+        *self.daemon_endpoint.lock().await = None;
+        *self.daemon_liveness.lock().await = None;
 
         let stdin_healthy_clone = self.stdin_healthy.clone();
         tauri::async_runtime::spawn(async move {
@@ -300,26 +648,32 @@ impl SidecarManager {
                 };
 
                 if let Err(e) = write_result {
-                    eprintln!("Failed to write to transport: {}", e);
+                    log::error!("Failed to write to transport: {}", e);
                     *stdin_healthy_clone.lock().await = false;
                     break;
                 }
 
                 if let Err(e) = newline_result {
-                    eprintln!("Failed to write newline to transport: {}", e);
+                    log::error!("Failed to write newline to transport: {}", e);
                     *stdin_healthy_clone.lock().await = false;
                     break;
                 }
 
                 if let Err(e) = flush_result {
-                    eprintln!("Failed to flush transport writer: {}", e);
+                    log::error!("Failed to flush transport writer: {}", e);
                     *stdin_healthy_clone.lock().await = false;
                     break;
                 }
             }
         });
 
+        // A new transport starts with no generations in flight.
+        self.active_generations.lock().await.clear();
+        self.transport_generation.fetch_add(1, Ordering::SeqCst);
+
         let pending_requests = self.pending_requests.clone();
+        let active_generations = self.active_generations.clone();
+        let outstanding_prompts = self.outstanding_prompts.clone();
         let event_handler = self.event_handler.clone();
 
         std::thread::spawn(move || {
@@ -350,6 +704,8 @@ impl SidecarManager {
                         }
                     }
                     Ok(SidecarMessage::Event(event)) => {
+                        track_generation_event(&mut active_generations.blocking_lock(), &event);
+                        track_prompt_event(&mut outstanding_prompts.blocking_lock(), &event);
                         let handler = event_handler.blocking_lock();
                         if let Some(ref handler) = *handler {
                             handler(event);
@@ -363,39 +719,108 @@ impl SidecarManager {
         });
     }
 
-    #[allow(dead_code)]
     pub async fn stop(&self) -> Result<(), String> {
-        let mode = *self.mode.lock().await;
+        self.embedded_supervised.store(false, Ordering::SeqCst);
+        let mode = self.transport.lock().await.mode;
 
         if mode == TransportMode::EmbeddedSidecar {
-            let mut process_guard = self.process.lock().await;
-            if let Some(mut child) = process_guard.take() {
+            let child = self.lock_process().take();
+            if let Some(mut child) = child {
                 child
                     .kill()
                     .map_err(|e| format!("Failed to kill sidecar: {}", e))?;
+                if let Ok(app_data_dir) = crate::paths::app_data_dir() {
+                    let _ = std::fs::remove_file(resolve_sidecar_pid_path(
+                        &app_data_dir.to_string_lossy(),
+                    ));
+                }
             }
         }
 
-        {
-            let mut tx_guard = self.tx.lock().await;
-            *tx_guard = None;
-        }
+        self.replace_transport(None, TransportState::disconnected())
+            .await;
 
         *self.stdin_healthy.lock().await = true;
-        *self.mode.lock().await = TransportMode::Disconnected;
-        *self.daemon_auth_token.lock().await = None;
+        *self.daemon_endpoint.lock().await = None;
+        *self.daemon_liveness.lock().await = None;
+
+        self.fail_pending_requests("Transport stopped").await;
+        Ok(())
+    }
+
+    /// Swap the writer while holding the transport lock so senders see the
+    /// new writer together with its mode and token.
+    async fn replace_transport(&self, tx: Option<mpsc::Sender<String>>, state: TransportState) {
+        let mut transport = self.transport.lock().await;
+        *self.tx.lock().await = tx;
+        *transport = state;
+    }
 
+    async fn fail_pending_requests(&self, reason: &str) {
         let mut pending = self.pending_requests.lock().await;
         for (_, sender) in pending.drain() {
             let _ = sender.send(IpcResponse {
                 id: String::new(),
                 success: false,
                 result: None,
-                error: Some("Transport stopped".to_string()),
+                error: Some(reason.to_string()),
+                error_code: Some(ErrorCode::Transport),
             });
         }
+    }
 
-        Ok(())
+    /// Ask the transport which commands it handles and cache the answer for
+    /// the current transport generation.
+    pub async fn refresh_capabilities(&self) -> DaemonCapabilities {
+        let generation = self.transport_generation();
+        let capabilities = match self
+            .send_command_typed("get_daemon_capabilities", serde_json::json!({}))
+            .await
+        {
+            Ok(result) => {
+                serde_json::from_value::<DaemonCapabilities>(result).unwrap_or_else(|error| {
+                    log::warn!("Ignoring malformed daemon capabilities: {}", error);
+                    DaemonCapabilities::default()
+                })
+            }
+            Err(error) => {
+                if !error.message().contains("Unknown command") {
+                    log::warn!("Daemon capabilities handshake failed: {}", error);
+                }
+                DaemonCapabilities::default()
+            }
+        };
+        *self.capabilities.lock().await = Some((generation, capabilities.clone()));
+        capabilities
+    }
+
+    /// Cached handshake result, refreshed when the transport has changed.
+    pub async fn capabilities(&self) -> DaemonCapabilities {
+        if let Some((generation, capabilities)) = self.capabilities.lock().await.clone() {
+            if generation == self.transport_generation() {
+                return capabilities;
+            }
+        }
+        self.refresh_capabilities().await
+    }
+
+    /// Only a handshake from the current transport can reject a command, so a
+    /// respawned sidecar is never judged by its predecessor's answer.
+    async fn ensure_command_supported(&self, command: &str) -> Result<(), CommandError> {
+        let guard = self.capabilities.lock().await;
+        match guard.as_ref() {
+            Some((generation, capabilities))
+                if *generation == self.transport_generation()
+                    && !capabilities.supports_command(command) =>
+            {
+                Err(CommandError::not_found(format!(
+                    "The running agent daemon does not support `{}`; restart or update it",
+                    command
+                ))
+                .with_details(serde_json::json!({ "command": command })))
+            }
+            _ => Ok(()),
+        }
     }
 
     pub async fn send_command(
@@ -403,6 +828,19 @@ impl SidecarManager {
         command: &str,
         params: serde_json::Value,
     ) -> Result<serde_json::Value, String> {
+        self.send_command_typed(command, params)
+            .await
+            .map_err(String::from)
+    }
+
+    /// `send_command` with the failure kept as a `CommandError`, so callers
+    /// can tell a dead transport from an error the sidecar reported.
+    pub async fn send_command_typed(
+        &self,
+        command: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, CommandError> {
+        self.ensure_command_supported(command).await?;
         let idempotency_key = format!(
             "{}-{}",
             command,
@@ -412,7 +850,7 @@ impl SidecarManager {
                 .unwrap_or(0)
         );
 
-        let mut last_error = String::new();
+        let mut last_error = CommandError::transport("No attempt was made");
         for attempt in 1..=DEFAULT_RETRY_ATTEMPTS {
             let mut params_with_envelope = params.clone();
             match &mut params_with_envelope {
@@ -438,7 +876,7 @@ impl SidecarManager {
             match self.send_command_once(command, params_with_envelope).await {
                 Ok(result) => return Ok(result),
                 Err(err) => {
-                    let retryable = Self::is_retryable_transport_error(&err);
+                    let retryable = Self::is_retryable_transport_error(err.message());
                     if !retryable || attempt >= DEFAULT_RETRY_ATTEMPTS {
                         return Err(err);
                     }
@@ -458,9 +896,11 @@ impl SidecarManager {
         &self,
         command: &str,
         params: serde_json::Value,
-    ) -> Result<serde_json::Value, String> {
+    ) -> Result<serde_json::Value, CommandError> {
         if !*self.stdin_healthy.lock().await {
-            return Err("Transport writer is not healthy - please restart the application".to_string());
+            return Err(CommandError::transport(
+                "Transport writer is not healthy - please restart the application",
+            ));
         }
 
         let id = {
@@ -475,11 +915,20 @@ impl SidecarManager {
             pending.insert(id.clone(), response_tx);
         }
 
-        let mode = *self.mode.lock().await;
-        let auth_token = if mode == TransportMode::Daemon {
-            self.daemon_auth_token.lock().await.clone()
-        } else {
-            None
+        // Read the token and clone the writer under the transport lock so the
+        // envelope matches the writer it is sent on, then release the lock
+        // before waiting for room on the bounded channel.
+        let (tx, auth_token) = {
+            let transport = self.transport.lock().await;
+            let auth_token = match transport.mode {
+                TransportMode::Daemon => transport.daemon_auth_token.clone(),
+                _ => None,
+            };
+            (self.tx.lock().await.clone(), auth_token)
+        };
+        let Some(tx) = tx else {
+            self.pending_requests.lock().await.remove(&id);
+            return Err(CommandError::transport("Transport is not running"));
         };
 
         let request = IpcRequest {
@@ -488,19 +937,14 @@ impl SidecarManager {
             params,
             auth_token,
         };
-
         let msg = serde_json::to_string(&request)
-            .map_err(|e| format!("Failed to serialize request: {}", e))?;
-
-        {
-            let tx_guard = self.tx.lock().await;
-            if let Some(ref tx) = *tx_guard {
-                tx.send(msg)
-                    .await
-                    .map_err(|e| format!("Failed to send to transport: {}", e))?;
-            } else {
-                return Err("Transport is not running".to_string());
-            }
+            .map_err(|e| CommandError::internal(format!("Failed to serialize request: {}", e)))?;
+        if let Err(e) = tx.send(msg).await {
+            self.pending_requests.lock().await.remove(&id);
+            return Err(CommandError::transport(format!(
+                "Failed to send to transport: {}",
+                e
+            )));
         }
 
         match tokio::time::timeout(
@@ -511,21 +955,30 @@ impl SidecarManager {
         {
             Err(_) => {
                 self.pending_requests.lock().await.remove(&id);
-                Err(format!(
+                Err(CommandError::transport(format!(
                     "Request timed out after {}s",
                     DEFAULT_REQUEST_TIMEOUT_SECS
-                ))
+                )))
             }
             Ok(Ok(response)) => {
+                let message = || {
+                    response
+                        .error
+                        .clone()
+                        .unwrap_or_else(|| "Unknown error".to_string())
+                };
                 if response.success {
                     Ok(response.result.unwrap_or(serde_json::Value::Null))
+                } else if response.id.is_empty() {
+                    // Synthesized by `fail_pending_requests` when the transport went away.
+                    Err(CommandError::transport(message()))
                 } else {
-                    Err(response
-                        .error
-                        .unwrap_or_else(|| "Unknown error".to_string()))
+                    let code = response.error_code.unwrap_or(ErrorCode::Internal);
+                    Err(CommandError::new(code, message())
+                        .with_details(serde_json::json!({ "command": command })))
                 }
             }
-            Ok(Err(_)) => Err("Response channel closed".to_string()),
+            Ok(Err(_)) => Err(CommandError::transport("Response channel closed")),
         }
     }
 
@@ -537,40 +990,110 @@ impl SidecarManager {
             || normalized.contains("response channel closed")
     }
 
+    /// Permission and question requests seen for a session that no resolve
+    /// event has cleared yet.
+    pub async fn outstanding_prompts(&self, session_id: &str) -> SessionPrompts {
+        self.outstanding_prompts
+            .lock()
+            .await
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Sessions with a generation in flight and the number of requests
+    /// still awaiting a response.
+    pub async fn activity(&self) -> SidecarActivity {
+        let mut active_sessions: Vec<String> = {
+            let mut generations = self.active_generations.lock().await;
+            generations.retain(|_, last_seen| last_seen.elapsed() < GENERATION_STALE_AFTER);
+            generations.keys().cloned().collect()
+        };
+        active_sessions.sort();
+        SidecarActivity {
+            active_sessions,
+            pending_requests: self.pending_requests.lock().await.len(),
+        }
+    }
+
+    /// Count a session as generating from the moment a message is sent,
+    /// before the sidecar's `stream:start` arrives.
+    pub async fn mark_generation_started(&self, session_id: &str) {
+        self.active_generations
+            .lock()
+            .await
+            .insert(session_id.to_string(), std::time::Instant::now());
+    }
+
+    pub async fn mark_generation_stopped(&self, session_id: &str) {
+        self.active_generations.lock().await.remove(session_id);
+    }
+
+    pub async fn is_daemon_transport(&self) -> bool {
+        self.transport.lock().await.mode == TransportMode::Daemon
+    }
+
     pub async fn is_running(&self) -> bool {
-        let mode = *self.mode.lock().await;
+        let mode = self.transport.lock().await.mode;
         match mode {
             TransportMode::EmbeddedSidecar => {
-                let mut guard = self.process.lock().await;
-                if let Some(ref mut child) = *guard {
-                    match child.try_wait() {
-                        Ok(None) => true,
-                        Ok(Some(_)) => {
-                            *guard = None;
-                            *self.mode.lock().await = TransportMode::Disconnected;
-                            false
-                        }
-                        Err(_) => false,
+                {
+                    let mut guard = self.lock_process();
+                    match guard.as_mut().map(|child| child.try_wait()) {
+                        Some(Ok(None)) => return true,
+                        Some(Ok(Some(_))) => *guard = None,
+                        Some(Err(_)) | None => return false,
                     }
-                } else {
-                    false
                 }
+                *self.transport.lock().await = TransportState::disconnected();
+                false
             }
             TransportMode::Daemon => {
                 let tx_ready = self.tx.lock().await.is_some();
-                tx_ready && *self.stdin_healthy.lock().await
+                if !tx_ready || !*self.stdin_healthy.lock().await {
+                    return false;
+                }
+                if self.daemon_alive().await {
+                    return true;
+                }
+                log::warn!("Daemon stopped answering; marking transport disconnected");
+                self.replace_transport(None, TransportState::disconnected())
+                    .await;
+                *self.daemon_endpoint.lock().await = None;
+                false
             }
             TransportMode::Disconnected => false,
         }
     }
+
+    /// Probe the daemon endpoint, reusing a recent result so frequent
+    /// `is_running` calls do not open a connection each time.
+    async fn daemon_alive(&self) -> bool {
+        let mut liveness = self.daemon_liveness.lock().await;
+        if let Some((checked_at, alive)) = *liveness {
+            if checked_at.elapsed() < DAEMON_LIVENESS_TTL {
+                return alive;
+            }
+        }
+
+        let Some(endpoint) = self.daemon_endpoint.lock().await.clone() else {
+            return true;
+        };
+        let alive = tauri::async_runtime::spawn_blocking(move || probe_daemon_endpoint(&endpoint))
+            .await
+            .unwrap_or(false);
+        *liveness = Some((std::time::Instant::now(), alive));
+        alive
+    }
 }
 
 impl Drop for SidecarManager {
     fn drop(&mut self) {
-        if let Ok(mut guard) = self.process.try_lock() {
-            if let Some(mut child) = guard.take() {
-                let _ = child.kill();
-            }
+        let child = self.lock_process().take();
+        if let Some(mut child) = child {
+            let _ = child.kill();
+            // Reap it so the killed sidecar does not linger as a zombie.
+            let _ = child.wait();
         }
     }
 }
@@ -589,6 +1112,12 @@ fn daemon_fallback_enabled() -> bool {
     env_bool("COWORK_DAEMON_FALLBACK_EMBEDDED_SIDECAR", true)
 }
 
+/// `codesign --verify` is opt-in: local and ad-hoc builds are not signed.
+#[cfg(target_os = "macos")]
+fn sidecar_signature_check_enabled() -> bool {
+    env_bool("COWORK_VERIFY_SIDECAR_SIGNATURE", false)
+}
+
 fn env_bool(key: &str, default_value: bool) -> bool {
     match std::env::var(key) {
         Ok(value) => {
@@ -712,6 +1241,94 @@ fn find_packaged_binary(app_data_dir: &str, base: &str) -> Option<PathBuf> {
     None
 }
 
+/// State of one `root/name` pair the packaged binary search looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BinaryCandidateStatus {
+    Found,
+    Empty,
+    NotAFile,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryCandidate {
+    pub path: String,
+    pub status: BinaryCandidateStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRoot {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Where `resolve_sidecar_dir` looks for the packaged sidecar and what it
+/// found there, without copying anything into the runtime directory.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarInstallDiagnostics {
+    pub debug_build: bool,
+    pub target_triple: String,
+    pub runtime_binary: String,
+    pub runtime_binary_status: BinaryCandidateStatus,
+    pub binary_names: Vec<String>,
+    pub search_roots: Vec<SearchRoot>,
+    pub candidates: Vec<BinaryCandidate>,
+    /// First candidate `ensure_runtime_binary` would copy, if any.
+    pub packaged_binary: Option<String>,
+}
+
+fn binary_candidate_status(path: &Path) -> BinaryCandidateStatus {
+    match std::fs::metadata(path) {
+        Ok(meta) if !meta.is_file() => BinaryCandidateStatus::NotAFile,
+        Ok(meta) if meta.len() == 0 => BinaryCandidateStatus::Empty,
+        Ok(_) => BinaryCandidateStatus::Found,
+        Err(_) => BinaryCandidateStatus::Missing,
+    }
+}
+
+pub fn diagnose_sidecar_install(app_data_dir: &str) -> SidecarInstallDiagnostics {
+    let base = "sidecar";
+    let runtime_binary = PathBuf::from(app_data_dir)
+        .join("sidecar")
+        .join(runtime_binary_name(base));
+    let binary_names = packaged_binary_names(base);
+    let roots = sidecar_binary_search_roots(app_data_dir);
+
+    let candidates: Vec<BinaryCandidate> = roots
+        .iter()
+        .flat_map(|root| binary_names.iter().map(move |name| root.join(name)))
+        .map(|path| BinaryCandidate {
+            status: binary_candidate_status(&path),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+    let packaged_binary = candidates
+        .iter()
+        .find(|candidate| candidate.status == BinaryCandidateStatus::Found)
+        .map(|candidate| candidate.path.clone());
+
+    SidecarInstallDiagnostics {
+        debug_build: cfg!(debug_assertions),
+        target_triple: platform_target_triple().to_string(),
+        runtime_binary_status: binary_candidate_status(&runtime_binary),
+        runtime_binary: runtime_binary.to_string_lossy().to_string(),
+        binary_names,
+        search_roots: roots
+            .iter()
+            .map(|root| SearchRoot {
+                path: root.to_string_lossy().to_string(),
+                exists: root.is_dir(),
+            })
+            .collect(),
+        candidates,
+        packaged_binary,
+    }
+}
+
 fn ensure_runtime_binary(runtime_dir: &Path, app_data_dir: &str, base: &str) -> Result<PathBuf, String> {
     let target = runtime_dir.join(runtime_binary_name(base));
     if is_non_empty_file(&target) {
@@ -744,6 +1361,66 @@ fn ensure_runtime_binary(runtime_dir: &Path, app_data_dir: &str, base: &str) ->
     Ok(target)
 }
 
+/// Catch the usual reasons a packaged binary will not start before spawning
+/// it; the OS error for them ("Permission denied", "Operation not permitted")
+/// does not say what to fix.
+fn verify_runtime_binary(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        let mode = std::fs::metadata(path)
+            .map_err(|e| format!("Failed to inspect runtime binary {:?}: {}", path, e))?
+            .permissions()
+            .mode();
+        if mode & 0o111 == 0 {
+            return Err(format!(
+                "Runtime binary {:?} is not executable (mode {:o}). Please reinstall the application.",
+                path,
+                mode & 0o777
+            ));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    verify_macos_runtime_binary(path)?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn verify_macos_runtime_binary(path: &Path) -> Result<(), String> {
+    let quarantined = Command::new("xattr")
+        .args(["-p", "com.apple.quarantine"])
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false);
+    if quarantined {
+        return Err(format!(
+            "Runtime binary {:?} is quarantined by Gatekeeper. Reinstall the application from a trusted download or run `xattr -d com.apple.quarantine` on it.",
+            path
+        ));
+    }
+
+    if !sidecar_signature_check_enabled() {
+        return Ok(());
+    }
+    let output = Command::new("codesign")
+        .args(["--verify", "--strict"])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run codesign: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Runtime binary {:?} is unsigned or its signature is invalid: {}",
+            path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
 pub fn resolve_sidecar_dir(app_data_dir: &str) -> Result<PathBuf, String> {
     if cfg!(debug_assertions) {
         let path = std::env::var("CARGO_MANIFEST_DIR")
@@ -826,6 +1503,171 @@ fn resolve_daemon_lock_path(app_data_dir: &str) -> PathBuf {
     PathBuf::from(app_data_dir).join("daemon").join("agentd.lock")
 }
 
+fn resolve_sidecar_pid_path(app_data_dir: &str) -> PathBuf {
+    PathBuf::from(app_data_dir).join("daemon").join("sidecar.pid")
+}
+
+/// Remember the embedded sidecar so a later run can clean it up if this one
+/// crashes without stopping it.
+fn record_embedded_sidecar_pid(app_data_dir: &str, pid: u32) {
+    let path = resolve_sidecar_pid_path(app_data_dir);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let record = serde_json::json!({
+        "pid": pid,
+        "appPid": std::process::id(),
+    });
+    if let Err(e) = std::fs::write(&path, record.to_string()) {
+        log::warn!("Failed to record sidecar pid: {}", e);
+    }
+}
+
+fn read_recorded_pid(path: &Path, key: &str) -> Option<u32> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    value[key].as_u64().and_then(|pid| u32::try_from(pid).ok())
+}
+
+/// One `ps` column for a live process, or `None` if it is not running.
+#[cfg(unix)]
+fn process_field(pid: u32, field: &str) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", field])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    process_field(pid, "pid=").is_some()
+}
+
+/// Whether a `ps -o args=` line is an embedded sidecar this app spawned,
+/// recognised by the marker argument rather than the program name so a
+/// reused PID is never mistaken for ours.
+#[cfg(unix)]
+fn is_embedded_sidecar_args(args: &str) -> bool {
+    args.split_whitespace()
+        .any(|arg| arg == EMBEDDED_SIDECAR_MARKER_ARG)
+}
+
+/// Whether a `ps -o args=` line is a daemon started with our lock file. The
+/// lock path is absolute and per data directory, so it identifies our daemon.
+#[cfg(unix)]
+fn is_daemon_args(args: &str, lock_path: &Path) -> bool {
+    let lock_arg = format!("--lock-file {}", lock_path.to_string_lossy());
+    args.split_whitespace().any(|arg| arg == "--daemon")
+        && (args.ends_with(&lock_arg) || args.contains(&format!("{} ", lock_arg)))
+}
+
+#[cfg(unix)]
+fn terminate_process(pid: u32) -> bool {
+    let _ = Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status();
+    for _ in 0..20 {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        if !process_alive(pid) {
+            return true;
+        }
+    }
+    let _ = Command::new("kill")
+        .args(["-KILL", &pid.to_string()])
+        .status();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    !process_alive(pid)
+}
+
+/// Stop sidecar and daemon processes a crashed previous run left behind.
+/// An embedded sidecar is only killed once the app that spawned it is gone;
+/// a daemon only if it holds our lock but no longer answers on its endpoint.
+#[cfg(unix)]
+fn cleanup_orphaned_processes(app_data_dir: &str) {
+    let pid_path = resolve_sidecar_pid_path(app_data_dir);
+    if let Some(pid) = read_recorded_pid(&pid_path, "pid") {
+        let app_alive = read_recorded_pid(&pid_path, "appPid")
+            .is_some_and(|app_pid| app_pid != std::process::id() && process_alive(app_pid));
+        match process_field(pid, "args=") {
+            Some(args) if !app_alive && is_embedded_sidecar_args(&args) => {
+                if terminate_process(pid) {
+                    log::info!("Stopped orphaned embedded sidecar (pid {})", pid);
+                } else {
+                    log::warn!("Failed to stop orphaned embedded sidecar (pid {})", pid);
+                }
+            }
+            Some(_) if app_alive => return,
+            _ => {}
+        }
+        let _ = std::fs::remove_file(&pid_path);
+    }
+
+    let lock_path = resolve_daemon_lock_path(app_data_dir);
+    let Some(pid) = read_recorded_pid(&lock_path, "pid") else {
+        return;
+    };
+    let Some(args) = process_field(pid, "args=") else {
+        return;
+    };
+    if !is_daemon_args(&args, &lock_path) {
+        return;
+    }
+    if probe_daemon_endpoint(&resolve_daemon_endpoint(app_data_dir)) {
+        return;
+    }
+    if terminate_process(pid) {
+        log::info!("Stopped unresponsive daemon (pid {})", pid);
+        let _ = std::fs::remove_file(&lock_path);
+    } else {
+        log::warn!("Failed to stop unresponsive daemon (pid {})", pid);
+    }
+}
+
+/// No-op on Windows: there is no `ps`/`kill` to inspect and stop processes
+/// with, so orphans from a crashed run are left for the OS to reap.
+#[cfg(not(unix))]
+fn cleanup_orphaned_processes(_app_data_dir: &str) {}
+
+/// Connect-only check that something is listening on a daemon endpoint.
+fn probe_daemon_endpoint(endpoint: &str) -> bool {
+    if endpoint.starts_with("tcp://") {
+        let Ok((host, port)) = parse_tcp_endpoint(endpoint) else {
+            return false;
+        };
+        let Ok(mut addrs) = (host.as_str(), port).to_socket_addrs() else {
+            return false;
+        };
+        return addrs
+            .next()
+            .map(|addr| TcpStream::connect_timeout(&addr, DAEMON_PROBE_TIMEOUT).is_ok())
+            .unwrap_or(false);
+    }
+
+    #[cfg(unix)]
+    {
+        connect_unix_with_timeout(endpoint, DAEMON_PROBE_TIMEOUT).is_some()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = endpoint;
+        true
+    }
+}
+
+/// Whether a daemon is accepting connections on this data dir's endpoint.
+pub fn daemon_reachable(app_data_dir: &str) -> bool {
+    matches!(
+        try_connect_daemon(&resolve_daemon_endpoint(app_data_dir)),
+        Ok(Some(_))
+    )
+}
+
 fn read_daemon_token(path: &PathBuf) -> Result<String, String> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read daemon auth token: {}", e))?;
@@ -854,12 +1696,54 @@ fn parse_tcp_endpoint(endpoint: &str) -> Result<(String, u16), String> {
     Ok((host.to_string(), port))
 }
 
+fn daemon_connect_timeout() -> std::time::Duration {
+    let millis = std::env::var("COWORK_DAEMON_CONNECT_TIMEOUT_MS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|millis| *millis > 0)
+        .unwrap_or(DEFAULT_DAEMON_CONNECT_TIMEOUT_MS);
+    std::time::Duration::from_millis(millis)
+}
+
+fn connect_tcp_with_timeout(
+    host: &str,
+    port: u16,
+    timeout: std::time::Duration,
+) -> std::io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{}:{} did not resolve to an address", host, port),
+        )
+    }))
+}
+
+/// std has no connect timeout for Unix sockets, so connect on a helper thread
+/// and stop waiting once `timeout` passes.
+#[cfg(unix)]
+fn connect_unix_with_timeout(endpoint: &str, timeout: std::time::Duration) -> Option<UnixStream> {
+    let (result_tx, result_rx) = std::sync::mpsc::channel();
+    let path = endpoint.to_string();
+    std::thread::spawn(move || {
+        let _ = result_tx.send(UnixStream::connect(path));
+    });
+    result_rx.recv_timeout(timeout).ok()?.ok()
+}
+
 fn try_connect_daemon(
     endpoint: &str,
 ) -> Result<Option<(Box<dyn Read + Send>, Box<dyn Write + Send>)>, String> {
+    let timeout = daemon_connect_timeout();
     if endpoint.starts_with("tcp://") {
         let (host, port) = parse_tcp_endpoint(endpoint)?;
-        match TcpStream::connect((host.as_str(), port)) {
+        match connect_tcp_with_timeout(&host, port, timeout) {
             Ok(stream) => {
                 let _ = stream.set_nodelay(true);
                 let reader = stream
@@ -873,14 +1757,14 @@ fn try_connect_daemon(
 
     #[cfg(unix)]
     {
-        match UnixStream::connect(endpoint) {
-            Ok(stream) => {
+        match connect_unix_with_timeout(endpoint, timeout) {
+            Some(stream) => {
                 let reader = stream
                     .try_clone()
                     .map_err(|e| format!("Failed to clone daemon UNIX stream: {}", e))?;
                 Ok(Some((Box::new(reader), Box::new(stream))))
             }
-            Err(_) => Ok(None),
+            None => Ok(None),
         }
     }
 
@@ -898,6 +1782,16 @@ fn spawn_daemon_process(
     token_path: &PathBuf,
     lock_path: &PathBuf,
 ) -> Result<Child, String> {
+    let token_file = token_path
+        .to_str()
+        .ok_or_else(|| "Invalid daemon token path".to_string())?;
+    let lock_file = lock_path
+        .to_str()
+        .ok_or_else(|| "Invalid daemon lock path".to_string())?;
+    for value in [app_data_dir, endpoint, token_file, lock_file] {
+        argv::validate_arg(value).map_err(|e| format!("Invalid daemon argument: {}", e))?;
+    }
+
     if cfg!(debug_assertions) {
         let pnpm_cmd = if cfg!(windows) { "pnpm.cmd" } else { "pnpm" };
         let mut command = Command::new(pnpm_cmd);
@@ -912,13 +1806,9 @@ fn spawn_daemon_process(
                 "--endpoint",
                 endpoint,
                 "--token-file",
-                token_path
-                    .to_str()
-                    .ok_or_else(|| "Invalid daemon token path".to_string())?,
+                token_file,
                 "--lock-file",
-                lock_path
-                    .to_str()
-                    .ok_or_else(|| "Invalid daemon lock path".to_string())?,
+                lock_file,
             ])
             .current_dir(sidecar_dir)
             .stdin(Stdio::null())
@@ -942,6 +1832,7 @@ fn spawn_daemon_process(
                 binary_path
             ));
         }
+        verify_runtime_binary(&binary_path)?;
 
         let mut command = Command::new(binary_path);
         command
@@ -952,13 +1843,9 @@ fn spawn_daemon_process(
                 "--endpoint",
                 endpoint,
                 "--token-file",
-                token_path
-                    .to_str()
-                    .ok_or_else(|| "Invalid daemon token path".to_string())?,
+                token_file,
                 "--lock-file",
-                lock_path
-                    .to_str()
-                    .ok_or_else(|| "Invalid daemon lock path".to_string())?,
+                lock_file,
             ])
             .current_dir(sidecar_dir)
             .stdin(Stdio::null())
@@ -971,16 +1858,418 @@ fn spawn_daemon_process(
     }
 }
 
+/// Program and arguments for the embedded sidecar: `COWORK_SIDECAR_CMD` when
+/// set (a program path, or a JSON array of program and arguments), otherwise
+/// `pnpm exec tsx` in debug builds and the packaged binary in release.
+fn resolve_embedded_sidecar_command(
+    sidecar_path: &Path,
+) -> Result<(String, Vec<String>, &'static str), String> {
+    if let Some(raw) = std::env::var(SIDECAR_CMD_ENV_VAR)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+    {
+        let raw = raw.trim();
+        if raw.starts_with('[') {
+            let mut parts: Vec<String> = serde_json::from_str(raw).map_err(|e| {
+                format!(
+                    "{} must be a program path or a JSON array of strings: {}",
+                    SIDECAR_CMD_ENV_VAR, e
+                )
+            })?;
+            if parts.is_empty() {
+                return Err(format!(
+                    "{} must not be an empty array",
+                    SIDECAR_CMD_ENV_VAR
+                ));
+            }
+            let program = parts.remove(0);
+            return Ok((program, parts, "override"));
+        }
+        return Ok((raw.to_string(), Vec::new(), "override"));
+    }
+
+    if cfg!(debug_assertions) {
+        let pnpm_cmd = if cfg!(windows) { "pnpm.cmd" } else { "pnpm" };
+        let args = vec![
+            "exec".to_string(),
+            "tsx".to_string(),
+            "src/index.ts".to_string(),
+            EMBEDDED_SIDECAR_MARKER_ARG.to_string(),
+        ];
+        return Ok((pnpm_cmd.to_string(), args, "dev mode"));
+    }
+
+    let binary_name = if cfg!(windows) {
+        "sidecar.exe"
+    } else {
+        "sidecar"
+    };
+    let binary_path = sidecar_path.join(binary_name);
+    if !binary_path.exists() {
+        return Err(format!(
+            "Sidecar binary not found at: {:?}. Please reinstall the application.",
+            binary_path
+        ));
+    }
+    verify_runtime_binary(&binary_path)?;
+    Ok((
+        binary_path.to_string_lossy().to_string(),
+        vec![EMBEDDED_SIDECAR_MARKER_ARG.to_string()],
+        "binary",
+    ))
+}
+
+/// Extra environment for the embedded sidecar from `COWORK_SIDECAR_EXTRA_ENV`,
+/// a JSON object of string values.
+fn sidecar_extra_env() -> Result<HashMap<String, String>, String> {
+    let raw = match std::env::var(SIDECAR_EXTRA_ENV_VAR) {
+        Ok(raw) if !raw.trim().is_empty() => raw,
+        _ => return Ok(HashMap::new()),
+    };
+    let env: HashMap<String, String> = serde_json::from_str(&raw).map_err(|e| {
+        format!(
+            "{} must be a JSON object of string values: {}",
+            SIDECAR_EXTRA_ENV_VAR, e
+        )
+    })?;
+    for (key, value) in &env {
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            return Err(format!(
+                "{} has an invalid variable name: {:?}",
+                SIDECAR_EXTRA_ENV_VAR, key
+            ));
+        }
+        if key == CONNECTOR_SECRET_ENV_VAR {
+            return Err(format!(
+                "{} cannot override {}",
+                SIDECAR_EXTRA_ENV_VAR, CONNECTOR_SECRET_ENV_VAR
+            ));
+        }
+        if value.contains('\0') {
+            return Err(format!(
+                "{} value for {} contains a NUL character",
+                SIDECAR_EXTRA_ENV_VAR, key
+            ));
+        }
+    }
+    Ok(env)
+}
+
 fn apply_connector_secret_seed_env(command: &mut Command) {
     match crate::commands::credentials::get_or_create_sidecar_connector_seed() {
         Ok(seed) => {
             command.env(CONNECTOR_SECRET_ENV_VAR, seed);
         }
         Err(err) => {
-            eprintln!(
-                "[security] Unable to load connector secret seed from secure store; using fallback in sidecar: {}",
+            log::warn!(
+                "Unable to load connector secret seed from secure store; using fallback in sidecar: {}",
                 err
             );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    const DAEMON_TOKEN: &str = "daemon-token";
+
+    /// Answer every request on `rx` and count envelopes whose token doesn't
+    /// belong to this writer's transport.
+    fn spawn_responder(
+        manager: Arc<SidecarManager>,
+        mut rx: mpsc::Receiver<String>,
+        expected_token: Option<&'static str>,
+        handled: Arc<AtomicUsize>,
+        mismatched: Arc<AtomicUsize>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                let request: serde_json::Value = serde_json::from_str(&message).unwrap();
+                if request["authToken"].as_str() != expected_token {
+                    mismatched.fetch_add(1, Ordering::SeqCst);
+                }
+                handled.fetch_add(1, Ordering::SeqCst);
+                let id = request["id"].as_str().unwrap().to_string();
+                if let Some(sender) = manager.pending_requests.lock().await.remove(&id) {
+                    let _ = sender.send(IpcResponse {
+                        id,
+                        success: true,
+                        result: Some(serde_json::json!({ "ok": true })),
+                        error: None,
+                        error_code: None,
+                    });
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn a_full_writer_queue_does_not_hold_the_transport_lock() {
+        let manager = Arc::new(SidecarManager::new());
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        tx.send("queued".to_string()).await.unwrap();
+        manager
+            .replace_transport(
+                Some(tx),
+                TransportState {
+                    mode: TransportMode::EmbeddedSidecar,
+                    daemon_auth_token: None,
+                },
+            )
+            .await;
+
+        let sender = {
+            let manager = manager.clone();
+            tokio::spawn(async move {
+                manager
+                    .send_command_once("ping", serde_json::json!({}))
+                    .await
+            })
+        };
+        while manager.pending_requests.lock().await.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        // The sender is parked waiting for room; swapping transports must not wait on it.
+        tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            manager.replace_transport(None, TransportState::disconnected()),
+        )
+        .await
+        .expect("transport lock was held across the channel send");
+
+        sender.abort();
+        rx.close();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn envelope_token_matches_transport_during_switches() {
+        let manager = Arc::new(SidecarManager::new());
+        let (daemon_tx, daemon_rx) = mpsc::channel::<String>(100);
+        let (embedded_tx, embedded_rx) = mpsc::channel::<String>(100);
+        let daemon_handled = Arc::new(AtomicUsize::new(0));
+        let embedded_handled = Arc::new(AtomicUsize::new(0));
+        let mismatched = Arc::new(AtomicUsize::new(0));
+        let responders = [
+            spawn_responder(
+                manager.clone(),
+                daemon_rx,
+                Some(DAEMON_TOKEN),
+                daemon_handled.clone(),
+                mismatched.clone(),
+            ),
+            spawn_responder(
+                manager.clone(),
+                embedded_rx,
+                None,
+                embedded_handled.clone(),
+                mismatched.clone(),
+            ),
+        ];
+
+        manager
+            .replace_transport(
+                Some(embedded_tx.clone()),
+                TransportState {
+                    mode: TransportMode::EmbeddedSidecar,
+                    daemon_auth_token: None,
+                },
+            )
+            .await;
+
+        let switcher = {
+            let manager = manager.clone();
+            tokio::spawn(async move {
+                for round in 0..400 {
+                    let (tx, state) = if round % 2 == 0 {
+                        (
+                            daemon_tx.clone(),
+                            TransportState {
+                                mode: TransportMode::Daemon,
+                                daemon_auth_token: Some(DAEMON_TOKEN.to_string()),
+                            },
+                        )
+                    } else {
+                        (
+                            embedded_tx.clone(),
+                            TransportState {
+                                mode: TransportMode::EmbeddedSidecar,
+                                daemon_auth_token: None,
+                            },
+                        )
+                    };
+                    manager.replace_transport(Some(tx), state).await;
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let senders: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    for _ in 0..50 {
+                        manager
+                            .send_command_once("ping", serde_json::json!({}))
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for sender in senders {
+            sender.await.unwrap();
+        }
+        switcher.await.unwrap();
+        for responder in responders {
+            responder.abort();
+        }
+
+        let daemon = daemon_handled.load(Ordering::SeqCst);
+        let embedded = embedded_handled.load(Ordering::SeqCst);
+        assert_eq!(daemon + embedded, 400);
+        assert_eq!(mismatched.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn drop_kills_the_embedded_child() {
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        let manager = SidecarManager::new();
+        *manager.lock_process() = Some(child);
+
+        // A poisoned lock must not stop teardown either.
+        let process = manager.process.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = process.lock().unwrap();
+            panic!("poison the process lock");
+        })
+        .join();
+        assert!(manager.process.is_poisoned());
+
+        drop(manager);
+        // Drop reaps the child, so its pid no longer exists.
+        let alive = Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success();
+        assert!(!alive);
+    }
+
+    #[test]
+    fn restart_decision_backs_off_and_caps_early_exits() {
+        let early = std::time::Duration::from_millis(800);
+        assert_eq!(
+            embedded_restart_decision(0, early),
+            RestartDecision::Retry {
+                attempt: 1,
+                delay_ms: 500
+            }
+        );
+        assert_eq!(
+            embedded_restart_decision(3, early),
+            RestartDecision::Retry {
+                attempt: 4,
+                delay_ms: 4_000
+            }
+        );
+        assert_eq!(
+            embedded_restart_decision(EMBEDDED_RESTART_MAX_ATTEMPTS, early),
+            RestartDecision::GiveUp {
+                attempts: EMBEDDED_RESTART_MAX_ATTEMPTS
+            }
+        );
+
+        // A sidecar that spawns fine but keeps crashing still hits the cap.
+        let mut failed = 0;
+        let mut retries = 0;
+        while let RestartDecision::Retry { attempt, .. } = embedded_restart_decision(failed, early)
+        {
+            failed = attempt;
+            retries += 1;
+        }
+        assert_eq!(retries, EMBEDDED_RESTART_MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn restart_decision_resets_after_a_stable_run() {
+        assert_eq!(
+            embedded_restart_decision(EMBEDDED_RESTART_MAX_ATTEMPTS, EMBEDDED_STABLE_AFTER),
+            RestartDecision::Retry {
+                attempt: 1,
+                delay_ms: EMBEDDED_RESTART_BASE_DELAY_MS
+            }
+        );
+        assert_eq!(
+            embedded_restart_decision(40, std::time::Duration::ZERO),
+            RestartDecision::GiveUp { attempts: 40 }
+        );
+        assert_eq!(
+            embedded_restart_decision(4, std::time::Duration::ZERO),
+            RestartDecision::Retry {
+                attempt: 5,
+                delay_ms: 8_000
+            }
+        );
+    }
+
+    #[test]
+    fn ipc_responses_carry_an_optional_error_code() {
+        let typed: IpcResponse = serde_json::from_value(serde_json::json!({
+            "id": "req_1",
+            "success": false,
+            "error": "Missing required secret: TOKEN",
+            "errorCode": "auth",
+        }))
+        .unwrap();
+        assert_eq!(typed.error_code, Some(ErrorCode::Auth));
+
+        let untyped: IpcResponse = serde_json::from_value(serde_json::json!({
+            "id": "req_2",
+            "success": false,
+            "error": "boom",
+        }))
+        .unwrap();
+        assert_eq!(untyped.error_code, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn embedded_sidecars_are_recognised_by_the_marker_argument() {
+        assert!(is_embedded_sidecar_args(
+            "node /opt/pnpm/tsx src/index.ts --cowork-embedded-sidecar"
+        ));
+        assert!(is_embedded_sidecar_args(
+            "/Applications/Cowork.app/Contents/Resources/sidecar --cowork-embedded-sidecar"
+        ));
+        // Another project's dev server with the same entry point is not ours.
+        assert!(!is_embedded_sidecar_args("node /opt/pnpm/tsx src/index.ts"));
+        assert!(!is_embedded_sidecar_args(
+            "node tsx src/index.ts --cowork-embedded-sidecar-extra"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn daemons_are_recognised_by_their_lock_file() {
+        let lock = Path::new("/Users/me/Library/Application Support/cowork/daemon.lock");
+        let args = "sidecar --daemon --app-data-dir /x --lock-file \
+                    /Users/me/Library/Application Support/cowork/daemon.lock";
+        assert!(is_daemon_args(args, lock));
+        assert!(is_daemon_args(&format!("{} --verbose", args), lock));
+        // Same lock file without --daemon, or another data dir's lock.
+        assert!(!is_daemon_args(&args.replace("--daemon ", ""), lock));
+        assert!(!is_daemon_args(
+            args,
+            Path::new("/Users/me/Library/Application Support/cowork")
+        ));
+        assert!(!is_daemon_args(&format!("{}.old", args), lock));
+    }
+}
@@ -24,7 +24,7 @@ import { cn } from '@/lib/utils';
 import { useAppStore, type SettingsTab } from '../../stores/app-store';
 import { useIntegrationStore } from '../../stores/integration-store';
 import { useHelpStore } from '../../stores/help-store';
-import { useCapabilityStore } from '../../stores/capability-store';
+import { isDaemonFeatureSupported, useCapabilityStore } from '../../stores/capability-store';
 import { toast } from '@/components/ui/Toast';
 import { useSettingsStore } from '@/stores/settings-store';
 import { useSessionStore } from '@/stores/session-store';
@@ -44,6 +44,8 @@ interface TabConfig {
   label: string;
   icon: React.ComponentType<{ className?: string; color?: string }>;
   color?: string;
+  /** Daemon feature the tab needs; hidden when the daemon lacks it. */
+  feature?: string;
 }
 
 const tabConfig: TabConfig[] = [
@@ -52,8 +54,8 @@ const tabConfig: TabConfig[] = [
   { id: 'capabilities', label: 'Capabilities', icon: Wrench },
   { id: 'runtime', label: 'Runtime', icon: Cpu },
   { id: 'benchmarks', label: 'Benchmarks', icon: BarChart3 },
-  { id: 'integrations', label: 'Integrations', icon: SlidersHorizontal },
-  { id: 'remote', label: 'Remote', icon: Smartphone },
+  { id: 'integrations', label: 'Integrations', icon: SlidersHorizontal, feature: 'integrations' },
+  { id: 'remote', label: 'Remote', icon: Smartphone, feature: 'remoteAccess' },
   { id: 'souls', label: 'Souls', icon: Bot },
 ];
 
@@ -79,13 +81,17 @@ export function SettingsView() {
   const openHelp = useHelpStore((s) => s.openHelp);
   const startTour = useHelpStore((s) => s.startTour);
   const refreshCapabilitySnapshot = useCapabilityStore((s) => s.refreshSnapshot);
+  const capabilitySnapshot = useCapabilityStore((s) => s.snapshot);
   const visibleTabs = useMemo(() => {
+    const supportedTabs = tabConfig.filter(
+      (tab) => !tab.feature || isDaemonFeatureSupported(capabilitySnapshot, tab.feature),
+    );
     if (uxProfile === 'pro') {
-      return tabConfig;
+      return supportedTabs;
     }
     const allowed = new Set<SettingsTab>(['provider', 'runtime', 'benchmarks']);
-    return tabConfig.filter((tab) => allowed.has(tab.id));
-  }, [uxProfile]);
+    return supportedTabs.filter((tab) => allowed.has(tab.id));
+  }, [capabilitySnapshot, uxProfile]);
 
   useEffect(() => {
     if (visibleTabs.some((tab) => tab.id === activeTab)) {
@@ -0,0 +1,36 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+import { describe, expect, it } from 'vitest';
+import { CommandError, commandErrorMessage, isCommandErrorPayload, toCommandError } from './command-error';
+
+describe('command-error', () => {
+  it('turns a typed command rejection into an Error with its code and details', () => {
+    const error = toCommandError({
+      code: 'auth',
+      message: 'Provider rejected the API key: 401 Unauthorized',
+      details: { status: 401 },
+    });
+
+    expect(error).toBeInstanceOf(CommandError);
+    expect(error).toBeInstanceOf(Error);
+    expect(error.code).toBe('auth');
+    expect(error.message).toBe('Provider rejected the API key: 401 Unauthorized');
+    expect(error.details).toEqual({ status: 401 });
+  });
+
+  it('treats plain string and Error rejections as internal errors', () => {
+    expect(toCommandError('Transport is not running')).toMatchObject({
+      code: 'internal',
+      message: 'Transport is not running',
+      details: null,
+    });
+    expect(commandErrorMessage(new Error('boom'))).toBe('boom');
+  });
+
+  it('does not mistake other objects for command errors', () => {
+    expect(isCommandErrorPayload({ code: 'subagent_conflict', message: 'conflict' })).toBe(false);
+    expect(isCommandErrorPayload(null)).toBe(false);
+    expect(commandErrorMessage({ code: 'not_found', message: 'missing', details: null })).toBe('missing');
+  });
+});
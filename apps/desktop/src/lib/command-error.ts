@@ -0,0 +1,65 @@
+// Copyright (c) 2026 Naresh. All rights reserved.
+// Licensed under the MIT License. See LICENSE file for details.
+
+/**
+ * Codes reported by typed Tauri commands. Mirrors `ErrorCode` in
+ * src-tauri/src/error.rs.
+ */
+export type CommandErrorCode =
+  | 'auth'
+  | 'network'
+  | 'transport'
+  | 'validation'
+  | 'not_found'
+  | 'internal';
+
+/** What a typed command rejects with: `{ code, message, details }`. */
+export interface CommandErrorPayload {
+  code: CommandErrorCode;
+  message: string;
+  details: unknown;
+}
+
+const COMMAND_ERROR_CODES: ReadonlySet<string> = new Set<CommandErrorCode>([
+  'auth',
+  'network',
+  'transport',
+  'validation',
+  'not_found',
+  'internal',
+]);
+
+export function isCommandErrorPayload(value: unknown): value is CommandErrorPayload {
+  if (typeof value !== 'object' || value === null) return false;
+  const candidate = value as Partial<Record<keyof CommandErrorPayload, unknown>>;
+  return typeof candidate.message === 'string'
+    && typeof candidate.code === 'string'
+    && COMMAND_ERROR_CODES.has(candidate.code);
+}
+
+export class CommandError extends Error {
+  readonly code: CommandErrorCode;
+  readonly details: unknown;
+
+  constructor(payload: CommandErrorPayload) {
+    super(payload.message);
+    this.name = 'CommandError';
+    this.code = payload.code;
+    this.details = payload.details ?? null;
+  }
+}
+
+/**
+ * Normalize anything an `invoke` can reject with. Commands that still return
+ * plain strings come back as `internal`.
+ */
+export function toCommandError(error: unknown): CommandError {
+  if (error instanceof CommandError) return error;
+  if (isCommandErrorPayload(error)) return new CommandError(error);
+  const message = error instanceof Error ? error.message : String(error);
+  return new CommandError({ code: 'internal', message, details: null });
+}
+
+export function commandErrorMessage(error: unknown): string {
+  return toCommandError(error).message;
+}
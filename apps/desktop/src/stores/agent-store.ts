@@ -80,6 +80,10 @@ interface AgentActions {
   // Context management
   setContextUsage: (sessionId: string, used: number, total: number) => void;
   refreshContextUsage: (sessionId: string) => Promise<void>;
+  compactContext: (
+    sessionId: string,
+    strategy: 'summarize' | 'truncate' | 'drop-tools'
+  ) => Promise<void>;
 
   // State management
   setRunning: (sessionId: string, running: boolean) => void;
@@ -326,6 +330,15 @@ export const useAgentStore = create<AgentState & AgentActions>((set, get) => ({
     }
   },
 
+  compactContext: async (sessionId, strategy) => {
+    if (!sessionId) return;
+    const result = await invoke<{ usedTokens: number; maxTokens: number }>(
+      'agent_compact_session',
+      { sessionId, strategy }
+    );
+    get().setContextUsage(sessionId, result.usedTokens, result.maxTokens);
+  },
+
   // State management
   setRunning: (sessionId: string, running: boolean) => {
     if (!sessionId) return;
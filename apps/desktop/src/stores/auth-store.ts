@@ -2,12 +2,19 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 import { create } from 'zustand';
+import type { InvokeArgs } from '@tauri-apps/api/core';
 import { useAppStore } from './app-store';
+import { toCommandError } from '../lib/command-error';
 
 const getTauriInvoke = async () => {
   if (typeof window !== 'undefined' && '__TAURI__' in window) {
     const { invoke } = await import('@tauri-apps/api/core');
-    return invoke;
+    // Typed commands reject with `{ code, message, details }`; rethrow them as
+    // Errors so `error.message` keeps working and callers can check `code`.
+    return <T>(cmd: string, args?: InvokeArgs) =>
+      invoke<T>(cmd, args).catch((error: unknown) => {
+        throw toCommandError(error);
+      });
   }
   throw new Error('Not running in Tauri context. Please use the desktop app.');
 };
@@ -2,7 +2,11 @@
 // Licensed under the MIT License. See LICENSE file for details.
 
 import { beforeEach, describe, expect, it, vi } from 'vitest';
-import { useCapabilityStore, normalizeCapabilitySnapshot } from './capability-store';
+import {
+  useCapabilityStore,
+  normalizeCapabilitySnapshot,
+  isDaemonFeatureSupported,
+} from './capability-store';
 import { clearMockInvokeResponses, setMockInvokeResponse } from '../test/mocks/tauri-core';
 
 describe('capability-store', () => {
@@ -31,6 +35,23 @@ describe('capability-store', () => {
     expect(snapshot.toolAccess[0].policyAction).toBe('ask');
   });
 
+  it('treats daemons without a capabilities handshake as supporting everything', () => {
+    const legacy = normalizeCapabilitySnapshot({ provider: 'google' });
+    expect(legacy.daemonCapabilities.advertised).toBe(false);
+    expect(isDaemonFeatureSupported(legacy, 'remoteAccess')).toBe(true);
+
+    const advertised = normalizeCapabilitySnapshot({
+      daemonCapabilities: {
+        advertised: true,
+        protocolVersion: 1,
+        commands: ['ping'],
+        features: ['integrations'],
+      },
+    });
+    expect(isDaemonFeatureSupported(advertised, 'integrations')).toBe(true);
+    expect(isDaemonFeatureSupported(advertised, 'remoteAccess')).toBe(false);
+  });
+
   it('loads capability snapshot via tauri command', async () => {
     setMockInvokeResponse('agent_get_capability_snapshot', {
       provider: 'google',
@@ -20,6 +20,18 @@ export interface IntegrationAccessEntry {
   reason: string;
 }
 
+/**
+ * What the connected daemon advertised in its capabilities handshake. Daemons
+ * that predate the handshake report `advertised: false`, meaning everything
+ * is assumed supported.
+ */
+export interface DaemonCapabilities {
+  advertised: boolean;
+  protocolVersion: number;
+  commands: string[];
+  features: string[];
+}
+
 export interface CapabilitySnapshot {
   provider: string;
   executionMode?: 'execute' | 'plan';
@@ -46,6 +58,7 @@ export interface CapabilitySnapshot {
   integrationAccess: IntegrationAccessEntry[];
   policyProfile: string;
   notes: string[];
+  daemonCapabilities: DaemonCapabilities;
 }
 
 interface CapabilityState {
@@ -95,11 +108,26 @@ function isRecord(value: unknown): value is Record<string, unknown> {
   return value !== null && typeof value === 'object' && !Array.isArray(value);
 }
 
+function asStringList(value: unknown): string[] {
+  return Array.isArray(value) ? value.map((entry) => asString(entry)).filter(Boolean) : [];
+}
+
+export function isDaemonFeatureSupported(
+  snapshot: CapabilitySnapshot | null,
+  feature: string,
+): boolean {
+  if (!snapshot || !snapshot.daemonCapabilities.advertised) {
+    return true;
+  }
+  return snapshot.daemonCapabilities.features.includes(feature);
+}
+
 export function normalizeCapabilitySnapshot(input: unknown): CapabilitySnapshot {
   const root = isRecord(input) ? input : {};
   const mediaRouting = isRecord(root.mediaRouting) ? root.mediaRouting : {};
   const sandbox = isRecord(root.sandbox) ? root.sandbox : {};
   const keyStatus = isRecord(root.keyStatus) ? root.keyStatus : {};
+  const daemonCapabilities = isRecord(root.daemonCapabilities) ? root.daemonCapabilities : {};
 
   const toolAccessRaw = Array.isArray(root.toolAccess) ? root.toolAccess : [];
   const integrationAccessRaw = Array.isArray(root.integrationAccess) ? root.integrationAccess : [];
@@ -151,6 +179,15 @@ export function normalizeCapabilitySnapshot(input: unknown): CapabilitySnapshot
       })),
     policyProfile: asString(root.policyProfile, 'coding'),
     notes: notesRaw.map((note) => asString(note)).filter(Boolean),
+    daemonCapabilities: {
+      advertised: asBoolean(daemonCapabilities.advertised),
+      protocolVersion:
+        typeof daemonCapabilities.protocolVersion === 'number'
+          ? daemonCapabilities.protocolVersion
+          : 0,
+      commands: asStringList(daemonCapabilities.commands),
+      features: asStringList(daemonCapabilities.features),
+    },
   };
 }
 
@@ -39,6 +39,9 @@ const DEFAULT_CONFIG: HeartbeatConfig = {
   intervalMs: 60000, // 1 minute
   systemEventsEnabled: true,
   cronEnabled: true,
+  maxQueuedEvents: 1000,
+  eventRetentionMs: 24 * 60 * 60 * 1000, // 1 day
+  wakeDebounceMs: 250,
 };
 
 // ============================================================================
@@ -3,7 +3,8 @@
 
 import { describe, it, expect, beforeEach } from 'vitest';
 import { useSessionStore } from './session-store';
-import { setMockInvokeResponse, clearMockInvokeResponses } from '../test/mocks/tauri-core';
+import { invoke, setMockInvokeResponse, clearMockInvokeResponses } from '../test/mocks/tauri-core';
+import { useToastStore } from '../components/ui/Toast';
 
 describe('session-store', () => {
   beforeEach(() => {
@@ -173,6 +174,50 @@ describe('session-store', () => {
       expect(state.isLoading).toBe(false);
     });
 
+    it('warns about unknown models but still creates the session', async () => {
+      useToastStore.getState().clearToasts();
+      setMockInvokeResponse('agent_create_session', {
+        id: 'typo-session',
+        title: null,
+        firstMessage: null,
+        workingDirectory: '/path',
+        model: 'gemini-3-flahs-preview',
+        createdAt: Date.now(),
+        updatedAt: Date.now(),
+        modelWarning: 'Model `gemini-3-flahs-preview` is not a known google model',
+      });
+
+      const sessionId = await useSessionStore.getState().createSession('/path');
+
+      expect(sessionId).toBe('typo-session');
+      const toasts = useToastStore.getState().toasts;
+      expect(toasts.some((t) => t.type === 'warning' && t.title === 'Unrecognized model')).toBe(true);
+    });
+
+    it('does not warn for known models', async () => {
+      useToastStore.getState().clearToasts();
+      setMockInvokeResponse('agent_create_session', {
+        id: 'known-session',
+        title: null,
+        firstMessage: null,
+        workingDirectory: '/path',
+        model: 'gemini-3-flash-preview',
+        createdAt: Date.now(),
+        updatedAt: Date.now(),
+        modelInfo: {
+          id: 'gemini-3-flash-preview',
+          name: 'Gemini 3 Flash Preview',
+          description: 'Latest fast preview model',
+          input_token_limit: 1048576,
+          output_token_limit: 65536,
+        },
+      });
+
+      await useSessionStore.getState().createSession('/path');
+
+      expect(useToastStore.getState().toasts.some((t) => t.type === 'warning')).toBe(false);
+    });
+
     it('should handle create errors', async () => {
       setMockInvokeResponse('agent_create_session', () => {
         throw new Error('Failed to create session');
@@ -372,6 +417,14 @@ describe('session-store', () => {
 
       expect(useSessionStore.getState().activeSessionId).toBeNull();
     });
+
+    it('should stop the memory watch of the closed session', () => {
+      setMockInvokeResponse('agent_stop_memory_watch', undefined);
+      useSessionStore.setState({ activeSessionId: 'session-1' });
+      useSessionStore.getState().setActiveSession(null);
+
+      expect(invoke).toHaveBeenCalledWith('agent_stop_memory_watch', { sessionId: 'session-1' });
+    });
   });
 
   describe('clearError', () => {
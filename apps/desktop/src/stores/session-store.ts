@@ -42,6 +42,17 @@ export interface SessionInfo {
   createdAt: number;
   updatedAt: number;
   lastAccessedAt: number;
+  /** Catalog entry for `model` when the provider lists it. */
+  modelInfo?: {
+    id: string;
+    name: string;
+    description: string;
+    input_token_limit: number;
+    output_token_limit: number;
+    token_limits_estimated?: boolean;
+  };
+  /** Set when `model` is not among the provider's known models. */
+  modelWarning?: string;
 }
 
 export interface SessionBranch {
@@ -165,15 +176,23 @@ export const useSessionStore = create<SessionState & SessionActions>()(
         const MAX_WAIT = 30000; // 30 seconds
         const POLL_INTERVAL = 500; // 500ms
         let elapsed = 0;
+        let initError: string | null = null;
 
         while (elapsed < MAX_WAIT) {
           try {
-            const status = await invoke<{ initialized: boolean; sessionCount: number }>('agent_get_initialization_status');
+            const status = await invoke<{
+              initialized: boolean;
+              sessionCount: number;
+              state?: 'ready' | 'initializing' | 'failed';
+              error?: string | null;
+            }>('agent_get_initialization_status');
             if (status.initialized) {
               set({ backendInitialized: true });
               useAppStore.getState().setStartupIssue(null);
               return;
             }
+            // Each poll retries a failed initialize; remember why it failed.
+            initError = status.state === 'failed' ? status.error || null : null;
           } catch {
             // Sidecar may still be starting, continue polling
           }
@@ -187,10 +206,14 @@ export const useSessionStore = create<SessionState & SessionActions>()(
           .setStartupIssue(
             createStartupIssue(
               'Backend not ready',
-              'Cowork services did not start in time. Open the highlighted recovery screen and retry.'
+              initError
+                ? `Cowork services failed to initialize: ${initError}`
+                : 'Cowork services did not start in time. Open the highlighted recovery screen and retry.'
             )
           );
-        const timeoutMessage = `Backend initialization timed out after ${MAX_WAIT}ms`;
+        const timeoutMessage = initError
+          ? `Backend initialization failed: ${initError}`
+          : `Backend initialization timed out after ${MAX_WAIT}ms`;
         console.error('[SessionStore] waitForBackend timeout', {
           maxWaitMs: MAX_WAIT,
           pollIntervalMs: POLL_INTERVAL,
@@ -447,6 +470,9 @@ export const useSessionStore = create<SessionState & SessionActions>()(
             };
           });
           useAppStore.getState().setRuntimeConfigNotice(null);
+          if (session.modelWarning) {
+            toast.warning('Unrecognized model', session.modelWarning);
+          }
 
           return session.id;
         } catch (error) {
@@ -792,7 +818,12 @@ export const useSessionStore = create<SessionState & SessionActions>()(
       },
 
       setActiveSession: (sessionId: string | null) => {
+        const previousActiveId = get().activeSessionId;
         set({ activeSessionId: sessionId });
+        if (sessionId === null && previousActiveId) {
+          // Closing the session view ends its GEMINI.md watch.
+          invoke('agent_stop_memory_watch', { sessionId: previousActiveId }).catch(() => {});
+        }
       },
 
       clearError: () => {
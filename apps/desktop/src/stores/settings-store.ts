@@ -12,6 +12,7 @@ import {
   type RuntimeSoulProfile,
 } from './auth-store';
 import { useAppStore } from './app-store';
+import { commandErrorMessage } from '../lib/command-error';
 
 export interface ModelInfo {
   id: string;
@@ -19,6 +20,12 @@ export interface ModelInfo {
   description: string;
   inputTokenLimit: number;
   outputTokenLimit: number;
+  tokenLimitsEstimated?: boolean;
+  /** True when the live fetch failed and this is the last successful list. */
+  stale?: boolean;
+  /** True when the live fetch failed and this is the curated default list. */
+  fallback?: boolean;
+  fetchedAt?: number | null;
 }
 
 export interface MCPServerConfig {
@@ -199,10 +206,6 @@ interface SoulCatalogPayload {
   userSoulsDir: string;
 }
 
-interface AgentCommandResult<T> {
-  result: T;
-}
-
 const DEFAULT_SOUL_ID = 'preset:professional';
 
 const FALLBACK_SOUL_PROFILE: SoulProfile = {
@@ -293,11 +296,10 @@ async function invokeAgentCommand<T>(
   command: string,
   params: Record<string, unknown>,
 ): Promise<T> {
-  const response = await invoke<AgentCommandResult<T>>('agent_command', {
+  return invoke<T>('agent_command_raw', {
     command,
     params,
   });
-  return response.result;
 }
 
 export const DEFAULT_SPECIALIZED_MODELS_V2: SpecializedModelsV2 = {
@@ -892,24 +894,34 @@ export const useSettingsStore = create<SettingsState & SettingsActions>()(
             return;
           }
 
-          const models = await invoke<Array<{
-            id: string;
-            name: string;
-            description: string;
-            input_token_limit: number;
-            output_token_limit: number;
-          }>>('fetch_provider_models', {
+          const fetched = await invoke<{
+            models: Array<{
+              id: string;
+              name: string;
+              description: string;
+              input_token_limit: number;
+              output_token_limit: number;
+              token_limits_estimated?: boolean;
+            }>;
+            stale: boolean;
+            fallback: boolean;
+            fetched_at: number | null;
+          }>('fetch_provider_models', {
             providerId: provider,
             apiKey: providerKey || '',
             baseUrl: baseUrl || null,
           });
 
-          const mappedModels: ModelInfo[] = models.map((m) => ({
+          const mappedModels: ModelInfo[] = fetched.models.map((m) => ({
             id: m.id,
             name: m.name,
             description: m.description,
             inputTokenLimit: m.input_token_limit,
             outputTokenLimit: m.output_token_limit,
+            tokenLimitsEstimated: m.token_limits_estimated ?? false,
+            stale: fetched.stale,
+            fallback: fetched.fallback,
+            fetchedAt: fetched.fetched_at,
           }));
 
           const customModels = state.customModelsByProvider[provider] || [];
@@ -955,7 +967,7 @@ export const useSettingsStore = create<SettingsState & SettingsActions>()(
         } catch (error) {
           set({
             modelsLoading: false,
-            error: error instanceof Error ? error.message : String(error),
+            error: commandErrorMessage(error),
           });
         }
       },
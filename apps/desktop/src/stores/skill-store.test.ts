@@ -9,6 +9,15 @@ import type { SkillManifest } from '@cowork/shared';
 
 const WORKING_DIRECTORY = '/tmp/cowork';
 
+const ELIGIBLE = {
+  eligible: true,
+  missingBins: [],
+  missingEnvVars: [],
+  platformMismatch: false,
+  installHints: [],
+  foundBins: {},
+};
+
 function createManagedSkill(name: string): SkillManifest {
   return {
     id: `managed:${name}`,
@@ -43,14 +52,10 @@ describe('skill store installed sync', () => {
     vi.useFakeTimers();
     vi.setSystemTime(new Date('2026-02-14T00:00:00.000Z'));
     clearMockInvokeResponses();
-    setMockInvokeResponse('agent_check_skill_eligibility', {
-      eligible: true,
-      missingBins: [],
-      missingEnvVars: [],
-      platformMismatch: false,
-      installHints: [],
-      foundBins: {},
-    });
+    setMockInvokeResponse('agent_check_skill_eligibility', ELIGIBLE);
+    setMockInvokeResponse('agent_check_skills_eligibility', (args: { skillIds: string[] }) =>
+      Object.fromEntries(args.skillIds.map((skillId) => [skillId, ELIGIBLE]))
+    );
     (invoke as unknown as { mockClear: () => void }).mockClear();
     resetState();
   });
@@ -83,4 +88,21 @@ describe('skill store installed sync', () => {
     expect(installedCount).toBe(installedSkills.length);
     expect(installedSkills.map((skill) => skill.id)).toEqual(['managed:skill-creator']);
   });
+
+  it('checks eligibility for all skills in a single batch call', async () => {
+    useSkillStore.setState({
+      availableSkills: [createManagedSkill('alpha'), createManagedSkill('beta')],
+    });
+    (invoke as unknown as { mockClear: () => void }).mockClear();
+
+    await useSkillStore.getState().checkAllEligibility();
+
+    const calls = (invoke as unknown as { mock: { calls: unknown[][] } }).mock.calls;
+    expect(calls.filter(([cmd]) => cmd === 'agent_check_skill_eligibility')).toHaveLength(0);
+    expect(calls.filter(([cmd]) => cmd === 'agent_check_skills_eligibility')).toEqual([
+      ['agent_check_skills_eligibility', { skillIds: ['managed:alpha', 'managed:beta'] }],
+    ]);
+    expect(useSkillStore.getState().getSkillEligibility('managed:beta')).toEqual(ELIGIBLE);
+    expect(useSkillStore.getState().isCheckingEligibility.size).toBe(0);
+  });
 });
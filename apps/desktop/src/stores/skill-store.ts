@@ -51,6 +51,25 @@ export interface SkillLifecycleInfo {
   verificationNotes?: string;
 }
 
+/**
+ * Result of agent_create_skill
+ */
+interface CreatedSkill {
+  skillId: string;
+  skillPath?: string;
+  metadata: {
+    name?: string;
+    description?: string;
+    triggers: string[];
+    allowedTools: string[];
+  };
+}
+
+/**
+ * Starter templates the backend can scaffold a new skill from
+ */
+export type SkillTemplateId = 'coding' | 'research' | 'messaging';
+
 /**
  * Parameters for creating a custom skill
  */
@@ -59,7 +78,9 @@ interface CreateSkillParams {
   description: string;
   emoji?: string;
   category?: string;
+  /** May be empty when a template is chosen */
   content: string;
+  template?: SkillTemplateId;
   requirements?: {
     bins?: string[];
     env?: string[];
@@ -240,6 +261,7 @@ export const useSkillStore = create<SkillStoreState & SkillStoreActions>()(
       try {
         const skills = await invoke<SkillManifest[]>('agent_discover_skills', {
           workingDirectory,
+          options: { refresh: force },
         });
 
         set({
@@ -427,13 +449,14 @@ export const useSkillStore = create<SkillStoreState & SkillStoreActions>()(
       set({ error: null });
 
       try {
-        const skillId = await invoke<string>('agent_create_skill', {
+        const { skillId } = await invoke<CreatedSkill>('agent_create_skill', {
           name: params.name,
           description: params.description,
           emoji: params.emoji,
           category: params.category,
           content: params.content,
           requirements: params.requirements,
+          template: params.template,
         });
 
         // Auto-install the created skill by adding to settings
@@ -492,13 +515,37 @@ export const useSkillStore = create<SkillStoreState & SkillStoreActions>()(
     },
 
     checkAllEligibility: async () => {
-      const { availableSkills, checkEligibility } = get();
+      const skillIds = get().availableSkills.map((skill) => skill.id);
+      if (skillIds.length === 0) return;
+
+      set((state) => ({
+        isCheckingEligibility: new Set([...state.isCheckingEligibility, ...skillIds]),
+      }));
+
+      try {
+        // One round-trip for the whole list; the backend caches results briefly
+        const results = await invoke<Record<string, SkillEligibility>>(
+          'agent_check_skills_eligibility',
+          { skillIds }
+        );
 
-      // Check in batches to avoid overwhelming the system
-      const BATCH_SIZE = 5;
-      for (let i = 0; i < availableSkills.length; i += BATCH_SIZE) {
-        const batch = availableSkills.slice(i, i + BATCH_SIZE);
-        await Promise.all(batch.map((skill) => checkEligibility(skill.id)));
+        set((state) => {
+          const newMap = new Map(state.eligibilityMap);
+          for (const [skillId, eligibility] of Object.entries(results)) {
+            newMap.set(skillId, eligibility);
+          }
+          return { eligibilityMap: newMap };
+        });
+      } catch (error) {
+        console.warn('Failed to check skill eligibility:', error);
+      } finally {
+        set((state) => {
+          const newChecking = new Set(state.isCheckingEligibility);
+          for (const skillId of skillIds) {
+            newChecking.delete(skillId);
+          }
+          return { isCheckingEligibility: newChecking };
+        });
       }
     },
 
@@ -47,6 +47,39 @@ export interface Subagent {
   installed: boolean;
 }
 
+/**
+ * Subagent that an install would clash with
+ */
+export interface SubagentConflict {
+  name: string;
+  displayName: string;
+  source?: SubagentSource;
+  installed: boolean;
+  reason: 'alreadyInstalled' | 'name' | 'displayName';
+}
+
+/**
+ * Error returned by deep_subagent_install when it refuses to install
+ */
+export interface SubagentInstallError {
+  code: 'subagent_conflict';
+  message: string;
+  subagentName: string;
+  conflicts: SubagentConflict[];
+}
+
+/**
+ * Parse a structured install error, if the backend returned one
+ */
+export function parseSubagentInstallError(error: unknown): SubagentInstallError | null {
+  try {
+    const parsed = JSON.parse(String(error)) as Partial<SubagentInstallError>;
+    return parsed?.code === 'subagent_conflict' ? (parsed as SubagentInstallError) : null;
+  } catch {
+    return null;
+  }
+}
+
 /**
  * Category metadata for UI display
  */
@@ -108,7 +141,11 @@ interface SubagentStoreActions {
   ) => Promise<void>;
 
   // Install/Uninstall
-  installSubagent: (name: string, workingDirectory?: string) => Promise<string | null>;
+  installSubagent: (
+    name: string,
+    workingDirectory?: string,
+    options?: { force?: boolean }
+  ) => Promise<string | null>;
   uninstallSubagent: (name: string, workingDirectory?: string) => Promise<void>;
   isSubagentInstalling: (name: string) => boolean;
 
@@ -209,7 +246,7 @@ export const useSubagentStore = create<SubagentStoreState & SubagentStoreActions
     // Install/Uninstall
     // ========================================================================
 
-    installSubagent: async (name, workingDirectory) => {
+    installSubagent: async (name, workingDirectory, options) => {
       set((state) => ({
         isInstalling: new Set([...state.isInstalling, name]),
         error: null,
@@ -219,14 +256,20 @@ export const useSubagentStore = create<SubagentStoreState & SubagentStoreActions
         await invoke('deep_subagent_install', {
           subagentName: name,
           workingDirectory,
+          force: options?.force ?? false,
         });
 
         await get().loadSubagents(workingDirectory, { force: true });
         return name;
       } catch (error) {
         console.error('Failed to install subagent:', error);
+        const conflict = parseSubagentInstallError(error);
         set({
-          error: error instanceof Error ? error.message : String(error),
+          error: conflict
+            ? conflict.message
+            : error instanceof Error
+              ? error.message
+              : String(error),
         });
         return null;
       } finally {
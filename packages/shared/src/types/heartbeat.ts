@@ -15,6 +15,24 @@ export const HeartbeatConfigSchema = z.object({
   intervalMs: z.number().min(1000).default(60000).describe('Heartbeat interval in ms (min 1 second)'),
   systemEventsEnabled: z.boolean().default(true),
   cronEnabled: z.boolean().default(true),
+  maxQueuedEvents: z
+    .number()
+    .int()
+    .min(1)
+    .max(10000)
+    .default(1000)
+    .describe('Queued events kept before the oldest are evicted'),
+  eventRetentionMs: z
+    .number()
+    .min(0)
+    .default(24 * 60 * 60 * 1000)
+    .describe('Age after which queued events are dropped (0 = no limit)'),
+  wakeDebounceMs: z
+    .number()
+    .min(0)
+    .max(60000)
+    .default(250)
+    .describe('Window in which wake requests are merged into one cycle'),
 });
 
 export type HeartbeatConfig = z.infer<typeof HeartbeatConfigSchema>;
@@ -91,6 +109,9 @@ export const HeartbeatStatusSchema = z.object({
   lastHeartbeat: z.number(),
   nextHeartbeat: z.number(),
   eventQueueSize: z.number(),
+  eventQueueCapacity: z.number(),
+  evictedEvents: z.number(),
+  coalescedWakes: z.number(),
   isProcessing: z.boolean(),
 });
 